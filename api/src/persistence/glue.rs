@@ -14,6 +14,8 @@ pub struct Glue {
     pub reverse: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub out_of_range_behavior: Option<OutOfRangeBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_curve: Option<ResponseCurve>,
     //endregion
 
     //region Relevant for control only (might change in future)
@@ -33,6 +35,8 @@ pub struct Glue {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub control_transformation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_transformation_kind: Option<TransformationScriptKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub step_size_interval: Option<Interval<f64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub step_factor_interval: Option<Interval<i32>>,
@@ -46,6 +50,8 @@ pub struct Glue {
     pub interaction: Option<Interaction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fire_mode: Option<FireMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smoothing: Option<Smoothing>,
     //endregion
 
     //region Relevant for feedback only (guaranteed)
@@ -63,6 +69,81 @@ pub enum FeedbackValueTable {
     FromTextToContinuous(ContinuousFeedbackValueTableContent),
 }
 
+/// Makes the target value follow the desired value gradually instead of jumping to it.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Smoothing {
+    /// Exponential smoothing, characterized by a time constant.
+    TimeConstant(TimeConstantSmoothing),
+    /// Linear slew limiting, characterized by a maximum rate of change.
+    MaxRate(MaxRateSmoothing),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TimeConstantSmoothing {
+    /// Time constant in milliseconds when the value goes up.
+    pub up_millis: u32,
+    /// Time constant in milliseconds when the value goes down. Defaults to `up_millis`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_millis: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MaxRateSmoothing {
+    /// Maximum normalized change per second when the value goes up.
+    pub up_per_second: f64,
+    /// Maximum normalized change per second when the value goes down. Defaults to
+    /// `up_per_second`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_per_second: Option<f64>,
+}
+
+/// Language in which a control or feedback transformation is written.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum TransformationScriptKind {
+    #[default]
+    #[serde(alias = "eel")]
+    Eel,
+    #[serde(alias = "lua")]
+    Lua,
+}
+
+/// Declarative alternative to writing a control transformation.
+///
+/// Maps input values to output values by interpolating between breakpoints.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ResponseCurve {
+    /// Breakpoints, ordered by their input value.
+    pub breakpoints: Vec<CurveBreakpoint>,
+    /// If `true`, feedback values are mapped through the inverse of this curve.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invert_for_feedback: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CurveBreakpoint {
+    /// Input value (0.0 to 1.0).
+    pub x: f64,
+    /// Output value (0.0 to 1.0).
+    pub y: f64,
+    /// Shape of the segment leading to the next breakpoint.
+    #[serde(default)]
+    pub shape: CurveSegmentShape,
+    /// Strength and direction of the bend (-1.0 to 1.0). Only relevant for bent shapes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curvature: Option<f64>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum CurveSegmentShape {
+    #[default]
+    Linear,
+    Exponential,
+    SCurve,
+    /// Holds the value of the breakpoint until the next breakpoint.
+    Step,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct DiscreteFeedbackValueTableContent {
     pub value: HashMap<String, u32>,
@@ -230,6 +311,8 @@ pub struct NumericFeedback {
     pub commons: FeedbackCommons,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transformation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transformation_kind: Option<TransformationScriptKind>,
 }
 
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
@@ -37,16 +37,50 @@ pub struct Mapping {
     pub glue: Option<Glue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<Target>,
+    /// Further targets which are controlled by the same source, in the given order.
+    ///
+    /// The main `target` comes first, followed by these ones. Each additional target can
+    /// override some glue settings, all others are taken from `glue`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_targets: Option<Vec<AdditionalTarget>>,
+    /// Index of the target which provides the feedback value for this mapping.
+    ///
+    /// 0 refers to the main `target`, 1 to the first additional target and so on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_target_index: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub success_audio_feedback: Option<SuccessAudioFeedback>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unprocessed: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
-#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+#[derive(PartialEq, Serialize, Deserialize)]
+pub struct AdditionalTarget {
+    pub target: Target,
+    /// Overrides the target interval of the mapping's glue for this target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_interval: Option<Interval<f64>>,
+    /// Overrides the control transformation of the mapping's glue for this target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_transformation: Option<String>,
+}
+
+#[derive(PartialEq, Default, Serialize, Deserialize)]
 pub struct LifecycleHook {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub send_midi_feedback: Option<Vec<SendMidiFeedbackAction>>,
+    /// OSC messages to be sent to the feedback output (only if it's an OSC device).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_osc_feedback: Option<Vec<SendOscFeedbackAction>>,
+    /// Lua code which is executed whenever the hook fires.
+    ///
+    /// It has access to the compartment parameters and can return MIDI and OSC messages to be
+    /// sent to the feedback output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_lua: Option<String>,
+    /// Target values to be set whenever the hook fires.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_target_value: Option<Vec<SetTargetValueAction>>,
 }
 
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
@@ -55,6 +89,36 @@ pub enum SendMidiFeedbackAction {
     Raw { message: RawMidiMessage },
 }
 
+#[derive(PartialEq, Serialize, Deserialize)]
+pub struct SendOscFeedbackAction {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Vec<OscFeedbackArgument>>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum OscFeedbackArgument {
+    Float { value: f32 },
+    Double { value: f64 },
+    Int { value: i32 },
+    Long { value: i64 },
+    Bool { value: bool },
+    String { value: String },
+    Nil,
+}
+
+#[derive(PartialEq, Serialize, Deserialize)]
+pub struct SetTargetValueAction {
+    /// Key of the mapping whose target should be set.
+    ///
+    /// If not given, the target of the mapping that owns the hook is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<String>,
+    /// Normalized target value (between 0.0 and 1.0).
+    pub value: f64,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RawMidiMessage {
@@ -76,6 +140,20 @@ pub enum ActivationCondition {
     Eel(EelActivationCondition),
     Expression(ExpressionActivationCondition),
     TargetValue(TargetValueActivationCondition),
+    /// Fulfilled if the transport of the current project is in the given state.
+    PlayState(PlayStateActivationCondition),
+    /// Fulfilled if at least one selected track has a name matching the given wildcard pattern.
+    TrackSelected(TrackSelectedActivationCondition),
+    /// Fulfilled if the last focused FX has a name matching the given wildcard pattern.
+    FxFocused(FxFocusedActivationCondition),
+    /// Fulfilled if a MIDI editor is open.
+    MidiEditorOpen,
+    /// Fulfilled if all the given conditions are fulfilled.
+    All(AllActivationCondition),
+    /// Fulfilled if at least one of the given conditions is fulfilled.
+    Any(AnyActivationCondition),
+    /// Fulfilled if the given condition is not fulfilled.
+    Not(NotActivationCondition),
 }
 
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
@@ -112,6 +190,46 @@ pub struct TargetValueActivationCondition {
     pub condition: String,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct PlayStateActivationCondition {
+    pub state: ActivationPlayState,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum ActivationPlayState {
+    #[default]
+    Stopped,
+    /// Also fulfilled while recording.
+    Playing,
+    Paused,
+    Recording,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrackSelectedActivationCondition {
+    pub track_name: String,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct FxFocusedActivationCondition {
+    pub fx_name: String,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct AllActivationCondition {
+    pub conditions: Vec<ActivationCondition>,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct AnyActivationCondition {
+    pub conditions: Vec<ActivationCondition>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct NotActivationCondition {
+    pub condition: Box<ActivationCondition>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ParamRef {
@@ -18,6 +18,7 @@ pub enum Source {
     Timer(TimerSource),
     RealearnParameter(RealearnParameterSource),
     Speech,
+    Modulator(ModulatorSource),
     // MIDI
     MidiNoteVelocity(MidiNoteVelocitySource),
     MidiNoteKeyNumber(MidiNoteKeyNumberSource),
@@ -305,6 +306,115 @@ pub struct TimerSource {
     pub duration: u64,
 }
 
+/// A source which continuously emits values generated by a modulator (LFO, envelope, ...).
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ModulatorSource {
+    pub modulator: Modulator,
+    /// Modulation depth between 0.0 and 1.0. Defaults to 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<f64>,
+    /// Compartment parameter which scales the modulation depth.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_parameter_index: Option<u32>,
+    /// Compartment parameter which scales the modulation rate (from 1/4 to 4 times, the center
+    /// being the unchanged rate).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_parameter_index: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Modulator {
+    Lfo(LfoModulator),
+    Envelope(EnvelopeModulator),
+    RandomWalk(RandomWalkModulator),
+}
+
+impl Default for Modulator {
+    fn default() -> Self {
+        Self::Lfo(Default::default())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct LfoModulator {
+    #[serde(default)]
+    pub shape: LfoShape,
+    #[serde(default)]
+    pub rate: ModulatorRate,
+    /// Phase offset as fraction of one cycle.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<f64>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum LfoShape {
+    #[default]
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+    SampleAndHold,
+}
+
+/// An ADSR envelope which is triggered by another mapping.
+///
+/// All times refer to a change over the complete value range.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct EnvelopeModulator {
+    /// Key of the mapping (in the same compartment) which acts as gate.
+    ///
+    /// The envelope starts as soon as the target value of that mapping rises above 0% and is
+    /// released as soon as it drops to 0% again (or the target becomes inactive).
+    pub gate_mapping: String,
+    #[serde(default)]
+    pub attack_millis: u32,
+    #[serde(default)]
+    pub decay_millis: u32,
+    /// Sustain level between 0.0 and 1.0. Defaults to 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sustain: Option<f64>,
+    #[serde(default)]
+    pub release_millis: u32,
+}
+
+/// Moves randomly up and down, one step per cycle.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct RandomWalkModulator {
+    #[serde(default)]
+    pub rate: ModulatorRate,
+    /// Maximum change per step between 0.0 and 1.0. Defaults to 0.1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_size: Option<f64>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ModulatorRate {
+    /// Runs independently of the project.
+    Free(FreeModulatorRate),
+    /// Follows the project position.
+    TempoSynced(TempoSyncedModulatorRate),
+}
+
+impl Default for ModulatorRate {
+    fn default() -> Self {
+        Self::Free(FreeModulatorRate { hertz: 1.0 })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FreeModulatorRate {
+    /// Number of cycles per second.
+    pub hertz: f64,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TempoSyncedModulatorRate {
+    /// Length of one cycle in beats.
+    pub beats: f64,
+}
+
 #[derive(Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct KeySource {
     #[serde(skip_serializing_if = "Option::is_none")]
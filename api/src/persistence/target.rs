@@ -93,11 +93,14 @@ pub enum Target {
     Mouse(MouseTarget),
     LastTouched(LastTouchedTarget),
     AutomationModeOverride(AutomationModeOverrideTarget),
+    LoadScreenset(LoadScreensetTarget),
+    WindowVisibility(WindowVisibilityTarget),
     ReaperAction(ReaperActionTarget),
     TransportAction(TransportActionTarget),
     AnyOn(AnyOnTarget),
     #[serde(alias = "CycleThroughTracks")]
     BrowseTracks(BrowseTracksTarget),
+    BrowseProjects(BrowseProjectsTarget),
     Seek(SeekTarget),
     PlayRate(PlayRateTarget),
     Tempo(TempoTarget),
@@ -111,6 +114,8 @@ pub enum Target {
     TrackSelectionState(TrackSelectionStateTarget),
     TrackAutomationMode(TrackAutomationModeTarget),
     TrackMonitoringMode(TrackMonitoringModeTarget),
+    TrackRecordInput(TrackRecordInputTarget),
+    TrackRecordMode(TrackRecordModeTarget),
     TrackAutomationTouchState(TrackAutomationTouchStateTarget),
     TrackPan(TrackPanTarget),
     TrackWidth(TrackWidthTarget),
@@ -119,6 +124,9 @@ pub enum Target {
     TrackTool(TrackToolTarget),
     TrackVisibility(TrackVisibilityTarget),
     TrackSoloState(TrackSoloStateTarget),
+    TrackName(TrackNameTarget),
+    TrackColor(TrackColorTarget),
+    TrackLayout(TrackLayoutTarget),
     #[serde(alias = "CycleThroughFx")]
     BrowseFxChain(BrowseFxChainTarget),
     FxOnOffState(FxOnOffStateTarget),
@@ -131,6 +139,9 @@ pub enum Target {
     FxVisibility(FxVisibilityTarget),
     FxParameterValue(FxParameterValueTarget),
     FxParameterAutomationTouchState(FxParameterAutomationTouchStateTarget),
+    EnvelopeValue(EnvelopeValueTarget),
+    EnvelopeAction(EnvelopeActionTarget),
+    AutomationItemAction(AutomationItemActionTarget),
     RouteAutomationMode(RouteAutomationModeTarget),
     RouteMonoState(RouteMonoStateTarget),
     RouteMuteState(RouteMuteStateTarget),
@@ -138,6 +149,8 @@ pub enum Target {
     RoutePan(RoutePanTarget),
     RouteVolume(RouteVolumeTarget),
     RouteTouchState(RouteTouchStateTarget),
+    RouteExistence(RouteExistenceTarget),
+    RouteSendMode(RouteSendModeTarget),
     #[serde(alias = "ClipTransportAction")]
     PlaytimeSlotTransportAction(PlaytimeSlotTransportActionTarget),
     #[serde(alias = "ClipColumnAction")]
@@ -160,6 +173,7 @@ pub enum Target {
     EnableInstances(EnableInstancesTarget),
     EnableMappings(EnableMappingsTarget),
     ModifyMapping(ModifyMappingTarget),
+    Macro(MacroTarget),
     CompartmentParameterValue(CompartmentParameterValueTarget),
     #[serde(alias = "LoadMappingSnapshots")]
     LoadMappingSnapshot(LoadMappingSnapshotTarget),
@@ -171,6 +185,8 @@ pub enum Target {
     BrowsePotPresets(BrowsePotPresetsTarget),
     PreviewPotPreset(PreviewPotPresetTarget),
     LoadPotPreset(LoadPotPresetTarget),
+    RatePotPreset(RatePotPresetTarget),
+    TagPotPreset(TagPotPresetTarget),
     StreamDeckBrightness(StreamDeckBrightnessTarget),
     Virtual(VirtualTarget),
 }
@@ -225,6 +241,46 @@ pub struct AutomationModeOverrideTarget {
     pub override_value: Option<AutomationModeOverride>,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct LoadScreensetTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    /// Zero-based index of the screenset (window set), so 0 means window set #01.
+    pub index: u32,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct WindowVisibilityTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub window: ReaperWindow,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum ReaperWindow {
+    #[default]
+    #[display(fmt = "Mixer")]
+    Mixer,
+    #[display(fmt = "MIDI editor")]
+    MidiEditor,
+    #[display(fmt = "FX browser")]
+    FxBrowser,
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct ReaperActionTarget {
     #[serde(flatten)]
@@ -313,6 +369,12 @@ pub struct BrowseTracksTarget {
     pub mode: Option<BrowseTracksMode>,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct BrowseProjectsTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct SeekTarget {
     #[serde(flatten)]
@@ -472,6 +534,65 @@ pub struct TrackMonitoringModeTarget {
     pub use_selection_ganging: Option<bool>,
 }
 
+/// Browses the recording inputs (audio and MIDI) of the track.
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct TrackRecordInputTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrackRecordModeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusivity: Option<TrackExclusivity>,
+    pub mode: RecordMode,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum RecordMode {
+    /// Records the input (audio or MIDI).
+    #[default]
+    #[display(fmt = "Input")]
+    Input,
+    #[display(fmt = "Output (stereo)")]
+    StereoOutput,
+    #[display(fmt = "Output (stereo, latency compensated)")]
+    StereoOutputLatencyCompensated,
+    #[display(fmt = "Output (mono)")]
+    MonoOutput,
+    #[display(fmt = "Output (mono, latency compensated)")]
+    MonoOutputLatencyCompensated,
+    #[display(fmt = "Output (MIDI)")]
+    MidiOutput,
+    #[display(fmt = "MIDI overdub")]
+    MidiOverdub,
+    #[display(fmt = "MIDI replace")]
+    MidiReplace,
+    /// Doesn't record at all (input monitoring only).
+    #[display(fmt = "Disabled (monitoring only)")]
+    Disabled,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct TrackAutomationTouchStateTarget {
     #[serde(flatten)]
@@ -838,6 +959,89 @@ pub struct FxParameterAutomationTouchStateTarget {
     pub parameter: FxParameterDescriptor,
 }
 
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct EnvelopeValueTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct EnvelopeActionTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<EnvelopeAction>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum EnvelopeAction {
+    /// Shows/hides the envelope lane.
+    #[default]
+    #[display(fmt = "Show/hide")]
+    Visibility,
+    /// Arms/disarms the envelope for recording automation.
+    #[display(fmt = "Arm/disarm")]
+    Arm,
+    /// Inserts a point at the play cursor (if playing) or edit cursor (if not playing), using
+    /// the current value of the envelope at that position.
+    #[display(fmt = "Insert point at cursor")]
+    InsertPoint,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct AutomationItemActionTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub envelope: EnvelopeDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<AutomationItemAction>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum AutomationItemAction {
+    /// Creates a new automation item covering the time selection (or one measure starting at the
+    /// cursor if there's no time selection).
+    #[default]
+    #[display(fmt = "Create")]
+    Create,
+    /// Inserts a pooled copy of the automation item at the cursor right after it.
+    #[display(fmt = "Duplicate pooled")]
+    DuplicatePooled,
+    /// Switches looping of the automation item at the cursor on/off.
+    #[display(fmt = "Loop on/off")]
+    Loop,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct RouteAutomationModeTarget {
     #[serde(flatten)]
@@ -897,6 +1101,84 @@ pub struct RouteTouchStateTarget {
     pub touched_parameter: TouchedRouteParameter,
 }
 
+/// Creates or removes a send/receive between the resolved track and a partner track.
+///
+/// Because the route might not exist yet, the route descriptor is interpreted as partner
+/// track descriptor: `ById` refers to the ID of the partner track, `ByName` to its name, `ByTag`
+/// to a `#<tag>` word in its name and `ByIndex`/`Dynamic` to its index within the project.
+/// Hardware outputs are not supported.
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct RouteExistenceTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub route: RouteDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<RouteExistenceAction>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum RouteExistenceAction {
+    /// Creates the route when switched on and removes it when switched off.
+    #[default]
+    #[display(fmt = "Create/remove")]
+    Toggle,
+    /// Creates the route if it doesn't exist yet.
+    #[display(fmt = "Create")]
+    Create,
+    /// Removes the route if it exists.
+    #[display(fmt = "Remove")]
+    Remove,
+}
+
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct RouteSendModeTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    pub route: RouteDescriptor,
+    pub mode: SendMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_for_feedback: Option<bool>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    strum::EnumIter,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[repr(usize)]
+pub enum SendMode {
+    #[default]
+    #[display(fmt = "Post-fader (post-pan)")]
+    PostFader,
+    #[display(fmt = "Pre-FX")]
+    PreFx,
+    #[display(fmt = "Post-FX (pre-fader)")]
+    PostFx,
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub struct PlaytimeSlotTransportActionTarget {
     #[serde(flatten)]
@@ -1073,6 +1355,41 @@ pub struct ModifyMappingTarget {
     pub modification: MappingModification,
 }
 
+#[derive(PartialEq, Default, Serialize, Deserialize)]
+pub struct MacroTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    /// The steps which are executed one after the other when the target is triggered.
+    ///
+    /// Triggering the target while the macro is still running cancels it.
+    pub steps: Vec<MacroStep>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum MacroStep {
+    /// Controls another mapping in the same compartment with the given value.
+    ///
+    /// The value passes the glue section of that mapping.
+    SetTargetValue {
+        /// Key of the mapping.
+        mapping: String,
+        /// Normalized control value between 0.0 and 1.0.
+        value: f64,
+    },
+    /// Invokes another mapping in the same compartment with a control value of 100%.
+    InvokeMapping {
+        /// Key of the mapping.
+        mapping: String,
+    },
+    /// Waits the given number of milliseconds.
+    WaitMillis { millis: u32 },
+    /// Waits the given number of beats, measured at the tempo of the current project.
+    WaitBeats { beats: f64 },
+    /// Waits until the next bar of the current project starts.
+    WaitUntilNextBar,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 #[serde(tag = "kind")]
 pub enum MappingModification {
@@ -1201,6 +1518,8 @@ pub struct BrowsePotFilterItemsTarget {
 pub struct BrowsePotPresetsTarget {
     #[serde(flatten)]
     pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
 }
 
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
@@ -1217,6 +1536,20 @@ pub struct LoadPotPresetTarget {
     pub fx: Option<FxDescriptor>,
 }
 
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct RatePotPresetTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+}
+
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct TagPotPresetTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
 #[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct StreamDeckBrightnessTarget {
     #[serde(flatten)]
@@ -1282,6 +1615,24 @@ pub enum PotFilterKind {
     Mode,
     #[display(fmt = "Preview")]
     HasPreview,
+    /// Tempo of audio samples (rounded BPM)
+    #[display(fmt = "Tempo")]
+    Tempo,
+    /// Musical key of audio samples
+    #[display(fmt = "Key")]
+    Key,
+    /// Tag assigned by the user
+    #[display(fmt = "Tag")]
+    Tag,
+    /// Minimum rating assigned by the user
+    #[display(fmt = "Rating")]
+    Rating,
+    /// Is duplicate of another preset or not
+    #[display(fmt = "Duplicates")]
+    IsDuplicate,
+    /// Preset collection put together by the user
+    #[display(fmt = "Collection")]
+    Collection,
 }
 
 impl PotFilterKind {
@@ -1300,7 +1651,7 @@ impl PotFilterKind {
         use PotFilterKind::*;
         matches!(
             self,
-            Database | Project | Bank | SubBank | Category | SubCategory | Mode
+            Database | Project | Collection | Bank | SubBank | Category | SubCategory | Mode
         )
     }
 
@@ -1328,7 +1679,16 @@ impl PotFilterKind {
         use PotFilterKind::*;
         matches!(
             self,
-            Database | IsAvailable | IsSupported | IsUser | ProductKind | IsFavorite | HasPreview
+            Database
+                | IsAvailable
+                | IsSupported
+                | IsUser
+                | ProductKind
+                | IsFavorite
+                | HasPreview
+                | Tag
+                | Rating
+                | IsDuplicate
         )
     }
 
@@ -1342,13 +1702,16 @@ impl PotFilterKind {
         use PotFilterKind::*;
         match self {
             Database | IsAvailable | IsSupported | IsUser | ProductKind | IsFavorite => 0,
-            Project => 1,
+            Project | Collection => 1,
             Bank => 2,
             SubBank => 3,
             Category => 4,
             SubCategory => 5,
             Mode => 6,
-            HasPreview => 7,
+            HasPreview | IsDuplicate => 7,
+            Tempo => 8,
+            Key => 9,
+            Tag | Rating => 10,
         }
     }
 }
@@ -1784,6 +2147,36 @@ pub enum TouchedTrackParameter {
     Width,
 }
 
+/// Sets the track name from text input (e.g. an OSC string argument).
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct TrackNameTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+}
+
+/// Cycles through a built-in color palette or sets the track color from text input in the
+/// format `#RRGGBB` (e.g. an OSC string or color argument).
+#[derive(Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct TrackColorTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+}
+
+/// Cycles through the TCP or MCP layouts of the current theme or selects one by name via text
+/// input.
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrackLayoutTarget {
+    #[serde(flatten)]
+    pub commons: TargetCommons,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackDescriptor>,
+    pub area: TrackArea,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TouchedRouteParameter {
     Volume,
@@ -1983,6 +2376,29 @@ pub enum FxParameterDescriptor {
     },
 }
 
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "address")]
+pub enum EnvelopeDescriptor {
+    /// A built-in track envelope such as "Volume", "Pan", "Width" or "Mute", addressed by the
+    /// name shown in REAPER.
+    Track {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        track: Option<TrackDescriptor>,
+        name: String,
+    },
+    /// The envelope of an FX parameter.
+    FxParameter { parameter: FxParameterDescriptor },
+}
+
+impl Default for EnvelopeDescriptor {
+    fn default() -> Self {
+        Self::Track {
+            track: None,
+            name: "Volume".to_string(),
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "address")]
 pub enum CompartmentParameterDescriptor {
@@ -2021,6 +2437,12 @@ pub enum RouteDescriptor {
         commons: RouteDescriptorCommons,
         name: String,
     },
+    /// Addresses the route whose partner track has a word `#<tag>` in its name.
+    ByTag {
+        #[serde(flatten)]
+        commons: RouteDescriptorCommons,
+        tag: String,
+    },
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
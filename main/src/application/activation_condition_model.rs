@@ -3,8 +3,10 @@ use crate::application::{
     ModifierConditionModel, ProcessingRelevance,
 };
 use crate::domain::{
-    ActivationCondition, EelCondition, ExpressionCondition, ExpressionEvaluator, MappingId,
+    ActivationCondition, ChildActivationCondition, EelCondition, ExpressionCondition,
+    ExpressionEvaluator, MappingId, PlayStateCondition, ReaperStateCondition,
 };
+use wildmatch::WildMatch;
 
 #[allow(clippy::enum_variant_names)]
 pub enum ActivationConditionCommand {
@@ -14,6 +16,8 @@ pub enum ActivationConditionCommand {
     SetBankCondition(BankConditionModel),
     SetScript(String),
     SetMappingId(Option<MappingId>),
+    SetConditions(Vec<ActivationConditionModel>),
+    SetPlayState(PlayStateCondition),
 }
 
 #[derive(Eq, PartialEq)]
@@ -24,6 +28,8 @@ pub enum ActivationConditionProp {
     BankCondition,
     Script,
     MappingId,
+    Conditions,
+    PlayState,
 }
 
 impl GetProcessingRelevance for ActivationConditionProp {
@@ -40,6 +46,9 @@ pub struct ActivationConditionModel {
     bank_condition: BankConditionModel,
     script: String,
     mapping_id: Option<MappingId>,
+    /// Child conditions of the composite activation types (All, Any and Not).
+    conditions: Vec<ActivationConditionModel>,
+    play_state: PlayStateCondition,
 }
 
 impl Change<'_> for ActivationConditionModel {
@@ -78,6 +87,14 @@ impl Change<'_> for ActivationConditionModel {
                 self.mapping_id = v;
                 One(P::MappingId)
             }
+            C::SetConditions(v) => {
+                self.conditions = v;
+                One(P::Conditions)
+            }
+            C::SetPlayState(v) => {
+                self.play_state = v;
+                One(P::PlayState)
+            }
         };
         Some(affected)
     }
@@ -108,6 +125,14 @@ impl ActivationConditionModel {
         self.mapping_id
     }
 
+    pub fn conditions(&self) -> &[ActivationConditionModel] {
+        &self.conditions
+    }
+
+    pub fn play_state(&self) -> PlayStateCondition {
+        self.play_state
+    }
+
     pub fn create_activation_condition(&self) -> ActivationCondition {
         use ActivationType::*;
         match self.activation_type() {
@@ -138,11 +163,38 @@ impl ActivationConditionModel {
                 },
                 Err(_) => ActivationCondition::Always,
             },
+            All => ActivationCondition::All(self.create_child_conditions()),
+            Any => ActivationCondition::Any(self.create_child_conditions()),
+            Not => match self.conditions.first() {
+                Some(c) => ActivationCondition::Not(Box::new(ChildActivationCondition::new(
+                    c.create_activation_condition(),
+                ))),
+                None => ActivationCondition::Always,
+            },
+            PlayState => reaper_state(ReaperStateCondition::PlayState(self.play_state)),
+            TrackSelected => reaper_state(ReaperStateCondition::TrackSelected(WildMatch::new(
+                self.script(),
+            ))),
+            FxFocused => reaper_state(ReaperStateCondition::FxFocused(WildMatch::new(
+                self.script(),
+            ))),
+            MidiEditorOpen => reaper_state(ReaperStateCondition::MidiEditorOpen),
         }
     }
 
+    fn create_child_conditions(&self) -> Vec<ChildActivationCondition> {
+        self.conditions
+            .iter()
+            .map(|c| ChildActivationCondition::new(c.create_activation_condition()))
+            .collect()
+    }
+
     fn modifier_conditions(&self) -> impl Iterator<Item = ModifierConditionModel> {
         use std::iter::once;
         once(self.modifier_condition_1()).chain(once(self.modifier_condition_2()))
     }
 }
+
+fn reaper_state(condition: ReaperStateCondition) -> ActivationCondition {
+    ActivationCondition::ReaperState(Box::new(condition))
+}
@@ -39,6 +39,27 @@ pub enum ActivationType {
     #[serde(rename = "target-value")]
     #[display(fmt = "When target value met")]
     TargetValue,
+    #[serde(rename = "all")]
+    #[display(fmt = "When all conditions met")]
+    All,
+    #[serde(rename = "any")]
+    #[display(fmt = "When any condition met")]
+    Any,
+    #[serde(rename = "not")]
+    #[display(fmt = "When condition not met")]
+    Not,
+    #[serde(rename = "play-state")]
+    #[display(fmt = "When play state")]
+    PlayState,
+    #[serde(rename = "track-selected")]
+    #[display(fmt = "When track selected")]
+    TrackSelected,
+    #[serde(rename = "fx-focused")]
+    #[display(fmt = "When FX focused")]
+    FxFocused,
+    #[serde(rename = "midi-editor-open")]
+    #[display(fmt = "When MIDI editor open")]
+    MidiEditorOpen,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, Default)]
@@ -1,8 +1,11 @@
 use crate::domain::{
-    parse_hex_string, DisplayAsPrettyHex, LifecycleMidiData, LifecycleMidiMessage, MappingExtension,
+    parse_hex_string, Backbone, DisplayAsPrettyHex, LifecycleAction, LifecycleMainData,
+    LifecycleMidiData, LifecycleMidiMessage, LuaLifecycleScript, MappingExtension,
 };
 
-use helgoboss_learn::RawMidiEvent;
+use anyhow::Context;
+use helgoboss_learn::{RawMidiEvent, UnitValue};
+use rosc::{OscMessage, OscType};
 use serde::{Deserialize, Serialize};
 use serde_with::SerializeDisplay;
 use std::convert::TryFrom;
@@ -19,6 +22,12 @@ pub struct MappingExtensionModel {
 #[serde(default)]
 pub struct LifecycleModel {
     pub send_midi_feedback: Vec<LifecycleMidiMessageModel>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub send_osc_feedback: Vec<LifecycleOscMessageModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_lua: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub set_target_value: Vec<LifecycleTargetValueModel>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -27,6 +36,33 @@ pub enum LifecycleMidiMessageModel {
     Raw(RawMidiMessage),
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LifecycleOscMessageModel {
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<LifecycleOscArgumentModel>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleOscArgumentModel {
+    Float(f32),
+    Double(f64),
+    Int(i32),
+    Long(i64),
+    Bool(bool),
+    String(String),
+    Nil,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LifecycleTargetValueModel {
+    /// If not given, the target of the mapping itself is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<String>,
+    pub value: UnitValue,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RawMidiMessage {
@@ -79,6 +115,60 @@ impl LifecycleMidiMessageModel {
     }
 }
 
+impl From<&LifecycleOscArgumentModel> for OscType {
+    fn from(value: &LifecycleOscArgumentModel) -> Self {
+        use LifecycleOscArgumentModel::*;
+        match value {
+            Float(v) => OscType::Float(*v),
+            Double(v) => OscType::Double(*v),
+            Int(v) => OscType::Int(*v),
+            Long(v) => OscType::Long(*v),
+            Bool(v) => OscType::Bool(*v),
+            String(v) => OscType::String(v.clone()),
+            Nil => OscType::Nil,
+        }
+    }
+}
+
+impl LifecycleModel {
+    /// A Lua script which doesn't compile is left out (with a warning), the other actions are
+    /// still created.
+    pub fn create_lifecycle_actions(&self) -> Vec<LifecycleAction> {
+        let osc_actions = self.send_osc_feedback.iter().map(|m| {
+            let msg = OscMessage {
+                addr: m.address.clone(),
+                args: m.arguments.iter().map(OscType::from).collect(),
+            };
+            LifecycleAction::SendOsc(msg)
+        });
+        let lua_action = self.create_lua_action().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring lifecycle Lua script: {e:#}");
+            None
+        });
+        let target_actions =
+            self.set_target_value
+                .iter()
+                .map(|m| LifecycleAction::SetTargetValue {
+                    mapping_key: m.mapping.clone().map(|key| key.into()),
+                    value: m.value,
+                });
+        osc_actions
+            .chain(lua_action)
+            .chain(target_actions)
+            .collect()
+    }
+
+    fn create_lua_action(&self) -> anyhow::Result<Option<LifecycleAction>> {
+        let Some(code) = &self.run_lua else {
+            return Ok(None);
+        };
+        let lua = unsafe { Backbone::main_thread_lua() };
+        let script = LuaLifecycleScript::compile(lua, code)
+            .context("couldn't compile lifecycle Lua script")?;
+        Ok(Some(LifecycleAction::RunLua(Box::new(script))))
+    }
+}
+
 impl MappingExtensionModel {
     pub fn create_mapping_extension(&self) -> Result<MappingExtension, &'static str> {
         fn convert_messages(
@@ -89,10 +179,18 @@ impl MappingExtensionModel {
                 .map(|m| m.create_lifecycle_midi_message())
                 .collect()
         }
-        let ext = MappingExtension::new(LifecycleMidiData {
-            activation_midi_messages: convert_messages(&self.on_activate.send_midi_feedback)?,
-            deactivation_midi_messages: convert_messages(&self.on_deactivate.send_midi_feedback)?,
-        });
+        let ext = MappingExtension::new(
+            LifecycleMidiData {
+                activation_midi_messages: convert_messages(&self.on_activate.send_midi_feedback)?,
+                deactivation_midi_messages: convert_messages(
+                    &self.on_deactivate.send_midi_feedback,
+                )?,
+            },
+            LifecycleMainData {
+                activation_actions: self.on_activate.create_lifecycle_actions(),
+                deactivation_actions: self.on_deactivate.create_lifecycle_actions(),
+            },
+        );
         Ok(ext)
     }
 }
@@ -6,11 +6,12 @@ use crate::application::{
     TargetModel, TargetModelFormatVeryShort, TargetModelWithContext, TargetProp,
 };
 use crate::domain::{
-    ActivationCondition, CompartmentKind, CompoundMappingSource, CompoundMappingTarget,
-    EelTransformation, ExtendedProcessorContext, ExtendedSourceCharacter, FeedbackSendBehavior,
-    GroupId, MainMapping, MappingId, MappingKey, Mode, PersistentMappingProcessingState,
-    ProcessorMappingOptions, QualifiedMappingId, RealearnTarget, ReaperTarget, Script, Tag,
-    TargetCharacter, UnresolvedCompoundMappingTarget, VirtualFx, VirtualTrack,
+    ActivationCondition, AdditionalMappingTarget, CompartmentKind, CompoundMappingSource,
+    CompoundMappingTarget, EelTransformation, ExtendedProcessorContext, ExtendedSourceCharacter,
+    FeedbackSendBehavior, GroupId, MainMapping, MappingId, MappingKey, Mode,
+    PersistentMappingProcessingState, ProcessorMappingOptions, QualifiedMappingId, RealearnTarget,
+    ReaperTarget, Script, Tag, TargetCharacter, UnresolvedCompoundMappingTarget, VirtualFx,
+    VirtualTrack,
 };
 use helgoboss_learn::{
     AbsoluteMode, ControlType, DetailedSourceCharacter, DiscreteIncrement, Interval,
@@ -20,6 +21,7 @@ use helgoboss_learn::{
 use reaper_high::{Fx, Track};
 use std::cell::RefCell;
 use std::error::Error;
+use std::iter;
 use std::rc::Rc;
 
 pub enum MappingCommand {
@@ -36,6 +38,8 @@ pub enum MappingCommand {
     ChangeSource(SourceCommand),
     ChangeMode(ModeCommand),
     ChangeTarget(TargetCommand),
+    SetAdditionalTargets(Vec<AdditionalTargetModel>),
+    SetFeedbackTargetIndex(u32),
 }
 
 #[derive(Eq, PartialEq)]
@@ -54,6 +58,8 @@ pub enum MappingProp {
     InSource(Affected<SourceProp>),
     InMode(Affected<ModeProp>),
     InTarget(Affected<TargetProp>),
+    AdditionalTargets,
+    FeedbackTargetIndex,
 }
 
 impl GetProcessingRelevance for MappingProp {
@@ -67,7 +73,9 @@ impl GetProcessingRelevance for MappingProp {
             | P::FeedbackSendBehavior
             | P::VisibleInProjection
             | P::AdvancedSettings
-            | P::BeepOnSuccess => Some(ProcessingRelevance::ProcessingRelevant),
+            | P::BeepOnSuccess
+            | P::AdditionalTargets
+            | P::FeedbackTargetIndex => Some(ProcessingRelevance::ProcessingRelevant),
             P::InActivationCondition(p) => p.processing_relevance(),
             P::InMode(p) => p.processing_relevance(),
             P::InSource(p) => p.processing_relevance(),
@@ -100,10 +108,24 @@ pub struct MappingModel {
     pub source_model: SourceModel,
     pub mode_model: ModeModel,
     pub target_model: TargetModel,
+    additional_targets: Vec<AdditionalTargetModel>,
+    /// 0 refers to the main target, 1 to the first additional target and so on.
+    feedback_target_index: u32,
     advanced_settings: Option<serde_yaml::mapping::Mapping>,
     extension_model: MappingExtensionModel,
 }
 
+/// A further target of a multi-target mapping.
+///
+/// Shares source and glue with the main target, except for the properties which can be overridden
+/// per target.
+#[derive(Clone, Debug)]
+pub struct AdditionalTargetModel {
+    pub target_model: TargetModel,
+    pub target_value_interval: Option<Interval<UnitValue>>,
+    pub eel_control_transformation: Option<String>,
+}
+
 pub type SharedMapping = Rc<RefCell<MappingModel>>;
 
 pub fn share_mapping(mapping: MappingModel) -> SharedMapping {
@@ -194,6 +216,14 @@ impl Change<'_> for MappingModel {
                     .change(cmd)
                     .map(|affected| One(P::InTarget(affected)));
             }
+            C::SetAdditionalTargets(v) => {
+                self.additional_targets = v;
+                One(P::AdditionalTargets)
+            }
+            C::SetFeedbackTargetIndex(v) => {
+                self.feedback_target_index = v;
+                One(P::FeedbackTargetIndex)
+            }
         };
         Some(affected)
     }
@@ -223,6 +253,8 @@ impl MappingModel {
             source_model: SourceModel::new(),
             mode_model: Default::default(),
             target_model: TargetModel::default_for_compartment(compartment),
+            additional_targets: vec![],
+            feedback_target_index: 0,
             advanced_settings: None,
             extension_model: Default::default(),
         }
@@ -264,6 +296,14 @@ impl MappingModel {
         self.beep_on_success
     }
 
+    pub fn additional_targets(&self) -> &[AdditionalTargetModel] {
+        &self.additional_targets
+    }
+
+    pub fn feedback_target_index(&self) -> u32 {
+        self.feedback_target_index
+    }
+
     pub fn activation_condition_model(&self) -> &ActivationConditionModel {
         &self.activation_condition_model
     }
@@ -486,8 +526,15 @@ impl MappingModel {
     }
 
     pub fn base_mode_applicability_check_input(&self) -> ModeApplicabilityCheckInput {
+        self.mode_applicability_check_input(&self.mode_model)
+    }
+
+    fn mode_applicability_check_input(
+        &self,
+        mode_model: &ModeModel,
+    ) -> ModeApplicabilityCheckInput {
         let transformation =
-            EelTransformation::compile_for_control(self.mode_model.eel_control_transformation());
+            EelTransformation::compile_for_control(mode_model.eel_control_transformation());
         ModeApplicabilityCheckInput {
             target_is_virtual: self.target_model.is_virtual(),
             // TODO-high-discrete Enable (also taking source into consideration!)
@@ -501,13 +548,13 @@ impl MappingModel {
                 .map(|t| t.produces_relative_values())
                 .unwrap_or(false),
             is_feedback: false,
-            make_absolute: self.mode_model.make_absolute(),
-            use_textual_feedback: self.mode_model.feedback_type().is_textual(),
+            make_absolute: mode_model.make_absolute(),
+            use_textual_feedback: mode_model.feedback_type().is_textual(),
             // Any is okay, will be overwritten.
             source_character: DetailedSourceCharacter::RangeControl,
-            absolute_mode: self.mode_model.absolute_mode(),
-            fire_mode: self.mode_model.fire_mode(),
-            target_value_sequence_is_set: !self.mode_model.target_value_sequence().is_empty(),
+            absolute_mode: mode_model.absolute_mode(),
+            fire_mode: mode_model.fire_mode(),
+            target_value_sequence_is_set: !mode_model.target_value_sequence().is_empty(),
         }
     }
 
@@ -554,6 +601,54 @@ impl MappingModel {
         self.target_model.create_target(self.compartment).ok()
     }
 
+    /// Creates the mode of an additional target by applying its overrides to the main mode.
+    fn create_additional_mode(&self, additional_target: &AdditionalTargetModel) -> Mode {
+        let mut mode_model = self.mode_model.clone();
+        if let Some(interval) = additional_target.target_value_interval {
+            let _ = mode_model.change(ModeCommand::SetTargetValueInterval(interval));
+        }
+        if let Some(transformation) = &additional_target.eel_control_transformation {
+            let _ = mode_model.change(ModeCommand::SetEelControlTransformation(
+                transformation.clone(),
+            ));
+        }
+        let possible_source_characters = self.source_model.possible_detailed_characters();
+        mode_model.create_mode(
+            self.mode_applicability_check_input(&mode_model),
+            &possible_source_characters,
+        )
+    }
+
+    /// Returns the mode and target of each target of this mapping in the user-defined order, the
+    /// main target being the first one.
+    ///
+    /// Additional targets are only supported for mappings with a REAPER target.
+    fn create_all_modes_and_targets(&self) -> Vec<(Mode, Option<UnresolvedCompoundMappingTarget>)> {
+        let main = (self.create_mode(), self.create_target());
+        if self.target_model.category() != TargetCategory::Reaper {
+            if !self.additional_targets.is_empty() {
+                tracing::warn!(
+                    msg = "Ignoring additional targets because main target is not a REAPER target",
+                    mapping_id = %self.id
+                );
+            }
+            return vec![main];
+        }
+        let additional = self.additional_targets.iter().map(|t| {
+            let target = if t.target_model.category() == TargetCategory::Reaper {
+                t.target_model.create_target(self.compartment).ok()
+            } else {
+                tracing::warn!(
+                    msg = "Ignoring additional target because it's not a REAPER target",
+                    mapping_id = %self.id
+                );
+                None
+            };
+            (self.create_additional_mode(t), target)
+        });
+        iter::once(main).chain(additional).collect()
+    }
+
     pub fn create_persistent_mapping_processing_state(&self) -> PersistentMappingProcessingState {
         PersistentMappingProcessingState {
             is_enabled: self.is_enabled(),
@@ -572,8 +667,32 @@ impl MappingModel {
     pub fn create_main_mapping(&self, group_data: GroupData) -> MainMapping {
         let id = self.id;
         let source = self.create_source();
-        let mode = self.create_mode();
-        let unresolved_target = self.create_target();
+        // In multi-target mappings, the feedback lead target becomes the primary target of the
+        // main mapping. That's the one which is used for feedback.
+        let mut modes_and_targets = self.create_all_modes_and_targets();
+        let feedback_target_position = self.feedback_target_index as usize;
+        let primary_target_position = if feedback_target_position < modes_and_targets.len() {
+            feedback_target_position
+        } else {
+            0
+        };
+        let (mode, unresolved_target) = modes_and_targets.remove(primary_target_position);
+        // Additional targets don't override smoothing, so all of them use the same settings.
+        let smoothing = self.mode_model.create_smoothing_settings();
+        let additional_targets = modes_and_targets
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, (mode, target))| {
+                let position = if i < primary_target_position {
+                    i
+                } else {
+                    i + 1
+                };
+                Some(AdditionalMappingTarget::new(
+                    position, mode, smoothing, target?,
+                ))
+            })
+            .collect();
         let activation_condition = self
             .activation_condition_model
             .create_activation_condition();
@@ -597,14 +716,20 @@ impl MappingModel {
             merged_tags,
             source,
             mode,
+            smoothing,
             self.mode_model.group_interaction(),
             unresolved_target,
+            additional_targets,
+            primary_target_position,
             group_data.activation_condition,
             activation_condition,
             options,
             self.extension_model
                 .create_mapping_extension()
-                .unwrap_or_default(),
+                .unwrap_or_else(|e| {
+                    tracing::warn!(msg = "Ignoring invalid lifecycle MIDI", mapping_id = %self.id, e);
+                    Default::default()
+                }),
         )
     }
 }
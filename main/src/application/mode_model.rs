@@ -1,4 +1,7 @@
-use crate::domain::{Backbone, EelTransformation, LuaFeedbackScript, Mode};
+use crate::domain::{
+    Backbone, CurveTransformation, EelTransformation, LuaFeedbackScript, LuaTransformation, Mode,
+    OutputVariable, RealearnTransformation, SmoothingSettings, TransformationScript,
+};
 
 use helgoboss_learn::{
     check_mode_applicability, create_unit_value_interval, full_discrete_interval,
@@ -11,7 +14,10 @@ use helgoboss_learn::{
 use crate::application::{Affected, Change, GetProcessingRelevance, ProcessingRelevance};
 use crate::base::CloneAsDefault;
 use base::hash_util::clone_to_other_hash_map;
-use helgobox_api::persistence::FeedbackValueTable;
+use fragile::Fragile;
+use helgobox_api::persistence::{
+    FeedbackValueTable, ResponseCurve, Smoothing, TransformationScriptKind,
+};
 use std::time::Duration;
 
 pub enum ModeCommand {
@@ -36,6 +42,8 @@ pub enum ModeCommand {
     SetEncoderUsage(EncoderUsage),
     SetEelControlTransformation(String),
     SetEelFeedbackTransformation(String),
+    SetControlTransformationKind(TransformationScriptKind),
+    SetFeedbackTransformationKind(TransformationScriptKind),
     SetStepSizeInterval(Interval<UnitValue>),
     SetStepFactorInterval(Interval<DiscreteIncrement>),
     SetMinStepSize(UnitValue),
@@ -51,6 +59,8 @@ pub enum ModeCommand {
     SetFeedbackColor(Option<VirtualColor>),
     SetFeedbackBackgroundColor(Option<VirtualColor>),
     SetFeedbackValueTable(Option<FeedbackValueTable>),
+    SetSmoothing(Option<Smoothing>),
+    SetResponseCurve(Option<ResponseCurve>),
     /// This doesn't reset the mode type, just all the values.
     ResetWithinType,
 }
@@ -72,6 +82,8 @@ pub enum ModeProp {
     EncoderUsage,
     EelControlTransformation,
     EelFeedbackTransformation,
+    ControlTransformationKind,
+    FeedbackTransformationKind,
     StepSizeInterval,
     StepFactorInterval,
     Rotate,
@@ -83,6 +95,8 @@ pub enum ModeProp {
     FeedbackColor,
     FeedbackBackgroundColor,
     FeedbackValueTable,
+    Smoothing,
+    ResponseCurve,
 }
 
 impl GetProcessingRelevance for ModeProp {
@@ -115,6 +129,8 @@ pub struct ModeModel {
     encoder_usage: EncoderUsage,
     eel_control_transformation: String,
     eel_feedback_transformation: String,
+    control_transformation_kind: TransformationScriptKind,
+    feedback_transformation_kind: TransformationScriptKind,
     // For relative control values.
     /// A step size is the positive, absolute size of an increment. 0.0 represents no increment,
     /// 1.0 represents an increment over the whole value range (not very useful).
@@ -140,6 +156,8 @@ pub struct ModeModel {
     feedback_color: Option<VirtualColor>,
     feedback_background_color: Option<VirtualColor>,
     feedback_value_table: Option<FeedbackValueTable>,
+    smoothing: Option<Smoothing>,
+    response_curve: Option<ResponseCurve>,
 }
 
 impl Default for ModeModel {
@@ -163,6 +181,8 @@ impl Default for ModeModel {
             encoder_usage: Default::default(),
             eel_control_transformation: String::new(),
             eel_feedback_transformation: String::new(),
+            control_transformation_kind: Default::default(),
+            feedback_transformation_kind: Default::default(),
             step_size_interval: Self::default_step_size_interval(),
             step_factor_interval: Self::default_step_factor_interval(),
             rotate: false,
@@ -174,6 +194,8 @@ impl Default for ModeModel {
             feedback_color: Default::default(),
             feedback_background_color: Default::default(),
             feedback_value_table: None,
+            smoothing: None,
+            response_curve: None,
         }
     }
 }
@@ -277,6 +299,14 @@ impl Change<'_> for ModeModel {
                 self.eel_feedback_transformation = v;
                 One(P::EelFeedbackTransformation)
             }
+            C::SetControlTransformationKind(v) => {
+                self.control_transformation_kind = v;
+                One(P::ControlTransformationKind)
+            }
+            C::SetFeedbackTransformationKind(v) => {
+                self.feedback_transformation_kind = v;
+                One(P::FeedbackTransformationKind)
+            }
             C::SetStepSizeInterval(v) => {
                 self.step_size_interval = v;
                 One(P::StepSizeInterval)
@@ -337,6 +367,14 @@ impl Change<'_> for ModeModel {
                 self.feedback_value_table = v;
                 One(P::FeedbackValueTable)
             }
+            C::SetSmoothing(v) => {
+                self.smoothing = v;
+                One(P::Smoothing)
+            }
+            C::SetResponseCurve(v) => {
+                self.response_curve = v;
+                One(P::ResponseCurve)
+            }
             C::ResetWithinType => {
                 *self = Default::default();
                 Multiple
@@ -365,6 +403,46 @@ impl ModeModel {
         self.feedback_value_table.as_ref()
     }
 
+    pub fn smoothing(&self) -> Option<&Smoothing> {
+        self.smoothing.as_ref()
+    }
+
+    /// Creates the smoothing settings for the main processor (`None` if values shouldn't be
+    /// smoothed).
+    pub fn create_smoothing_settings(&self) -> Option<SmoothingSettings> {
+        let settings = match self.smoothing.as_ref()? {
+            Smoothing::TimeConstant(s) => SmoothingSettings::TimeConstant {
+                up: Duration::from_millis(s.up_millis as u64),
+                down: Duration::from_millis(s.down_millis.unwrap_or(s.up_millis) as u64),
+            },
+            Smoothing::MaxRate(s) => SmoothingSettings::MaxRate {
+                up_per_second: s.up_per_second,
+                down_per_second: s.down_per_second.unwrap_or(s.up_per_second),
+            },
+        };
+        Some(settings)
+    }
+
+    pub fn response_curve(&self) -> Option<&ResponseCurve> {
+        self.response_curve.as_ref()
+    }
+
+    /// Returns `None` if there's no valid response curve.
+    fn create_response_curve(&self) -> Option<crate::domain::ResponseCurve> {
+        let curve = self.response_curve.as_ref()?;
+        let breakpoints = curve
+            .breakpoints
+            .iter()
+            .map(|b| crate::domain::CurveBreakpoint {
+                x: b.x,
+                y: b.y,
+                shape: b.shape,
+                curvature: b.curvature.unwrap_or(0.5),
+            })
+            .collect();
+        crate::domain::ResponseCurve::new(breakpoints).ok()
+    }
+
     pub fn absolute_mode(&self) -> AbsoluteMode {
         self.absolute_mode
     }
@@ -425,6 +503,14 @@ impl ModeModel {
         &self.eel_feedback_transformation
     }
 
+    pub fn control_transformation_kind(&self) -> TransformationScriptKind {
+        self.control_transformation_kind
+    }
+
+    pub fn feedback_transformation_kind(&self) -> TransformationScriptKind {
+        self.feedback_transformation_kind
+    }
+
     pub fn step_size_interval(&self) -> Interval<UnitValue> {
         self.step_size_interval
     }
@@ -609,12 +695,33 @@ impl ModeModel {
                 OutOfRangeBehavior::default()
             },
             control_transformation: if is_relevant(ModeParameter::ControlTransformation) {
-                EelTransformation::compile_for_control(&self.eel_control_transformation).ok()
+                RealearnTransformation::new(
+                    self.create_response_curve()
+                        .map(CurveTransformation::for_control),
+                    create_transformation_script(
+                        self.control_transformation_kind,
+                        &self.eel_control_transformation,
+                        OutputVariable::Y,
+                    ),
+                )
             } else {
                 None
             },
             feedback_transformation: if is_relevant(ModeParameter::FeedbackTransformation) {
-                EelTransformation::compile_for_feedback(&self.eel_feedback_transformation).ok()
+                let invert_curve = self
+                    .response_curve
+                    .as_ref()
+                    .is_some_and(|c| c.invert_for_feedback == Some(true));
+                RealearnTransformation::new(
+                    self.create_response_curve()
+                        .filter(|_| invert_curve)
+                        .map(CurveTransformation::for_feedback),
+                    create_transformation_script(
+                        self.feedback_transformation_kind,
+                        &self.eel_feedback_transformation,
+                        OutputVariable::X,
+                    ),
+                )
             } else {
                 None
             },
@@ -665,6 +772,34 @@ impl ModeModel {
     }
 }
 
+/// Returns `None` if the script is empty or doesn't compile.
+fn create_transformation_script(
+    kind: TransformationScriptKind,
+    script: &str,
+    output_var: OutputVariable,
+) -> Option<TransformationScript> {
+    let script = match kind {
+        TransformationScriptKind::Eel => {
+            let transformation = match output_var {
+                OutputVariable::X => EelTransformation::compile_for_feedback(script),
+                OutputVariable::Y => EelTransformation::compile_for_control(script),
+            };
+            TransformationScript::Eel(transformation.ok()?)
+        }
+        TransformationScriptKind::Lua => {
+            let lua = unsafe { Backbone::main_thread_lua() };
+            let transformation = match output_var {
+                OutputVariable::X => LuaTransformation::compile_for_feedback(lua, script),
+                OutputVariable::Y => LuaTransformation::compile_for_control(lua, script),
+            };
+            TransformationScript::Lua(CloneAsDefault::new(Some(Fragile::new(
+                transformation.ok()?,
+            ))))
+        }
+    };
+    Some(script)
+}
+
 fn default_jump_interval() -> Interval<UnitValue> {
     create_unit_value_interval(0.0, 0.03)
 }
@@ -5,7 +5,8 @@ use crate::base::CloneAsDefault;
 use crate::domain::{
     Backbone, CompartmentKind, CompartmentParamIndex, CompoundMappingSource, EelMidiSourceScript,
     ExtendedSourceCharacter, FlexibleMidiSourceScript, KeySource, Keystroke, LuaMidiSourceScript,
-    MidiSource, RealearnParameterSource, ReaperSource, SpeechSource, StreamDeckSource, TimerSource,
+    MidiSource, ModulatorKind, ModulatorRate, ModulatorSettings, ModulatorSource,
+    RealearnParameterSource, ReaperSource, SpeechSource, StreamDeckSource, TimerSource,
     VirtualControlElement, VirtualControlElementId, VirtualSource,
 };
 use camino::{Utf8Path, Utf8PathBuf};
@@ -17,6 +18,7 @@ use helgoboss_learn::{
     DEFAULT_OSC_ARG_VALUE_RANGE,
 };
 use helgoboss_midi::{Channel, U14, U7};
+use helgobox_api::persistence;
 use helgobox_api::persistence::{
     MidiScriptKind, StreamDeckButtonBackground, StreamDeckButtonDesign,
     StreamDeckButtonFadingImageForeground, StreamDeckButtonForeground,
@@ -60,6 +62,7 @@ pub enum SourceCommand {
     SetReaperSourceType(ReaperSourceType),
     SetTimerMillis(u64),
     SetParameterIndex(CompartmentParamIndex),
+    SetModulator(persistence::ModulatorSource),
     SetKeystroke(Option<Keystroke>),
     SetButtonIndex(u32),
     SetButtonBackgroundType(StreamDeckButtonBackgroundType),
@@ -99,6 +102,7 @@ pub enum SourceProp {
     ControlElementId,
     TimerMillis,
     ParameterIndex,
+    Modulator,
     Keystroke,
     ButtonIndex,
     ButtonBackgroundType,
@@ -228,6 +232,10 @@ impl Change<'_> for SourceModel {
                 self.parameter_index = v;
                 One(P::ParameterIndex)
             }
+            C::SetModulator(v) => {
+                self.modulator = v;
+                One(P::Modulator)
+            }
             C::SetKeystroke(v) => {
                 self.keystroke = v;
                 One(P::Keystroke)
@@ -291,6 +299,7 @@ pub struct SourceModel {
     reaper_source_type: ReaperSourceType,
     timer_millis: u64,
     parameter_index: CompartmentParamIndex,
+    modulator: persistence::ModulatorSource,
     // Key
     keystroke: Option<Keystroke>,
     // Stream Deck
@@ -340,6 +349,7 @@ impl SourceModel {
             reaper_source_type: Default::default(),
             timer_millis: Default::default(),
             parameter_index: Default::default(),
+            modulator: Default::default(),
             keystroke: None,
             button_index: 0,
             button_background_type: Default::default(),
@@ -474,6 +484,10 @@ impl SourceModel {
         self.timer_millis
     }
 
+    pub fn modulator(&self) -> &persistence::ModulatorSource {
+        &self.modulator
+    }
+
     pub fn control_element_character(&self) -> VirtualControlElementCharacter {
         self.control_element_character
     }
@@ -595,7 +609,8 @@ impl SourceModel {
                     | RealearnInstanceStart
                     | RealearnCompartmentLoaded
                     | Timer(_)
-                    | Speech(_) => {}
+                    | Speech(_)
+                    | Modulator(_) => {}
                 }
             }
             StreamDeck(s) => {
@@ -771,6 +786,7 @@ impl SourceModel {
                         ReaperSource::RealearnParameter(self.create_realearn_parameter_source())
                     }
                     Speech => ReaperSource::Speech(SpeechSource::new()),
+                    Modulator => ReaperSource::Modulator(self.create_modulator_source()?),
                 };
                 CompoundMappingSource::Reaper(reaper_source)
             }
@@ -833,6 +849,48 @@ impl SourceModel {
         TimerSource::new(Duration::from_millis(self.timer_millis))
     }
 
+    /// Returns `None` if the modulator settings are invalid.
+    fn create_modulator_source(&self) -> Option<ModulatorSource> {
+        let param = |index: Option<u32>| match index {
+            None => Ok(None),
+            Some(i) => CompartmentParamIndex::try_from(i).map(Some),
+        };
+        let rate = |rate: &persistence::ModulatorRate| match rate {
+            persistence::ModulatorRate::Free(r) => ModulatorRate::Free { hertz: r.hertz },
+            persistence::ModulatorRate::TempoSynced(r) => {
+                ModulatorRate::TempoSynced { beats: r.beats }
+            }
+        };
+        let kind = match &self.modulator.modulator {
+            persistence::Modulator::Lfo(m) => ModulatorKind::Lfo {
+                shape: m.shape,
+                rate: rate(&m.rate),
+                phase: m.phase.unwrap_or_default(),
+            },
+            persistence::Modulator::Envelope(m) => ModulatorKind::Envelope {
+                gate_mapping: m.gate_mapping.clone().into(),
+                attack: Duration::from_millis(m.attack_millis as _),
+                decay: Duration::from_millis(m.decay_millis as _),
+                sustain: UnitValue::new_clamped(m.sustain.unwrap_or(1.0)),
+                release: Duration::from_millis(m.release_millis as _),
+            },
+            persistence::Modulator::RandomWalk(m) => ModulatorKind::RandomWalk {
+                rate: rate(&m.rate),
+                step_size: UnitValue::new_clamped(m.step_size.unwrap_or(0.1)),
+            },
+        };
+        let settings = ModulatorSettings {
+            kind,
+            depth: UnitValue::new_clamped(self.modulator.depth.unwrap_or(1.0)),
+            depth_param: param(self.modulator.depth_parameter_index).ok()?,
+            rate_param: param(self.modulator.rate_parameter_index).ok()?,
+        };
+        if !settings.is_valid() {
+            return None;
+        }
+        Some(ModulatorSource::new(settings))
+    }
+
     fn create_realearn_parameter_source(&self) -> RealearnParameterSource {
         RealearnParameterSource {
             parameter_index: self.parameter_index,
@@ -1074,6 +1132,14 @@ impl Display for SourceModel {
                             format!("Parameter #{}", self.parameter_index.get() + 1).into(),
                         ]
                     }
+                    ReaperSourceType::Modulator => {
+                        let kind = match &self.modulator.modulator {
+                            persistence::Modulator::Lfo(_) => "LFO",
+                            persistence::Modulator::Envelope(_) => "Envelope",
+                            persistence::Modulator::RandomWalk(_) => "Random walk",
+                        };
+                        vec![type_label, kind.into()]
+                    }
                     _ => {
                         vec![type_label]
                     }
@@ -1339,6 +1405,9 @@ pub enum ReaperSourceType {
     #[serde(rename = "speech")]
     #[display(fmt = "Speech (feedback only, no Linux)")]
     Speech,
+    #[serde(rename = "modulator")]
+    #[display(fmt = "Modulator")]
+    Modulator,
 }
 
 impl ReaperSourceType {
@@ -1351,6 +1420,7 @@ impl ReaperSourceType {
             Timer(_) => Self::Timer,
             RealearnParameter(_) => Self::RealearnParameter,
             Speech(_) => Self::Speech,
+            Modulator(_) => Self::Modulator,
         }
     }
 
@@ -1361,7 +1431,8 @@ impl ReaperSourceType {
             | RealearnUnitStart
             | RealearnCompartmentLoaded
             | Timer
-            | RealearnParameter => true,
+            | RealearnParameter
+            | Modulator => true,
             Speech => false,
         }
     }
@@ -1373,7 +1444,8 @@ impl ReaperSourceType {
             | RealearnUnitStart
             | RealearnCompartmentLoaded
             | Timer
-            | RealearnParameter => false,
+            | RealearnParameter
+            | Modulator => false,
             Speech => true,
         }
     }
@@ -19,7 +19,7 @@ use crate::application::{
 use crate::domain::{
     find_bookmark, get_fx_name, get_fx_params, get_non_present_virtual_route_label,
     get_non_present_virtual_track_label, get_track_routes, ActionInvocationType, AnyOnParameter,
-    CompartmentKind, CompartmentParamIndex, CompoundMappingTarget, Exclusivity,
+    CompartmentKind, CompartmentParamIndex, CompoundMappingTarget, EnvelopeDescriptor, Exclusivity,
     ExpressionEvaluator, ExtendedProcessorContext, FeedbackResolution, FxDescriptor, FxDisplayType,
     FxParameterDescriptor, GroupId, MappingId, MappingKey, MappingRef, MappingSnapshotId,
     MouseActionType, OscDeviceId, PotFilterItemsTargetSettings, ProcessorContext,
@@ -28,28 +28,33 @@ use crate::domain::{
     TouchedTrackParameterType, TrackDescriptor, TrackExclusivity, TrackGangBehavior,
     TrackRouteDescriptor, TrackRouteSelector, TrackRouteType, TransportAction,
     UnresolvedActionTarget, UnresolvedAllTrackFxEnableTarget, UnresolvedAnyOnTarget,
-    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget,
-    UnresolvedBrowsePotFilterItemsTarget, UnresolvedBrowsePotPresetsTarget,
-    UnresolvedBrowseTracksTarget, UnresolvedCompartmentParameterValueTarget,
-    UnresolvedCompoundMappingTarget, UnresolvedDummyTarget, UnresolvedEnableInstancesTarget,
-    UnresolvedEnableMappingsTarget, UnresolvedFxEnableTarget, UnresolvedFxOnlineTarget,
-    UnresolvedFxOpenTarget, UnresolvedFxParameterTarget, UnresolvedFxParameterTouchStateTarget,
-    UnresolvedFxPresetTarget, UnresolvedFxToolTarget, UnresolvedGoToBookmarkTarget,
-    UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
-    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget, UnresolvedMidiSendTarget,
+    UnresolvedAutomationItemActionTarget, UnresolvedAutomationModeOverrideTarget,
+    UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget, UnresolvedBrowsePotFilterItemsTarget,
+    UnresolvedBrowsePotPresetsTarget, UnresolvedBrowseProjectsTarget, UnresolvedBrowseTracksTarget,
+    UnresolvedCompartmentParameterValueTarget, UnresolvedCompoundMappingTarget,
+    UnresolvedDummyTarget, UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget,
+    UnresolvedEnvelopeActionTarget, UnresolvedEnvelopeValueTarget, UnresolvedFxEnableTarget,
+    UnresolvedFxOnlineTarget, UnresolvedFxOpenTarget, UnresolvedFxParameterTarget,
+    UnresolvedFxParameterTouchStateTarget, UnresolvedFxPresetTarget, UnresolvedFxToolTarget,
+    UnresolvedGoToBookmarkTarget, UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
+    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget,
+    UnresolvedLoadScreensetTarget, UnresolvedMacroTarget, UnresolvedMidiSendTarget,
     UnresolvedModifyMappingTarget, UnresolvedMouseTarget, UnresolvedOscSendTarget,
-    UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget, UnresolvedReaperTarget,
-    UnresolvedRouteAutomationModeTarget, UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget,
-    UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget,
+    UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget, UnresolvedRatePotPresetTarget,
+    UnresolvedReaperTarget, UnresolvedRouteAutomationModeTarget, UnresolvedRouteExistenceTarget,
+    UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget, UnresolvedRoutePanTarget,
+    UnresolvedRoutePhaseTarget, UnresolvedRouteSendModeTarget, UnresolvedRouteTouchStateTarget,
     UnresolvedRouteVolumeTarget, UnresolvedSeekTarget, UnresolvedStreamDeckBrightnessTarget,
-    UnresolvedTakeMappingSnapshotTarget, UnresolvedTempoTarget, UnresolvedTrackArmTarget,
-    UnresolvedTrackAutomationModeTarget, UnresolvedTrackMonitoringModeTarget,
-    UnresolvedTrackMuteTarget, UnresolvedTrackPanTarget, UnresolvedTrackParentSendTarget,
-    UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget, UnresolvedTrackSelectionTarget,
-    UnresolvedTrackShowTarget, UnresolvedTrackSoloTarget, UnresolvedTrackToolTarget,
-    UnresolvedTrackTouchStateTarget, UnresolvedTrackVolumeTarget, UnresolvedTrackWidthTarget,
-    UnresolvedTransportTarget, VirtualChainFx, VirtualControlElement, VirtualControlElementId,
-    VirtualFx, VirtualFxParameter, VirtualMappingSnapshotIdForLoad,
+    UnresolvedTagPotPresetTarget, UnresolvedTakeMappingSnapshotTarget, UnresolvedTempoTarget,
+    UnresolvedTrackArmTarget, UnresolvedTrackAutomationModeTarget, UnresolvedTrackColorTarget,
+    UnresolvedTrackLayoutTarget, UnresolvedTrackMonitoringModeTarget, UnresolvedTrackMuteTarget,
+    UnresolvedTrackNameTarget, UnresolvedTrackPanTarget, UnresolvedTrackParentSendTarget,
+    UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget, UnresolvedTrackRecordInputTarget,
+    UnresolvedTrackRecordModeTarget, UnresolvedTrackSelectionTarget, UnresolvedTrackShowTarget,
+    UnresolvedTrackSoloTarget, UnresolvedTrackToolTarget, UnresolvedTrackTouchStateTarget,
+    UnresolvedTrackVolumeTarget, UnresolvedTrackWidthTarget, UnresolvedTransportTarget,
+    UnresolvedWindowVisibilityTarget, VirtualChainFx, VirtualControlElement,
+    VirtualControlElementId, VirtualFx, VirtualFxParameter, VirtualMappingSnapshotIdForLoad,
     VirtualMappingSnapshotIdForTake, VirtualTarget, VirtualTrack, VirtualTrackRoute,
 };
 
@@ -61,13 +66,14 @@ use std::error::Error;
 use crate::domain::ui_util::format_tags_as_csv;
 use base::hash_util::NonCryptoHashSet;
 use helgobox_api::persistence::{
-    ActionScope, Axis, BrowseTracksMode, ClipColumnTrackContext, FxChainDescriptor,
-    FxDescriptorCommons, FxToolAction, InputDeviceMidiDestination, LearnTargetMappingModification,
-    LearnableTargetKind, MappingModification, MappingSnapshotDescForLoad,
-    MappingSnapshotDescForTake, MonitoringMode, MouseAction, MouseButton, PlaytimeColumnAction,
-    PlaytimeColumnDescriptor, PlaytimeMatrixAction, PlaytimeRowAction, PlaytimeRowDescriptor,
-    PlaytimeSlotDescriptor, PlaytimeSlotManagementAction, PlaytimeSlotTransportAction,
-    PotFilterKind, SeekBehavior, SendMidiDestination, SetTargetToLastTouchedMappingModification,
+    ActionScope, AutomationItemAction, Axis, BrowseTracksMode, ClipColumnTrackContext,
+    EnvelopeAction, FxChainDescriptor, FxDescriptorCommons, FxToolAction,
+    InputDeviceMidiDestination, LearnTargetMappingModification, LearnableTargetKind, MacroStep,
+    MappingModification, MappingSnapshotDescForLoad, MappingSnapshotDescForTake, MonitoringMode,
+    MouseAction, MouseButton, PlaytimeColumnAction, PlaytimeColumnDescriptor, PlaytimeMatrixAction,
+    PlaytimeRowAction, PlaytimeRowDescriptor, PlaytimeSlotDescriptor, PlaytimeSlotManagementAction,
+    PlaytimeSlotTransportAction, PotFilterKind, ReaperWindow, RecordMode, RouteExistenceAction,
+    SeekBehavior, SendMidiDestination, SendMode, SetTargetToLastTouchedMappingModification,
     TargetTouchCause, TrackDescriptorCommons, TrackFxChain, TrackScope, TrackToolAction,
     VirtualControlElementCharacter,
 };
@@ -115,10 +121,16 @@ pub enum TargetCommand {
     SetRouteIndex(u32),
     SetRouteName(String),
     SetRouteExpression(String),
+    SetRouteExistenceAction(RouteExistenceAction),
+    SetSendMode(SendMode),
     SetSeekBehavior(SeekBehavior),
     SetSoloBehavior(SoloBehavior),
     SetTrackExclusivity(TrackExclusivity),
     SetTrackToolAction(TrackToolAction),
+    SetEnvelopeType(EnvelopeType),
+    SetEnvelopeName(String),
+    SetEnvelopeAction(EnvelopeAction),
+    SetAutomationItemAction(AutomationItemAction),
     SetGangBehavior(TrackGangBehavior),
     SetBrowseTracksMode(BrowseTracksMode),
     SetFxToolAction(FxToolAction),
@@ -140,6 +152,10 @@ pub enum TargetCommand {
     SetTrackArea(RealearnTrackArea),
     SetAutomationMode(RealearnAutomationMode),
     SetMonitoringMode(MonitoringMode),
+    SetRecordMode(RecordMode),
+    SetScreensetIndex(u32),
+    SetReaperWindow(ReaperWindow),
+    SetMacroSteps(Vec<MacroStep>),
     SetAutomationModeOverrideType(AutomationModeOverrideType),
     SetFxDisplayType(FxDisplayType),
     SetScrollArrangeView(bool),
@@ -174,6 +190,7 @@ pub enum TargetCommand {
     SetMappingSnapshotId(Option<MappingSnapshotId>),
     SetMappingSnapshotDefaultValue(Option<AbsoluteValue>),
     SetPotFilterItemKind(PotFilterKind),
+    SetPotCollection(Option<String>),
     SetMappingModificationKind(MappingModificationKind),
     SetMappingRef(MappingRefModel),
     SetLearnableTargetKinds(NonCryptoHashSet<LearnableTargetKind>),
@@ -217,10 +234,16 @@ pub enum TargetProp {
     RouteIndex,
     RouteName,
     RouteExpression,
+    RouteExistenceAction,
+    SendMode,
     SoloBehavior,
     SeekBehavior,
     TrackExclusivity,
     TrackToolAction,
+    EnvelopeType,
+    EnvelopeName,
+    EnvelopeAction,
+    AutomationItemAction,
     GangBehavior,
     BrowseTracksMode,
     FxToolAction,
@@ -242,6 +265,10 @@ pub enum TargetProp {
     TrackArea,
     AutomationMode,
     MonitoringMode,
+    RecordMode,
+    ScreensetIndex,
+    ReaperWindow,
+    MacroSteps,
     AutomationModeOverrideType,
     FxDisplayType,
     ScrollArrangeView,
@@ -276,6 +303,7 @@ pub enum TargetProp {
     MappingSnapshotId,
     MappingSnapshotDefaultValue,
     PotFilterItemKind,
+    PotCollection,
     MappingModificationKind,
     MappingRef,
     IncludedTargets,
@@ -438,6 +466,30 @@ impl Change<'_> for TargetModel {
                 self.track_tool_action = v;
                 One(P::TrackToolAction)
             }
+            C::SetRouteExistenceAction(v) => {
+                self.route_existence_action = v;
+                One(P::RouteExistenceAction)
+            }
+            C::SetSendMode(v) => {
+                self.send_mode = v;
+                One(P::SendMode)
+            }
+            C::SetEnvelopeType(v) => {
+                self.envelope_type = v;
+                One(P::EnvelopeType)
+            }
+            C::SetEnvelopeName(v) => {
+                self.envelope_name = v;
+                One(P::EnvelopeName)
+            }
+            C::SetEnvelopeAction(v) => {
+                self.envelope_action = v;
+                One(P::EnvelopeAction)
+            }
+            C::SetAutomationItemAction(v) => {
+                self.automation_item_action = v;
+                One(P::AutomationItemAction)
+            }
             C::SetGangBehavior(v) => {
                 self.gang_behavior = v;
                 One(P::GangBehavior)
@@ -522,6 +574,22 @@ impl Change<'_> for TargetModel {
                 self.monitoring_mode = v;
                 One(P::MonitoringMode)
             }
+            C::SetRecordMode(v) => {
+                self.record_mode = v;
+                One(P::RecordMode)
+            }
+            C::SetScreensetIndex(v) => {
+                self.screenset_index = v;
+                One(P::ScreensetIndex)
+            }
+            C::SetReaperWindow(v) => {
+                self.reaper_window = v;
+                One(P::ReaperWindow)
+            }
+            C::SetMacroSteps(v) => {
+                self.macro_steps = v;
+                One(P::MacroSteps)
+            }
             C::SetAutomationModeOverrideType(v) => {
                 self.automation_mode_override_type = v;
                 One(P::AutomationModeOverrideType)
@@ -658,6 +726,10 @@ impl Change<'_> for TargetModel {
                 self.pot_filter_item_kind = v;
                 One(P::PotFilterItemKind)
             }
+            C::SetPotCollection(v) => {
+                self.pot_collection = v;
+                One(P::PotCollection)
+            }
             C::SetMappingModificationKind(k) => {
                 self.mapping_modification_kind = k;
                 One(P::MappingModificationKind)
@@ -733,6 +805,13 @@ pub struct TargetModel {
     route_name: String,
     route_expression: String,
     touched_route_parameter_type: TouchedRouteParameterType,
+    route_existence_action: RouteExistenceAction,
+    send_mode: SendMode,
+    // # For envelope targets
+    envelope_type: EnvelopeType,
+    envelope_name: String,
+    envelope_action: EnvelopeAction,
+    automation_item_action: AutomationItemAction,
     // # For track solo targets
     solo_behavior: SoloBehavior,
     // # For seek and goto bookmark targets
@@ -766,6 +845,14 @@ pub struct TargetModel {
     automation_mode: RealearnAutomationMode,
     // # For track monitoring mode target
     monitoring_mode: MonitoringMode,
+    // # For track record mode target
+    record_mode: RecordMode,
+    // # For load screenset target
+    screenset_index: u32,
+    // # For window visibility target
+    reaper_window: ReaperWindow,
+    // # For macro target
+    macro_steps: Vec<MacroStep>,
     // # For automation mode override target
     automation_mode_override_type: AutomationModeOverrideType,
     // # For FX Open and Browse FXs target
@@ -811,6 +898,7 @@ pub struct TargetModel {
     mapping_ref: MappingRefModel,
     // # For Pot targets
     pot_filter_item_kind: PotFilterKind,
+    pot_collection: Option<String>,
     // # For targets that deal with target learning/touching
     included_targets: NonCryptoHashSet<LearnableTargetKind>,
     touch_cause: TargetTouchCause,
@@ -920,6 +1008,10 @@ impl Default for TargetModel {
             track_area: Default::default(),
             automation_mode: Default::default(),
             monitoring_mode: Default::default(),
+            record_mode: Default::default(),
+            screenset_index: 0,
+            reaper_window: Default::default(),
+            macro_steps: vec![],
             automation_mode_override_type: Default::default(),
             fx_display_type: Default::default(),
             scroll_arrange_view: false,
@@ -956,9 +1048,16 @@ impl Default for TargetModel {
             playtime_row_action: Default::default(),
             track_tool_action: Default::default(),
             fx_tool_action: Default::default(),
+            route_existence_action: Default::default(),
+            send_mode: Default::default(),
+            envelope_type: Default::default(),
+            envelope_name: DEFAULT_TRACK_ENVELOPE_NAME.to_string(),
+            envelope_action: Default::default(),
+            automation_item_action: Default::default(),
             gang_behavior: Default::default(),
             browse_tracks_mode: Default::default(),
             pot_filter_item_kind: Default::default(),
+            pot_collection: None,
             mapping_modification_kind: Default::default(),
             mapping_ref: Default::default(),
             included_targets: LearnableTargetKind::iter().collect(),
@@ -1100,6 +1199,14 @@ impl TargetModel {
         &self.route_expression
     }
 
+    pub fn route_existence_action(&self) -> RouteExistenceAction {
+        self.route_existence_action
+    }
+
+    pub fn send_mode(&self) -> SendMode {
+        self.send_mode
+    }
+
     pub fn solo_behavior(&self) -> SoloBehavior {
         self.solo_behavior
     }
@@ -1116,6 +1223,22 @@ impl TargetModel {
         self.track_tool_action
     }
 
+    pub fn envelope_type(&self) -> EnvelopeType {
+        self.envelope_type
+    }
+
+    pub fn envelope_name(&self) -> &str {
+        &self.envelope_name
+    }
+
+    pub fn envelope_action(&self) -> EnvelopeAction {
+        self.envelope_action
+    }
+
+    pub fn automation_item_action(&self) -> AutomationItemAction {
+        self.automation_item_action
+    }
+
     pub fn fx_tool_action(&self) -> FxToolAction {
         self.fx_tool_action
     }
@@ -1216,6 +1339,22 @@ impl TargetModel {
         self.monitoring_mode
     }
 
+    pub fn record_mode(&self) -> RecordMode {
+        self.record_mode
+    }
+
+    pub fn screenset_index(&self) -> u32 {
+        self.screenset_index
+    }
+
+    pub fn reaper_window(&self) -> ReaperWindow {
+        self.reaper_window
+    }
+
+    pub fn macro_steps(&self) -> &[MacroStep] {
+        &self.macro_steps
+    }
+
     pub fn automation_mode_override_type(&self) -> AutomationModeOverrideType {
         self.automation_mode_override_type
     }
@@ -1799,6 +1938,24 @@ impl TargetModel {
             RouteAutomationMode(t) => {
                 self.automation_mode = RealearnAutomationMode::from_reaper(t.mode);
             }
+            RouteSendMode(t) => {
+                self.send_mode = t.mode;
+            }
+            TrackRecordMode(t) => {
+                self.record_mode = t.mode;
+            }
+            LoadScreenset(t) => {
+                self.screenset_index = t.index;
+            }
+            WindowVisibility(t) => {
+                self.reaper_window = t.window;
+            }
+            TrackLayout(t) => {
+                self.track_area = match t.area {
+                    TrackArea::Tcp => RealearnTrackArea::Tcp,
+                    TrackArea::Mcp => RealearnTrackArea::Mcp,
+                };
+            }
             AutomationModeOverride(t) => match t.mode_override {
                 None => {
                     self.automation_mode_override_type = AutomationModeOverrideType::None;
@@ -2005,6 +2162,7 @@ impl TargetModel {
             }
             ByName => TrackRouteSelector::ByName(WildMatch::new(&self.route_name)),
             ByIndex => TrackRouteSelector::ByIndex(self.route_index),
+            ByTag => TrackRouteSelector::ByTag(self.route_name.clone()),
         };
         Some(selector)
     }
@@ -2265,6 +2423,19 @@ impl TargetModel {
         Ok(desc)
     }
 
+    fn envelope_descriptor(&self) -> Result<EnvelopeDescriptor, &'static str> {
+        let desc = match self.envelope_type {
+            EnvelopeType::Track => EnvelopeDescriptor::Track {
+                track_descriptor: self.track_descriptor()?,
+                name: self.envelope_name.clone(),
+            },
+            EnvelopeType::FxParameter => {
+                EnvelopeDescriptor::FxParameter(self.fx_parameter_descriptor()?)
+            }
+        };
+        Ok(desc)
+    }
+
     pub fn create_target(
         &self,
         compartment: CompartmentKind,
@@ -2308,6 +2479,23 @@ impl TargetModel {
                             fx_parameter_descriptor: self.fx_parameter_descriptor()?,
                         },
                     ),
+                    EnvelopeValue => {
+                        UnresolvedReaperTarget::EnvelopeValue(UnresolvedEnvelopeValueTarget {
+                            envelope_descriptor: self.envelope_descriptor()?,
+                        })
+                    }
+                    EnvelopeAction => {
+                        UnresolvedReaperTarget::EnvelopeAction(UnresolvedEnvelopeActionTarget {
+                            envelope_descriptor: self.envelope_descriptor()?,
+                            action: self.envelope_action,
+                        })
+                    }
+                    AutomationItemAction => UnresolvedReaperTarget::AutomationItemAction(
+                        UnresolvedAutomationItemActionTarget {
+                            envelope_descriptor: self.envelope_descriptor()?,
+                            action: self.automation_item_action,
+                        },
+                    ),
                     TrackVolume => {
                         UnresolvedReaperTarget::TrackVolume(UnresolvedTrackVolumeTarget {
                             track_descriptor: self.track_descriptor()?,
@@ -2373,6 +2561,21 @@ impl TargetModel {
                             RealearnTrackArea::Mcp => TrackArea::Mcp,
                         },
                     }),
+                    TrackName => UnresolvedReaperTarget::TrackName(UnresolvedTrackNameTarget {
+                        track_descriptor: self.track_descriptor()?,
+                    }),
+                    TrackColor => UnresolvedReaperTarget::TrackColor(UnresolvedTrackColorTarget {
+                        track_descriptor: self.track_descriptor()?,
+                    }),
+                    TrackLayout => {
+                        UnresolvedReaperTarget::TrackLayout(UnresolvedTrackLayoutTarget {
+                            track_descriptor: self.track_descriptor()?,
+                            area: match self.track_area {
+                                RealearnTrackArea::Tcp => TrackArea::Tcp,
+                                RealearnTrackArea::Mcp => TrackArea::Mcp,
+                            },
+                        })
+                    }
                     TrackAutomationMode => UnresolvedReaperTarget::TrackAutomationMode(
                         UnresolvedTrackAutomationModeTarget {
                             track_descriptor: self.track_descriptor()?,
@@ -2388,6 +2591,18 @@ impl TargetModel {
                             gang_behavior: self.fixed_gang_behavior(),
                         },
                     ),
+                    TrackRecordInput => {
+                        UnresolvedReaperTarget::TrackRecordInput(UnresolvedTrackRecordInputTarget {
+                            track_descriptor: self.track_descriptor()?,
+                        })
+                    }
+                    TrackRecordMode => {
+                        UnresolvedReaperTarget::TrackRecordMode(UnresolvedTrackRecordModeTarget {
+                            track_descriptor: self.track_descriptor()?,
+                            exclusivity: self.track_exclusivity,
+                            mode: self.record_mode,
+                        })
+                    }
                     TrackSolo => UnresolvedReaperTarget::TrackSolo(UnresolvedTrackSoloTarget {
                         track_descriptor: self.track_descriptor()?,
                         behavior: self.solo_behavior,
@@ -2422,6 +2637,19 @@ impl TargetModel {
                             parameter_type: self.touched_route_parameter_type,
                         })
                     }
+                    RouteExistence => {
+                        UnresolvedReaperTarget::RouteExistence(UnresolvedRouteExistenceTarget {
+                            descriptor: self.route_descriptor()?,
+                            action: self.route_existence_action,
+                        })
+                    }
+                    RouteSendMode => {
+                        UnresolvedReaperTarget::RouteSendMode(UnresolvedRouteSendModeTarget {
+                            descriptor: self.route_descriptor()?,
+                            mode: self.send_mode,
+                            poll_for_feedback: self.poll_for_feedback,
+                        })
+                    }
                     Tempo => UnresolvedReaperTarget::Tempo(UnresolvedTempoTarget),
                     PlayRate => UnresolvedReaperTarget::Playrate(UnresolvedPlayrateTarget),
                     AutomationModeOverride => UnresolvedReaperTarget::AutomationModeOverride(
@@ -2457,6 +2685,19 @@ impl TargetModel {
                     FxPreset => UnresolvedReaperTarget::FxPreset(UnresolvedFxPresetTarget {
                         fx_descriptor: self.fx_descriptor()?,
                     }),
+                    BrowseProjects => {
+                        UnresolvedReaperTarget::BrowseProjects(UnresolvedBrowseProjectsTarget)
+                    }
+                    LoadScreenset => {
+                        UnresolvedReaperTarget::LoadScreenset(UnresolvedLoadScreensetTarget {
+                            index: self.screenset_index,
+                        })
+                    }
+                    WindowVisibility => {
+                        UnresolvedReaperTarget::WindowVisibility(UnresolvedWindowVisibilityTarget {
+                            window: self.reaper_window,
+                        })
+                    }
                     BrowseTracks => {
                         UnresolvedReaperTarget::SelectedTrack(UnresolvedBrowseTracksTarget {
                             scroll_arrange_view: self.scroll_arrange_view,
@@ -2649,6 +2890,18 @@ impl TargetModel {
                             mapping_ref: self.mapping_ref.create_mapping_ref()?,
                         })
                     }
+                    Macro => {
+                        let steps = self
+                            .macro_steps
+                            .iter()
+                            .cloned()
+                            .map(TryInto::try_into)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        UnresolvedReaperTarget::Macro(UnresolvedMacroTarget::new(
+                            compartment,
+                            steps,
+                        ))
+                    }
                     EnableInstances => {
                         UnresolvedReaperTarget::EnableInstances(UnresolvedEnableInstancesTarget {
                             scope: TagScope {
@@ -2675,9 +2928,11 @@ impl TargetModel {
                             },
                         },
                     ),
-                    BrowsePotPresets => UnresolvedReaperTarget::BrowsePotPresets(
-                        UnresolvedBrowsePotPresetsTarget {},
-                    ),
+                    BrowsePotPresets => {
+                        UnresolvedReaperTarget::BrowsePotPresets(UnresolvedBrowsePotPresetsTarget {
+                            collection: self.pot_collection.clone(),
+                        })
+                    }
                     PreviewPotPreset => UnresolvedReaperTarget::PreviewPotPreset(
                         UnresolvedPreviewPotPresetTarget {},
                     ),
@@ -2686,6 +2941,14 @@ impl TargetModel {
                             fx_descriptor: self.fx_descriptor()?,
                         })
                     }
+                    RatePotPreset => {
+                        UnresolvedReaperTarget::RatePotPreset(UnresolvedRatePotPresetTarget {})
+                    }
+                    TagPotPreset => {
+                        UnresolvedReaperTarget::TagPotPreset(UnresolvedTagPotPresetTarget {
+                            tags: self.tags.clone(),
+                        })
+                    }
                     StreamDeckBrightness => UnresolvedReaperTarget::StreamDeckBrightness(
                         UnresolvedStreamDeckBrightnessTarget {},
                     ),
@@ -2830,6 +3093,10 @@ impl TargetModel {
         self.pot_filter_item_kind
     }
 
+    pub fn pot_collection(&self) -> Option<&str> {
+        self.pot_collection.as_deref()
+    }
+
     pub fn included_targets(&self) -> &NonCryptoHashSet<LearnableTargetKind> {
         &self.included_targets
     }
@@ -3353,11 +3620,42 @@ impl Display for TargetModelFormatMultiLine<'_> {
                         self.fx_label(),
                         self.fx_param_label()
                     ),
+                    EnvelopeValue | EnvelopeAction | AutomationItemAction => {
+                        write!(f, "{}\nTrack {}\n", tt, self.track_label())?;
+                        match self.target.envelope_type {
+                            EnvelopeType::Track => {
+                                write!(f, "Envelope \"{}\"", self.target.envelope_name)?
+                            }
+                            EnvelopeType::FxParameter => write!(
+                                f,
+                                "FX {}\nParam {}",
+                                self.fx_label(),
+                                self.fx_param_label()
+                            )?,
+                        }
+                        match tt {
+                            EnvelopeAction => write!(f, "\n{}", self.target.envelope_action),
+                            AutomationItemAction => {
+                                write!(f, "\n{}", self.target.automation_item_action)
+                            }
+                            _ => Ok(()),
+                        }
+                    }
                     TrackTool | TrackVolume | TrackPeak | TrackPan | TrackWidth | TrackArm
                     | TrackSelection | TrackMute | TrackPhase | TrackSolo | TrackShow
-                    | BrowseFxs | AllTrackFxEnable | TrackParentSend => {
+                    | TrackName | TrackColor | TrackRecordInput | BrowseFxs | AllTrackFxEnable
+                    | TrackParentSend => {
                         write!(f, "{}\nTrack {}", tt, self.track_label())
                     }
+                    TrackLayout => {
+                        write!(
+                            f,
+                            "{}\nTrack {}\n{}",
+                            tt,
+                            self.track_label(),
+                            self.target.track_area
+                        )
+                    }
                     TrackAutomationMode => {
                         write!(
                             f,
@@ -3367,6 +3665,15 @@ impl Display for TargetModelFormatMultiLine<'_> {
                             self.target.automation_mode
                         )
                     }
+                    TrackRecordMode => {
+                        write!(
+                            f,
+                            "{}\nTrack {}\n{}",
+                            tt,
+                            self.track_label(),
+                            self.target.record_mode
+                        )
+                    }
                     RouteVolume | RoutePan | RouteMute | RoutePhase | RouteMono
                     | RouteAutomationMode => write!(
                         f,
@@ -3376,6 +3683,24 @@ impl Display for TargetModelFormatMultiLine<'_> {
                         self.target.route_type,
                         self.route_label()
                     ),
+                    RouteExistence => write!(
+                        f,
+                        "{}\nTrack {}\n{} {}\n{}",
+                        tt,
+                        self.track_label(),
+                        self.target.route_type,
+                        self.route_label(),
+                        self.target.route_existence_action
+                    ),
+                    RouteSendMode => write!(
+                        f,
+                        "{}\nTrack {}\n{} {}\n{}",
+                        tt,
+                        self.track_label(),
+                        self.target.route_type,
+                        self.route_label(),
+                        self.target.send_mode
+                    ),
                     FxOpen | FxEnable | FxPreset | FxTool => write!(
                         f,
                         "{}\nTrack {}\nFX {}",
@@ -3388,6 +3713,16 @@ impl Display for TargetModelFormatMultiLine<'_> {
                     AutomationModeOverride => {
                         write!(f, "{}\n{}", tt, self.target.automation_mode_override_type)
                     }
+                    LoadScreenset => {
+                        write!(
+                            f,
+                            "{}\nWindow set #{:02}",
+                            tt,
+                            self.target.screenset_index + 1
+                        )
+                    }
+                    WindowVisibility => write!(f, "{}\n{}", tt, self.target.reaper_window),
+                    Macro => write!(f, "{}\n{} steps", tt, self.target.macro_steps.len()),
                     LoadFxSnapshot => write!(
                         f,
                         "{}\n{}",
@@ -4106,6 +4441,34 @@ impl VirtualFxType {
     }
 }
 
+/// Name of the track envelope which is used by default in envelope targets.
+pub const DEFAULT_TRACK_ENVELOPE_NAME: &str = "Volume";
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[repr(usize)]
+pub enum EnvelopeType {
+    #[default]
+    #[display(fmt = "Track envelope")]
+    #[serde(rename = "track")]
+    Track,
+    #[display(fmt = "FX parameter envelope")]
+    #[serde(rename = "fx-parameter")]
+    FxParameter,
+}
+
 #[derive(
     Clone,
     Copy,
@@ -4185,6 +4548,9 @@ pub enum TrackRouteSelectorType {
     #[display(fmt = "At position")]
     #[serde(rename = "index")]
     ByIndex,
+    #[display(fmt = "Tagged")]
+    #[serde(rename = "tag")]
+    ByTag,
 }
 
 impl Default for TrackRouteSelectorType {
@@ -4201,6 +4567,7 @@ impl TrackRouteSelectorType {
             ById(_) => Self::ById,
             ByName(_) => Self::ByName,
             ByIndex(_) => Self::ByIndex,
+            ByTag(_) => Self::ByTag,
         }
     }
 
@@ -8,18 +8,19 @@ use crate::application::{
 };
 use crate::base::{notification, prop, when, AsyncNotifier, Prop};
 use crate::domain::{
-    convert_plugin_param_index_range_to_iter, create_lua_feedback_script_runtime,
+    analyze_mappings, convert_plugin_param_index_range_to_iter, create_lua_feedback_script_runtime,
     create_lua_midi_script_source_runtime, lua_module_path_without_ext, Backbone, BasicSettings,
     CompartmentKind, CompartmentParamIndex, CompartmentSettings, CompoundMappingSource,
     ControlContext, ControlInput, DomainEvent, DomainEventHandler, ExtendedProcessorContext,
     FeedbackAudioHookTask, FeedbackOutput, FeedbackRealTimeTask, FinalSourceFeedbackValue, GroupId,
     GroupKey, IncomingCompoundSourceValue, InputDescriptor, InstanceId, InternalInfoEvent,
-    LastTouchedTargetFilter, MainMapping, MappingId, MappingKey, MappingMatchedEvent,
-    MessageCaptureEvent, MidiControlInput, NormalMainTask, OscFeedbackTask, ParamSetting,
-    PluginParams, ProcessorContext, ProjectionFeedbackValue, QualifiedMappingId,
-    RealearnControlSurfaceMainTask, RealearnTarget, ReaperTarget, ReaperTargetType, SharedInstance,
-    SharedUnit, SourceFeedbackEvent, StayActiveWhenProjectInBackground, StreamDeckDeviceId, Tag,
-    TargetControlEvent, TargetTouchEvent, TargetValueChangedEvent, Unit, UnitContainer, UnitId,
+    LastTouchedTargetFilter, MainMapping, MappingAnalysisInput, MappingId, MappingIssue,
+    MappingKey, MappingMatchedEvent, MessageCaptureEvent, MidiControlInput, NormalMainTask,
+    OscFeedbackTask, ParamSetting, PluginParams, ProcessorContext, ProjectionFeedbackValue,
+    QualifiedMappingId, RealearnControlSurfaceMainTask, RealearnTarget, ReaperTarget,
+    ReaperTargetType, SharedInstance, SharedUnit, SourceFeedbackEvent,
+    StayActiveWhenProjectInBackground, StreamDeckDeviceId, Tag, TargetControlEvent,
+    TargetTouchEvent, TargetValueChangedEvent, Unit, UnitContainer, UnitId,
     VirtualControlElementId, VirtualFx, VirtualSource, VirtualSourceValue,
     LUA_FEEDBACK_SCRIPT_RUNTIME_NAME, LUA_MIDI_SCRIPT_SOURCE_RUNTIME_NAME,
 };
@@ -2453,6 +2454,21 @@ impl UnitModel {
         Ok(())
     }
 
+    /// Statically analyzes the mappings of the given compartment for conflicts and broken
+    /// references.
+    pub fn analyze_mappings(&self, compartment: CompartmentKind) -> Vec<MappingIssue> {
+        let group_ids: NonCryptoHashSet<_> = self
+            .groups_including_default_group(compartment)
+            .map(|g| g.borrow().id())
+            .collect();
+        let inputs: Vec<_> = self
+            .create_main_mappings(compartment)
+            .iter()
+            .map(|m| MappingAnalysisInput::from_mapping(m, Default::default()))
+            .collect();
+        analyze_mappings(&inputs, &group_ids)
+    }
+
     pub fn mapping_is_on(&self, id: QualifiedMappingId) -> bool {
         self.unit.borrow().mapping_is_on(id)
     }
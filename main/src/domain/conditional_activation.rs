@@ -1,12 +1,21 @@
 use crate::base::eel;
 use crate::domain::{
-    CompartmentParamIndex, CompartmentParams, EffectiveParamValue, ExpressionEvaluator, MappingId,
-    RawParamValue, COMPARTMENT_PARAMETER_COUNT, EXPRESSION_NONE_VALUE,
+    with_fx_name, AdditionalFeedbackEvent, Backbone, CompartmentParamIndex, CompartmentParams,
+    EffectiveParamValue, ExpressionEvaluator, MappingId, ProcessorContext, RawParamValue,
+    COMPARTMENT_PARAMETER_COUNT, EXPRESSION_NONE_VALUE,
 };
-use base::hash_util::NonCryptoHashSet;
+use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
 use base::regex;
+use derive_more::Display;
 use helgoboss_learn::AbsoluteValue;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use reaper_high::{ChangeEvent, Reaper};
+use reaper_medium::{MasterTrackBehavior, PlayState};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::error::Error;
+use strum::EnumIter;
+use wildmatch::WildMatch;
 
 #[derive(Debug)]
 pub enum ActivationCondition {
@@ -23,6 +32,94 @@ pub enum ActivationCondition {
         lead_mapping: Option<MappingId>,
         condition: Box<ExpressionEvaluator>,
     },
+    ReaperState(Box<ReaperStateCondition>),
+    /// Fulfilled if all child conditions are fulfilled.
+    All(Vec<ChildActivationCondition>),
+    /// Fulfilled if at least one child condition is fulfilled.
+    Any(Vec<ChildActivationCondition>),
+    /// Fulfilled if the child condition is not fulfilled.
+    Not(Box<ChildActivationCondition>),
+}
+
+/// Activation condition nested in a composite one.
+///
+/// Remembers its last result, so that an update which only affects one child doesn't require
+/// re-evaluating the other children (some of which can't even be evaluated on demand, e.g.
+/// target-value based ones). The result is initialized via [`ActivationCondition::evaluate`].
+#[derive(Debug)]
+pub struct ChildActivationCondition {
+    condition: ActivationCondition,
+    is_fulfilled: Cell<bool>,
+}
+
+impl ChildActivationCondition {
+    pub fn new(condition: ActivationCondition) -> Self {
+        Self {
+            condition,
+            is_fulfilled: Cell::new(false),
+        }
+    }
+
+    fn is_fulfilled(&self) -> bool {
+        self.is_fulfilled.get()
+    }
+
+    /// Stores the given result if available and returns whether there was one.
+    fn update(&self, result: Option<bool>) -> bool {
+        match result {
+            None => false,
+            Some(is_fulfilled) => {
+                self.is_fulfilled.set(is_fulfilled);
+                true
+            }
+        }
+    }
+}
+
+/// Parameter states which are necessarily given whenever an activation condition is fulfilled.
+///
+/// Only used for static analysis. Conditions which can't be analyzed statically (e.g. EEL or
+/// target-value based ones) are assumed to be fulfillable and don't contribute any requirements.
+#[derive(Clone, Debug, Default)]
+pub struct RequiredParamStates {
+    modifiers: NonCryptoHashMap<CompartmentParamIndex, bool>,
+    programs: NonCryptoHashMap<CompartmentParamIndex, u32>,
+    is_contradictory: bool,
+}
+
+impl RequiredParamStates {
+    /// Returns `true` if the condition can never be fulfilled.
+    pub fn is_contradictory(&self) -> bool {
+        self.is_contradictory
+    }
+
+    /// Returns `true` if both conditions can be fulfilled at the same time.
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        let mut merged = self.clone();
+        merged.merge(other);
+        !merged.is_contradictory
+    }
+
+    /// Adds the requirements of the given states (logical AND).
+    pub fn merge(&mut self, other: &Self) {
+        self.is_contradictory |= other.is_contradictory;
+        for (index, is_on) in &other.modifiers {
+            self.require_modifier(*index, *is_on);
+        }
+        for (index, program_index) in &other.programs {
+            self.require_program(*index, *program_index);
+        }
+    }
+
+    fn require_modifier(&mut self, index: CompartmentParamIndex, is_on: bool) {
+        let required_is_on = *self.modifiers.entry(index).or_insert(is_on);
+        self.is_contradictory |= required_is_on != is_on;
+    }
+
+    fn require_program(&mut self, index: CompartmentParamIndex, program_index: u32) {
+        let required_program_index = *self.programs.entry(index).or_insert(program_index);
+        self.is_contradictory |= required_program_index != program_index;
+    }
 }
 
 impl ActivationCondition {
@@ -31,15 +128,111 @@ impl ActivationCondition {
         !matches!(self, ActivationCondition::Always)
     }
 
-    /// Returns the referenced lead mapping of this activation condition if it's a target-value
-    /// based one.
-    pub fn target_value_lead_mapping(&self) -> Option<MappingId> {
+    /// Returns the referenced lead mappings of all target-value based conditions within this
+    /// activation condition.
+    pub fn target_value_lead_mappings(&self) -> Vec<MappingId> {
+        let mut mappings = vec![];
+        self.collect_target_value_lead_mappings(&mut mappings);
+        mappings
+    }
+
+    fn collect_target_value_lead_mappings(&self, mappings: &mut Vec<MappingId>) {
+        use ActivationCondition::*;
         match self {
-            ActivationCondition::TargetValue {
+            TargetValue {
                 lead_mapping: Some(m),
                 ..
-            } => Some(*m),
-            _ => None,
+            } => {
+                if !mappings.contains(m) {
+                    mappings.push(*m);
+                }
+            }
+            All(children) | Any(children) => {
+                for child in children {
+                    child.condition.collect_target_value_lead_mappings(mappings);
+                }
+            }
+            Not(child) => child.condition.collect_target_value_lead_mappings(mappings),
+            _ => {}
+        }
+    }
+
+    /// Determines the parameter states which must be given for this condition to be fulfilled.
+    pub fn required_param_states(&self) -> RequiredParamStates {
+        self.required_param_states_internal(false)
+    }
+
+    /// If `negated` is `true`, determines the parameter states which must be given for this
+    /// condition to be *not* fulfilled (pushing the negation down to the leaves).
+    fn required_param_states_internal(&self, negated: bool) -> RequiredParamStates {
+        use ActivationCondition::*;
+        let mut states = RequiredParamStates::default();
+        match self {
+            Always => {
+                states.is_contradictory = negated;
+            }
+            Modifiers(conditions) => {
+                if negated {
+                    // At least one modifier must be in the opposite state
+                    match conditions.as_slice() {
+                        [] => states.is_contradictory = true,
+                        [c] => states.require_modifier(c.param_index, !c.is_on),
+                        _ => {}
+                    }
+                } else {
+                    for c in conditions {
+                        states.require_modifier(c.param_index, c.is_on);
+                    }
+                }
+            }
+            Program {
+                param_index,
+                program_index,
+            } => {
+                if !negated {
+                    states.require_program(*param_index, *program_index);
+                }
+            }
+            All(children) | Any(children) => {
+                // Negating a conjunction results in a disjunction and vice versa
+                let is_conjunction = matches!(self, All(_)) != negated;
+                let child_states = children
+                    .iter()
+                    .map(|c| c.condition.required_param_states_internal(negated));
+                if is_conjunction {
+                    for child_states in child_states {
+                        states.merge(&child_states);
+                    }
+                } else {
+                    // A disjunction doesn't imply any particular state, but it can be
+                    // contradictory
+                    states.is_contradictory = child_states.into_iter().all(|s| s.is_contradictory);
+                }
+            }
+            Not(child) => {
+                return child.condition.required_param_states_internal(!negated);
+            }
+            _ => {}
+        }
+        states
+    }
+
+    /// Evaluates this condition from scratch, including all children of composite conditions.
+    ///
+    /// Returns `None` if the condition can't be evaluated on demand (target-value based
+    /// conditions, which are evaluated as soon as the lead mapping reports its target value).
+    pub fn evaluate(&self, params: &CompartmentParams, context: &ProcessorContext) -> Option<bool> {
+        use ActivationCondition::*;
+        match self {
+            TargetValue { .. } => None,
+            ReaperState(condition) => Some(condition.is_fulfilled(context)),
+            All(_) | Any(_) | Not(_) => {
+                self.for_each_child(|child| {
+                    child.update(child.condition.evaluate(params, context));
+                });
+                Some(self.combine_children())
+            }
+            _ => self.is_fulfilled(params),
         }
     }
 
@@ -62,7 +255,13 @@ impl ActivationCondition {
                 condition.is_fulfilled()
             }
             Expression(condition) => condition.is_fulfilled(params),
-            TargetValue { .. } => return None,
+            TargetValue { .. } | ReaperState(_) => return None,
+            All(_) | Any(_) | Not(_) => {
+                self.for_each_child(|child| {
+                    child.update(child.condition.is_fulfilled(params));
+                });
+                self.combine_children()
+            }
         };
         Some(res)
     }
@@ -76,8 +275,14 @@ impl ActivationCondition {
         lead_mapping_id: MappingId,
         target_value: Option<AbsoluteValue>,
     ) -> Option<bool> {
+        use ActivationCondition::*;
         match self {
-            ActivationCondition::TargetValue {
+            All(_) | Any(_) | Not(_) => self.process_child_updates(|child| {
+                child
+                    .condition
+                    .process_target_value_update(lead_mapping_id, target_value)
+            }),
+            TargetValue {
                 lead_mapping: Some(rm),
                 condition,
             } if lead_mapping_id == *rm => {
@@ -152,9 +357,204 @@ impl ActivationCondition {
             // This conditional activation doesn't depend on parameter values, it's evaluated
             // in other ways.
             TargetValue { .. } => return None,
+            // Evaluated whenever the relevant REAPER state changes.
+            ReaperState(_) => return None,
+            All(_) | Any(_) | Not(_) => {
+                return self.process_child_updates(|child| {
+                    child
+                        .condition
+                        .process_param_update(params, index, previous_value)
+                });
+            }
         };
         Some(is_fulfilled)
     }
+
+    /// Returns `Some` if the given REAPER state change affects the mapping's activation state and
+    /// if the resulting state is on or off.
+    pub fn process_reaper_state_change(
+        &self,
+        change: ReaperStateChange,
+        context: &ProcessorContext,
+    ) -> Option<bool> {
+        use ActivationCondition::*;
+        match self {
+            ReaperState(condition) if condition.is_affected_by(change) => {
+                Some(condition.is_fulfilled(context))
+            }
+            All(_) | Any(_) | Not(_) => self.process_child_updates(|child| {
+                child.condition.process_reaper_state_change(change, context)
+            }),
+            _ => None,
+        }
+    }
+
+    /// Lets each child of a composite condition process an update and returns the combined
+    /// result if at least one child was affected.
+    ///
+    /// All children are visited (not just until the first affected one) because EEL conditions
+    /// need to see every parameter change.
+    fn process_child_updates(
+        &self,
+        process: impl Fn(&ChildActivationCondition) -> Option<bool>,
+    ) -> Option<bool> {
+        let mut is_affected = false;
+        self.for_each_child(|child| {
+            if child.update(process(child)) {
+                is_affected = true;
+            }
+        });
+        if is_affected {
+            Some(self.combine_children())
+        } else {
+            None
+        }
+    }
+
+    fn for_each_child(&self, mut f: impl FnMut(&ChildActivationCondition)) {
+        use ActivationCondition::*;
+        match self {
+            All(children) | Any(children) => children.iter().for_each(f),
+            Not(child) => f(child),
+            _ => {}
+        }
+    }
+
+    /// Combines the last results of the children of a composite condition.
+    fn combine_children(&self) -> bool {
+        use ActivationCondition::*;
+        match self {
+            All(children) => children.iter().all(|c| c.is_fulfilled()),
+            Any(children) => children.iter().any(|c| c.is_fulfilled()),
+            Not(child) => !child.is_fulfilled(),
+            _ => true,
+        }
+    }
+}
+
+/// Activation condition which depends on the current state of REAPER.
+///
+/// It's not polled but re-evaluated whenever a relevant [`ReaperStateChange`] occurs.
+#[derive(Debug)]
+pub enum ReaperStateCondition {
+    PlayState(PlayStateCondition),
+    /// Fulfilled if at least one selected track has a name matching the given pattern.
+    TrackSelected(WildMatch),
+    /// Fulfilled if the last focused FX has a name matching the given pattern.
+    FxFocused(WildMatch),
+    MidiEditorOpen,
+}
+
+impl ReaperStateCondition {
+    pub fn is_affected_by(&self, change: ReaperStateChange) -> bool {
+        use ReaperStateChange as C;
+        use ReaperStateCondition::*;
+        match change {
+            C::Everything => true,
+            C::PlayState => matches!(self, PlayState(_)),
+            C::TrackSelection => matches!(self, TrackSelected(_)),
+            C::FxFocus => matches!(self, FxFocused(_)),
+            C::MidiEditor => matches!(self, MidiEditorOpen),
+        }
+    }
+
+    pub fn is_fulfilled(&self, context: &ProcessorContext) -> bool {
+        use ReaperStateCondition::*;
+        match self {
+            PlayState(s) => s.matches(context.project_or_current_project().play_state()),
+            TrackSelected(name) => context
+                .project_or_current_project()
+                .selected_tracks(MasterTrackBehavior::ExcludeMasterTrack)
+                .any(|t| t.name().is_some_and(|n| name.matches(n.to_str()))),
+            FxFocused(name) => Backbone::get()
+                .last_relevant_available_focused_fx(context.containing_fx())
+                .is_some_and(|fx| with_fx_name(&fx, |n| name.matches(&n))),
+            MidiEditorOpen => Reaper::get()
+                .medium_reaper()
+                .midi_editor_get_active()
+                .is_some(),
+        }
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    TryFromPrimitive,
+    IntoPrimitive,
+    Display,
+)]
+#[repr(usize)]
+pub enum PlayStateCondition {
+    #[default]
+    #[serde(rename = "stopped")]
+    #[display(fmt = "Stopped")]
+    Stopped,
+    /// Also fulfilled while recording.
+    #[serde(rename = "playing")]
+    #[display(fmt = "Playing")]
+    Playing,
+    #[serde(rename = "paused")]
+    #[display(fmt = "Paused")]
+    Paused,
+    #[serde(rename = "recording")]
+    #[display(fmt = "Recording")]
+    Recording,
+}
+
+impl PlayStateCondition {
+    pub fn matches(&self, play_state: PlayState) -> bool {
+        use PlayStateCondition::*;
+        match self {
+            Stopped => !play_state.is_playing && !play_state.is_paused,
+            Playing => play_state.is_playing,
+            Paused => play_state.is_paused,
+            Recording => play_state.is_recording,
+        }
+    }
+}
+
+/// A change in REAPER which might affect the outcome of [`ReaperStateCondition`]s.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ReaperStateChange {
+    /// Used when switching projects.
+    Everything,
+    PlayState,
+    TrackSelection,
+    FxFocus,
+    MidiEditor,
+}
+
+impl ReaperStateChange {
+    pub fn from_change_event(evt: &ChangeEvent) -> Option<Self> {
+        use ChangeEvent::*;
+        let change = match evt {
+            PlayStateChanged(_) => Self::PlayState,
+            // Track renames are relevant because tracks are matched by name
+            TrackSelectedChanged(_) | TrackNameChanged(_) | TrackRemoved(_) => Self::TrackSelection,
+            FxFocused(_) | FxRemoved(_) => Self::FxFocus,
+            ProjectSwitched(_) => Self::Everything,
+            _ => return None,
+        };
+        Some(change)
+    }
+
+    pub fn from_additional_feedback_event(evt: &AdditionalFeedbackEvent) -> Option<Self> {
+        use AdditionalFeedbackEvent::*;
+        let change = match evt {
+            FocusSwitchedBetweenMainAndFx => Self::FxFocus,
+            MidiEditorOpenedOrClosed => Self::MidiEditor,
+            _ => return None,
+        };
+        Some(change)
+    }
 }
 
 fn modifier_conditions_are_fulfilled(
@@ -318,3 +718,132 @@ fn extract_used_param_indexes(eel_script: &str) -> NonCryptoHashSet<u32> {
         .map(|i: u32| i - 1)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::UnitValue;
+
+    fn index(i: u32) -> CompartmentParamIndex {
+        CompartmentParamIndex::try_from(i).unwrap()
+    }
+
+    fn modifier(i: u32, is_on: bool) -> ActivationCondition {
+        ActivationCondition::Modifiers(vec![ModifierCondition::new(index(i), is_on)])
+    }
+
+    fn child(condition: ActivationCondition) -> ChildActivationCondition {
+        ChildActivationCondition::new(condition)
+    }
+
+    fn set_param(params: &mut CompartmentParams, i: u32, value: RawParamValue) -> RawParamValue {
+        let param = params.at_mut(index(i));
+        let previous_value = param.raw_value();
+        param.set_raw_value(value);
+        previous_value
+    }
+
+    #[test]
+    fn incremental_evaluation_all() {
+        // Given
+        let mut params = CompartmentParams::default();
+        let condition =
+            ActivationCondition::All(vec![child(modifier(0, true)), child(modifier(1, true))]);
+        // When
+        let initial = condition.is_fulfilled(&params);
+        let previous_value = set_param(&mut params, 0, 1.0);
+        let after_first = condition.process_param_update(&params, index(0), previous_value);
+        let previous_value = set_param(&mut params, 1, 1.0);
+        let after_second = condition.process_param_update(&params, index(1), previous_value);
+        let previous_value = set_param(&mut params, 2, 1.0);
+        let after_unrelated = condition.process_param_update(&params, index(2), previous_value);
+        // Then
+        assert_eq!(initial, Some(false));
+        assert_eq!(after_first, Some(false));
+        assert_eq!(after_second, Some(true));
+        assert_eq!(after_unrelated, None);
+    }
+
+    #[test]
+    fn incremental_evaluation_any_and_not() {
+        // Given
+        let mut params = CompartmentParams::default();
+        let condition = ActivationCondition::Any(vec![
+            child(modifier(0, true)),
+            child(ActivationCondition::Not(Box::new(child(modifier(1, true))))),
+        ]);
+        // When
+        let initial = condition.is_fulfilled(&params);
+        let previous_value = set_param(&mut params, 1, 1.0);
+        let after_first = condition.process_param_update(&params, index(1), previous_value);
+        let previous_value = set_param(&mut params, 0, 1.0);
+        let after_second = condition.process_param_update(&params, index(0), previous_value);
+        // Then
+        assert_eq!(initial, Some(true));
+        assert_eq!(after_first, Some(false));
+        assert_eq!(after_second, Some(true));
+    }
+
+    #[test]
+    fn incremental_evaluation_keeps_results_of_unaffected_children() {
+        // Given
+        let mut params = CompartmentParams::default();
+        set_param(&mut params, 0, 1.0);
+        let lead_mapping = MappingId::random();
+        let condition = ActivationCondition::All(vec![
+            child(modifier(0, true)),
+            child(ActivationCondition::TargetValue {
+                lead_mapping: Some(lead_mapping),
+                condition: Box::new(ExpressionEvaluator::compile("y > 0.5").unwrap()),
+            }),
+        ]);
+        // When
+        let initial = condition.is_fulfilled(&params);
+        let after_target_value = condition.process_target_value_update(
+            lead_mapping,
+            Some(AbsoluteValue::Continuous(UnitValue::MAX)),
+        );
+        let previous_value = set_param(&mut params, 0, 0.0);
+        let after_off = condition.process_param_update(&params, index(0), previous_value);
+        let previous_value = set_param(&mut params, 0, 1.0);
+        let after_on = condition.process_param_update(&params, index(0), previous_value);
+        // Then
+        // The target-value based child hasn't reported a value yet
+        assert_eq!(initial, Some(false));
+        assert_eq!(after_target_value, Some(true));
+        assert_eq!(after_off, Some(false));
+        // The target-value based child is not re-evaluated but remembered
+        assert_eq!(after_on, Some(true));
+    }
+
+    #[test]
+    fn required_param_states_of_negations() {
+        // Given
+        let on = modifier(0, true);
+        let not_on = ActivationCondition::Not(Box::new(child(modifier(0, true))));
+        let not_not_on = ActivationCondition::Not(Box::new(child(ActivationCondition::Not(
+            Box::new(child(modifier(0, true))),
+        ))));
+        let not_any_always =
+            ActivationCondition::Not(Box::new(child(ActivationCondition::Any(vec![
+                child(modifier(1, true)),
+                child(ActivationCondition::Always),
+            ]))));
+        let not_all = ActivationCondition::Not(Box::new(child(ActivationCondition::All(vec![
+            child(modifier(0, true)),
+            child(modifier(1, true)),
+        ]))));
+        // Then
+        assert!(!on
+            .required_param_states()
+            .is_compatible_with(&not_on.required_param_states()));
+        assert!(on
+            .required_param_states()
+            .is_compatible_with(&not_not_on.required_param_states()));
+        assert!(not_any_always.required_param_states().is_contradictory());
+        assert!(!not_all.required_param_states().is_contradictory());
+        assert!(on
+            .required_param_states()
+            .is_compatible_with(&not_all.required_param_states()));
+    }
+}
@@ -60,6 +60,8 @@ pub struct RealearnControlSurfaceMiddleware<EH: DomainEventHandler> {
     full_beats: NonCryptoHashMap<ReaProject, u32>,
     deprecated_fx_focus_state: Option<GetFocusedFx2Result>,
     _modern_fx_focus_state: Option<GetTouchedOrFocusedFxCurrentlyFocusedFxResult>,
+    midi_editor_is_open: bool,
+    project_tabs: Vec<Project>,
     target_capture_senders: NonCryptoHashMap<Option<UnitId>, TargetCaptureSender>,
     osc_capture_sender: Option<OscCaptureSender>,
     osc_input_devices: Vec<OscInputDevice>,
@@ -138,6 +140,16 @@ pub enum AdditionalFeedbackEvent {
         unit_event: UnitEvent,
     },
     LastTouchedTargetChanged,
+    /// This event is raised when the first MIDI editor is opened or the last one is closed.
+    ///
+    /// REAPER doesn't notify about this, so it's detected once per main loop cycle (centrally,
+    /// not per mapping).
+    MidiEditorOpenedOrClosed,
+    /// This event is raised when a project tab is opened, closed or moved.
+    ///
+    /// REAPER doesn't notify about this, so it's detected once per main loop cycle (centrally,
+    /// not per mapping).
+    ProjectTabsChanged,
 }
 
 #[derive(Debug)]
@@ -240,6 +252,8 @@ impl<EH: DomainEventHandler> RealearnControlSurfaceMiddleware<EH> {
             full_beats: Default::default(),
             deprecated_fx_focus_state: Default::default(),
             _modern_fx_focus_state: Default::default(),
+            midi_editor_is_open: false,
+            project_tabs: vec![],
             target_capture_senders: Default::default(),
             osc_capture_sender: None,
             osc_input_devices: vec![],
@@ -297,6 +311,8 @@ impl<EH: DomainEventHandler> RealearnControlSurfaceMiddleware<EH> {
         // Inform ReaLearn about various changes that are not relevant for target learning
         self.detect_reaper_config_changes();
         self.emit_focus_switch_between_main_and_fx_as_feedback_event();
+        self.emit_midi_editor_open_state_change_as_feedback_event();
+        self.emit_project_tab_changes_as_feedback_event();
         self.emit_instance_events();
         self.emit_stream_deck_events(timestamp);
         self.emit_beats_as_feedback_events();
@@ -640,6 +656,34 @@ impl<EH: DomainEventHandler> RealearnControlSurfaceMiddleware<EH> {
         }
     }
 
+    fn emit_midi_editor_open_state_change_as_feedback_event(&mut self) {
+        let is_open = Reaper::get()
+            .medium_reaper()
+            .midi_editor_get_active()
+            .is_some();
+        if mem::replace(&mut self.midi_editor_is_open, is_open) == is_open {
+            return;
+        }
+        let event = AdditionalFeedbackEvent::MidiEditorOpenedOrClosed;
+        for p in &mut *self.main_processors.borrow_mut() {
+            p.process_additional_feedback_event(&event);
+        }
+    }
+
+    fn emit_project_tab_changes_as_feedback_event(&mut self) {
+        if Reaper::get()
+            .projects()
+            .eq(self.project_tabs.iter().copied())
+        {
+            return;
+        }
+        self.project_tabs = Reaper::get().projects().collect();
+        let event = AdditionalFeedbackEvent::ProjectTabsChanged;
+        for p in &mut *self.main_processors.borrow_mut() {
+            p.process_additional_feedback_event(&event);
+        }
+    }
+
     #[allow(unused)]
     fn detect_focus_switch_between_main_and_fx_as_feedback_event_modern(&mut self) -> bool {
         let reaper = Reaper::get().medium_reaper();
@@ -1,4 +1,5 @@
 use crate::base::eel;
+use crate::domain::AdditionalLuaTransformationInput;
 use helgoboss_learn::{
     ControlValueKind, Transformation, TransformationInput, TransformationInstruction,
     TransformationOutput,
@@ -13,6 +14,8 @@ use std::sync::Arc;
 #[derive(Default)]
 pub struct AdditionalTransformationInput {
     pub y_last: f64,
+    /// Only relevant for Lua transformations. Not available in real-time threads.
+    pub lua: Option<AdditionalLuaTransformationInput>,
 }
 
 #[derive(Debug)]
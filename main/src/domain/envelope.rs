@@ -0,0 +1,514 @@
+use crate::domain::ui_util::volume_unit_value;
+use helgoboss_learn::UnitValue;
+use reaper_high::{FxParameter, Project, Reaper, SliderVolume, Track};
+use reaper_low::raw;
+use reaper_medium::{reaper_str, PositionInSeconds, ReaperStr, ReaperVolumeValue};
+use std::ffi::CString;
+use std::ptr::{null_mut, NonNull};
+
+/// A resolved track or FX parameter envelope.
+///
+/// REAPER doesn't offer stable envelope IDs, so we keep the raw pointer and validate it before
+/// each use.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutomationEnvelope {
+    track: Track,
+    raw: NonNull<raw::TrackEnvelope>,
+    kind: AutomationEnvelopeKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AutomationEnvelopeKind {
+    Volume,
+    Pan,
+    Width,
+    Mute,
+    FxParameter(FxParameter),
+    Other,
+}
+
+impl AutomationEnvelopeKind {
+    fn from_track_envelope_name(name: &str) -> Self {
+        match name {
+            "Volume" | "Volume (Pre-FX)" | "Trim Volume" => Self::Volume,
+            "Pan" | "Pan (Pre-FX)" => Self::Pan,
+            "Width" | "Width (Pre-FX)" => Self::Width,
+            "Mute" => Self::Mute,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Information about an automation item on an envelope.
+#[derive(Copy, Clone, Debug)]
+pub struct AutomationItemInfo {
+    pub index: u32,
+    pub pool_id: i32,
+    pub position: PositionInSeconds,
+    pub length: f64,
+}
+
+impl AutomationEnvelope {
+    /// Looks up a built-in track envelope by the name shown in REAPER (e.g. "Volume").
+    pub fn from_track_by_name(track: Track, name: &str) -> Result<Self, &'static str> {
+        let raw_track = track.raw().map_err(|_| "track not available")?;
+        let c_name = CString::new(name).map_err(|_| "invalid envelope name")?;
+        let raw = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetTrackEnvelopeByName(raw_track.as_ptr(), c_name.as_ptr())
+        };
+        let raw = NonNull::new(raw).ok_or("track envelope not found")?;
+        let env = Self {
+            track,
+            raw,
+            kind: AutomationEnvelopeKind::from_track_envelope_name(name),
+        };
+        Ok(env)
+    }
+
+    /// Looks up the envelope of the given FX parameter.
+    ///
+    /// Doesn't create the envelope if it doesn't exist yet because resolving a target shouldn't
+    /// modify the project.
+    pub fn from_fx_parameter(param: FxParameter) -> Result<Self, &'static str> {
+        let fx = param.fx();
+        let track = fx
+            .track()
+            .ok_or("FX parameter envelopes need a track FX")?
+            .clone();
+        let raw_track = track.raw().map_err(|_| "track not available")?;
+        let fx_index = fx.query_index().to_raw();
+        let raw = unsafe {
+            Reaper::get().medium_reaper().low().GetFXEnvelope(
+                raw_track.as_ptr(),
+                fx_index,
+                param.index() as i32,
+                false,
+            )
+        };
+        let raw = NonNull::new(raw).ok_or("FX parameter envelope not available")?;
+        let env = Self {
+            track,
+            raw,
+            kind: AutomationEnvelopeKind::FxParameter(param),
+        };
+        Ok(env)
+    }
+
+    pub fn track(&self) -> &Track {
+        &self.track
+    }
+
+    pub fn project(&self) -> Project {
+        self.track.project()
+    }
+
+    pub fn fx_parameter(&self) -> Option<&FxParameter> {
+        match &self.kind {
+            AutomationEnvelopeKind::FxParameter(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        if !self.track.is_available() {
+            return false;
+        }
+        unsafe {
+            Reaper::get().medium_reaper().low().ValidatePtr2(
+                self.project().raw().as_ptr(),
+                self.raw.as_ptr() as _,
+                reaper_str!("TrackEnvelope*").as_ptr(),
+            )
+        }
+    }
+
+    /// Position at which the envelope is read and written: The play cursor if playing, otherwise
+    /// the edit cursor.
+    pub fn cursor_position(&self) -> PositionInSeconds {
+        self.project()
+            .play_or_edit_cursor_position()
+            .unwrap_or_default()
+    }
+
+    /// Returns the normalized envelope value at the given position.
+    pub fn value_at(&self, pos: PositionInSeconds) -> UnitValue {
+        self.normalize(self.raw_value_at(pos))
+    }
+
+    /// Sets the envelope value at the given position, either by adjusting an existing point at
+    /// exactly that position or by inserting a new one.
+    pub fn set_value_at(
+        &self,
+        pos: PositionInSeconds,
+        value: UnitValue,
+    ) -> Result<(), &'static str> {
+        let raw_value = self.denormalize(value);
+        self.set_raw_value_at(pos, raw_value)
+    }
+
+    /// Inserts a point at the given position without changing the shape of the envelope.
+    pub fn insert_point_at(&self, pos: PositionInSeconds) -> Result<(), &'static str> {
+        let raw_value = self.raw_value_at(pos);
+        self.set_raw_value_at(pos, raw_value)
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.get_string_info(reaper_str!("VISIBLE")) == "1"
+    }
+
+    pub fn set_visible(&self, visible: bool) -> Result<(), &'static str> {
+        self.set_bool_info(reaper_str!("VISIBLE"), visible)
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.get_string_info(reaper_str!("ARM")) == "1"
+    }
+
+    pub fn set_armed(&self, armed: bool) -> Result<(), &'static str> {
+        self.set_bool_info(reaper_str!("ARM"), armed)
+    }
+
+    /// Returns the automation item which covers the given position (the topmost one if several).
+    pub fn automation_item_at(&self, pos: PositionInSeconds) -> Option<AutomationItemInfo> {
+        let low = Reaper::get().medium_reaper().low();
+        let count = unsafe { low.CountAutomationItems(self.raw.as_ptr()) };
+        (0..count.max(0) as u32).rev().find_map(|index| {
+            let info = self.automation_item(index);
+            let start = info.position.get();
+            if (start..start + info.length).contains(&pos.get()) {
+                Some(info)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Creates a new automation item (with a new pool) and returns its index.
+    pub fn insert_automation_item(
+        &self,
+        pool_id: Option<i32>,
+        pos: PositionInSeconds,
+        length: f64,
+    ) -> Result<u32, &'static str> {
+        if length <= 0.0 {
+            return Err("automation item length must be positive");
+        }
+        let index = unsafe {
+            Reaper::get().medium_reaper().low().InsertAutomationItem(
+                self.raw.as_ptr(),
+                pool_id.unwrap_or(-1),
+                pos.get(),
+                length,
+            )
+        };
+        if index < 0 {
+            return Err("couldn't insert automation item");
+        }
+        update_arrange();
+        Ok(index as u32)
+    }
+
+    pub fn automation_item_is_looped(&self, index: u32) -> bool {
+        self.get_automation_item_value(index, reaper_str!("D_LOOPSRC")) > 0.0
+    }
+
+    pub fn set_automation_item_looped(&self, index: u32, looped: bool) {
+        let value = if looped { 1.0 } else { 0.0 };
+        unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetSetAutomationItemInfo(
+                    self.raw.as_ptr(),
+                    index as i32,
+                    reaper_str!("D_LOOPSRC").as_ptr(),
+                    value,
+                    true,
+                );
+        }
+        update_arrange();
+    }
+
+    fn automation_item(&self, index: u32) -> AutomationItemInfo {
+        AutomationItemInfo {
+            index,
+            pool_id: self.get_automation_item_value(index, reaper_str!("D_POOL_ID")) as i32,
+            position: PositionInSeconds::new_panic(
+                self.get_automation_item_value(index, reaper_str!("D_POSITION")),
+            ),
+            length: self.get_automation_item_value(index, reaper_str!("D_LENGTH")),
+        }
+    }
+
+    fn get_automation_item_value(&self, index: u32, key: &ReaperStr) -> f64 {
+        unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetSetAutomationItemInfo(self.raw.as_ptr(), index as i32, key.as_ptr(), 0.0, false)
+        }
+    }
+
+    fn raw_value_at(&self, pos: PositionInSeconds) -> f64 {
+        let mut value = 0.0;
+        unsafe {
+            Reaper::get().medium_reaper().low().Envelope_Evaluate(
+                self.raw.as_ptr(),
+                pos.get(),
+                0.0,
+                0,
+                &mut value,
+                null_mut(),
+                null_mut(),
+                null_mut(),
+            );
+        }
+        value
+    }
+
+    fn set_raw_value_at(&self, pos: PositionInSeconds, raw_value: f64) -> Result<(), &'static str> {
+        let low = Reaper::get().medium_reaper().low();
+        let env = self.raw.as_ptr();
+        unsafe {
+            let point_index = low.GetEnvelopePointByTime(env, pos.get());
+            if point_index >= 0 {
+                let mut point_time = 0.0;
+                low.GetEnvelopePoint(
+                    env,
+                    point_index,
+                    &mut point_time,
+                    null_mut(),
+                    null_mut(),
+                    null_mut(),
+                    null_mut(),
+                );
+                if (point_time - pos.get()).abs() < POINT_TIME_EPSILON {
+                    let mut new_value = raw_value;
+                    let mut no_sort = true;
+                    low.SetEnvelopePoint(
+                        env,
+                        point_index,
+                        null_mut(),
+                        &mut new_value,
+                        null_mut(),
+                        null_mut(),
+                        null_mut(),
+                        &mut no_sort,
+                    );
+                    update_arrange();
+                    return Ok(());
+                }
+            }
+            let mut no_sort = true;
+            let inserted =
+                low.InsertEnvelopePoint(env, pos.get(), raw_value, 0, 0.0, false, &mut no_sort);
+            if !inserted {
+                return Err("couldn't insert envelope point");
+            }
+            low.Envelope_SortPoints(env);
+        }
+        update_arrange();
+        Ok(())
+    }
+
+    fn normalize(&self, raw_value: f64) -> UnitValue {
+        use AutomationEnvelopeKind::*;
+        match &self.kind {
+            Volume => {
+                let amplitude = self.scale_from_envelope_mode(raw_value);
+                let volume = ReaperVolumeValue::new_panic(amplitude.max(0.0));
+                volume_unit_value(SliderVolume::from_reaper_value(volume))
+            }
+            Pan => pan_envelope_value_to_unit_value(raw_value),
+            Width => width_envelope_value_to_unit_value(raw_value),
+            FxParameter(param) => {
+                let range = param.value_range();
+                let span = range.max_value - range.min_value;
+                if span == 0.0 {
+                    return UnitValue::MIN;
+                }
+                UnitValue::new_clamped((raw_value - range.min_value) / span)
+            }
+            Mute | Other => UnitValue::new_clamped(raw_value),
+        }
+    }
+
+    fn denormalize(&self, value: UnitValue) -> f64 {
+        use AutomationEnvelopeKind::*;
+        match &self.kind {
+            Volume => {
+                let volume = SliderVolume::try_from_normalized_slider_value(value.get())
+                    .unwrap_or(SliderVolume::MIN);
+                self.scale_to_envelope_mode(volume.reaper_value().get())
+            }
+            Pan => unit_value_to_pan_envelope_value(value),
+            Width => unit_value_to_width_envelope_value(value),
+            FxParameter(param) => {
+                let range = param.value_range();
+                range.min_value + value.get() * (range.max_value - range.min_value)
+            }
+            Mute | Other => value.get(),
+        }
+    }
+
+    fn scale_from_envelope_mode(&self, raw_value: f64) -> f64 {
+        let low = Reaper::get().medium_reaper().low();
+        unsafe {
+            let mode = low.GetEnvelopeScalingMode(self.raw.as_ptr());
+            low.ScaleFromEnvelopeMode(mode, raw_value)
+        }
+    }
+
+    fn scale_to_envelope_mode(&self, value: f64) -> f64 {
+        let low = Reaper::get().medium_reaper().low();
+        unsafe {
+            let mode = low.GetEnvelopeScalingMode(self.raw.as_ptr());
+            low.ScaleToEnvelopeMode(mode, value)
+        }
+    }
+
+    fn get_string_info(&self, key: &ReaperStr) -> String {
+        let mut buffer = [0u8; 64];
+        let successful = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetSetEnvelopeInfo_String(
+                    self.raw.as_ptr(),
+                    key.as_ptr(),
+                    buffer.as_mut_ptr() as _,
+                    false,
+                )
+        };
+        if !successful {
+            return String::new();
+        }
+        let len = buffer.iter().position(|b| *b == 0).unwrap_or(buffer.len());
+        String::from_utf8_lossy(&buffer[..len]).into_owned()
+    }
+
+    fn set_bool_info(&self, key: &ReaperStr, value: bool) -> Result<(), &'static str> {
+        let mut buffer = *if value { b"1\0" } else { b"0\0" };
+        let successful = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetSetEnvelopeInfo_String(
+                    self.raw.as_ptr(),
+                    key.as_ptr(),
+                    buffer.as_mut_ptr() as _,
+                    true,
+                )
+        };
+        if !successful {
+            return Err("couldn't change envelope property");
+        }
+        update_arrange();
+        Ok(())
+    }
+}
+
+/// Returns the length of the measure which contains the given position.
+pub fn measure_length_at(project: Project, pos: PositionInSeconds) -> f64 {
+    let low = Reaper::get().medium_reaper().low();
+    let proj = project.raw().as_ptr();
+    unsafe {
+        let mut measure_index = 0;
+        low.TimeMap2_timeToBeats(
+            proj,
+            pos.get(),
+            &mut measure_index,
+            null_mut(),
+            null_mut(),
+            null_mut(),
+        );
+        let measure_start = low.TimeMap2_beatsToTime(proj, 0.0, &measure_index);
+        let next_measure_index = measure_index + 1;
+        let next_measure_start = low.TimeMap2_beatsToTime(proj, 0.0, &next_measure_index);
+        next_measure_start - measure_start
+    }
+}
+
+/// REAPER's pan envelope is upside down compared to the track pan: +1.0 means 100% left and -1.0
+/// means 100% right. We want 0% to be left, just like in the "Track: Set pan" target.
+fn pan_envelope_value_to_unit_value(raw_value: f64) -> UnitValue {
+    UnitValue::new_clamped((1.0 - raw_value) / 2.0)
+}
+
+fn unit_value_to_pan_envelope_value(value: UnitValue) -> f64 {
+    1.0 - value.get() * 2.0
+}
+
+/// The width envelope goes from -1.0 (-100%) to +1.0 (100%), same as the track width.
+fn width_envelope_value_to_unit_value(raw_value: f64) -> UnitValue {
+    UnitValue::new_clamped((raw_value + 1.0) / 2.0)
+}
+
+fn unit_value_to_width_envelope_value(value: UnitValue) -> f64 {
+    value.get() * 2.0 - 1.0
+}
+
+fn update_arrange() {
+    Reaper::get().medium_reaper().low().UpdateArrange();
+}
+
+/// Points closer than this are considered to be at the same position.
+const POINT_TIME_EPSILON: f64 = 0.000_001;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pan_envelope_left_is_min() {
+        // Given
+        let left = 1.0;
+        let right = -1.0;
+        // When
+        let left_value = pan_envelope_value_to_unit_value(left);
+        let right_value = pan_envelope_value_to_unit_value(right);
+        // Then
+        assert_eq!(left_value, UnitValue::MIN);
+        assert_eq!(right_value, UnitValue::MAX);
+        assert_eq!(pan_envelope_value_to_unit_value(0.0), UnitValue::new(0.5));
+    }
+
+    #[test]
+    fn pan_envelope_round_trip() {
+        // Given
+        let values = [0.0, 0.25, 0.5, 0.8, 1.0];
+        for v in values {
+            // When
+            let raw = unit_value_to_pan_envelope_value(UnitValue::new(v));
+            let back = pan_envelope_value_to_unit_value(raw);
+            // Then
+            assert!((back.get() - v).abs() < 1e-9);
+        }
+        assert_eq!(unit_value_to_pan_envelope_value(UnitValue::MIN), 1.0);
+    }
+
+    #[test]
+    fn width_envelope_is_not_inverted() {
+        // Given
+        let full_width = 1.0;
+        // When
+        let value = width_envelope_value_to_unit_value(full_width);
+        // Then
+        assert_eq!(value, UnitValue::MAX);
+        assert_eq!(width_envelope_value_to_unit_value(-1.0), UnitValue::MIN);
+        assert_eq!(unit_value_to_width_envelope_value(UnitValue::new(0.5)), 0.0);
+    }
+
+    #[test]
+    fn out_of_range_envelope_values_are_clamped() {
+        // Given
+        let too_far_left = 1.5;
+        // When
+        let value = pan_envelope_value_to_unit_value(too_far_left);
+        // Then
+        assert_eq!(value, UnitValue::MIN);
+    }
+}
@@ -451,6 +451,12 @@ impl PotIntegration for RealearnPotIntegration {
         ));
     }
 
+    fn notify_user_metadata_changed(&self, id: PresetId) {
+        self.emit(InstanceStateChanged::PotStateChanged(
+            PotStateChangedEvent::UserMetadataChanged { id },
+        ));
+    }
+
     fn protected_fx(&self) -> &Fx {
         &self.containing_fx
     }
@@ -473,6 +479,9 @@ pub enum PotStateChangedEvent {
     },
     IndexesRebuilt,
     PresetLoaded,
+    UserMetadataChanged {
+        id: PresetId,
+    },
 }
 
 #[cfg(feature = "playtime")]
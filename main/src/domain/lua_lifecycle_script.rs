@@ -0,0 +1,145 @@
+use crate::domain::{
+    lua_module_path_without_ext, CompartmentParamIndex, CompartmentParams, LifecyclePhase, SafeLua,
+};
+use anyhow::ensure;
+use helgoboss_learn::RawMidiEvent;
+use mlua::{Function, IntoLua, LuaSerdeExt, Table, Value};
+use rosc::{OscMessage, OscType};
+
+#[derive(Copy, Clone, Debug)]
+pub struct LuaLifecycleScriptInput<'a> {
+    pub phase: LifecyclePhase,
+    pub params: &'a CompartmentParams,
+    pub compartment_lua: Option<&'a mlua::Value>,
+}
+
+#[derive(Debug, Default)]
+pub struct LuaLifecycleScriptOutcome {
+    pub midi_messages: Vec<RawMidiEvent>,
+    pub osc_messages: Vec<OscMessage>,
+}
+
+/// Lua script which is executed when a mapping becomes active or inactive.
+#[derive(Debug)]
+pub struct LuaLifecycleScript<'a> {
+    lua: &'a SafeLua,
+    function: Function,
+    env: Table,
+    context_key: Value,
+}
+
+impl<'a> LuaLifecycleScript<'a> {
+    pub fn compile(lua: &'a SafeLua, lua_script: &str) -> anyhow::Result<Self> {
+        ensure!(!lua_script.trim().is_empty(), "script empty");
+        let env = lua.create_fresh_environment(false)?;
+        let function = lua.compile_as_function("Lifecycle script", lua_script, env.clone())?;
+        let script = Self {
+            lua,
+            env,
+            function,
+            context_key: "context".into_lua(lua.as_ref())?,
+        };
+        Ok(script)
+    }
+
+    pub fn execute(
+        &self,
+        input: LuaLifecycleScriptInput,
+    ) -> anyhow::Result<LuaLifecycleScriptOutcome> {
+        let lua = self.lua.as_ref();
+        let value = lua.scope(|scope| {
+            // Set require function
+            let require = scope.create_function(move |_, path: String| {
+                let val = match lua_module_path_without_ext(&path) {
+                    "compartment" => input.compartment_lua.cloned().unwrap_or(Value::Nil),
+                    _ => return Err(mlua::Error::runtime("Lifecycle scripts don't support the usage of 'require' for anything else than 'compartment'!"))
+                };
+                Ok(val)
+            })?;
+            self.env.raw_set("require", require)?;
+            // Build input data
+            let context_table = {
+                let table = lua.create_table()?;
+                let phase = match input.phase {
+                    LifecyclePhase::Activation => "activation",
+                    LifecyclePhase::Deactivation => "deactivation",
+                };
+                table.set("phase", phase)?;
+                let param = scope.create_function(move |_, index_or_key: Value| {
+                    Ok(get_param_value(input.params, index_or_key))
+                })?;
+                table.set("param", param)?;
+                table
+            };
+            self.env.raw_set(self.context_key.clone(), context_table)?;
+            // Invoke script
+            let value: Value = self.function.call(())?;
+            Ok(value)
+        })?;
+        // Process return value
+        if value.is_nil() {
+            return Ok(Default::default());
+        }
+        let output: LuaScriptLifecycleOutput = lua.from_value(value)?;
+        let outcome = LuaLifecycleScriptOutcome {
+            midi_messages: output
+                .midi
+                .into_iter()
+                .flat_map(|msg| RawMidiEvent::try_from_slice(0, &msg))
+                .collect(),
+            osc_messages: output
+                .osc
+                .into_iter()
+                .map(|msg| OscMessage {
+                    addr: msg.address,
+                    args: msg.arguments.into_iter().map(|arg| arg.into()).collect(),
+                })
+                .collect(),
+        };
+        Ok(outcome)
+    }
+}
+
+/// Returns the effective value of the compartment parameter with the given zero-based index or key.
+fn get_param_value(params: &CompartmentParams, index_or_key: Value) -> Option<f64> {
+    let index = match index_or_key {
+        Value::Integer(i) => CompartmentParamIndex::try_from(u32::try_from(i).ok()?).ok()?,
+        Value::Number(n) => CompartmentParamIndex::try_from(n as u32).ok()?,
+        Value::String(key) => params.find_setting_by_key(&key.to_str().ok()?)?.0,
+        _ => return None,
+    };
+    Some(params.at(index).effective_value().into())
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct LuaScriptLifecycleOutput {
+    midi: Vec<Vec<u8>>,
+    osc: Vec<LuaScriptOscMessage>,
+}
+
+#[derive(serde::Deserialize)]
+struct LuaScriptOscMessage {
+    address: String,
+    #[serde(default)]
+    arguments: Vec<LuaScriptOscArgument>,
+}
+
+/// Lua doesn't distinguish between integers and floats, so numbers are always sent as floats.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum LuaScriptOscArgument {
+    Bool(bool),
+    Float(f32),
+    String(String),
+}
+
+impl From<LuaScriptOscArgument> for OscType {
+    fn from(value: LuaScriptOscArgument) -> Self {
+        match value {
+            LuaScriptOscArgument::Bool(v) => OscType::Bool(v),
+            LuaScriptOscArgument::Float(v) => OscType::Float(v),
+            LuaScriptOscArgument::String(v) => OscType::String(v),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::domain::{
+    lua_module_path_without_ext, AdditionalTransformationInput, CompartmentKind,
+    CompartmentParamIndex, OutputVariable, SafeLua, Script, SharedUnit,
+};
+use anyhow::{bail, ensure};
+use helgoboss_learn::{
+    Transformation, TransformationInput, TransformationInstruction, TransformationOutput,
+};
+use mlua::{Function, IntoLua, LuaSerdeExt, Table, Value};
+
+/// Lua-specific input which is only available when controlling from the main thread.
+#[derive(Clone)]
+pub struct AdditionalLuaTransformationInput {
+    pub compartment_lua: Option<Value>,
+    pub unit: SharedUnit,
+    pub compartment: CompartmentKind,
+}
+
+impl AdditionalLuaTransformationInput {
+    fn param_value(&self, index: u32) -> Option<f64> {
+        let index = CompartmentParamIndex::try_from(index).ok()?;
+        let unit = self.unit.try_borrow().ok()?;
+        let params = unit.parameter_manager().params();
+        let value = params
+            .compartment_params(self.compartment)
+            .at(index)
+            .raw_value();
+        Some(value as f64)
+    }
+}
+
+/// Represents a value transformation done via Lua.
+///
+/// The script environment is kept between invocations, so the script can store state in global
+/// variables or in the `state` table.
+///
+/// A script which wants to be invoked repeatedly (e.g. in order to make use of `rel_time`) must
+/// declare this with the hot comment `--!poll` at its beginning.
+#[derive(Debug)]
+pub struct LuaTransformation<'lua> {
+    lua: &'lua SafeLua,
+    function: Function,
+    env: Table,
+    input_key: Value,
+    output_key: Value,
+    context_key: Value,
+    wants_to_be_polled: bool,
+}
+
+impl<'lua> LuaTransformation<'lua> {
+    pub fn compile_for_control(lua: &'lua SafeLua, lua_script: &str) -> anyhow::Result<Self> {
+        Self::compile(lua, lua_script, OutputVariable::Y)
+    }
+
+    pub fn compile_for_feedback(lua: &'lua SafeLua, lua_script: &str) -> anyhow::Result<Self> {
+        Self::compile(lua, lua_script, OutputVariable::X)
+    }
+
+    fn compile(
+        lua: &'lua SafeLua,
+        lua_script: &str,
+        output_var: OutputVariable,
+    ) -> anyhow::Result<Self> {
+        ensure!(!lua_script.trim().is_empty(), "script empty");
+        let env = lua.create_fresh_environment(false)?;
+        env.raw_set("state", lua.as_ref().create_table()?)?;
+        let function = lua.compile_as_function("Transformation", lua_script, env.clone())?;
+        let (input_name, output_name) = match output_var {
+            OutputVariable::X => ("y", "x"),
+            OutputVariable::Y => ("x", "y"),
+        };
+        let transformation = Self {
+            lua,
+            function,
+            env,
+            input_key: input_name.into_lua(lua.as_ref())?,
+            output_key: output_name.into_lua(lua.as_ref())?,
+            context_key: "context".into_lua(lua.as_ref())?,
+            wants_to_be_polled: has_hot_comment(lua_script, POLL_HOT_COMMENT),
+        };
+        Ok(transformation)
+    }
+
+    fn transform_internal(
+        &self,
+        input: TransformationInput<AdditionalTransformationInput>,
+    ) -> anyhow::Result<TransformationOutput> {
+        let lua = self.lua.as_ref();
+        let additional_input = &input.additional_input;
+        let value = lua.scope(|scope| {
+            // Set require function
+            let require = scope.create_function(|_, path: String| {
+                let val = match lua_module_path_without_ext(&path) {
+                    "compartment" => additional_input
+                        .lua
+                        .as_ref()
+                        .and_then(|i| i.compartment_lua.clone())
+                        .unwrap_or(Value::Nil),
+                    _ => return Err(mlua::Error::runtime("Transformations don't support the usage of 'require' for anything else than 'compartment'!"))
+                };
+                Ok(val)
+            })?;
+            self.env.raw_set("require", require)?;
+            // Build input data
+            self.env
+                .raw_set(self.input_key.clone(), input.event.input_value)?;
+            self.env
+                .raw_set(self.output_key.clone(), input.context.output_value)?;
+            self.env.raw_set("y_last", additional_input.y_last)?;
+            self.env
+                .raw_set("rel_time", input.context.rel_time.as_millis() as f64)?;
+            let context_table = {
+                let table = lua.create_table()?;
+                let param = scope.create_function(|_, index: u32| {
+                    let value = additional_input
+                        .lua
+                        .as_ref()
+                        .and_then(|i| i.param_value(index));
+                    Ok(value)
+                })?;
+                table.set("param", param)?;
+                table
+            };
+            self.env.raw_set(self.context_key.clone(), context_table)?;
+            // Invoke script
+            let value: Value = self.function.call(())?;
+            Ok(value)
+        })?;
+        // Process return value
+        let (value, instruction) = match value {
+            Value::Nil => (None, None),
+            Value::Integer(v) => (Some(v as f64), None),
+            Value::Number(v) => (Some(v), None),
+            Value::Table(_) => {
+                let output: LuaTransformationOutput = lua.from_value(value)?;
+                let instruction = output.stop.then_some(TransformationInstruction::Stop);
+                (output.value, instruction)
+            }
+            _ => bail!("Lua transformation must return a number, nil or a table"),
+        };
+        let output = TransformationOutput {
+            produced_kind: Default::default(),
+            value,
+            instruction,
+        };
+        Ok(output)
+    }
+}
+
+const POLL_HOT_COMMENT: &str = "poll";
+
+/// Returns `true` if the given hot comment (e.g. `--!poll`) is among the comments at the beginning
+/// of the script.
+fn has_hot_comment(lua_script: &str, name: &str) -> bool {
+    lua_script
+        .lines()
+        .map(|line| line.trim())
+        .take_while(|line| line.is_empty() || line.starts_with("--"))
+        .filter_map(|line| line.strip_prefix("--!"))
+        .any(|hot_comment| hot_comment.trim() == name)
+}
+
+#[derive(serde::Deserialize)]
+struct LuaTransformationOutput {
+    value: Option<f64>,
+    #[serde(default)]
+    stop: bool,
+}
+
+impl Transformation for LuaTransformation<'_> {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<Self::AdditionalInput>,
+    ) -> Result<TransformationOutput, &'static str> {
+        self.transform_internal(input).map_err(|e| {
+            let error = e.to_string();
+            tracing::debug!(msg = "Failed to execute Lua transformation", %error);
+            "Lua transformation failed"
+        })
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        self.wants_to_be_polled
+    }
+}
+
+impl Script for LuaTransformation<'_> {
+    fn uses_time(&self) -> bool {
+        self.wants_to_be_polled
+    }
+
+    fn produces_relative_values(&self) -> bool {
+        false
+    }
+
+    fn evaluate(
+        &self,
+        input: TransformationInput<AdditionalTransformationInput>,
+    ) -> Result<TransformationOutput, &'static str> {
+        self.transform(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helgoboss_learn::TransformationInputEvent;
+
+    fn input(x: f64) -> TransformationInput<AdditionalTransformationInput> {
+        TransformationInput {
+            event: TransformationInputEvent {
+                input_value: x,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn basics() {
+        // Given
+        let text = "return x * x";
+        let lua = SafeLua::new().unwrap();
+        let transformation = LuaTransformation::compile_for_control(&lua, text).unwrap();
+        // When
+        let output = transformation.transform(input(0.5)).unwrap();
+        // Then
+        assert_eq!(output.value, Some(0.25));
+        assert!(output.instruction.is_none());
+    }
+
+    #[test]
+    fn poll_declaration() {
+        // Given
+        let lua = SafeLua::new().unwrap();
+        // When
+        let declared =
+            LuaTransformation::compile_for_control(&lua, "--!poll\nreturn rel_time / 1000")
+                .unwrap();
+        let undeclared =
+            LuaTransformation::compile_for_control(&lua, "-- Uses rel_time\nreturn x").unwrap();
+        let too_late =
+            LuaTransformation::compile_for_control(&lua, "local a = x\n--!poll\nreturn a").unwrap();
+        // Then
+        assert!(declared.wants_to_be_polled());
+        assert!(!undeclared.wants_to_be_polled());
+        assert!(!too_late.wants_to_be_polled());
+    }
+
+    #[test]
+    fn state() {
+        // Given
+        let text = "
+            state.count = (state.count or 0) + 1
+            if state.count < 3 then
+                return nil
+            end
+            return { value = x, stop = true }
+        ";
+        let lua = SafeLua::new().unwrap();
+        let transformation = LuaTransformation::compile_for_control(&lua, text).unwrap();
+        // When
+        let first_output = transformation.transform(input(0.7)).unwrap();
+        let second_output = transformation.transform(input(0.7)).unwrap();
+        let third_output = transformation.transform(input(0.7)).unwrap();
+        // Then
+        assert_eq!(first_output.value, None);
+        assert_eq!(second_output.value, None);
+        assert_eq!(third_output.value, Some(0.7));
+        assert!(matches!(
+            third_output.instruction,
+            Some(TransformationInstruction::Stop)
+        ));
+    }
+}
@@ -9,7 +9,8 @@ use crate::domain::{
     FeedbackDestinations, FeedbackLogEntry, FeedbackOutput, FeedbackRealTimeTask,
     FeedbackResolution, FeedbackSendBehavior, FinalRealFeedbackValue, FinalSourceFeedbackValue,
     GlobalControlAndFeedbackState, GroupId, HitInstructionContext, HitInstructionResponse,
-    InstanceId, InternalInfoEvent, IoUpdatedEvent, KeyMessage, MainMapping, MainSourceMessage,
+    InstanceId, InternalInfoEvent, IoUpdatedEvent, KeyMessage, LifecycleAction, LifecycleMainData,
+    LifecyclePhase, LuaLifecycleScriptInput, MainMapping, MainSourceMessage,
     MappingActivationEffect, MappingControlResult, MappingId, MappingInfo, MessageCaptureEvent,
     MessageCaptureResult, MidiControlInput, MidiDestination, MidiScanResult, NoopLogger,
     NormalRealTimeTask, OrderedMappingIdSet, OrderedMappingMap, OscDeviceId, OscFeedbackTask,
@@ -17,20 +18,21 @@ use crate::domain::{
     QualifiedInstanceEvent, QualifiedMappingId, RawParamValue, RealTimeMappingUpdate,
     RealTimeTargetUpdate, RealearnModeContext, RealearnMonitoringFxParameterValueChangedEvent,
     RealearnParameterChangePayload, RealearnSourceContext, ReaperConfigChange, ReaperMessage,
-    ReaperSourceFeedbackValue, ReaperTarget, SharedInstance, SharedUnit, SourceFeedbackEvent,
-    SourceFeedbackLogger, SourceReleasedEvent, SpecificCompoundFeedbackValue, StreamDeckDeviceId,
-    StreamDeckMessage, StreamDeckSourceFeedbackValue, TargetControlEvent, TargetValueChangedEvent,
-    UnitContainer, UnitEvent, UnitOrchestrationEvent, UpdatedSingleMappingOnStateEvent,
-    VirtualControlElement, VirtualSourceValue,
+    ReaperSourceFeedbackValue, ReaperSourcePollContext, ReaperStateChange, ReaperTarget,
+    SharedInstance, SharedUnit, SourceFeedbackEvent, SourceFeedbackLogger, SourceReleasedEvent,
+    SpecificCompoundFeedbackValue, StreamDeckDeviceId, StreamDeckMessage,
+    StreamDeckSourceFeedbackValue, TargetControlEvent, TargetValueChangedEvent, UnitContainer,
+    UnitEvent, UnitOrchestrationEvent, UpdatedSingleMappingOnStateEvent, VirtualControlElement,
+    VirtualSourceValue,
 };
 use derive_more::Display;
 use enum_map::EnumMap;
 use helgoboss_learn::{
     AbsoluteValue, ControlValue, GroupInteraction, MidiSourceValue, MinIsMaxBehavior,
-    ModeControlOptions, RawMidiEvent, Target, BASE_EPSILON,
+    ModeControlOptions, RawMidiEvent, Target, UnitValue, BASE_EPSILON,
 };
 use std::borrow::Cow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use crate::domain::ui_util::{
     format_control_input_with_match_result, format_incoming_midi_message, format_midi_source_value,
@@ -52,6 +54,7 @@ use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
 use strum::EnumIter;
 use tracing::{debug, trace};
 
@@ -249,6 +252,14 @@ struct Collections {
     milli_dependent_feedback_mappings: EnumMap<CompartmentKind, OrderedMappingIdSet>,
     parameters: PluginParams,
     previous_target_values: EnumMap<CompartmentKind, NonCryptoHashMap<MappingId, AbsoluteValue>>,
+    /// Contains those mappings with main-thread lifecycle actions which are currently on.
+    mappings_with_active_lifecycle_hooks:
+        NonCryptoHashMap<QualifiedMappingId, Rc<LifecycleMainData>>,
+    /// Set whenever the on state of mappings might have changed, so that lifecycle actions are
+    /// only determined on activation changes and not in every main loop cycle.
+    ///
+    /// A `Cell` because the on state is reported from non-mutable contexts as well.
+    lifecycle_hooks_are_outdated: Cell<bool>,
 }
 
 #[derive(Debug)]
@@ -343,6 +354,8 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 milli_dependent_feedback_mappings: Default::default(),
                 parameters: Default::default(),
                 previous_target_values: Default::default(),
+                mappings_with_active_lifecycle_hooks: Default::default(),
+                lifecycle_hooks_are_outdated: Cell::new(true),
             },
             poll_control_mappings: Default::default(),
         }
@@ -432,6 +445,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             }
         }
         self.poll_control(timestamp);
+        self.advance_macros();
     }
 
     fn process_control_task(&mut self, task: ControlMainTask) {
@@ -532,7 +546,11 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                         (false, mode_poll_result)
                     } else if m.source().wants_to_be_polled() && m.control_is_effectively_on() {
                         // Mode was either not polled at all or without result, poll source.
-                        let res = if let Some(source_control_value) = m.poll_source() {
+                        let poll_context = ReaperSourcePollContext {
+                            params: self.collections.parameters.compartment_params(compartment),
+                            project: self.basics.context.project_or_current_project(),
+                        };
+                        let res = if let Some(source_control_value) = m.poll_source(poll_context) {
                             let control_event = ControlEvent::new(source_control_value, timestamp);
                             control_mapping_stage_one(
                                 &self.basics,
@@ -550,6 +568,21 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                         // want to be polled.
                         (false, mode_poll_result)
                     };
+                    if m.is_smoothing() {
+                        // Smoothing continues even if control is effectively off, otherwise
+                        // targets could get stuck somewhere between their old and desired value.
+                        let smoothing_result = m.poll_smoothing(
+                            control_context,
+                            self.basics.target_control_logger(
+                                ControlLogContext::Polling,
+                                m.qualified_id(),
+                            ),
+                        );
+                        final_poll_result.at_least_one_target_was_reached |=
+                            smoothing_result.at_least_one_target_was_reached;
+                        final_poll_result.at_least_one_target_caused_effect |=
+                            smoothing_result.at_least_one_target_caused_effect;
+                    }
                     control_mapping_stage_two(
                         &self.basics,
                         &mut final_poll_result,
@@ -594,6 +627,223 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         }
     }
 
+    /// Executes the steps of running macros (target "ReaLearn: Run macro") which are due.
+    fn advance_macros(&mut self) {
+        let running_macros = self.basics.unit.borrow_mut().running_macros();
+        if running_macros.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let project = self.basics.context.project_or_current_project();
+        for state in running_macros {
+            // Release the borrow before invoking mappings because the macro target itself could
+            // be among them.
+            let (compartment, invocations) = {
+                let mut state = state.borrow_mut();
+                (state.compartment(), state.advance(now, project))
+            };
+            for invocation in invocations {
+                let mapping_id = self.collections.mappings[compartment]
+                    .values()
+                    .find(|m| m.has_key(&invocation.mapping_key))
+                    .filter(|m| m.control_is_effectively_on())
+                    .map(|m| m.qualified_id());
+                if let Some(id) = mapping_id {
+                    self.control_mapping_via_glue(id, invocation.value, ControlLogContext::Macro);
+                }
+            }
+        }
+    }
+
+    /// Carries out the main-thread lifecycle actions of all mappings which turned on or off since
+    /// the last invocation.
+    ///
+    /// Does nothing if the on state of the mappings hasn't changed in the meantime. Lifecycle MIDI
+    /// is handled by the real-time processor.
+    fn process_lifecycle_hooks(&mut self) {
+        if !self.collections.lifecycle_hooks_are_outdated.replace(false) {
+            return;
+        }
+        let mut now_on: NonCryptoHashMap<QualifiedMappingId, Rc<LifecycleMainData>> =
+            HashMap::default();
+        if self.basics.feedback_is_globally_enabled {
+            for m in self.all_mappings() {
+                let data = m.lifecycle_main_data();
+                if !data.is_empty() && m.feedback_is_effectively_on() {
+                    now_on.insert(m.qualified_id(), data.clone());
+                }
+            }
+        }
+        let was_on = &self.collections.mappings_with_active_lifecycle_hooks;
+        if now_on.is_empty() && was_on.is_empty() {
+            return;
+        }
+        let deactivated: Vec<_> = was_on
+            .iter()
+            .filter(|(id, _)| !now_on.contains_key(id))
+            .map(|(id, data)| (*id, data.clone()))
+            .collect();
+        // A changed mapping counts as newly activated (consistent with lifecycle MIDI).
+        let activated: Vec<_> = now_on
+            .iter()
+            .filter(|(id, data)| !was_on.get(id).is_some_and(|d| Rc::ptr_eq(d, data)))
+            .map(|(id, data)| (*id, data.clone()))
+            .collect();
+        self.collections.mappings_with_active_lifecycle_hooks = now_on;
+        for (id, data) in deactivated {
+            self.execute_lifecycle_actions(id, &data, LifecyclePhase::Deactivation);
+        }
+        for (id, data) in activated {
+            self.execute_lifecycle_actions(id, &data, LifecyclePhase::Activation);
+        }
+    }
+
+    fn execute_lifecycle_actions(
+        &mut self,
+        id: QualifiedMappingId,
+        data: &LifecycleMainData,
+        phase: LifecyclePhase,
+    ) {
+        for action in data.actions(phase) {
+            match action {
+                LifecycleAction::SendOsc(msg) => {
+                    self.send_lifecycle_feedback(FinalSourceFeedbackValue::Osc(msg.clone()));
+                }
+                LifecycleAction::RunLua(script) => {
+                    let input = LuaLifecycleScriptInput {
+                        phase,
+                        params: self
+                            .collections
+                            .parameters
+                            .compartment_params(id.compartment),
+                        compartment_lua: self.basics.common_lua[id.compartment].as_ref(),
+                    };
+                    let outcome = match script.execute(input) {
+                        Ok(o) => o,
+                        Err(e) => {
+                            tracing::warn!(msg = "Failed to execute lifecycle script", ?id, %e);
+                            continue;
+                        }
+                    };
+                    for event in outcome.midi_messages {
+                        let value = MidiSourceValue::single_raw(None, event);
+                        self.send_lifecycle_feedback(FinalSourceFeedbackValue::Midi(value));
+                    }
+                    for msg in outcome.osc_messages {
+                        self.send_lifecycle_feedback(FinalSourceFeedbackValue::Osc(msg));
+                    }
+                }
+                LifecycleAction::SetTargetValue { mapping_key, value } => {
+                    let target_mapping_id = match mapping_key {
+                        None => Some(id),
+                        Some(key) => self.collections.mappings[id.compartment]
+                            .values()
+                            .find(|m| m.has_key(key))
+                            .map(|m| m.qualified_id()),
+                    };
+                    if let Some(target_mapping_id) = target_mapping_id {
+                        self.control_mapping_directly(
+                            target_mapping_id,
+                            *value,
+                            ControlLogContext::LifecycleHook,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn send_lifecycle_feedback(&self, value: FinalSourceFeedbackValue) {
+        self.basics.send_direct_device_feedback(
+            FeedbackReason::Normal,
+            FeedbackCause::Normal,
+            Some(value),
+        );
+    }
+
+    /// Controls the targets of the given mapping directly, bypassing the glue section.
+    /// Controls the target of the given mapping, bypassing its glue section.
+    fn control_mapping_directly(
+        &mut self,
+        id: QualifiedMappingId,
+        value: UnitValue,
+        log_context: ControlLogContext,
+    ) {
+        self.control_mapping_by_id(id, |basics, m, control_context, processor_context| {
+            m.control_from_target_directly(
+                control_context,
+                processor_context,
+                ControlValue::AbsoluteContinuous(value),
+                basics.target_control_logger(log_context, id),
+            )
+        });
+    }
+
+    /// Controls the given mapping as if its source had emitted the given value, so the value
+    /// passes its glue section.
+    fn control_mapping_via_glue(
+        &mut self,
+        id: QualifiedMappingId,
+        value: UnitValue,
+        log_context: ControlLogContext,
+    ) {
+        self.control_mapping_by_id(id, |basics, m, control_context, processor_context| {
+            let control_event = ControlEvent::new(
+                ControlValue::AbsoluteContinuous(value),
+                ControlEventTimestamp::from_main_thread(),
+            );
+            m.control_from_mode(
+                control_event,
+                ControlOptions::default(),
+                control_context,
+                processor_context,
+                m.last_non_performance_target_value(),
+                basics.target_control_logger(log_context, id),
+            )
+        });
+    }
+
+    fn control_mapping_by_id(
+        &mut self,
+        id: QualifiedMappingId,
+        control: impl FnOnce(
+            &Basics<EH>,
+            &mut MainMapping,
+            ControlContext,
+            ExtendedProcessorContext,
+        ) -> MappingControlResult,
+    ) {
+        let compartment = id.compartment;
+        let control_result = {
+            let Some(m) = self.collections.mappings[compartment].get_mut(&id.id) else {
+                return;
+            };
+            let control_context = self.basics.control_context(compartment);
+            let processor_context = ExtendedProcessorContext::new(
+                &self.basics.context,
+                &self.collections.parameters,
+                control_context,
+            );
+            let mut control_result = control(&self.basics, m, control_context, processor_context);
+            control_mapping_stage_two(
+                &self.basics,
+                &mut control_result,
+                m,
+                ManualFeedbackProcessing::On {
+                    mappings_with_virtual_targets: &self.collections.mappings_with_virtual_targets,
+                },
+            );
+            control_result
+        };
+        control_mapping_stage_three(
+            &self.basics,
+            &mut self.collections,
+            compartment,
+            control_result,
+            GroupInteractionProcessing::Off,
+        );
+    }
+
     /// Processes incoming control messages from the real-time processor.
     fn control_from_real_time(
         &mut self,
@@ -657,6 +907,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         self.process_parameter_tasks();
         self.process_feedback_tasks();
         self.process_unit_events();
+        self.process_lifecycle_hooks();
         self.poll_for_feedback();
     }
 
@@ -938,12 +1189,25 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                     compartment,
                     follow_mapping_id,
                 )?;
+                follow_mapping.process_source_gate_update(id.id, target_value);
                 follow_mapping.check_activation_effect_of_target_value_update(id.id, target_value)
             })
             .collect();
         self.process_activation_effects(compartment, activation_effects, false);
     }
 
+    fn process_reaper_state_change(&mut self, change: ReaperStateChange) {
+        for compartment in CompartmentKind::enum_iter() {
+            let activation_effects: Vec<MappingActivationEffect> = self
+                .all_mappings_in_compartment(compartment)
+                .filter_map(|m| {
+                    m.check_activation_effect_of_reaper_state_change(change, &self.basics.context)
+                })
+                .collect();
+            self.process_activation_effects(compartment, activation_effects, false);
+        }
+    }
+
     fn process_change_of_last_touched_target(&mut self) {
         // The last touched target has changed! We re-resolve all "Last touched" targets.
         for compartment in CompartmentKind::enum_iter() {
@@ -1261,6 +1525,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 NotifyConditionsChanged => {
                     conditions_changed = true;
                 }
+                ProcessReaperStateChange(change) => {
+                    self.process_reaper_state_change(change);
+                }
                 UpdateSingleMapping(mapping) => {
                     self.update_single_mapping(mapping);
                 }
@@ -1370,6 +1637,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             project_options,
         );
         if let Some(new_feedback_is_enabled) = new_feedback_is_enabled {
+            self.collections.lifecycle_hooks_are_outdated.set(true);
             if new_feedback_is_enabled {
                 for compartment in CompartmentKind::enum_iter() {
                     self.handle_feedback_after_having_updated_all_mappings(
@@ -1490,10 +1758,20 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         self.basics.target_based_conditional_activation_processors[compartment].clear();
         self.collections.previous_target_values[compartment].clear();
         self.poll_control_mappings[compartment].clear();
+        // Resolve envelope gate mappings
+        let gate_mapping_ids: Vec<_> = mappings
+            .iter()
+            .map(|m| {
+                let key = m.source_gate_mapping_key()?;
+                mappings.iter().find(|l| l.has_key(key)).map(|l| l.id())
+            })
+            .collect();
         // Refresh and splinter real-time mappings
         let real_time_mappings = mappings
             .iter_mut()
-            .map(|m| {
+            .zip(gate_mapping_ids)
+            .map(|(m, gate_mapping_id)| {
+                m.set_source_gate_mapping_id(gate_mapping_id);
                 mappings_by_group
                     .entry(m.group_id())
                     .or_default()
@@ -1530,10 +1808,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 if m.wants_to_be_polled_for_control() {
                     self.poll_control_mappings[compartment].insert(m.id());
                 }
-                let target_value_activation_reference_mappings =
-                    m.activation_can_be_affected_by_target_values();
+                let target_value_reference_mappings = m.target_value_lead_mappings();
                 self.basics.target_based_conditional_activation_processors[compartment]
-                    .notify_usage_add_only(m.id(), target_value_activation_reference_mappings);
+                    .notify_usage_add_only(m.id(), target_value_reference_mappings);
                 m.splinter_real_time_mapping()
             })
             .collect();
@@ -1700,6 +1977,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
     }
 
     pub fn process_additional_feedback_event(&mut self, event: &AdditionalFeedbackEvent) {
+        if let Some(change) = ReaperStateChange::from_additional_feedback_event(event) {
+            self.process_reaper_state_change(change);
+        }
         if matches!(event, AdditionalFeedbackEvent::LastTouchedTargetChanged) {
             self.process_change_of_last_touched_target();
         }
@@ -1776,6 +2056,23 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 .self_normal_sender
                 .send_complaining(NormalMainTask::PotentiallyEnableOrDisableControlOrFeedback);
         }
+        // Re-evaluate REAPER-state based activation conditions if necessary (deferred because we
+        // don't have mutable access at this point)
+        let mut reaper_state_changes: Vec<ReaperStateChange> = vec![];
+        for change in events
+            .iter()
+            .filter_map(ReaperStateChange::from_change_event)
+        {
+            if !reaper_state_changes.contains(&change) {
+                reaper_state_changes.push(change);
+            }
+        }
+        for change in reaper_state_changes {
+            self.basics
+                .channels
+                .self_normal_sender
+                .send_complaining(NormalMainTask::ProcessReaperStateChange(change));
+        }
         // Refresh targets if necessary
         let we_have_a_potential_target_change_event = events
             .iter()
@@ -2106,6 +2403,31 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
                 if !mapping_is_active {
                     continue;
                 }
+                // Text-producing sources (e.g. OSC string arguments) can control text-capable
+                // targets.
+                if let Some(text) = m.text_from_source(evt.payload()) {
+                    let options = ControlOptions {
+                        enforce_target_refresh,
+                        ..Default::default()
+                    };
+                    let control_result = control_mapping_with_text(
+                        &self.basics,
+                        &self.collections.parameters,
+                        m,
+                        &text,
+                        options,
+                        ManualFeedbackProcessing::On {
+                            mappings_with_virtual_targets: &self
+                                .collections
+                                .mappings_with_virtual_targets,
+                        },
+                    );
+                    if control_result.is_some() {
+                        enforce_target_refresh = true;
+                        match_outcome.upgrade_from(MatchOutcome::Matched);
+                        continue;
+                    }
+                }
                 let control_value = match control_outcome {
                     Some(ControlOutcome::Matched(v)) => v,
                     _ => continue,
@@ -2234,6 +2556,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
     }
 
     fn update_single_mapping_on_state(&self, id: QualifiedMappingId) {
+        self.collections.lifecycle_hooks_are_outdated.set(true);
         let is_on =
             if let Some(m) = self.get_normal_or_virtual_target_mapping(id.compartment, id.id) {
                 m.is_effectively_on()
@@ -2249,6 +2572,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
     }
 
     fn update_on_mappings(&self) {
+        self.collections.lifecycle_hooks_are_outdated.set(true);
         let on_mappings = self
             .all_mappings()
             .filter(|m| m.is_effectively_on())
@@ -2592,6 +2916,31 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             compartment,
         );
         self.basics.clear_last_feedback();
+        // Resolve envelope gate mappings, both of this mapping and of the ones using it as gate
+        let gate_mapping_id = mapping.source_gate_mapping_key().and_then(|key| {
+            self.all_mappings_in_compartment(compartment)
+                .find(|m| m.has_key(key))
+                .map(|m| m.id())
+        });
+        mapping.set_source_gate_mapping_id(gate_mapping_id);
+        let gated_mapping_ids: Vec<_> = self
+            .all_mappings_in_compartment(compartment)
+            .filter(|m| {
+                m.source_gate_mapping_key()
+                    .is_some_and(|key| mapping.has_key(key))
+            })
+            .map(|m| m.id())
+            .collect();
+        for gated_mapping_id in gated_mapping_ids {
+            if let Some(m) = self.get_normal_or_virtual_target_mapping_mut(QualifiedMappingId::new(
+                compartment,
+                gated_mapping_id,
+            )) {
+                m.set_source_gate_mapping_id(Some(mapping.id()));
+            }
+            self.basics.target_based_conditional_activation_processors[compartment]
+                .notify_usage_add_only(gated_mapping_id, std::iter::once(mapping.id()));
+        }
         // Refresh
         let control_context = self.basics.control_context(compartment);
         mapping.init_target_and_activation(
@@ -2603,7 +2952,7 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
             control_context,
         );
         let initial_target_value = mapping.initial_target_value();
-        let lead_mapping_ids = mapping.activation_can_be_affected_by_target_values();
+        let lead_mapping_ids = mapping.target_value_lead_mappings();
         // Sync to real-time processor
         self.basics
             .channels
@@ -2814,10 +3163,9 @@ impl<EH: DomainEventHandler> MainProcessor<EH> {
         } else {
             self.poll_control_mappings[compartment].shift_remove(&m.id());
         }
-        let target_value_activation_reference_mappings =
-            m.activation_can_be_affected_by_target_values();
+        let target_value_reference_mappings = m.target_value_lead_mappings();
         self.basics.target_based_conditional_activation_processors[compartment]
-            .notify_usage(m.id(), target_value_activation_reference_mappings);
+            .notify_usage(m.id(), target_value_reference_mappings);
         let relevant_map = if m.has_virtual_target() {
             self.collections.mappings[compartment].shift_remove(&m.id());
             &mut self.collections.mappings_with_virtual_targets
@@ -2915,6 +3263,8 @@ pub enum NormalMainTask {
     /// It will trigger a refresh of all targets (re-resolve) or even a preset change (if
     /// auto-load is enabled).
     NotifyConditionsChanged,
+    /// This should be sent on events which might affect REAPER-state based activation conditions.
+    ProcessReaperStateChange(ReaperStateChange),
     UpdateSettings(BasicSettings),
     UpdateCompartmentSettings(CompartmentKind, CompartmentSettings),
     PotentiallyEnableOrDisableControlOrFeedback,
@@ -3120,6 +3470,9 @@ pub struct ControlOptions {
     /// This information is used by some particular targets whose work is partially done in real-time and partially
     /// in the main thread.
     pub coming_from_real_time: bool,
+    /// This means the primary target of a multi-target mapping has been controlled in the
+    /// real-time processor already, so only the additional targets should be controlled.
+    pub skip_primary_target: bool,
 }
 
 impl<EH: DomainEventHandler> Drop for MainProcessor<EH> {
@@ -3749,6 +4102,7 @@ impl<EH: DomainEventHandler> Basics<EH> {
                         enforce_target_refresh: false,
                         // If we are here, we know control originated in main thread, not in real-time
                         coming_from_real_time: false,
+                        skip_primary_target: false,
                     },
                     params,
                 );
@@ -4246,6 +4600,37 @@ fn control_mapping_stage_one_and_two<EH: DomainEventHandler>(
     control_result
 }
 
+/// Controls the text-capable targets of the given mapping with text, including manual feedback.
+///
+/// Returns `None` if the mapping doesn't have any target which supports text input. Text-capable
+/// targets don't return hit instructions and text doesn't participate in group interaction, so
+/// there's no stage three.
+fn control_mapping_with_text<EH: DomainEventHandler>(
+    basics: &Basics<EH>,
+    params: &PluginParams,
+    m: &mut MainMapping,
+    text: &str,
+    options: ControlOptions,
+    feedback_handling: ManualFeedbackProcessing,
+) -> Option<MappingControlResult> {
+    let control_context = basics.control_context(m.compartment());
+    let mut control_result = m.control_with_text(
+        text,
+        options,
+        control_context,
+        ExtendedProcessorContext::new(&basics.context, params, control_context),
+        basics.target_control_logger(ControlLogContext::Normal, m.qualified_id()),
+    )?;
+    basics
+        .event_handler
+        .notify_mapping_matched(m.compartment(), m.id());
+    if control_result.at_least_one_target_caused_effect && control_result.celebrate_success {
+        basics.celebrate_success();
+    }
+    control_mapping_stage_two(basics, &mut control_result, m, feedback_handling);
+    Some(control_result)
+}
+
 /// Executes stage one of a typical mapping control invocation.
 ///
 /// Takes care of:
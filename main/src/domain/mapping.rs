@@ -2,16 +2,18 @@ use crate::domain::{
     prop_feedback_resolution, prop_is_affected_by, ActivationChange, ActivationCondition,
     BoxedHitInstruction, CompartmentParamIndex, CompoundChangeEvent, ControlContext, ControlEvent,
     ControlEventTimestamp, ControlOptions, ExtendedProcessorContext, FeedbackResolution, GroupId,
-    HitResponse, KeyMessage, KeySource, MappingActivationEffect, MappingControlContext,
-    MappingData, MappingInfo, MappingPropProvider, MessageCaptureEvent, MidiScanResult, MidiSource,
-    Mode, OscDeviceId, OscScanResult, PersistentMappingProcessingState, PluginParamIndex,
-    PluginParams, RealTimeMappingUpdate, RealTimeReaperTarget, RealTimeTargetUpdate,
-    RealearnParameterChangePayload, RealearnParameterSource, RealearnSourceContext, RealearnTarget,
-    ReaperMessage, ReaperSource, ReaperSourceFeedbackValue, ReaperTarget, ReaperTargetType,
+    HitResponse, KeyMessage, KeySource, LuaLifecycleScript, MappingActivationEffect,
+    MappingControlContext, MappingData, MappingInfo, MappingPropProvider, MessageCaptureEvent,
+    MidiScanResult, MidiSource, Mode, OscDeviceId, OscScanResult, PersistentMappingProcessingState,
+    PluginParamIndex, PluginParams, ProcessorContext, RealTimeMappingUpdate, RealTimeReaperTarget,
+    RealTimeTargetUpdate, RealearnParameterChangePayload, RealearnParameterSource,
+    RealearnSourceContext, RealearnTarget, ReaperMessage, ReaperSource, ReaperSourceFeedbackValue,
+    ReaperSourcePollContext, ReaperStateChange, ReaperTarget, ReaperTargetType, SmoothingSettings,
     StreamDeckDeviceId, StreamDeckMessage, StreamDeckScanResult, StreamDeckSource,
-    StreamDeckSourceAddress, StreamDeckSourceFeedbackValue, Tag, TargetCharacter, TrackExclusivity,
-    UnresolvedReaperTarget, VirtualControlElement, VirtualFeedbackValue, VirtualSource,
-    VirtualSourceAddress, VirtualSourceValue, VirtualTarget, COMPARTMENT_PARAMETER_COUNT,
+    StreamDeckSourceAddress, StreamDeckSourceFeedbackValue, Tag, TargetCharacter,
+    TargetValueSmoother, TrackExclusivity, UnresolvedReaperTarget, VirtualControlElement,
+    VirtualFeedbackValue, VirtualSource, VirtualSourceAddress, VirtualSourceValue, VirtualTarget,
+    COMPARTMENT_PARAMETER_COUNT,
 };
 use derive_more::Display;
 use enum_map::Enum;
@@ -26,17 +28,20 @@ use helgoboss_midi::{Channel, RawShortMessage, ShortMessage};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::borrow::Cow;
 use std::cell::Cell;
+use std::cmp::Ordering;
 
 use crate::domain::unresolved_reaper_target::UnresolvedReaperTargetDef;
 use base::hash_util::{NonCryptoHashSet, NonCryptoIndexMap, NonCryptoIndexSet};
 use playtime_api::persistence::{ColumnAddress, RowAddress, SlotAddress};
 use reaper_high::{Fx, Project, Track, TrackRoute};
 use reaper_medium::MidiInputDeviceId;
-use rosc::OscMessage;
+use rosc::address::{Matcher, OscAddress};
+use rosc::{OscMessage, OscType};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::fmt;
 use std::fmt::{Display, Formatter, Write};
+use std::iter;
 use std::ops::RangeInclusive;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
@@ -163,16 +168,55 @@ pub struct LifecycleMidiData {
     pub deactivation_midi_messages: Vec<LifecycleMidiMessage>,
 }
 
+/// Lifecycle actions which are carried out in the main thread (in contrast to lifecycle MIDI).
+#[derive(Debug, Default)]
+pub struct LifecycleMainData {
+    pub activation_actions: Vec<LifecycleAction>,
+    pub deactivation_actions: Vec<LifecycleAction>,
+}
+
+impl LifecycleMainData {
+    pub fn is_empty(&self) -> bool {
+        self.activation_actions.is_empty() && self.deactivation_actions.is_empty()
+    }
+
+    pub fn actions(&self, phase: LifecyclePhase) -> &[LifecycleAction] {
+        use LifecyclePhase::*;
+        match phase {
+            Activation => &self.activation_actions,
+            Deactivation => &self.deactivation_actions,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LifecycleAction {
+    SendOsc(OscMessage),
+    RunLua(Box<LuaLifecycleScript<'static>>),
+    SetTargetValue {
+        /// If `None`, the target of the mapping itself is set.
+        mapping_key: Option<MappingKey>,
+        value: UnitValue,
+    },
+}
+
 #[derive(Debug, Default)]
 pub struct MappingExtension {
     /// If it's None, it means it's splintered already.
     lifecycle_midi_data: Option<LifecycleMidiData>,
+    /// Shared with the main processor, which needs the deactivation actions even after the
+    /// mapping is gone.
+    lifecycle_main_data: Rc<LifecycleMainData>,
 }
 
 impl MappingExtension {
-    pub fn new(lifecycle_midi_data: LifecycleMidiData) -> Self {
+    pub fn new(
+        lifecycle_midi_data: LifecycleMidiData,
+        lifecycle_main_data: LifecycleMainData,
+    ) -> Self {
         Self {
             lifecycle_midi_data: Some(lifecycle_midi_data),
+            lifecycle_main_data: Rc::new(lifecycle_main_data),
         }
     }
 }
@@ -193,6 +237,12 @@ pub struct MainMapping {
     unresolved_target: Option<UnresolvedCompoundMappingTarget>,
     /// Is non-empty if the target resolved successfully.
     targets: Vec<CompoundMappingTarget>,
+    /// Further targets of a multi-target mapping, ordered by their position.
+    additional_targets: Vec<AdditionalMappingTarget>,
+    /// Position of the primary target within the user-defined order of all targets.
+    primary_target_position: usize,
+    /// Is `Some` if the glue of the primary target smoothes target values.
+    smoother: Option<TargetValueSmoother>,
     activation_condition_1: ActivationCondition,
     activation_condition_2: ActivationCondition,
     activation_state: ActivationState,
@@ -202,6 +252,74 @@ pub struct MainMapping {
     last_non_performance_target_value: Cell<Option<AbsoluteValue>>,
 }
 
+/// A target which is controlled by the same source as the primary target of a multi-target mapping,
+/// but with its own mode.
+///
+/// Additional targets don't contribute to feedback. The primary target of the mapping is the
+/// feedback lead.
+#[derive(Debug)]
+pub struct AdditionalMappingTarget {
+    /// Position within the user-defined order of all targets of the mapping.
+    position: usize,
+    mode: Mode,
+    unresolved_target: UnresolvedCompoundMappingTarget,
+    /// Is non-empty if the target resolved successfully and its conditions are met.
+    targets: Vec<CompoundMappingTarget>,
+    /// Is `Some` if the glue of this target smoothes target values.
+    smoother: Option<TargetValueSmoother>,
+}
+
+impl AdditionalMappingTarget {
+    pub fn new(
+        position: usize,
+        mode: Mode,
+        smoothing: Option<SmoothingSettings>,
+        unresolved_target: UnresolvedCompoundMappingTarget,
+    ) -> Self {
+        Self {
+            position,
+            mode,
+            unresolved_target,
+            targets: vec![],
+            smoother: smoothing.map(TargetValueSmoother::new),
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        context: ExtendedProcessorContext,
+        control_context: ControlContext,
+        compartment: CompartmentKind,
+        is_just_refresh: bool,
+    ) {
+        let targets = match self.unresolved_target.resolve(context, compartment) {
+            Ok(targets) if self.unresolved_target.conditions_are_met(&targets) => {
+                if let Some(t) = targets.first() {
+                    self.mode
+                        .update_from_target(t, control_context, is_just_refresh);
+                }
+                targets
+            }
+            _ => vec![],
+        };
+        if targets != self.targets {
+            if let Some(smoother) = &mut self.smoother {
+                smoother.stop();
+            }
+        }
+        self.targets = targets;
+    }
+
+    fn smoothing_slot(
+        &mut self,
+    ) -> (
+        &mut Vec<CompoundMappingTarget>,
+        &mut Option<TargetValueSmoother>,
+    ) {
+        (&mut self.targets, &mut self.smoother)
+    }
+}
+
 #[derive(Default, Debug)]
 struct ActivationState {
     is_active_1: bool,
@@ -225,8 +343,11 @@ impl MainMapping {
         tags: Vec<Tag>,
         source: CompoundMappingSource,
         mode: Mode,
+        smoothing: Option<SmoothingSettings>,
         group_interaction: GroupInteraction,
         unresolved_target: Option<UnresolvedCompoundMappingTarget>,
+        additional_targets: Vec<AdditionalMappingTarget>,
+        primary_target_position: usize,
         activation_condition_1: ActivationCondition,
         activation_condition_2: ActivationCondition,
         options: ProcessorMappingOptions,
@@ -252,6 +373,9 @@ impl MainMapping {
             tags,
             unresolved_target,
             targets: vec![],
+            additional_targets,
+            primary_target_position,
+            smoother: smoothing.map(TargetValueSmoother::new),
             activation_condition_1,
             activation_condition_2,
             activation_state: Default::default(),
@@ -382,6 +506,10 @@ impl MainMapping {
         }
     }
 
+    pub fn lifecycle_main_data(&self) -> &Rc<LifecycleMainData> {
+        &self.extension.lifecycle_main_data
+    }
+
     pub fn splinter_real_time_mapping(&mut self) -> RealTimeMapping {
         RealTimeMapping {
             core: MappingCore {
@@ -398,6 +526,7 @@ impl MainMapping {
             }),
             target_is_resolved: !self.targets.is_empty(),
             resolved_target: self.splinter_first_real_time_target(),
+            has_additional_targets: !self.additional_targets.is_empty(),
             lifecycle_midi_data: self
                 .extension
                 .lifecycle_midi_data
@@ -406,7 +535,21 @@ impl MainMapping {
         }
     }
 
+    /// Returns the real-time target of the primary target.
+    ///
+    /// In multi-target mappings, only the primary target is controlled in real-time. The real-time
+    /// processor forwards the control event to the main thread in order to control the additional
+    /// targets.
     pub fn splinter_first_real_time_target(&self) -> Option<RealTimeCompoundMappingTarget> {
+        if self.smoother.is_some() {
+            // Smoothing happens in the main thread over time. Hitting the target in real-time would
+            // make it jump to the desired value immediately.
+            return None;
+        }
+        if self.control_transformation_requires_main_thread() {
+            // Lua transformations are not available in the real-time processor.
+            return None;
+        }
         self.targets
             .first()
             .and_then(|t| t.splinter_real_time_target())
@@ -449,6 +592,21 @@ impl MainMapping {
         MappingActivationEffect::new(self.id(), effect_1, effect_2)
     }
 
+    /// Returns `Some` if this affects the mapping's activation state in any way.
+    pub fn check_activation_effect_of_reaper_state_change(
+        &self,
+        change: ReaperStateChange,
+        context: &ProcessorContext,
+    ) -> Option<MappingActivationEffect> {
+        let effect_1 = self
+            .activation_condition_1
+            .process_reaper_state_change(change, context);
+        let effect_2 = self
+            .activation_condition_2
+            .process_reaper_state_change(change, context);
+        MappingActivationEffect::new(self.id(), effect_1, effect_2)
+    }
+
     /// Returns `Some` if this affects the mapping's activation state in any way.
     pub fn check_activation_effect_of_param_update(
         &self,
@@ -495,10 +653,27 @@ impl MainMapping {
     /// In particular, it returns the IDs of the lead mappings (the ones which provide the
     /// target values that influence the activation state).
     pub fn activation_can_be_affected_by_target_values(&self) -> impl Iterator<Item = MappingId> {
-        self.activation_condition_1
-            .target_value_lead_mapping()
-            .into_iter()
-            .chain(self.activation_condition_2.target_value_lead_mapping())
+        let mut lead_mappings = self.activation_condition_1.target_value_lead_mappings();
+        lead_mappings.extend(self.activation_condition_2.target_value_lead_mappings());
+        lead_mappings.into_iter()
+    }
+
+    /// Returns the mappings whose target values affect this mapping, either its activation or its
+    /// source (envelope gate).
+    pub fn target_value_lead_mappings(&self) -> impl Iterator<Item = MappingId> {
+        self.activation_can_be_affected_by_target_values()
+            .chain(self.source_gate_mapping_id())
+    }
+
+    /// Returns the parameter states which must be given for this mapping to be active.
+    pub fn required_param_states(&self) -> RequiredParamStates {
+        let mut states = self.activation_condition_1.required_param_states();
+        states.merge(&self.activation_condition_2.required_param_states());
+        states
+    }
+
+    pub fn unresolved_target(&self) -> Option<&UnresolvedCompoundMappingTarget> {
+        self.unresolved_target.as_ref()
     }
 
     pub fn update_activation_from_effect(
@@ -543,7 +718,10 @@ impl MainMapping {
         let (targets, is_active) = self.resolve_target(context, control_context, false);
         self.targets = targets;
         self.core.options.target_is_active = is_active;
-        self.update_activation_from_params(context.params());
+        for t in &mut self.additional_targets {
+            t.resolve(context, control_context, self.core.compartment, false);
+        }
+        self.init_activation(context);
         let target_value = self.current_aggregated_target_value(control_context);
         self.initial_target_value = target_value;
         self.last_non_performance_target_value = Cell::new(target_value);
@@ -610,7 +788,27 @@ impl MainMapping {
     }
 
     pub fn wants_to_be_polled_for_control(&self) -> bool {
-        self.core.source.wants_to_be_polled() || self.core.mode.wants_to_be_polled()
+        self.core.source.wants_to_be_polled()
+            || self.core.mode.wants_to_be_polled()
+            || self.uses_smoothing()
+    }
+
+    /// Returns `true` if the glue of at least one target smoothes target values.
+    pub fn uses_smoothing(&self) -> bool {
+        self.smoother.is_some() || self.additional_targets.iter().any(|t| t.smoother.is_some())
+    }
+
+    /// Returns `true` if at least one target hasn't reached its smoothed desired value yet.
+    pub fn is_smoothing(&self) -> bool {
+        self.smoothers().any(|s| s.is_smoothing())
+    }
+
+    fn smoothers(&self) -> impl Iterator<Item = &TargetValueSmoother> {
+        self.smoother.iter().chain(
+            self.additional_targets
+                .iter()
+                .filter_map(|t| t.smoother.as_ref()),
+        )
     }
 
     /// The boolean return value tells if the resolved target changed in some way, the activation
@@ -621,6 +819,7 @@ impl MainMapping {
         context: ExtendedProcessorContext,
         control_context: ControlContext,
     ) -> Option<RealTimeTargetUpdate> {
+        self.refresh_additional_targets(context, control_context);
         match self.unresolved_target.as_ref() {
             None => return None,
             Some(t) => {
@@ -632,6 +831,11 @@ impl MainMapping {
         let was_effectively_active_before = self.target_is_effectively_active();
         let (targets, is_active) = self.resolve_target(context, control_context, true);
         let target_changed = targets != self.targets;
+        if target_changed {
+            if let Some(smoother) = &mut self.smoother {
+                smoother.stop();
+            }
+        }
         self.targets = targets;
         self.core.options.target_is_active = is_active;
         // Build real-time target update if necessary
@@ -656,6 +860,18 @@ impl MainMapping {
         Some(update)
     }
 
+    fn refresh_additional_targets(
+        &mut self,
+        context: ExtendedProcessorContext,
+        control_context: ControlContext,
+    ) {
+        for t in &mut self.additional_targets {
+            if t.unresolved_target.can_be_affected_by_change_events() {
+                t.resolve(context, control_context, self.core.compartment, true);
+            }
+        }
+    }
+
     pub fn update_activation_from_params(
         &mut self,
         params: &PluginParams,
@@ -667,6 +883,17 @@ impl MainMapping {
         )
     }
 
+    /// Evaluates the activation conditions from scratch, in one pass.
+    fn init_activation(&mut self, context: ExtendedProcessorContext) {
+        let compartment_params = context.params().compartment_params(self.core.compartment);
+        self.update_activation(
+            self.activation_condition_1
+                .evaluate(compartment_params, context.context()),
+            self.activation_condition_2
+                .evaluate(compartment_params, context.context()),
+        );
+    }
+
     fn update_activation(
         &mut self,
         is_active_1: Option<bool>,
@@ -761,11 +988,98 @@ impl MainMapping {
             context,
             processor_context,
             true,
+            None,
             log_mode_control_result,
             |_, context, mode, target| mode.poll(target, context, timestamp),
         )
     }
 
+    /// This moves targets with smoothed glue towards their desired values.
+    #[must_use]
+    pub fn poll_smoothing(
+        &mut self,
+        context: ControlContext,
+        log_mode_control_result: impl Fn(ControlLogEntry),
+    ) -> MappingControlResult {
+        let mut result = MappingControlResult::default();
+        let ctx = MappingControlContext {
+            control_context: context,
+            mapping_data: self.data(),
+            coming_from_real_time: false,
+        };
+        let invocation_count = self.core.invocation_count();
+        let now = Instant::now();
+        let steps = step_smoothers(
+            self.smoothing_slots().map(|(targets, smoother)| {
+                let reaper_target_count = targets.iter().filter(|t| !t.is_virtual()).count();
+                (reaper_target_count, smoother.as_mut())
+            }),
+            now,
+        );
+        if steps.is_empty() {
+            return result;
+        }
+        let reaper_targets = self
+            .smoothing_slots()
+            .flat_map(|(targets, _)| targets.iter_mut())
+            .filter_map(|t| match t {
+                CompoundMappingTarget::Reaper(t) => Some(t),
+                _ => None,
+            });
+        let mut steps = steps.into_iter().peekable();
+        for (target_index, target) in reaper_targets.enumerate() {
+            let Some((_, value)) = steps.next_if(|(i, _)| *i == target_index) else {
+                continue;
+            };
+            result.at_least_one_target_was_reached = true;
+            let control_value = ControlValue::AbsoluteContinuous(value);
+            let (kind, error) = match target.hit(control_value, ctx) {
+                // Intermediate values don't create hit instructions
+                Ok(response) if response.hit_instruction.is_some() => {
+                    (ControlLogEntryKind::DiscardedHitInstruction, "")
+                }
+                Ok(response) if response.caused_effect => {
+                    result.at_least_one_target_caused_effect = true;
+                    (ControlLogEntryKind::HitSuccessfully, "")
+                }
+                Ok(_) => (ControlLogEntryKind::IgnoredByTarget, ""),
+                Err(msg) => (ControlLogEntryKind::HitFailed, msg),
+            };
+            log_mode_control_result(ControlLogEntry {
+                kind,
+                control_value: Some(control_value),
+                target_index,
+                invocation_count,
+                error,
+            });
+        }
+        result
+    }
+
+    /// Returns the targets and smoothers of all target slots, in control order.
+    fn smoothing_slots(
+        &mut self,
+    ) -> impl Iterator<
+        Item = (
+            &mut Vec<CompoundMappingTarget>,
+            &mut Option<TargetValueSmoother>,
+        ),
+    > {
+        let primary_slot = self
+            .additional_targets
+            .partition_point(|t| t.position < self.primary_target_position);
+        let (before, after) = self.additional_targets.split_at_mut(primary_slot);
+        before
+            .iter_mut()
+            .map(AdditionalMappingTarget::smoothing_slot)
+            .chain(iter::once((&mut self.targets, &mut self.smoother)))
+            .chain(
+                after
+                    .iter_mut()
+                    .map(AdditionalMappingTarget::smoothing_slot),
+            )
+    }
+
     pub fn group_interaction(&self) -> GroupInteraction {
         self.core.group_interaction
     }
@@ -790,6 +1104,7 @@ impl MainMapping {
             context,
             processor_context,
             false,
+            None,
             log_mode_control_result,
             |options, context, mode, target| {
                 mode.control_with_options(
@@ -833,6 +1148,7 @@ impl MainMapping {
             context,
             processor_context,
             false,
+            None,
             log_mode_control_result,
             |_, _, mode, target| {
                 let mut v = value;
@@ -865,9 +1181,19 @@ impl MainMapping {
             mapping_id: self.core.id,
             group_id: self.core.group_id,
             last_non_performance_target_value: self.last_non_performance_target_value(),
+            uses_lua_transformation: self.control_transformation_requires_main_thread(),
         }
     }
 
+    fn control_transformation_requires_main_thread(&self) -> bool {
+        self.core
+            .mode
+            .settings()
+            .control_transformation
+            .as_ref()
+            .is_some_and(|t| t.requires_main_thread())
+    }
+
     #[must_use]
     pub fn control_from_target_directly(
         &mut self,
@@ -883,6 +1209,7 @@ impl MainMapping {
             context,
             processor_context,
             false,
+            None,
             log_mode_control_result,
             |_, _, _, _| Some(ModeControlResult::hit_target(value)),
         )
@@ -896,6 +1223,9 @@ impl MainMapping {
         context: ControlContext,
         processor_context: ExtendedProcessorContext,
         is_polling: bool,
+        // If given, targets are hit with this text instead of the value returned by the mode.
+        // Targets which don't support text input are skipped.
+        text: Option<&str>,
         log_mode_control_result: impl Fn(ControlLogEntry),
         get_mode_control_result: impl Fn(
             ControlOptions,
@@ -930,6 +1260,9 @@ impl MainMapping {
         } else {
             vec![]
         };
+        if options.enforce_target_refresh {
+            self.refresh_additional_targets(processor_context, context);
+        }
         let ctx = MappingControlContext {
             control_context: context,
             mapping_data: self.data(),
@@ -940,87 +1273,155 @@ impl MainMapping {
         } else {
             &mut self.targets
         };
-        self.core.increase_invocation_count();
-        for (target_index, target) in actual_targets.iter_mut().enumerate() {
-            let target = if let CompoundMappingTarget::Reaper(t) = target {
-                t
-            } else {
-                continue;
-            };
-            at_least_one_relevant_target_exists = true;
-            let (log_entry_kind, control_value, error) =
-                match get_mode_control_result(options, ctx, &mut self.core.mode, target) {
-                    None => {
-                        // The incoming source value doesn't reach the target because the source value
-                        // was filtered out. If `send_feedback_after_control` is enabled, we
-                        // still send feedback - this can be useful with controllers which insist on
-                        // controlling the LED on their own. The feedback sent by ReaLearn
-                        // will fix this self-controlled LED state.
-                        (ControlLogEntryKind::IgnoredByGlue, None, "")
+        if !options.skip_primary_target {
+            // Otherwise the real-time processor increased it already.
+            self.core.increase_invocation_count();
+        }
+        let invocation_count = self.core.invocation_count();
+        // In multi-target mappings, the targets are controlled in the user-defined order, each one
+        // with its own mode. The primary target is not necessarily the first one.
+        let primary_slot = self
+            .additional_targets
+            .partition_point(|t| t.position < self.primary_target_position);
+        let mut target_index = 0;
+        let now = Instant::now();
+        for slot in 0..=self.additional_targets.len() {
+            let (mode, targets, smoother, unresolved_target) =
+                if let Some(i) = additional_target_index(slot, primary_slot) {
+                    let t = &mut self.additional_targets[i];
+                    (
+                        &mut t.mode,
+                        &mut t.targets,
+                        &mut t.smoother,
+                        Some(&t.unresolved_target),
+                    )
+                } else {
+                    if options.skip_primary_target {
+                        target_index += actual_targets.len();
+                        continue;
                     }
-                    Some(HitTarget { value }) => {
-                        at_least_one_target_was_reached = true;
-                        if !is_polling {
-                            self.core.time_of_last_control = Some(Instant::now());
+                    (
+                        &mut self.core.mode,
+                        &mut *actual_targets,
+                        &mut self.smoother,
+                        self.unresolved_target.as_ref(),
+                    )
+                };
+            let mut slot_target_index = 0;
+            for target in targets.iter_mut() {
+                let target = if let CompoundMappingTarget::Reaper(t) = target {
+                    t
+                } else {
+                    continue;
+                };
+                if text.is_some() && !target.supports_text_input() {
+                    continue;
+                }
+                at_least_one_relevant_target_exists = true;
+                let (log_entry_kind, control_value, error) =
+                    match get_mode_control_result(options, ctx, mode, target) {
+                        None => {
+                            // The incoming source value doesn't reach the target because the source value
+                            // was filtered out. If `send_feedback_after_control` is enabled, we
+                            // still send feedback - this can be useful with controllers which insist on
+                            // controlling the LED on their own. The feedback sent by ReaLearn
+                            // will fix this self-controlled LED state.
+                            (ControlLogEntryKind::IgnoredByGlue, None, "")
                         }
-                        // Be graceful here.
-                        let (log_entry_kind, error) = match target.hit(value, ctx) {
-                            Ok(response) => {
-                                if response.caused_effect {
-                                    at_least_one_target_caused_effect = true;
-                                }
-                                let log_entry_kind = if let Some(hi) = response.hit_instruction {
-                                    // We have a hit instruction! Save it so it can be executed in
-                                    // the next step.
-                                    // TODO-low For now, the first hit instruction wins (at the moment we don't
-                                    //  have multi-targets in which multiple targets send hit instructions
-                                    //  anyway).
-                                    if first_hit_instruction.is_none() {
-                                        first_hit_instruction = Some(hi);
-                                        ControlLogEntryKind::CreatedHitInstruction
-                                    } else {
-                                        ControlLogEntryKind::DiscardedHitInstruction
-                                    }
-                                } else if response.caused_effect {
-                                    ControlLogEntryKind::HitSuccessfully
-                                } else {
-                                    ControlLogEntryKind::IgnoredByTarget
-                                };
-                                (log_entry_kind, "")
+                        Some(HitTarget {
+                            value: ControlValue::AbsoluteContinuous(desired),
+                        }) if smoother.is_some() && text.is_none() => {
+                            // Don't hit the target right now. It will be moved towards the desired
+                            // value step by step when polling.
+                            at_least_one_target_was_reached = true;
+                            if !is_polling {
+                                self.core.time_of_last_control = Some(now);
                             }
-                            Err(msg) => {
-                                debug!("Control failed: {}", msg);
-                                (ControlLogEntryKind::HitFailed, msg)
+                            if let Some(smoother) = smoother.as_mut() {
+                                let current = target
+                                    .current_value(context)
+                                    .map(|v| v.to_unit_value())
+                                    .unwrap_or(desired);
+                                smoother.start(slot_target_index, current, desired, now);
                             }
-                        };
-                        if should_send_manual_feedback_due_to_target(
-                            target,
-                            &self.core.options,
-                            &self.activation_state,
-                            self.unresolved_target.as_ref(),
-                        ) {
-                            send_manual_feedback_because_of_target = true;
+                            (
+                                ControlLogEntryKind::StartedSmoothing,
+                                Some(ControlValue::AbsoluteContinuous(desired)),
+                                "",
+                            )
                         }
-                        (log_entry_kind, Some(value), error)
-                    }
-                    Some(LeaveTargetUntouched(v)) => {
-                        // The target already has the desired value.
-                        // If `send_feedback_after_control` is enabled, we still send feedback - this
-                        // can be useful with controllers which insist on controlling the LED on their
-                        // own. The feedback sent by ReaLearn will fix this self-controlled LED state.
-                        at_least_one_target_was_reached = true;
-                        (ControlLogEntryKind::LeftTargetUntouched, Some(v), "")
-                    }
+                        Some(HitTarget { value }) => {
+                            at_least_one_target_was_reached = true;
+                            if !is_polling {
+                                self.core.time_of_last_control = Some(now);
+                            }
+                            let hit_result = match text {
+                                None => target.hit(value, ctx),
+                                Some(text) => target.hit_with_text(text, ctx),
+                            };
+                            // Be graceful here.
+                            let (log_entry_kind, error) = match hit_result {
+                                Ok(response) => {
+                                    if response.caused_effect {
+                                        at_least_one_target_caused_effect = true;
+                                    }
+                                    let log_entry_kind = if let Some(hi) = response.hit_instruction
+                                    {
+                                        // We have a hit instruction! Save it so it can be executed in
+                                        // the next step.
+                                        // TODO-low For now, the first hit instruction wins (multi-target
+                                        //  mappings in which multiple targets send hit instructions are
+                                        //  rare).
+                                        if first_hit_instruction.is_none() {
+                                            first_hit_instruction = Some(hi);
+                                            ControlLogEntryKind::CreatedHitInstruction
+                                        } else {
+                                            ControlLogEntryKind::DiscardedHitInstruction
+                                        }
+                                    } else if response.caused_effect {
+                                        ControlLogEntryKind::HitSuccessfully
+                                    } else {
+                                        ControlLogEntryKind::IgnoredByTarget
+                                    };
+                                    (log_entry_kind, "")
+                                }
+                                Err(msg) => {
+                                    debug!("Control failed: {}", msg);
+                                    (ControlLogEntryKind::HitFailed, msg)
+                                }
+                            };
+                            if should_send_manual_feedback_due_to_target(
+                                target,
+                                &self.core.options,
+                                &self.activation_state,
+                                unresolved_target,
+                            ) {
+                                send_manual_feedback_because_of_target = true;
+                            }
+                            let logged_value = if text.is_none() { Some(value) } else { None };
+                            (log_entry_kind, logged_value, error)
+                        }
+                        Some(LeaveTargetUntouched(v)) => {
+                            // The target already has the desired value.
+                            // If `send_feedback_after_control` is enabled, we still send feedback - this
+                            // can be useful with controllers which insist on controlling the LED on their
+                            // own. The feedback sent by ReaLearn will fix this self-controlled LED state.
+                            at_least_one_target_was_reached = true;
+                            (ControlLogEntryKind::LeftTargetUntouched, Some(v), "")
+                        }
+                    };
+                // Log
+                let log_entry = ControlLogEntry {
+                    kind: log_entry_kind,
+                    control_value,
+                    target_index,
+                    invocation_count,
+                    error,
                 };
-            // Log
-            let log_entry = ControlLogEntry {
-                kind: log_entry_kind,
-                control_value,
-                target_index,
-                invocation_count: self.core.invocation_count(),
-                error,
-            };
-            log_mode_control_result(log_entry);
+                log_mode_control_result(log_entry);
+                target_index += 1;
+                slot_target_index += 1;
+            }
         }
         if send_manual_feedback_because_of_target {
             let new_target_value = self.current_aggregated_target_value(context);
@@ -1070,6 +1471,10 @@ impl MainMapping {
         }
     }
 
+    pub fn has_key(&self, key: &MappingKey) -> bool {
+        &*self.key == key.as_ref()
+    }
+
     pub fn virtual_source_control_element(&self) -> Option<VirtualControlElement> {
         match &self.core.source {
             CompoundMappingSource::Virtual(s) => Some(s.control_element()),
@@ -1345,10 +1750,108 @@ impl MainMapping {
         }
     }
 
+    /// Extracts text from the given source message if the source delivers text instead of a
+    /// numeric value (currently OSC string and color arguments).
+    pub fn text_from_source(&self, msg: MainSourceMessage) -> Option<String> {
+        match (msg, &self.core.source) {
+            (MainSourceMessage::Osc(m), CompoundMappingSource::Osc(s)) => {
+                if !osc_address_matches(s.address_pattern(), &m.addr) {
+                    return None;
+                }
+                let arg_index = s.arg_descriptor()?.index();
+                match m.args.get(arg_index as usize)? {
+                    OscType::String(text) => Some(text.clone()),
+                    OscType::Color(c) => {
+                        Some(format!("#{:02x}{:02x}{:02x}", c.red, c.green, c.blue))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Controls all targets which support text input with the given text.
+    ///
+    /// Goes through the same control pipeline as numeric values (target refresh, logging,
+    /// feedback after control), but skips the transformations of the glue section because they
+    /// only work on numbers.
+    ///
+    /// Returns `None` if none of the targets supports text input.
+    #[must_use]
+    pub fn control_with_text(
+        &mut self,
+        text: &str,
+        options: ControlOptions,
+        context: ControlContext,
+        processor_context: ExtendedProcessorContext,
+        log_mode_control_result: impl Fn(ControlLogEntry),
+    ) -> Option<MappingControlResult> {
+        let at_least_one_target_supports_text = self
+            .targets
+            .iter()
+            .chain(
+                self.additional_targets
+                    .iter()
+                    .flat_map(|t| t.targets.iter()),
+            )
+            .any(|t| t.supports_text_input());
+        if !at_least_one_target_supports_text {
+            return None;
+        }
+        let result = self.control_internal(
+            options,
+            context,
+            processor_context,
+            false,
+            Some(text),
+            log_mode_control_result,
+            // The value is not used because the targets are hit with the text.
+            |_, _, _, _| {
+                Some(ModeControlResult::hit_target(
+                    ControlValue::AbsoluteContinuous(UnitValue::MAX),
+                ))
+            },
+        );
+        Some(result)
+    }
+
+    /// Returns the key of the mapping whose target value triggers the source (envelopes).
+    pub fn source_gate_mapping_key(&self) -> Option<&MappingKey> {
+        match &self.core.source {
+            CompoundMappingSource::Reaper(s) => s.gate_mapping_key(),
+            _ => None,
+        }
+    }
+
+    fn source_gate_mapping_id(&self) -> Option<MappingId> {
+        match &self.core.source {
+            CompoundMappingSource::Reaper(s) => s.gate_mapping_id(),
+            _ => None,
+        }
+    }
+
+    pub fn set_source_gate_mapping_id(&mut self, id: Option<MappingId>) {
+        if let CompoundMappingSource::Reaper(s) = &mut self.core.source {
+            s.set_gate_mapping_id(id);
+        }
+    }
+
+    /// Lets the source react to target value changes of other mappings (envelope gates).
+    pub fn process_source_gate_update(
+        &mut self,
+        lead_mapping_id: MappingId,
+        target_value: Option<AbsoluteValue>,
+    ) {
+        if let CompoundMappingSource::Reaper(s) = &mut self.core.source {
+            s.process_target_value_update(lead_mapping_id, target_value);
+        }
+    }
+
     /// Polls the source.
-    pub fn poll_source(&mut self) -> Option<ControlValue> {
+    pub fn poll_source(&mut self, context: ReaperSourcePollContext) -> Option<ControlValue> {
         match &mut self.core.source {
-            CompoundMappingSource::Reaper(s) => s.poll(),
+            CompoundMappingSource::Reaper(s) => s.poll(context),
             _ => None,
         }
     }
@@ -1427,6 +1930,9 @@ pub struct RealTimeMapping {
     pub target_is_resolved: bool,
     /// Is `Some` if virtual or this target needs to be processed in real-time.
     pub resolved_target: Option<RealTimeCompoundMappingTarget>,
+    /// If `true`, this is a multi-target mapping whose additional targets need to be controlled
+    /// in the main thread, even if the primary target is controlled in real-time.
+    pub has_additional_targets: bool,
     pub lifecycle_midi_data: LifecycleMidiData,
 }
 
@@ -2351,6 +2857,26 @@ impl RealearnTarget for CompoundMappingTarget {
         }
     }
 
+    fn supports_text_input(&self) -> bool {
+        use CompoundMappingTarget::*;
+        match self {
+            Reaper(t) => t.supports_text_input(),
+            Virtual(_) => false,
+        }
+    }
+
+    fn hit_with_text(
+        &mut self,
+        text: &str,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        use CompoundMappingTarget::*;
+        match self {
+            Reaper(t) => t.hit_with_text(text, context),
+            Virtual(_) => Err("not supported for virtual targets"),
+        }
+    }
+
     fn can_report_current_value(&self) -> bool {
         use CompoundMappingTarget::*;
         match self {
@@ -2679,6 +3205,22 @@ pub struct MappingControlResult {
 }
 
 /// Not usable for mappings with virtual targets.
+/// Checks whether the given OSC address matches the given address pattern, supporting OSC
+/// pattern syntax such as `*`, `?`, `[a-z]` and `{foo,bar}`.
+fn osc_address_matches(pattern: &str, address: &str) -> bool {
+    const PATTERN_CHARS: &[char] = &['*', '?', '[', '{'];
+    if !pattern.contains(PATTERN_CHARS) {
+        return pattern == address;
+    }
+    let Ok(matcher) = Matcher::new(pattern) else {
+        return false;
+    };
+    let Ok(address) = OscAddress::new(address.to_string()) else {
+        return false;
+    };
+    matcher.match_address(&address)
+}
+
 fn should_send_manual_feedback_due_to_target(
     target: &ReaperTarget,
     options: &ProcessorMappingOptions,
@@ -2831,6 +3373,10 @@ pub enum ControlLogContext {
     GroupInteraction,
     #[display(fmt = "loading mapping snapshot")]
     LoadingMappingSnapshot,
+    #[display(fmt = "macro")]
+    Macro,
+    #[display(fmt = "lifecycle hook")]
+    LifecycleHook,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -2918,8 +3464,110 @@ pub enum ControlLogEntryKind {
     /// instruction for multi-targets).
     #[display(fmt = "Discarded target hit instruction")]
     DiscardedHitInstruction,
+    /// Target was not hit immediately because its glue smoothes target values.
+    #[display(fmt = "Started smoothing towards control value")]
+    StartedSmoothing,
     #[display(fmt = "Executed hit instruction successfully")]
     ExecutedHitInstructionSuccessfully,
     #[display(fmt = "Failed to execute hit instruction")]
     FailedExecutingHitInstruction,
 }
+
+/// Advances the given smoothers, each one belonging to a target slot with the given number of
+/// targets.
+///
+/// Returns the values to be sent to the targets, along with the index of the target across all
+/// slots, in ascending order.
+fn step_smoothers<'a>(
+    slots: impl Iterator<Item = (usize, Option<&'a mut TargetValueSmoother>)>,
+    now: Instant,
+) -> Vec<(usize, UnitValue)> {
+    let mut steps = vec![];
+    let mut target_index = 0;
+    for (target_count, smoother) in slots {
+        if let Some(smoother) = smoother {
+            for slot_target_index in 0..target_count {
+                if let Some(value) = smoother.step(slot_target_index, now) {
+                    steps.push((target_index + slot_target_index, value));
+                }
+            }
+        }
+        target_index += target_count;
+    }
+    steps
+}
+
+/// Returns the index of the additional target controlled in the given slot or `None` if the
+/// slot belongs to the primary target.
+fn additional_target_index(slot: usize, primary_slot: usize) -> Option<usize> {
+    match slot.cmp(&primary_slot) {
+        Ordering::Less => Some(slot),
+        Ordering::Equal => None,
+        Ordering::Greater => Some(slot - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smoothing_steps() {
+        // Given
+        let start = Instant::now();
+        let settings = SmoothingSettings::MaxRate {
+            up_per_second: 1.0,
+            down_per_second: 1.0,
+        };
+        let mut smoother_1 = TargetValueSmoother::new(settings);
+        let mut smoother_3 = TargetValueSmoother::new(settings);
+        smoother_1.start(1, UnitValue::MIN, UnitValue::MAX, start);
+        smoother_3.start(0, UnitValue::MIN, UnitValue::new(0.1), start);
+        // When
+        let steps = step_smoothers(
+            [
+                (2, Some(&mut smoother_1)),
+                (1, None),
+                (1, Some(&mut smoother_3)),
+            ]
+            .into_iter(),
+            start + Duration::from_millis(500),
+        );
+        // Then
+        assert_eq!(
+            steps,
+            vec![(1, UnitValue::new(0.5)), (3, UnitValue::new(0.1))]
+        );
+        assert!(smoother_1.is_smoothing());
+        assert!(!smoother_3.is_smoothing());
+    }
+
+    #[test]
+    fn additional_target_slots() {
+        // Given
+        let primary_slot = 1;
+        // When
+        let indexes: Vec<_> = (0..=3)
+            .map(|slot| additional_target_index(slot, primary_slot))
+            .collect();
+        // Then
+        assert_eq!(indexes, vec![Some(0), None, Some(1), Some(2)]);
+        assert_eq!(additional_target_index(0, 0), None);
+        assert_eq!(additional_target_index(2, 2), None);
+        assert_eq!(additional_target_index(1, 2), Some(1));
+    }
+
+    #[test]
+    fn osc_address_matching() {
+        // Given
+        let exact = "/track/1/name";
+        let pattern = "/track/*/name";
+        // Then
+        assert!(osc_address_matches(exact, "/track/1/name"));
+        assert!(!osc_address_matches(exact, "/track/2/name"));
+        assert!(osc_address_matches(pattern, "/track/2/name"));
+        assert!(!osc_address_matches(pattern, "/track/2/color"));
+        assert!(osc_address_matches("/track/{1,2}/name", "/track/2/name"));
+        assert!(!osc_address_matches("/track/{1,2}/name", "/track/3/name"));
+    }
+}
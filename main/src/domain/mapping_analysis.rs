@@ -0,0 +1,281 @@
+use crate::domain::{
+    CompoundMappingSource, CompoundMappingSourceAddress, GroupId, MainMapping, MappingId,
+    RealearnSourceContext, RequiredParamStates, Tag, UnresolvedCompoundMappingTarget,
+    UnresolvedReaperTarget,
+};
+use base::hash_util::NonCryptoHashSet;
+
+/// A potential problem within the mappings of one compartment, found by static analysis.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum MappingIssue {
+    /// Both mappings react to the same source and can be active at the same time.
+    SourceCollision { mappings: [MappingId; 2] },
+    /// Both mappings send feedback to the same address and can be active at the same time.
+    FeedbackAddressCollision {
+        mappings: [MappingId; 2],
+        address: CompoundMappingSourceAddress,
+    },
+    /// The activation conditions of the mapping can never be fulfilled.
+    Unreachable { mapping: MappingId },
+    /// The mapping refers to a group which doesn't exist.
+    MissingGroup { mapping: MappingId, group: GroupId },
+    /// The activation condition of the mapping refers to a lead mapping which doesn't exist.
+    MissingLeadMapping {
+        mapping: MappingId,
+        lead_mapping: MappingId,
+    },
+    /// The mapping snapshot target of the mapping is scoped to a tag which no mapping has.
+    UnknownSnapshotTag { mapping: MappingId, tag: Tag },
+}
+
+impl MappingIssue {
+    /// Returns the IDs of all mappings involved in this issue.
+    pub fn mappings(&self) -> Vec<MappingId> {
+        use MappingIssue::*;
+        match self {
+            SourceCollision { mappings } | FeedbackAddressCollision { mappings, .. } => {
+                mappings.to_vec()
+            }
+            Unreachable { mapping }
+            | MissingGroup { mapping, .. }
+            | MissingLeadMapping { mapping, .. }
+            | UnknownSnapshotTag { mapping, .. } => vec![*mapping],
+        }
+    }
+}
+
+/// Everything the analysis needs to know about one mapping.
+///
+/// Usually created from a [`MainMapping`], but can also be assembled manually (e.g. in tests).
+#[derive(Clone, Debug)]
+pub struct MappingAnalysisInput {
+    pub id: MappingId,
+    pub group_id: GroupId,
+    pub tags: Vec<Tag>,
+    pub control_is_enabled: bool,
+    pub feedback_is_enabled: bool,
+    pub source: CompoundMappingSource,
+    pub source_address: Option<CompoundMappingSourceAddress>,
+    pub required_param_states: RequiredParamStates,
+    pub lead_mappings: Vec<MappingId>,
+    /// Tags by which a mapping snapshot target of this mapping is scoped.
+    pub snapshot_tags: Vec<Tag>,
+}
+
+impl MappingAnalysisInput {
+    pub fn from_mapping(mapping: &MainMapping, source_context: RealearnSourceContext) -> Self {
+        let snapshot_tags = match mapping.unresolved_target() {
+            Some(UnresolvedCompoundMappingTarget::Reaper(t)) => match &**t {
+                UnresolvedReaperTarget::LoadMappingSnapshot(t) => {
+                    t.scope.tags.iter().cloned().collect()
+                }
+                UnresolvedReaperTarget::TakeMappingSnapshot(t) => {
+                    t.scope.tags.iter().cloned().collect()
+                }
+                _ => vec![],
+            },
+            _ => vec![],
+        };
+        Self {
+            id: mapping.id(),
+            group_id: mapping.group_id(),
+            tags: mapping.tags().to_vec(),
+            control_is_enabled: mapping.control_is_enabled(),
+            feedback_is_enabled: mapping.feedback_is_enabled(),
+            source: mapping.source().clone(),
+            source_address: mapping.source().extract_feedback_address(source_context),
+            required_param_states: mapping.required_param_states(),
+            lead_mappings: mapping
+                .activation_can_be_affected_by_target_values()
+                .collect(),
+            snapshot_tags,
+        }
+    }
+
+    fn has_same_source_as(&self, other: &Self) -> bool {
+        match (&self.source_address, &other.source_address) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.source != CompoundMappingSource::Never && self.source == other.source,
+        }
+    }
+}
+
+/// Analyzes the given mappings of one compartment for conflicts and broken references.
+///
+/// `group_ids` must contain the IDs of all groups in the compartment, including the default group.
+///
+/// The analysis is conservative: Activation conditions which can't be analyzed statically are
+/// assumed to be fulfillable, so collisions are reported unless the conditions are clearly
+/// mutually exclusive.
+pub fn analyze_mappings(
+    mappings: &[MappingAnalysisInput],
+    group_ids: &NonCryptoHashSet<GroupId>,
+) -> Vec<MappingIssue> {
+    let mut issues = vec![];
+    let mapping_ids: NonCryptoHashSet<_> = mappings.iter().map(|m| m.id).collect();
+    let tags: NonCryptoHashSet<_> = mappings.iter().flat_map(|m| m.tags.iter()).collect();
+    for m in mappings {
+        if m.required_param_states.is_contradictory() {
+            issues.push(MappingIssue::Unreachable { mapping: m.id });
+        }
+        if !group_ids.contains(&m.group_id) {
+            issues.push(MappingIssue::MissingGroup {
+                mapping: m.id,
+                group: m.group_id,
+            });
+        }
+        for lead_mapping in &m.lead_mappings {
+            if !mapping_ids.contains(lead_mapping) {
+                issues.push(MappingIssue::MissingLeadMapping {
+                    mapping: m.id,
+                    lead_mapping: *lead_mapping,
+                });
+            }
+        }
+        for tag in &m.snapshot_tags {
+            if !tags.contains(tag) {
+                issues.push(MappingIssue::UnknownSnapshotTag {
+                    mapping: m.id,
+                    tag: tag.clone(),
+                });
+            }
+        }
+    }
+    for (i, a) in mappings.iter().enumerate() {
+        for b in &mappings[i + 1..] {
+            if !a.has_same_source_as(b)
+                || !a
+                    .required_param_states
+                    .is_compatible_with(&b.required_param_states)
+            {
+                continue;
+            }
+            let ids = [a.id, b.id];
+            if a.control_is_enabled && b.control_is_enabled {
+                issues.push(MappingIssue::SourceCollision { mappings: ids });
+            }
+            if a.feedback_is_enabled && b.feedback_is_enabled {
+                if let Some(address) = &a.source_address {
+                    issues.push(MappingIssue::FeedbackAddressCollision {
+                        mappings: ids,
+                        address: address.clone(),
+                    });
+                }
+            }
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        ActivationCondition, CompartmentParamIndex, ModifierCondition, VirtualControlElement,
+        VirtualSource,
+    };
+    use helgobox_api::persistence::VirtualControlElementCharacter;
+    use std::str::FromStr;
+
+    fn input(element_id: u32, condition: ActivationCondition) -> MappingAnalysisInput {
+        let element = VirtualControlElement::Indexed {
+            id: element_id,
+            character: VirtualControlElementCharacter::Button,
+        };
+        MappingAnalysisInput {
+            id: MappingId::random(),
+            group_id: GroupId::default(),
+            tags: vec![],
+            control_is_enabled: true,
+            feedback_is_enabled: true,
+            source: CompoundMappingSource::Virtual(VirtualSource::new(element)),
+            source_address: Some(CompoundMappingSourceAddress::Virtual(element)),
+            required_param_states: condition.required_param_states(),
+            lead_mappings: vec![],
+            snapshot_tags: vec![],
+        }
+    }
+
+    fn modifier(param_index: u32, is_on: bool) -> ActivationCondition {
+        let index = CompartmentParamIndex::try_from(param_index).unwrap();
+        ActivationCondition::Modifiers(vec![ModifierCondition::new(index, is_on)])
+    }
+
+    fn group_ids() -> NonCryptoHashSet<GroupId> {
+        [GroupId::default()].into_iter().collect()
+    }
+
+    #[test]
+    fn collisions() {
+        // Given
+        let a = input(0, modifier(0, true));
+        let b = input(0, ActivationCondition::Always);
+        let c = input(0, modifier(0, false));
+        let d = input(1, ActivationCondition::Always);
+        // When
+        let issues = analyze_mappings(&[a.clone(), b.clone(), c.clone(), d], &group_ids());
+        // Then
+        let source_collisions: Vec<_> = issues
+            .iter()
+            .filter(|i| matches!(i, MappingIssue::SourceCollision { .. }))
+            .map(|i| i.mappings())
+            .collect();
+        assert_eq!(source_collisions, vec![vec![a.id, b.id], vec![b.id, c.id]]);
+        let feedback_collision_count = issues
+            .iter()
+            .filter(|i| matches!(i, MappingIssue::FeedbackAddressCollision { .. }))
+            .count();
+        assert_eq!(feedback_collision_count, 2);
+    }
+
+    #[test]
+    fn unreachable() {
+        // Given
+        let contradictory_modifiers = ActivationCondition::Modifiers(vec![
+            ModifierCondition::new(CompartmentParamIndex::try_from(3).unwrap(), true),
+            ModifierCondition::new(CompartmentParamIndex::try_from(3).unwrap(), false),
+        ]);
+        let a = input(0, contradictory_modifiers);
+        let b = input(1, ActivationCondition::Any(vec![]));
+        let c = input(2, modifier(3, true));
+        // When
+        let issues = analyze_mappings(&[a.clone(), b.clone(), c], &group_ids());
+        // Then
+        assert_eq!(
+            issues,
+            vec![
+                MappingIssue::Unreachable { mapping: a.id },
+                MappingIssue::Unreachable { mapping: b.id },
+            ]
+        );
+    }
+
+    #[test]
+    fn dangling_references() {
+        // Given
+        let mut a = input(0, ActivationCondition::Always);
+        let missing_group = GroupId::random();
+        let missing_mapping = MappingId::random();
+        let tag = Tag::from_str("verse").unwrap();
+        a.group_id = missing_group;
+        a.lead_mappings = vec![missing_mapping];
+        a.snapshot_tags = vec![tag.clone()];
+        // When
+        let issues = analyze_mappings(&[a.clone()], &group_ids());
+        // Then
+        assert_eq!(
+            issues,
+            vec![
+                MappingIssue::MissingGroup {
+                    mapping: a.id,
+                    group: missing_group
+                },
+                MappingIssue::MissingLeadMapping {
+                    mapping: a.id,
+                    lead_mapping: missing_mapping
+                },
+                MappingIssue::UnknownSnapshotTag { mapping: a.id, tag },
+            ]
+        );
+    }
+}
@@ -7,6 +7,9 @@ pub use main_processor::*;
 mod mapping;
 pub use mapping::*;
 
+mod mapping_analysis;
+pub use mapping_analysis::*;
+
 mod control_surface;
 pub use control_surface::*;
 
@@ -28,6 +31,9 @@ pub use midi_source::*;
 mod eel_transformation;
 pub use eel_transformation::*;
 
+mod response_curve;
+pub use response_curve::*;
+
 mod eel_midi_source_script;
 pub use eel_midi_source_script::*;
 
@@ -40,6 +46,12 @@ pub use lua_midi_source_script::*;
 mod lua_feedback_script;
 pub use lua_feedback_script::*;
 
+mod lua_transformation;
+pub use lua_transformation::*;
+
+mod lua_lifecycle_script;
+pub use lua_lifecycle_script::*;
+
 mod flexible_midi_source_script;
 pub use flexible_midi_source_script::*;
 
@@ -49,6 +61,9 @@ pub use realearn_target::*;
 mod reaper_target;
 pub use reaper_target::*;
 
+mod envelope;
+pub use envelope::*;
+
 mod unresolved_reaper_target;
 pub use unresolved_reaper_target::*;
 
@@ -73,6 +88,9 @@ pub use midi_clock_calculator::*;
 mod conditional_activation;
 pub use conditional_activation::*;
 
+mod smoothing;
+pub use smoothing::*;
+
 mod eventing;
 pub use eventing::*;
 
@@ -111,6 +129,9 @@ pub use midi_types::*;
 mod reaper_source;
 pub use reaper_source::*;
 
+mod modulator_source;
+pub use modulator_source::*;
+
 mod key_source;
 pub use key_source::*;
 
@@ -1,9 +1,14 @@
 use crate::base::CloneAsDefault;
 use crate::domain::{
-    AdditionalLuaFeedbackScriptInput, ControlEventTimestamp, EelTransformation, LuaFeedbackScript,
+    AdditionalLuaFeedbackScriptInput, AdditionalTransformationInput, ControlEventTimestamp,
+    CurveTransformation, EelTransformation, LuaFeedbackScript, LuaTransformation,
 };
 use base::hash_util::NonCryptoHashSet;
-use helgoboss_learn::{FeedbackScript, FeedbackScriptInput, FeedbackScriptOutput, ModeContext};
+use fragile::Fragile;
+use helgoboss_learn::{
+    FeedbackScript, FeedbackScriptInput, FeedbackScriptOutput, ModeContext, Transformation,
+    TransformationInput, TransformationInputEvent, TransformationOutput,
+};
 use std::borrow::Cow;
 use std::error::Error;
 
@@ -12,7 +17,126 @@ pub type RealearnModeContext<'a> = ModeContext<AdditionalLuaFeedbackScriptInput<
 /// See [`crate::domain::MidiSource`] for an explanation of the feedback script wrapping.
 type FeedbackScriptType = CloneAsDefault<Option<LuaFeedbackScript<'static>>>;
 
-pub type Mode = helgoboss_learn::Mode<EelTransformation, FeedbackScriptType, ControlEventTimestamp>;
+pub type Mode =
+    helgoboss_learn::Mode<RealearnTransformation, FeedbackScriptType, ControlEventTimestamp>;
+
+/// Combines a response curve and a transformation script.
+///
+/// In control direction, the curve is applied first. In feedback direction, the curve is applied
+/// last (inversely), so that the feedback path mirrors the control path.
+#[derive(Clone, Debug)]
+pub struct RealearnTransformation {
+    curve: Option<CurveTransformation>,
+    script: Option<TransformationScript>,
+}
+
+/// A transformation formula written in one of the supported languages.
+#[derive(Clone, Debug)]
+pub enum TransformationScript {
+    Eel(EelTransformation),
+    /// See [`crate::domain::MidiSource`] for an explanation of the wrapping. Lua transformations
+    /// are only executed in the main thread, which `Fragile` makes sure of.
+    Lua(CloneAsDefault<Option<Fragile<LuaTransformation<'static>>>>),
+}
+
+impl RealearnTransformation {
+    /// Returns `None` if there's nothing to transform.
+    pub fn new(
+        curve: Option<CurveTransformation>,
+        script: Option<TransformationScript>,
+    ) -> Option<Self> {
+        if curve.is_none() && script.is_none() {
+            return None;
+        }
+        Some(Self { curve, script })
+    }
+
+    pub fn set_last_feedback_value(&self, value: f64) {
+        if let Some(TransformationScript::Eel(eel)) = &self.script {
+            eel.set_last_feedback_value(value);
+        }
+    }
+
+    /// Returns `true` if this transformation can't be executed in real-time threads.
+    pub fn requires_main_thread(&self) -> bool {
+        matches!(self.script, Some(TransformationScript::Lua(_)))
+    }
+}
+
+impl Transformation for TransformationScript {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<Self::AdditionalInput>,
+    ) -> Result<TransformationOutput, &'static str> {
+        match self {
+            TransformationScript::Eel(t) => t.transform(input),
+            TransformationScript::Lua(t) => t
+                .get()
+                .as_ref()
+                .ok_or("script was removed on clone")?
+                .try_get()
+                .map_err(|_| "Lua transformation not available in this thread")?
+                .transform(input),
+        }
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        match self {
+            TransformationScript::Eel(t) => t.wants_to_be_polled(),
+            TransformationScript::Lua(t) => t
+                .get()
+                .as_ref()
+                .and_then(|t| t.try_get().ok())
+                .is_some_and(|t| t.wants_to_be_polled()),
+        }
+    }
+}
+
+impl Transformation for RealearnTransformation {
+    type AdditionalInput = AdditionalTransformationInput;
+
+    fn transform(
+        &self,
+        input: TransformationInput<Self::AdditionalInput>,
+    ) -> Result<TransformationOutput, &'static str> {
+        let Some(curve) = &self.curve else {
+            return self
+                .script
+                .as_ref()
+                .ok_or("no transformation")?
+                .transform(input);
+        };
+        let Some(script) = &self.script else {
+            return Ok(TransformationOutput {
+                produced_kind: Default::default(),
+                value: Some(curve.apply(input.event.input_value)),
+                instruction: None,
+            });
+        };
+        if curve.is_inverse() {
+            let mut output = script.transform(input)?;
+            output.value = output.value.map(|v| curve.apply(v));
+            Ok(output)
+        } else {
+            let input = TransformationInput {
+                event: TransformationInputEvent {
+                    input_value: curve.apply(input.event.input_value),
+                    ..input.event
+                },
+                ..input
+            };
+            script.transform(input)
+        }
+    }
+
+    fn wants_to_be_polled(&self) -> bool {
+        self.script
+            .as_ref()
+            .is_some_and(|script| script.wants_to_be_polled())
+    }
+}
 
 impl FeedbackScriptType {
     fn get_script(&self) -> Result<&LuaFeedbackScript<'static>, Cow<'static, str>> {
@@ -0,0 +1,568 @@
+use crate::domain::{CompartmentParamIndex, MappingId, MappingKey, ReaperSourcePollContext};
+use helgoboss_learn::{AbsoluteValue, ControlValue, UnitValue};
+use helgobox_api::persistence::LfoShape;
+use std::f64::consts::TAU;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A source which continuously generates control values.
+#[derive(Clone, Debug)]
+pub struct ModulatorSource {
+    settings: ModulatorSettings,
+    state: ModulatorState,
+}
+
+/// Two modulator sources are equal if their settings are equal. The runtime state doesn't matter.
+impl PartialEq for ModulatorSource {
+    fn eq(&self, other: &Self) -> bool {
+        self.settings == other.settings
+    }
+}
+
+/// The floating-point settings are never NaN (see [`ModulatorSettings::is_valid`]).
+impl Eq for ModulatorSource {}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ModulatorSettings {
+    pub kind: ModulatorKind,
+    pub depth: UnitValue,
+    /// If set, the depth is multiplied with the value of this parameter.
+    pub depth_param: Option<CompartmentParamIndex>,
+    /// If set, the rate is scaled by the value of this parameter (from 1/4 to 4 times).
+    pub rate_param: Option<CompartmentParamIndex>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ModulatorKind {
+    Lfo {
+        shape: LfoShape,
+        rate: ModulatorRate,
+        /// Fraction of one cycle.
+        phase: f64,
+    },
+    Envelope {
+        /// The envelope is triggered whenever the target value of this mapping (in the same
+        /// compartment) rises above zero and released when it drops to zero again.
+        gate_mapping: MappingKey,
+        attack: Duration,
+        decay: Duration,
+        sustain: UnitValue,
+        release: Duration,
+    },
+    RandomWalk {
+        rate: ModulatorRate,
+        step_size: UnitValue,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ModulatorRate {
+    /// Cycles per second.
+    Free { hertz: f64 },
+    /// Length of one cycle in project beats.
+    TempoSynced { beats: f64 },
+}
+
+impl ModulatorSettings {
+    /// Returns `false` if any of the numbers is not finite or out of range.
+    pub fn is_valid(&self) -> bool {
+        let rate_is_valid = |rate: &ModulatorRate| match *rate {
+            ModulatorRate::Free { hertz } => hertz.is_finite() && hertz >= 0.0,
+            ModulatorRate::TempoSynced { beats } => beats.is_finite() && beats > 0.0,
+        };
+        match &self.kind {
+            ModulatorKind::Lfo { rate, phase, .. } => rate_is_valid(rate) && phase.is_finite(),
+            ModulatorKind::Envelope { .. } => true,
+            ModulatorKind::RandomWalk { rate, .. } => rate_is_valid(rate),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ModulatorState {
+    last_poll: Option<Instant>,
+    /// Accumulated cycles. They are accumulated (and not derived from the absolute time or
+    /// project position) so that rate changes don't make the phase jump.
+    cycles: f64,
+    /// Project position in beats at the last poll, for tempo-synced modulators.
+    last_project_beats: Option<f64>,
+    /// Resolved from the gate mapping key of envelopes.
+    gate_mapping_id: Option<MappingId>,
+    gate_is_open: bool,
+    /// Index of the cycle which produced the current random values.
+    random_cycle: Option<i64>,
+    random_from: f64,
+    random_to: f64,
+    random_generator: u64,
+    envelope_stage: EnvelopeStage,
+    envelope_value: f64,
+    last_value: Option<UnitValue>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum EnvelopeStage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+impl Default for ModulatorState {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            last_poll: None,
+            cycles: 0.0,
+            last_project_beats: None,
+            gate_mapping_id: None,
+            gate_is_open: false,
+            random_cycle: None,
+            random_from: 0.5,
+            random_to: 0.5,
+            // Xorshift must not be seeded with zero
+            random_generator: seed | 1,
+            envelope_stage: EnvelopeStage::Idle,
+            envelope_value: 0.0,
+            last_value: None,
+        }
+    }
+}
+
+impl ModulatorSource {
+    pub fn new(settings: ModulatorSettings) -> Self {
+        Self {
+            settings,
+            state: Default::default(),
+        }
+    }
+
+    pub fn on_deactivate(&mut self) {
+        let gate_mapping_id = self.state.gate_mapping_id;
+        self.state = Default::default();
+        self.state.gate_mapping_id = gate_mapping_id;
+    }
+
+    /// Returns the key of the mapping which triggers this envelope.
+    pub fn gate_mapping_key(&self) -> Option<&MappingKey> {
+        match &self.settings.kind {
+            ModulatorKind::Envelope { gate_mapping, .. } => Some(gate_mapping),
+            _ => None,
+        }
+    }
+
+    pub fn gate_mapping_id(&self) -> Option<MappingId> {
+        self.state.gate_mapping_id
+    }
+
+    pub fn set_gate_mapping_id(&mut self, id: Option<MappingId>) {
+        self.state.gate_mapping_id = id;
+    }
+
+    /// Opens or closes the gate of an envelope if the given mapping is its gate mapping.
+    ///
+    /// Passing a `None` target value means the target is inactive.
+    pub fn process_target_value_update(
+        &mut self,
+        mapping_id: MappingId,
+        target_value: Option<AbsoluteValue>,
+    ) {
+        if self.state.gate_mapping_id != Some(mapping_id) {
+            return;
+        }
+        self.state.gate_is_open = target_value.is_some_and(|v| !v.to_unit_value().is_zero());
+    }
+
+    /// Returns a control value whenever the modulator output changed.
+    pub fn poll(&mut self, context: ReaperSourcePollContext) -> Option<ControlValue> {
+        let now = Instant::now();
+        let elapsed = self
+            .state
+            .last_poll
+            .replace(now)
+            .map(|last_poll| now.saturating_duration_since(last_poll))
+            .unwrap_or_default();
+        let rate_factor = match self.settings.rate_param {
+            None => 1.0,
+            Some(p) => 4.0f64.powf(2.0 * context.param_value(p) - 1.0),
+        };
+        let depth = match self.settings.depth_param {
+            None => self.settings.depth.get(),
+            Some(p) => self.settings.depth.get() * context.param_value(p),
+        };
+        let value = match self.settings.kind.clone() {
+            ModulatorKind::Lfo { shape, rate, phase } => {
+                let cycles = self.advance(rate, elapsed, rate_factor, context) + phase;
+                let raw = self.lfo_value(shape, cycles);
+                0.5 + (raw - 0.5) * depth
+            }
+            ModulatorKind::Envelope {
+                attack,
+                decay,
+                sustain,
+                release,
+                ..
+            } => {
+                let raw = self.envelope_value(
+                    self.state.gate_is_open,
+                    elapsed.mul_f64(rate_factor),
+                    attack,
+                    decay,
+                    sustain.get(),
+                    release,
+                );
+                raw * depth
+            }
+            ModulatorKind::RandomWalk { rate, step_size } => {
+                let cycles = self.advance(rate, elapsed, rate_factor, context);
+                let raw = self.random_walk_value(step_size.get(), cycles);
+                0.5 + (raw - 0.5) * depth
+            }
+        };
+        let value = UnitValue::new_clamped(value);
+        if self.state.last_value == Some(value) {
+            return None;
+        }
+        self.state.last_value = Some(value);
+        Some(ControlValue::AbsoluteContinuous(value))
+    }
+
+    /// Returns the current position in cycles.
+    fn advance(
+        &mut self,
+        rate: ModulatorRate,
+        elapsed: Duration,
+        rate_factor: f64,
+        context: ReaperSourcePollContext,
+    ) -> f64 {
+        match rate {
+            ModulatorRate::Free { hertz } => {
+                self.advance_free(hertz * rate_factor, elapsed);
+            }
+            ModulatorRate::TempoSynced { beats } => {
+                self.advance_tempo_synced(beats, rate_factor, context.project_beats());
+            }
+        }
+        self.state.cycles
+    }
+
+    fn advance_free(&mut self, hertz: f64, elapsed: Duration) {
+        self.state.cycles += hertz * elapsed.as_secs_f64();
+    }
+
+    /// Tempo-synced modulators start in phase with the project position. Afterwards, they follow
+    /// the position changes, so they stay in phase as long as the rate factor doesn't change.
+    fn advance_tempo_synced(&mut self, beats_per_cycle: f64, rate_factor: f64, project_beats: f64) {
+        let cycles_per_beat = rate_factor / beats_per_cycle;
+        match self.state.last_project_beats.replace(project_beats) {
+            None => {
+                self.state.cycles = project_beats * cycles_per_beat;
+            }
+            Some(last_project_beats) => {
+                self.state.cycles += (project_beats - last_project_beats) * cycles_per_beat;
+            }
+        }
+    }
+
+    fn lfo_value(&mut self, shape: LfoShape, cycles: f64) -> f64 {
+        if shape == LfoShape::SampleAndHold {
+            let cycle = cycles.floor() as i64;
+            if self.state.random_cycle != Some(cycle) {
+                self.state.random_cycle = Some(cycle);
+                self.state.random_to = self.next_random();
+            }
+            return self.state.random_to;
+        }
+        periodic_lfo_value(shape, cycles.rem_euclid(1.0))
+    }
+
+    fn random_walk_value(&mut self, step_size: f64, cycles: f64) -> f64 {
+        let cycle = cycles.floor() as i64;
+        if self.state.random_cycle != Some(cycle) {
+            self.state.random_cycle = Some(cycle);
+            self.state.random_from = self.state.random_to;
+            let step = (2.0 * self.next_random() - 1.0) * step_size;
+            // Reflect at the boundaries
+            let mut next = self.state.random_to + step;
+            if next < 0.0 {
+                next = -next;
+            } else if next > 1.0 {
+                next = 2.0 - next;
+            }
+            self.state.random_to = next.clamp(0.0, 1.0);
+        }
+        let from = self.state.random_from;
+        from + (self.state.random_to - from) * cycles.rem_euclid(1.0)
+    }
+
+    fn envelope_value(
+        &mut self,
+        gate_is_on: bool,
+        elapsed: Duration,
+        attack: Duration,
+        decay: Duration,
+        sustain: f64,
+        release: Duration,
+    ) -> f64 {
+        use EnvelopeStage::*;
+        let state = &mut self.state;
+        state.envelope_stage = match (state.envelope_stage, gate_is_on) {
+            (Idle | Release, true) => Attack,
+            (Attack | Decay | Sustain, false) => Release,
+            (stage, _) => stage,
+        };
+        // Times refer to a change over the complete value range
+        let delta = |time: Duration| {
+            if time.is_zero() {
+                1.0
+            } else {
+                elapsed.as_secs_f64() / time.as_secs_f64()
+            }
+        };
+        match state.envelope_stage {
+            Idle => {}
+            Attack => {
+                state.envelope_value += delta(attack);
+                if state.envelope_value >= 1.0 {
+                    state.envelope_value = 1.0;
+                    state.envelope_stage = Decay;
+                }
+            }
+            Decay => {
+                state.envelope_value -= delta(decay);
+                if state.envelope_value <= sustain {
+                    state.envelope_value = sustain;
+                    state.envelope_stage = Sustain;
+                }
+            }
+            Sustain => {
+                state.envelope_value = sustain;
+            }
+            Release => {
+                state.envelope_value -= delta(release);
+                if state.envelope_value <= 0.0 {
+                    state.envelope_value = 0.0;
+                    state.envelope_stage = Idle;
+                }
+            }
+        }
+        state.envelope_value
+    }
+
+    /// Xorshift is good enough for modulation purposes.
+    fn next_random(&mut self) -> f64 {
+        let mut x = self.state.random_generator;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state.random_generator = x;
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Returns the value of the given periodic waveform at the given phase (fraction of one cycle).
+///
+/// Sample & hold is not periodic, it returns 0.5.
+fn periodic_lfo_value(shape: LfoShape, phase: f64) -> f64 {
+    match shape {
+        LfoShape::Sine => 0.5 + 0.5 * (TAU * phase).sin(),
+        LfoShape::Triangle => 1.0 - (2.0 * phase - 1.0).abs(),
+        LfoShape::Saw => phase,
+        LfoShape::Square => {
+            if phase < 0.5 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        LfoShape::SampleAndHold => 0.5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(kind: ModulatorKind) -> ModulatorSource {
+        let mut source = ModulatorSource::new(ModulatorSettings {
+            kind,
+            depth: UnitValue::MAX,
+            depth_param: None,
+            rate_param: None,
+        });
+        source.state.random_generator = 12345;
+        source
+    }
+
+    fn lfo(shape: LfoShape) -> ModulatorKind {
+        ModulatorKind::Lfo {
+            shape,
+            rate: ModulatorRate::Free { hertz: 1.0 },
+            phase: 0.0,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.000001,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn periodic_lfo_shapes() {
+        assert_close(periodic_lfo_value(LfoShape::Sine, 0.0), 0.5);
+        assert_close(periodic_lfo_value(LfoShape::Sine, 0.25), 1.0);
+        assert_close(periodic_lfo_value(LfoShape::Sine, 0.75), 0.0);
+        assert_close(periodic_lfo_value(LfoShape::Triangle, 0.0), 0.0);
+        assert_close(periodic_lfo_value(LfoShape::Triangle, 0.5), 1.0);
+        assert_close(periodic_lfo_value(LfoShape::Triangle, 0.75), 0.5);
+        assert_close(periodic_lfo_value(LfoShape::Saw, 0.3), 0.3);
+        assert_close(periodic_lfo_value(LfoShape::Square, 0.2), 1.0);
+        assert_close(periodic_lfo_value(LfoShape::Square, 0.7), 0.0);
+    }
+
+    #[test]
+    fn lfo_wraps_cycles() {
+        // Given
+        let mut source = source(lfo(LfoShape::Saw));
+        // When
+        let value = source.lfo_value(LfoShape::Saw, 2.25);
+        let negative_value = source.lfo_value(LfoShape::Saw, -0.25);
+        // Then
+        assert_close(value, 0.25);
+        assert_close(negative_value, 0.75);
+    }
+
+    #[test]
+    fn sample_and_hold_changes_once_per_cycle() {
+        // Given
+        let mut source = source(lfo(LfoShape::SampleAndHold));
+        // When
+        let a = source.lfo_value(LfoShape::SampleAndHold, 0.1);
+        let b = source.lfo_value(LfoShape::SampleAndHold, 0.9);
+        let c = source.lfo_value(LfoShape::SampleAndHold, 1.1);
+        // Then
+        assert_eq!(a, b);
+        assert_ne!(b, c);
+        assert!((0.0..1.0).contains(&a));
+        assert!((0.0..1.0).contains(&c));
+    }
+
+    #[test]
+    fn random_walk_stays_in_range_and_glides() {
+        // Given
+        let mut source = source(ModulatorKind::RandomWalk {
+            rate: ModulatorRate::Free { hertz: 1.0 },
+            step_size: UnitValue::new(0.5),
+        });
+        // When
+        let start = source.random_walk_value(0.5, 0.0);
+        let target = source.state.random_to;
+        let halfway = source.random_walk_value(0.5, 0.5);
+        // Then
+        assert_close(start, 0.5);
+        assert!((0.0..=1.0).contains(&target));
+        assert!((target - 0.5).abs() <= 0.5);
+        assert_close(halfway, 0.5 + (target - 0.5) * 0.5);
+        for i in 1..1000 {
+            let value = source.random_walk_value(0.5, i as f64);
+            assert!((0.0..=1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn envelope_stages() {
+        // Given
+        let attack = Duration::from_millis(100);
+        let decay = Duration::from_millis(100);
+        let release = Duration::from_millis(200);
+        let mut source = source(ModulatorKind::Envelope {
+            gate_mapping: "gate".to_string().into(),
+            attack,
+            decay,
+            sustain: UnitValue::new(0.5),
+            release,
+        });
+        let mut step = |gate_is_open: bool, millis: u64| {
+            source.envelope_value(
+                gate_is_open,
+                Duration::from_millis(millis),
+                attack,
+                decay,
+                0.5,
+                release,
+            )
+        };
+        // Then
+        assert_close(step(false, 50), 0.0);
+        assert_close(step(true, 50), 0.5);
+        assert_close(step(true, 50), 1.0);
+        assert_close(step(true, 25), 0.75);
+        assert_close(step(true, 100), 0.5);
+        assert_close(step(true, 1000), 0.5);
+        assert_close(step(false, 100), 0.0);
+        assert_close(step(false, 100), 0.0);
+    }
+
+    #[test]
+    fn envelope_gate_follows_gate_mapping() {
+        // Given
+        let gate_mapping_id = MappingId::random();
+        let mut source = source(ModulatorKind::Envelope {
+            gate_mapping: "gate".to_string().into(),
+            attack: Duration::ZERO,
+            decay: Duration::ZERO,
+            sustain: UnitValue::MAX,
+            release: Duration::ZERO,
+        });
+        source.set_gate_mapping_id(Some(gate_mapping_id));
+        let on = Some(AbsoluteValue::Continuous(UnitValue::MAX));
+        // When
+        source.process_target_value_update(MappingId::random(), on);
+        let after_other_mapping = source.state.gate_is_open;
+        source.process_target_value_update(gate_mapping_id, on);
+        let after_on = source.state.gate_is_open;
+        source.process_target_value_update(gate_mapping_id, None);
+        let after_inactive = source.state.gate_is_open;
+        // Then
+        assert!(!after_other_mapping);
+        assert!(after_on);
+        assert!(!after_inactive);
+    }
+
+    #[test]
+    fn tempo_synced_rate_change_keeps_phase() {
+        // Given
+        let mut source = source(lfo(LfoShape::Saw));
+        // When
+        source.advance_tempo_synced(4.0, 1.0, 10.0);
+        let initial = source.state.cycles;
+        source.advance_tempo_synced(4.0, 1.0, 11.0);
+        let after_one_beat = source.state.cycles;
+        source.advance_tempo_synced(4.0, 2.0, 11.0);
+        let after_rate_change = source.state.cycles;
+        source.advance_tempo_synced(4.0, 2.0, 12.0);
+        let after_faster_beat = source.state.cycles;
+        // Then
+        assert_close(initial, 2.5);
+        assert_close(after_one_beat, 2.75);
+        assert_close(after_rate_change, 2.75);
+        assert_close(after_faster_beat, 3.25);
+    }
+
+    #[test]
+    fn equality_ignores_state() {
+        // Given
+        let a = source(lfo(LfoShape::Sine));
+        let mut b = source(lfo(LfoShape::Sine));
+        // When
+        b.advance_free(1.0, Duration::from_millis(300));
+        b.state.random_generator = 1;
+        // Then
+        assert_eq!(a, b);
+        assert_ne!(a, source(lfo(LfoShape::Saw)));
+    }
+}
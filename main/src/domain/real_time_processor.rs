@@ -940,6 +940,7 @@ impl RealTimeProcessor {
                         mode_control_options: Default::default(),
                         enforce_target_refresh: matched_already_before,
                         coming_from_real_time: true,
+                        skip_primary_target: false,
                     },
                     caller,
                     midi_feedback_output: self.settings.midi_destination(),
@@ -1366,6 +1367,7 @@ fn control_controller_mappings_midi(
                     // subsequent virtual targets.
                     enforce_target_refresh: false,
                     coming_from_real_time: true,
+                    skip_primary_target: false,
                 },
                 caller,
                 instance,
@@ -1402,6 +1404,7 @@ fn control_controller_mappings_midi(
                     mode_control_options: Default::default(),
                     enforce_target_refresh,
                     coming_from_real_time: true,
+                    skip_primary_target: false,
                 },
                 caller,
                 midi_feedback_output,
@@ -1448,8 +1451,13 @@ fn process_real_mapping(mapping: &mut RealTimeMapping, mut args: ProcessRtMappin
                 reaper_target,
             );
             if !forward_to_main_thread {
-                // We are done here.
-                return;
+                if !mapping.has_additional_targets {
+                    // We are done here.
+                    return;
+                }
+                // The additional targets of a multi-target mapping are controlled in the main
+                // thread.
+                args.options.skip_primary_target = true;
             }
         }
     }
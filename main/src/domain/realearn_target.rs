@@ -4,29 +4,34 @@ use crate::domain::ui_util::{
 };
 use crate::domain::{
     format_as_pretty_hex, new_set_track_ui_functions_are_available, scoped_track_index,
-    AdditionalFeedbackEvent, AdditionalTransformationInput, BasicSettings, CompartmentKind,
-    DomainEventHandler, Exclusivity, ExtendedProcessorContext, FeedbackAudioHookTask,
-    FeedbackOutput, FeedbackRealTimeTask, GroupId, InstanceStateChanged, MainMapping,
-    MappingControlResult, MappingId, OrderedMappingMap, OscFeedbackTask, PluginParamIndex,
-    ProcessorContext, QualifiedMappingId, RealTimeReaperTarget, RealearnModeContext,
-    RealearnSourceContext, ReaperTarget, SharedInstance, SharedUnit, StreamDeckDeviceId, Tag,
-    TagScope, TargetCharacter, TrackExclusivity, UnitEvent, UnitId, WeakRealTimeInstance,
-    ACTION_TARGET, ALL_TRACK_FX_ENABLE_TARGET, ANY_ON_TARGET, AUTOMATION_MODE_OVERRIDE_TARGET,
-    BROWSE_FXS_TARGET, BROWSE_GROUP_MAPPINGS_TARGET, BROWSE_POT_FILTER_ITEMS_TARGET,
-    BROWSE_POT_PRESETS_TARGET, COMPARTMENT_PARAMETER_VALUE_TARGET, DUMMY_TARGET,
-    ENABLE_INSTANCES_TARGET, ENABLE_MAPPINGS_TARGET, FX_ENABLE_TARGET, FX_ONLINE_TARGET,
-    FX_OPEN_TARGET, FX_PARAMETER_TARGET, FX_PARAMETER_TOUCH_STATE_TARGET, FX_PRESET_TARGET,
-    FX_TOOL_TARGET, GO_TO_BOOKMARK_TARGET, LAST_TOUCHED_TARGET, LEARN_MAPPING_TARGET,
-    LOAD_FX_SNAPSHOT_TARGET, LOAD_MAPPING_SNAPSHOT_TARGET, LOAD_POT_PRESET_TARGET,
+    AdditionalFeedbackEvent, AdditionalLuaTransformationInput, AdditionalTransformationInput,
+    BasicSettings, CompartmentKind, DomainEventHandler, Exclusivity, ExtendedProcessorContext,
+    FeedbackAudioHookTask, FeedbackOutput, FeedbackRealTimeTask, GroupId, InstanceStateChanged,
+    MainMapping, MappingControlResult, MappingId, OrderedMappingMap, OscFeedbackTask,
+    PluginParamIndex, ProcessorContext, QualifiedMappingId, RealTimeReaperTarget,
+    RealearnModeContext, RealearnSourceContext, ReaperTarget, SharedInstance, SharedUnit,
+    StreamDeckDeviceId, Tag, TagScope, TargetCharacter, TrackExclusivity, UnitEvent, UnitId,
+    WeakRealTimeInstance, ACTION_TARGET, ALL_TRACK_FX_ENABLE_TARGET, ANY_ON_TARGET,
+    AUTOMATION_ITEM_ACTION_TARGET, AUTOMATION_MODE_OVERRIDE_TARGET, BROWSE_FXS_TARGET,
+    BROWSE_GROUP_MAPPINGS_TARGET, BROWSE_POT_FILTER_ITEMS_TARGET, BROWSE_POT_PRESETS_TARGET,
+    BROWSE_PROJECTS_TARGET, COMPARTMENT_PARAMETER_VALUE_TARGET, DUMMY_TARGET,
+    ENABLE_INSTANCES_TARGET, ENABLE_MAPPINGS_TARGET, ENVELOPE_ACTION_TARGET, ENVELOPE_VALUE_TARGET,
+    FX_ENABLE_TARGET, FX_ONLINE_TARGET, FX_OPEN_TARGET, FX_PARAMETER_TARGET,
+    FX_PARAMETER_TOUCH_STATE_TARGET, FX_PRESET_TARGET, FX_TOOL_TARGET, GO_TO_BOOKMARK_TARGET,
+    LAST_TOUCHED_TARGET, LEARN_MAPPING_TARGET, LOAD_FX_SNAPSHOT_TARGET,
+    LOAD_MAPPING_SNAPSHOT_TARGET, LOAD_POT_PRESET_TARGET, LOAD_SCREENSET_TARGET, MACRO_TARGET,
     MIDI_SEND_TARGET, MOUSE_TARGET, OSC_SEND_TARGET, PLAYRATE_TARGET, PREVIEW_POT_PRESET_TARGET,
-    ROUTE_AUTOMATION_MODE_TARGET, ROUTE_MONO_TARGET, ROUTE_MUTE_TARGET, ROUTE_PAN_TARGET,
-    ROUTE_PHASE_TARGET, ROUTE_TOUCH_STATE_TARGET, ROUTE_VOLUME_TARGET,
+    RATE_POT_PRESET_TARGET, ROUTE_AUTOMATION_MODE_TARGET, ROUTE_EXISTENCE_TARGET,
+    ROUTE_MONO_TARGET, ROUTE_MUTE_TARGET, ROUTE_PAN_TARGET, ROUTE_PHASE_TARGET,
+    ROUTE_SEND_MODE_TARGET, ROUTE_TOUCH_STATE_TARGET, ROUTE_VOLUME_TARGET,
     SAVE_MAPPING_SNAPSHOT_TARGET, SEEK_TARGET, SELECTED_TRACK_TARGET,
-    STREAM_DECK_BRIGHTNESS_TARGET, TEMPO_TARGET, TRACK_ARM_TARGET, TRACK_AUTOMATION_MODE_TARGET,
-    TRACK_MONITORING_MODE_TARGET, TRACK_MUTE_TARGET, TRACK_PAN_TARGET, TRACK_PARENT_SEND_TARGET,
-    TRACK_PEAK_TARGET, TRACK_PHASE_TARGET, TRACK_SELECTION_TARGET, TRACK_SHOW_TARGET,
-    TRACK_SOLO_TARGET, TRACK_TOOL_TARGET, TRACK_TOUCH_STATE_TARGET, TRACK_VOLUME_TARGET,
-    TRACK_WIDTH_TARGET, TRANSPORT_TARGET,
+    STREAM_DECK_BRIGHTNESS_TARGET, TAG_POT_PRESET_TARGET, TEMPO_TARGET, TRACK_ARM_TARGET,
+    TRACK_AUTOMATION_MODE_TARGET, TRACK_COLOR_TARGET, TRACK_LAYOUT_TARGET,
+    TRACK_MONITORING_MODE_TARGET, TRACK_MUTE_TARGET, TRACK_NAME_TARGET, TRACK_PAN_TARGET,
+    TRACK_PARENT_SEND_TARGET, TRACK_PEAK_TARGET, TRACK_PHASE_TARGET, TRACK_RECORD_INPUT_TARGET,
+    TRACK_RECORD_MODE_TARGET, TRACK_SELECTION_TARGET, TRACK_SHOW_TARGET, TRACK_SOLO_TARGET,
+    TRACK_TOOL_TARGET, TRACK_TOUCH_STATE_TARGET, TRACK_VOLUME_TARGET, TRACK_WIDTH_TARGET,
+    TRANSPORT_TARGET, WINDOW_VISIBILITY_TARGET,
 };
 use base::hash_util::NonCryptoHashSet;
 use base::{SenderToNormalThread, SenderToRealTimeThread};
@@ -230,6 +235,22 @@ pub trait RealearnTarget {
         Err("not supported")
     }
 
+    /// Whether this target can be controlled with text (e.g. an OSC string argument) in addition
+    /// to numeric values.
+    fn supports_text_input(&self) -> bool {
+        false
+    }
+
+    /// Controls this target with text. Text input bypasses the glue section.
+    fn hit_with_text(
+        &mut self,
+        text: &str,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let (_, _) = (text, context);
+        Err("text input not supported")
+    }
+
     fn can_report_current_value(&self) -> bool {
         // We will quickly realize if not.
         true
@@ -517,6 +538,19 @@ impl TransformationInputProvider<AdditionalTransformationInput> for MappingContr
                 .last_non_performance_target_value
                 .map(|v| v.to_unit_value().get())
                 .unwrap_or_default(),
+            // Only Lua transformations need this, so we don't clone for all the others
+            lua: self.mapping_data.uses_lua_transformation.then(|| {
+                AdditionalLuaTransformationInput {
+                    compartment_lua: self
+                        .control_context
+                        .mode_context
+                        .additional_script_input
+                        .compartment_lua
+                        .cloned(),
+                    unit: self.control_context.unit.clone(),
+                    compartment: self.mapping_data.compartment,
+                }
+            }),
         }
     }
 }
@@ -533,6 +567,7 @@ pub struct MappingData {
     pub mapping_id: MappingId,
     pub group_id: GroupId,
     pub last_non_performance_target_value: Option<AbsoluteValue>,
+    pub uses_lua_transformation: bool,
 }
 
 impl MappingData {
@@ -635,10 +670,13 @@ pub enum ReaperTargetType {
     LastTouched = 20,
     Mouse = 57,
     AutomationModeOverride = 26,
+    LoadScreenset = 78,
+    WindowVisibility = 79,
 
     // Project targets
     AnyOn = 43,
     BrowseTracks = 14,
+    BrowseProjects = 77,
     Action = 0,
     Transport = 16,
     Seek = 23,
@@ -660,11 +698,16 @@ pub enum ReaperTargetType {
     TrackAutomationMode = 25,
     TrackTouchState = 21,
     TrackMonitoringMode = 49,
+    TrackRecordInput = 75,
+    TrackRecordMode = 76,
     TrackPan = 4,
     TrackWidth = 17,
     TrackVolume = 2,
     TrackShow = 24,
     TrackSolo = 8,
+    TrackName = 72,
+    TrackColor = 73,
+    TrackLayout = 74,
 
     // FX chain targets
     BrowseFxs = 28,
@@ -682,11 +725,18 @@ pub enum ReaperTargetType {
     #[default]
     FxParameterValue = 1,
 
+    // Envelope targets
+    EnvelopeValue = 67,
+    EnvelopeAction = 68,
+    AutomationItemAction = 69,
+
     // Pot targets
     BrowsePotFilterItems = 61,
     BrowsePotPresets = 58,
     PreviewPotPreset = 59,
     LoadPotPreset = 60,
+    RatePotPreset = 81,
+    TagPotPreset = 82,
 
     // Send targets
     RouteTouchState = 48,
@@ -696,6 +746,8 @@ pub enum ReaperTargetType {
     RouteAutomationMode = 45,
     RoutePan = 9,
     RouteVolume = 3,
+    RouteExistence = 70,
+    RouteSendMode = 71,
 
     // Clip targets
     PlaytimeSlotManagementAction = 46,
@@ -724,6 +776,7 @@ pub enum ReaperTargetType {
     EnableInstances = 38,
     EnableMappings = 36,
     ModifyMapping = 62,
+    Macro = 80,
     CompartmentParameterValue = 63,
     LoadMappingSnapshot = 35,
     TakeMappingSnapshot = 55,
@@ -796,10 +849,13 @@ impl ReaperTargetType {
             Mouse => &MOUSE_TARGET,
             LastTouched => &LAST_TOUCHED_TARGET,
             AutomationModeOverride => &AUTOMATION_MODE_OVERRIDE_TARGET,
+            LoadScreenset => &LOAD_SCREENSET_TARGET,
+            WindowVisibility => &WINDOW_VISIBILITY_TARGET,
             AnyOn => &ANY_ON_TARGET,
             Action => &ACTION_TARGET,
             Transport => &TRANSPORT_TARGET,
             BrowseTracks => &SELECTED_TRACK_TARGET,
+            BrowseProjects => &BROWSE_PROJECTS_TARGET,
             Seek => &SEEK_TARGET,
             PlayRate => &PLAYRATE_TARGET,
             Tempo => &TEMPO_TARGET,
@@ -814,12 +870,17 @@ impl ReaperTargetType {
             TrackSelection => &TRACK_SELECTION_TARGET,
             TrackAutomationMode => &TRACK_AUTOMATION_MODE_TARGET,
             TrackMonitoringMode => &TRACK_MONITORING_MODE_TARGET,
+            TrackRecordInput => &TRACK_RECORD_INPUT_TARGET,
+            TrackRecordMode => &TRACK_RECORD_MODE_TARGET,
             TrackTouchState => &TRACK_TOUCH_STATE_TARGET,
             TrackPan => &TRACK_PAN_TARGET,
             TrackWidth => &TRACK_WIDTH_TARGET,
             TrackVolume => &TRACK_VOLUME_TARGET,
             TrackShow => &TRACK_SHOW_TARGET,
             TrackSolo => &TRACK_SOLO_TARGET,
+            TrackName => &TRACK_NAME_TARGET,
+            TrackColor => &TRACK_COLOR_TARGET,
+            TrackLayout => &TRACK_LAYOUT_TARGET,
             FxTool => &FX_TOOL_TARGET,
             BrowseFxs => &BROWSE_FXS_TARGET,
             FxEnable => &FX_ENABLE_TARGET,
@@ -829,6 +890,9 @@ impl ReaperTargetType {
             FxOpen => &FX_OPEN_TARGET,
             FxParameterValue => &FX_PARAMETER_TARGET,
             FxParameterTouchState => &FX_PARAMETER_TOUCH_STATE_TARGET,
+            EnvelopeValue => &ENVELOPE_VALUE_TARGET,
+            EnvelopeAction => &ENVELOPE_ACTION_TARGET,
+            AutomationItemAction => &AUTOMATION_ITEM_ACTION_TARGET,
             RouteAutomationMode => &ROUTE_AUTOMATION_MODE_TARGET,
             RouteMono => &ROUTE_MONO_TARGET,
             RouteMute => &ROUTE_MUTE_TARGET,
@@ -836,6 +900,8 @@ impl ReaperTargetType {
             RoutePan => &ROUTE_PAN_TARGET,
             RouteVolume => &ROUTE_VOLUME_TARGET,
             RouteTouchState => &ROUTE_TOUCH_STATE_TARGET,
+            RouteExistence => &ROUTE_EXISTENCE_TARGET,
+            RouteSendMode => &ROUTE_SEND_MODE_TARGET,
             PlaytimeSlotTransportAction => &crate::domain::PLAYTIME_SLOT_TRANSPORT_TARGET,
             PlaytimeColumnAction => &crate::domain::PLAYTIME_COLUMN_TARGET,
             PlaytimeRowAction => &crate::domain::PLAYTIME_ROW_TARGET,
@@ -851,6 +917,7 @@ impl ReaperTargetType {
             EnableInstances => &ENABLE_INSTANCES_TARGET,
             EnableMappings => &ENABLE_MAPPINGS_TARGET,
             ModifyMapping => &LEARN_MAPPING_TARGET,
+            Macro => &MACRO_TARGET,
             LoadMappingSnapshot => &LOAD_MAPPING_SNAPSHOT_TARGET,
             TakeMappingSnapshot => &SAVE_MAPPING_SNAPSHOT_TARGET,
             BrowseGroup => &BROWSE_GROUP_MAPPINGS_TARGET,
@@ -858,6 +925,8 @@ impl ReaperTargetType {
             BrowsePotPresets => &BROWSE_POT_PRESETS_TARGET,
             PreviewPotPreset => &PREVIEW_POT_PRESET_TARGET,
             LoadPotPreset => &LOAD_POT_PRESET_TARGET,
+            RatePotPreset => &RATE_POT_PRESET_TARGET,
+            TagPotPreset => &TAG_POT_PRESET_TARGET,
             CompartmentParameterValue => &COMPARTMENT_PARAMETER_VALUE_TARGET,
             StreamDeckBrightness => &STREAM_DECK_BRIGHTNESS_TARGET,
         }
@@ -950,6 +1019,7 @@ pub enum TargetSection {
     Fx,
     #[strum(serialize = "FX parameter")]
     FxParameter,
+    Envelope,
     Pot,
     Send,
     Playtime,
@@ -1,13 +1,15 @@
 use crate::domain::{
-    CompartmentKind, CompartmentParamIndex, RawParamValue, ReaperSourceAddress, StreamDeckDeviceId,
+    CompartmentKind, CompartmentParamIndex, CompartmentParams, MappingId, MappingKey,
+    ModulatorSource, RawParamValue, ReaperSourceAddress, StreamDeckDeviceId,
 };
 use base::hash_util::NonCryptoHashSet;
 use core::fmt;
 use derive_more::Display;
 use helgoboss_learn::{
-    format_percentage_without_unit, parse_percentage_without_unit, ControlValue,
+    format_percentage_without_unit, parse_percentage_without_unit, AbsoluteValue, ControlValue,
     DetailedSourceCharacter, FeedbackValue, SourceCharacter, UnitValue,
 };
+use reaper_high::Project;
 use reaper_medium::{MidiInputDeviceId, MidiOutputDeviceId};
 use std::convert::TryInto;
 use std::error::Error;
@@ -22,6 +24,28 @@ pub enum ReaperSource {
     Timer(TimerSource),
     RealearnParameter(RealearnParameterSource),
     Speech(SpeechSource),
+    Modulator(ModulatorSource),
+}
+
+/// Information required for polling REAPER sources.
+#[derive(Copy, Clone)]
+pub struct ReaperSourcePollContext<'a> {
+    pub params: &'a CompartmentParams,
+    pub project: Project,
+}
+
+impl ReaperSourcePollContext<'_> {
+    pub fn param_value(&self, index: CompartmentParamIndex) -> f64 {
+        self.params.at(index).raw_value() as f64
+    }
+
+    pub fn project_beats(&self) -> f64 {
+        let pos = self
+            .project
+            .play_or_edit_cursor_position()
+            .unwrap_or_default();
+        self.project.beat_info_at(pos).full_beats.get()
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
@@ -137,13 +161,45 @@ impl ReaperSource {
     pub fn on_deactivate(&mut self) {
         match self {
             ReaperSource::Timer(s) => s.on_deactivate(),
+            ReaperSource::Modulator(s) => s.on_deactivate(),
             _ => {}
         }
     }
 
+    /// Returns the key of the mapping whose target value triggers this source (envelopes).
+    pub fn gate_mapping_key(&self) -> Option<&MappingKey> {
+        match self {
+            ReaperSource::Modulator(s) => s.gate_mapping_key(),
+            _ => None,
+        }
+    }
+
+    pub fn gate_mapping_id(&self) -> Option<MappingId> {
+        match self {
+            ReaperSource::Modulator(s) => s.gate_mapping_id(),
+            _ => None,
+        }
+    }
+
+    pub fn set_gate_mapping_id(&mut self, id: Option<MappingId>) {
+        if let ReaperSource::Modulator(s) = self {
+            s.set_gate_mapping_id(id);
+        }
+    }
+
+    pub fn process_target_value_update(
+        &mut self,
+        mapping_id: MappingId,
+        target_value: Option<AbsoluteValue>,
+    ) {
+        if let ReaperSource::Modulator(s) = self {
+            s.process_target_value_update(mapping_id, target_value);
+        }
+    }
+
     /// If this returns `true`, the `poll` method should be called, on a regular basis.
     pub fn wants_to_be_polled(&self) -> bool {
-        matches!(self, ReaperSource::Timer(_))
+        matches!(self, ReaperSource::Timer(_) | ReaperSource::Modulator(_))
     }
 
     pub fn possible_detailed_characters(&self) -> Vec<DetailedSourceCharacter> {
@@ -159,7 +215,7 @@ impl ReaperSource {
                 DetailedSourceCharacter::MomentaryOnOffButton,
                 DetailedSourceCharacter::Trigger,
             ],
-            Speech(_) => vec![DetailedSourceCharacter::RangeControl],
+            Speech(_) | Modulator(_) => vec![DetailedSourceCharacter::RangeControl],
         }
     }
 
@@ -179,15 +235,15 @@ impl ReaperSource {
                 SourceCharacter::MomentaryButton
             }
             RealearnParameter(_) => SourceCharacter::RangeElement,
-            Speech(_) => SourceCharacter::RangeElement,
+            Speech(_) | Modulator(_) => SourceCharacter::RangeElement,
         }
     }
 
-    pub fn poll(&mut self) -> Option<ControlValue> {
-        if let ReaperSource::Timer(t) = self {
-            t.poll()
-        } else {
-            None
+    pub fn poll(&mut self, context: ReaperSourcePollContext) -> Option<ControlValue> {
+        match self {
+            ReaperSource::Timer(t) => t.poll(),
+            ReaperSource::Modulator(m) => m.poll(context),
+            _ => None,
         }
     }
 
@@ -241,7 +297,8 @@ impl ReaperSource {
             | RealearnInstanceStart
             | RealearnCompartmentLoaded
             | Timer(_)
-            | RealearnParameter(_) => None,
+            | RealearnParameter(_)
+            | Modulator(_) => None,
             Speech(s) => Some(ReaperSourceFeedbackValue::Speech(
                 s.feedback(feedback_value),
             )),
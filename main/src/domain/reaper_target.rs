@@ -27,20 +27,24 @@ use helgobox_api::persistence::{ActionScope, SeekBehavior, TrackScope};
 use crate::domain::ui_util::convert_bool_to_unit_value;
 use crate::domain::{
     get_reaper_track_area_of_scope, handle_exclusivity, ActionTarget, AdditionalFeedbackEvent,
-    AllTrackFxEnableTarget, AutomationModeOverrideTarget, BrowseFxsTarget,
-    BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseTracksTarget, Caller,
-    CompartmentParameterValueTarget, ControlContext, DummyTarget, EnigoMouseTarget, FxEnableTarget,
-    FxOnlineTarget, FxOpenTarget, FxParameterTarget, FxParameterTouchStateTarget, FxPresetTarget,
-    FxToolTarget, GoToBookmarkTarget, HierarchyEntry, HierarchyEntryProvider, LoadFxSnapshotTarget,
-    LoadPotPresetTarget, MappingControlContext, MidiSendTarget, ModifyMappingTarget, OscSendTarget,
-    PlayrateTarget, PlaytimeColumnActionTarget, PlaytimeMatrixActionTarget,
-    PlaytimeRowActionTarget, PlaytimeSlotTransportTarget, PreviewPotPresetTarget,
-    RealTimeControlContext, RealTimeFxParameterTarget, RouteMuteTarget, RoutePanTarget,
+    AllTrackFxEnableTarget, AutomationItemActionTarget, AutomationModeOverrideTarget,
+    BrowseFxsTarget, BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseProjectsTarget,
+    BrowseTracksTarget, Caller, CompartmentParameterValueTarget, ControlContext, DummyTarget,
+    EnigoMouseTarget, EnvelopeActionTarget, EnvelopeValueTarget, FxEnableTarget, FxOnlineTarget,
+    FxOpenTarget, FxParameterTarget, FxParameterTouchStateTarget, FxPresetTarget, FxToolTarget,
+    GoToBookmarkTarget, HierarchyEntry, HierarchyEntryProvider, LoadFxSnapshotTarget,
+    LoadPotPresetTarget, LoadScreensetTarget, MacroTarget, MappingControlContext, MidiSendTarget,
+    ModifyMappingTarget, OscSendTarget, PlayrateTarget, PlaytimeColumnActionTarget,
+    PlaytimeMatrixActionTarget, PlaytimeRowActionTarget, PlaytimeSlotTransportTarget,
+    PreviewPotPresetTarget, RatePotPresetTarget, RealTimeControlContext, RealTimeFxParameterTarget,
+    RouteExistenceTarget, RouteMuteTarget, RoutePanTarget, RouteSendModeTarget,
     RouteTouchStateTarget, RouteVolumeTarget, SeekTarget, StreamDeckBrightnessTarget,
-    TakeMappingSnapshotTarget, TargetTypeDef, TempoTarget, TrackArmTarget,
-    TrackAutomationModeTarget, TrackMonitoringModeTarget, TrackMuteTarget, TrackPanTarget,
-    TrackParentSendTarget, TrackPeakTarget, TrackSelectionTarget, TrackShowTarget, TrackSoloTarget,
-    TrackTouchStateTarget, TrackVolumeTarget, TrackWidthTarget, TransportTarget, UnitEvent,
+    TagPotPresetTarget, TakeMappingSnapshotTarget, TargetTypeDef, TempoTarget, TrackArmTarget,
+    TrackAutomationModeTarget, TrackColorTarget, TrackLayoutTarget, TrackMonitoringModeTarget,
+    TrackMuteTarget, TrackNameTarget, TrackPanTarget, TrackParentSendTarget, TrackPeakTarget,
+    TrackRecordInputTarget, TrackRecordModeTarget, TrackSelectionTarget, TrackShowTarget,
+    TrackSoloTarget, TrackTouchStateTarget, TrackVolumeTarget, TrackWidthTarget, TransportTarget,
+    UnitEvent, WindowVisibilityTarget,
 };
 use crate::domain::{
     AnyOnTarget, BrowseGroupMappingsTarget, CompoundChangeEvent, EnableInstancesTarget,
@@ -101,6 +105,9 @@ pub enum ReaperTarget {
     FxTool(FxToolTarget),
     FxParameter(FxParameterTarget),
     FxParameterTouchState(FxParameterTouchStateTarget),
+    EnvelopeValue(EnvelopeValueTarget),
+    EnvelopeAction(EnvelopeActionTarget),
+    AutomationItemAction(AutomationItemActionTarget),
     TrackVolume(TrackVolumeTarget),
     TrackTool(TrackToolTarget),
     TrackPeak(TrackPeakTarget),
@@ -114,14 +121,21 @@ pub enum ReaperTarget {
     TrackPhase(TrackPhaseTarget),
     TrackShow(TrackShowTarget),
     TrackSolo(TrackSoloTarget),
+    TrackName(TrackNameTarget),
+    TrackColor(TrackColorTarget),
+    TrackLayout(TrackLayoutTarget),
     TrackAutomationMode(TrackAutomationModeTarget),
     TrackMonitoringMode(TrackMonitoringModeTarget),
+    TrackRecordInput(TrackRecordInputTarget),
+    TrackRecordMode(TrackRecordModeTarget),
     RoutePan(RoutePanTarget),
     RouteMute(RouteMuteTarget),
     RoutePhase(RoutePhaseTarget),
     RouteMono(RouteMonoTarget),
     RouteAutomationMode(RouteAutomationModeTarget),
     RouteTouchState(RouteTouchStateTarget),
+    RouteExistence(RouteExistenceTarget),
+    RouteSendMode(RouteSendModeTarget),
     Tempo(TempoTarget),
     Playrate(PlayrateTarget),
     AutomationModeOverride(AutomationModeOverrideTarget),
@@ -137,6 +151,9 @@ pub enum ReaperTarget {
     LoadFxSnapshot(LoadFxSnapshotTarget),
     TrackAutomationTouchState(TrackTouchStateTarget),
     GoToBookmark(GoToBookmarkTarget),
+    BrowseProjects(BrowseProjectsTarget),
+    LoadScreenset(LoadScreensetTarget),
+    WindowVisibility(WindowVisibilityTarget),
     Seek(SeekTarget),
     SendMidi(MidiSendTarget),
     SendOsc(OscSendTarget),
@@ -154,12 +171,15 @@ pub enum ReaperTarget {
     TakeMappingSnapshot(TakeMappingSnapshotTarget),
     EnableMappings(EnableMappingsTarget),
     ModifyMapping(ModifyMappingTarget),
+    Macro(MacroTarget),
     EnableInstances(EnableInstancesTarget),
     BrowseGroupMappings(BrowseGroupMappingsTarget),
     BrowsePotFilterItems(BrowsePotFilterItemsTarget),
     BrowsePotPresets(BrowsePotPresetsTarget),
     PreviewPotPreset(PreviewPotPresetTarget),
     LoadPotPreset(LoadPotPresetTarget),
+    RatePotPreset(RatePotPresetTarget),
+    TagPotPreset(TagPotPresetTarget),
     CompartmentParameterValue(CompartmentParameterValueTarget),
     StreamDeckBrightness(StreamDeckBrightnessTarget),
 }
@@ -635,6 +655,9 @@ impl<'a> Target<'a> for ReaperTarget {
             Action(t) => t.current_value(context),
             FxParameter(t) => t.current_value(context),
             FxParameterTouchState(t) => t.current_value(context),
+            EnvelopeValue(t) => t.current_value(context),
+            EnvelopeAction(t) => t.current_value(context),
+            AutomationItemAction(t) => t.current_value(context),
             TrackVolume(t) => t.current_value(context),
             TrackTool(t) => t.current_value(context),
             TrackPan(t) => t.current_value(context),
@@ -646,15 +669,22 @@ impl<'a> Target<'a> for ReaperTarget {
             TrackMute(t) => t.current_value(context),
             TrackPhase(t) => t.current_value(context),
             TrackShow(t) => t.current_value(context),
+            TrackName(t) => t.current_value(context),
+            TrackColor(t) => t.current_value(context),
+            TrackLayout(t) => t.current_value(context),
             TrackSolo(t) => t.current_value(context),
             TrackAutomationMode(t) => t.current_value(context),
             TrackMonitoringMode(t) => t.current_value(context),
+            TrackRecordInput(t) => t.current_value(context),
+            TrackRecordMode(t) => t.current_value(context),
             RoutePan(t) => t.current_value(context),
             RouteMute(t) => t.current_value(context),
             RoutePhase(t) => t.current_value(context),
             RouteMono(t) => t.current_value(context),
             RouteAutomationMode(t) => t.current_value(context),
             RouteTouchState(t) => t.current_value(context),
+            RouteExistence(t) => t.current_value(context),
+            RouteSendMode(t) => t.current_value(context),
             Tempo(t) => t.current_value(context),
             Playrate(t) => t.current_value(context),
             AutomationModeOverride(t) => t.current_value(context),
@@ -674,6 +704,9 @@ impl<'a> Target<'a> for ReaperTarget {
             AnyOn(t) => t.current_value(context),
             TrackAutomationTouchState(t) => t.current_value(context),
             GoToBookmark(t) => t.current_value(context),
+            BrowseProjects(t) => t.current_value(context),
+            LoadScreenset(t) => t.current_value(context),
+            WindowVisibility(t) => t.current_value(context),
             Seek(t) => t.current_value(context),
             PlaytimeSlotTransportAction(t) => t.current_value(context),
             PlaytimeColumnAction(t) => t.current_value(context),
@@ -688,12 +721,15 @@ impl<'a> Target<'a> for ReaperTarget {
             TakeMappingSnapshot(t) => t.current_value(context),
             EnableMappings(t) => t.current_value(context),
             ModifyMapping(t) => t.current_value(context),
+            Macro(t) => t.current_value(context),
             EnableInstances(t) => t.current_value(context),
             BrowseGroupMappings(t) => t.current_value(context),
             BrowsePotFilterItems(t) => t.current_value(context),
             BrowsePotPresets(t) => t.current_value(context),
             PreviewPotPreset(t) => t.current_value(context),
             LoadPotPreset(t) => t.current_value(context),
+            RatePotPreset(t) => t.current_value(context),
+            TagPotPreset(t) => t.current_value(context),
             CompartmentParameterValue(t) => t.current_value(context),
             StreamDeckBrightness(t) => t.current_value(context),
         }
@@ -0,0 +1,191 @@
+use helgobox_api::persistence::CurveSegmentShape;
+use std::sync::Arc;
+
+/// Number of bisection steps when inverting a curve. Gives a precision way beyond what's
+/// noticeable.
+const INVERSION_STEP_COUNT: u32 = 40;
+
+/// A response curve defined by breakpoints, evaluated without any scripting engine.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ResponseCurve {
+    /// Sorted by `x`, contains at least one breakpoint.
+    breakpoints: Vec<CurveBreakpoint>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CurveBreakpoint {
+    pub x: f64,
+    pub y: f64,
+    /// Shape of the segment leading to the next breakpoint.
+    pub shape: CurveSegmentShape,
+    /// From -1.0 to 1.0.
+    pub curvature: f64,
+}
+
+impl ResponseCurve {
+    /// Returns an error if there are no breakpoints.
+    pub fn new(mut breakpoints: Vec<CurveBreakpoint>) -> Result<Self, &'static str> {
+        if breakpoints.is_empty() {
+            return Err("response curve needs at least one breakpoint");
+        }
+        for b in &mut breakpoints {
+            b.x = b.x.clamp(0.0, 1.0);
+            b.y = b.y.clamp(0.0, 1.0);
+            b.curvature = b.curvature.clamp(-1.0, 1.0);
+        }
+        breakpoints.sort_by(|a, b| a.x.total_cmp(&b.x));
+        Ok(Self { breakpoints })
+    }
+
+    /// Maps the given input value to the output value.
+    ///
+    /// Input values outside the breakpoint range map to the value of the nearest breakpoint.
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let first = &self.breakpoints[0];
+        if x <= first.x {
+            return first.y;
+        }
+        let index = self.breakpoints.partition_point(|b| b.x <= x);
+        let from = &self.breakpoints[index - 1];
+        let Some(to) = self.breakpoints.get(index) else {
+            return from.y;
+        };
+        let width = to.x - from.x;
+        if width <= 0.0 {
+            return to.y;
+        }
+        let t = (x - from.x) / width;
+        from.y + (to.y - from.y) * shape_segment(from.shape, from.curvature, t)
+    }
+
+    /// Maps the given output value back to an input value.
+    ///
+    /// This only makes sense for monotonic curves. For other curves, it returns one of the
+    /// possible input values.
+    pub fn evaluate_inverse(&self, y: f64) -> f64 {
+        let first = &self.breakpoints[0];
+        let last = &self.breakpoints[self.breakpoints.len() - 1];
+        let ascending = last.y >= first.y;
+        let (mut low, mut high) = (first.x, last.x);
+        for _ in 0..INVERSION_STEP_COUNT {
+            let mid = (low + high) / 2.0;
+            if (self.evaluate(mid) < y) == ascending {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+}
+
+/// Maps the position within a segment (0.0 to 1.0) to the fraction of the segment's value change.
+fn shape_segment(shape: CurveSegmentShape, curvature: f64, t: f64) -> f64 {
+    match shape {
+        CurveSegmentShape::Linear => t,
+        CurveSegmentShape::Exponential => {
+            // Positive curvature starts slow and ends fast
+            let k = 8.0 * curvature;
+            if k.abs() < 0.001 {
+                return t;
+            }
+            (k * t).exp_m1() / k.exp_m1()
+        }
+        CurveSegmentShape::SCurve => {
+            // Positive curvature flattens the ends, negative curvature flattens the middle
+            let a = 8.0f64.powf(curvature);
+            let rising = t.powf(a);
+            rising / (rising + (1.0 - t).powf(a))
+        }
+        CurveSegmentShape::Step => 0.0,
+    }
+}
+
+/// Applies a response curve, either in control direction or inversely in feedback direction.
+#[derive(Clone, Debug)]
+pub struct CurveTransformation {
+    // Arc because this is cloned together with the mode
+    curve: Arc<ResponseCurve>,
+    inverse: bool,
+}
+
+impl CurveTransformation {
+    pub fn for_control(curve: ResponseCurve) -> Self {
+        Self {
+            curve: Arc::new(curve),
+            inverse: false,
+        }
+    }
+
+    pub fn for_feedback(curve: ResponseCurve) -> Self {
+        Self {
+            curve: Arc::new(curve),
+            inverse: true,
+        }
+    }
+
+    pub fn is_inverse(&self) -> bool {
+        self.inverse
+    }
+
+    pub fn apply(&self, value: f64) -> f64 {
+        if self.inverse {
+            self.curve.evaluate_inverse(value)
+        } else {
+            self.curve.evaluate(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakpoint(x: f64, y: f64, shape: CurveSegmentShape) -> CurveBreakpoint {
+        CurveBreakpoint {
+            x,
+            y,
+            shape,
+            curvature: 0.5,
+        }
+    }
+
+    #[test]
+    fn linear_and_step() {
+        // Given
+        let curve = ResponseCurve::new(vec![
+            breakpoint(0.5, 1.0, CurveSegmentShape::Step),
+            breakpoint(0.0, 0.0, CurveSegmentShape::Linear),
+            breakpoint(0.8, 0.2, CurveSegmentShape::Linear),
+        ])
+        .unwrap();
+        // Then
+        assert_eq!(curve.evaluate(0.0), 0.0);
+        assert_eq!(curve.evaluate(0.25), 0.5);
+        assert_eq!(curve.evaluate(0.5), 1.0);
+        assert_eq!(curve.evaluate(0.7), 1.0);
+        assert_eq!(curve.evaluate(0.8), 0.2);
+        assert_eq!(curve.evaluate(1.0), 0.2);
+    }
+
+    #[test]
+    fn inverse() {
+        // Given
+        let curve = ResponseCurve::new(vec![
+            breakpoint(0.0, 0.0, CurveSegmentShape::Exponential),
+            breakpoint(0.5, 0.3, CurveSegmentShape::SCurve),
+            breakpoint(1.0, 1.0, CurveSegmentShape::Linear),
+        ])
+        .unwrap();
+        // Then
+        for x in [0.0, 0.1, 0.25, 0.5, 0.6, 0.9, 1.0] {
+            let y = curve.evaluate(x);
+            assert!((curve.evaluate_inverse(y) - x).abs() < 0.00001);
+        }
+    }
+
+    #[test]
+    fn no_breakpoints() {
+        assert!(ResponseCurve::new(vec![]).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use helgoboss_learn::UnitValue;
+use std::time::{Duration, Instant};
+
+/// If the smoothed value gets closer than this to the desired value, it snaps to the desired
+/// value. Exponential smoothing would never arrive otherwise.
+const SNAP_THRESHOLD: f64 = 0.0001;
+
+/// Describes how target values follow the desired value over time.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SmoothingSettings {
+    /// Exponential smoothing. After one time constant, about 63% of the distance is covered.
+    ///
+    /// A zero time constant means no smoothing in that direction.
+    TimeConstant { up: Duration, down: Duration },
+    /// Linear slew limiting. The value changes by at most the given normalized amount per second.
+    ///
+    /// A non-positive rate means no smoothing in that direction.
+    MaxRate {
+        up_per_second: f64,
+        down_per_second: f64,
+    },
+}
+
+impl SmoothingSettings {
+    fn next_value(&self, current: f64, desired: f64, elapsed: Duration) -> f64 {
+        let distance = desired - current;
+        let going_up = distance > 0.0;
+        let next = match *self {
+            SmoothingSettings::TimeConstant { up, down } => {
+                let tau = if going_up { up } else { down }.as_secs_f64();
+                if tau <= 0.0 {
+                    return desired;
+                }
+                let factor = 1.0 - (-elapsed.as_secs_f64() / tau).exp();
+                current + distance * factor
+            }
+            SmoothingSettings::MaxRate {
+                up_per_second,
+                down_per_second,
+            } => {
+                let rate = if going_up {
+                    up_per_second
+                } else {
+                    down_per_second
+                };
+                if rate <= 0.0 {
+                    return desired;
+                }
+                let max_delta = rate * elapsed.as_secs_f64();
+                if distance.abs() <= max_delta {
+                    return desired;
+                }
+                current + max_delta.copysign(distance)
+            }
+        };
+        if (desired - next).abs() < SNAP_THRESHOLD {
+            desired
+        } else {
+            next
+        }
+    }
+}
+
+/// Smoothes the values of all targets controlled by one mode.
+///
+/// There's one independent smoothing state per target because in case of multiple resolved
+/// targets, each target can have its own current and desired value.
+#[derive(Debug)]
+pub struct TargetValueSmoother {
+    settings: SmoothingSettings,
+    states: Vec<Option<SmoothingState>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct SmoothingState {
+    current: f64,
+    desired: f64,
+    last_step: Instant,
+}
+
+impl TargetValueSmoother {
+    pub fn new(settings: SmoothingSettings) -> Self {
+        Self {
+            settings,
+            states: vec![],
+        }
+    }
+
+    /// Returns `true` if at least one target hasn't reached its desired value yet.
+    pub fn is_smoothing(&self) -> bool {
+        self.states.iter().any(|s| s.is_some())
+    }
+
+    /// Lets the target with the given index move towards the given desired value.
+    ///
+    /// If that target is already being smoothed, it continues from its intermediate value.
+    /// Otherwise, it starts from the given current value.
+    pub fn start(
+        &mut self,
+        target_index: usize,
+        current: UnitValue,
+        desired: UnitValue,
+        now: Instant,
+    ) {
+        if self.states.len() <= target_index {
+            self.states.resize(target_index + 1, None);
+        }
+        let state = &mut self.states[target_index];
+        match state {
+            Some(s) => {
+                s.desired = desired.get();
+            }
+            None => {
+                *state = Some(SmoothingState {
+                    current: current.get(),
+                    desired: desired.get(),
+                    last_step: now,
+                });
+            }
+        }
+    }
+
+    /// Advances the smoothing of the target with the given index.
+    ///
+    /// Returns the value which should be sent to the target or `None` if that target is not
+    /// being smoothed.
+    pub fn step(&mut self, target_index: usize, now: Instant) -> Option<UnitValue> {
+        let slot = self.states.get_mut(target_index)?;
+        let state = slot.as_mut()?;
+        let elapsed = now.saturating_duration_since(state.last_step);
+        let next = self
+            .settings
+            .next_value(state.current, state.desired, elapsed);
+        if next == state.desired {
+            *slot = None;
+        } else {
+            state.current = next;
+            state.last_step = now;
+        }
+        Some(UnitValue::new_clamped(next))
+    }
+
+    /// Cancels any ongoing smoothing, e.g. because the targets changed.
+    pub fn stop(&mut self) {
+        self.states.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_rate() {
+        // Given
+        let settings = SmoothingSettings::MaxRate {
+            up_per_second: 0.5,
+            down_per_second: 1.0,
+        };
+        let mut smoother = TargetValueSmoother::new(settings);
+        let start = Instant::now();
+        // When
+        smoother.start(0, UnitValue::MIN, UnitValue::MAX, start);
+        // Then
+        assert!(smoother.is_smoothing());
+        let v = smoother
+            .step(0, start + Duration::from_millis(1000))
+            .unwrap();
+        assert!((v.get() - 0.5).abs() < 0.000001);
+        let v = smoother
+            .step(0, start + Duration::from_millis(3000))
+            .unwrap();
+        assert_eq!(v, UnitValue::MAX);
+        assert!(!smoother.is_smoothing());
+        assert_eq!(smoother.step(0, start + Duration::from_millis(4000)), None);
+    }
+
+    #[test]
+    fn time_constant() {
+        // Given
+        let settings = SmoothingSettings::TimeConstant {
+            up: Duration::from_millis(100),
+            down: Duration::ZERO,
+        };
+        let mut smoother = TargetValueSmoother::new(settings);
+        let start = Instant::now();
+        // When
+        smoother.start(1, UnitValue::MIN, UnitValue::MAX, start);
+        // Then
+        assert_eq!(smoother.step(0, start), None);
+        let v = smoother
+            .step(1, start + Duration::from_millis(100))
+            .unwrap();
+        assert!((v.get() - (1.0 - (-1.0f64).exp())).abs() < 0.000001);
+        // Going down has no smoothing
+        smoother.start(1, UnitValue::MAX, UnitValue::MIN, start);
+        let v = smoother
+            .step(1, start + Duration::from_millis(110))
+            .unwrap();
+        assert_eq!(v, UnitValue::MIN);
+        assert!(!smoother.is_smoothing());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, measure_length_at, AutomationEnvelope, CompartmentKind, ControlContext,
+    EnvelopeDescriptor, ExtendedProcessorContext, FeedbackResolution, FxParameterDescriptor,
+    HitResponse, MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType,
+    TargetCharacter, TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence::AutomationItemAction;
+use reaper_high::{Fx, Project, Track};
+use reaper_medium::PositionInSeconds;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedAutomationItemActionTarget {
+    pub envelope_descriptor: EnvelopeDescriptor,
+    pub action: AutomationItemAction,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedAutomationItemActionTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(self
+            .envelope_descriptor
+            .resolve(context, compartment)?
+            .into_iter()
+            .map(|envelope| {
+                ReaperTarget::AutomationItemAction(AutomationItemActionTarget {
+                    envelope,
+                    action: self.action,
+                })
+            })
+            .collect())
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        match self.action {
+            // Loop state depends on the automation item under the cursor.
+            AutomationItemAction::Loop => Some(FeedbackResolution::High),
+            AutomationItemAction::Create | AutomationItemAction::DuplicatePooled => None,
+        }
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        self.envelope_descriptor.track_descriptor()
+    }
+
+    fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        self.envelope_descriptor.fx_parameter_descriptor()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutomationItemActionTarget {
+    pub envelope: AutomationEnvelope,
+    pub action: AutomationItemAction,
+}
+
+impl AutomationItemActionTarget {
+    fn create(&self) -> Result<(), &'static str> {
+        let project = self.envelope.project();
+        let (pos, length) = match project.time_selection() {
+            Some(r) => (r.start, r.end.get() - r.start.get()),
+            None => {
+                let pos = self.envelope.cursor_position();
+                (pos, measure_length_at(project, pos))
+            }
+        };
+        self.envelope.insert_automation_item(None, pos, length)?;
+        Ok(())
+    }
+
+    fn duplicate_pooled(&self) -> Result<(), &'static str> {
+        let pos = self.envelope.cursor_position();
+        let item = self
+            .envelope
+            .automation_item_at(pos)
+            .ok_or("no automation item at cursor")?;
+        let new_pos = PositionInSeconds::new_panic(item.position.get() + item.length);
+        self.envelope
+            .insert_automation_item(Some(item.pool_id), new_pos, item.length)?;
+        Ok(())
+    }
+}
+
+impl RealearnTarget for AutomationItemActionTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        match self.action {
+            AutomationItemAction::Loop => {
+                (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
+            }
+            AutomationItemAction::Create | AutomationItemAction::DuplicatePooled => (
+                ControlType::AbsoluteContinuousRetriggerable,
+                TargetCharacter::Trigger,
+            ),
+        }
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let on = !value.to_unit_value()?.is_zero();
+        match self.action {
+            AutomationItemAction::Create => {
+                if !on {
+                    return Ok(HitResponse::ignored());
+                }
+                self.create()?;
+            }
+            AutomationItemAction::DuplicatePooled => {
+                if !on {
+                    return Ok(HitResponse::ignored());
+                }
+                self.duplicate_pooled()?;
+            }
+            AutomationItemAction::Loop => {
+                let pos = self.envelope.cursor_position();
+                let item = self
+                    .envelope
+                    .automation_item_at(pos)
+                    .ok_or("no automation item at cursor")?;
+                self.envelope.set_automation_item_looped(item.index, on);
+            }
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.envelope.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.envelope.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(self.envelope.track())
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        Some(self.envelope.fx_parameter()?.fx())
+    }
+
+    fn can_report_current_value(&self) -> bool {
+        self.action == AutomationItemAction::Loop
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::AutomationItemAction)
+    }
+}
+
+impl<'a> Target<'a> for AutomationItemActionTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        match self.action {
+            AutomationItemAction::Loop => {
+                let pos = self.envelope.cursor_position();
+                let looped = self
+                    .envelope
+                    .automation_item_at(pos)
+                    .map(|item| self.envelope.automation_item_is_looped(item.index))
+                    .unwrap_or(false);
+                Some(AbsoluteValue::Continuous(convert_bool_to_unit_value(
+                    looped,
+                )))
+            }
+            AutomationItemAction::Create | AutomationItemAction::DuplicatePooled => None,
+        }
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const AUTOMATION_ITEM_ACTION_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Envelope,
+    name: "Automation item action",
+    short_name: "Automation item",
+    hint: "Acts on the automation item at the cursor",
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
@@ -10,11 +10,13 @@ use base::{blocking_lock, blocking_lock_arc};
 use helgoboss_learn::{
     AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, PropValue, Target, UnitValue,
 };
-use pot::{PotPreset, PresetId, RuntimePotUnit};
+use pot::{pot_db, PotPreset, PresetId, RuntimePotUnit};
 use std::borrow::Cow;
 
 #[derive(Debug)]
-pub struct UnresolvedBrowsePotPresetsTarget {}
+pub struct UnresolvedBrowsePotPresetsTarget {
+    pub collection: Option<String>,
+}
 
 impl UnresolvedReaperTargetDef for UnresolvedBrowsePotPresetsTarget {
     fn resolve(
@@ -23,13 +25,19 @@ impl UnresolvedReaperTargetDef for UnresolvedBrowsePotPresetsTarget {
         _: CompartmentKind,
     ) -> Result<Vec<ReaperTarget>, &'static str> {
         Ok(vec![ReaperTarget::BrowsePotPresets(
-            BrowsePotPresetsTarget {},
+            BrowsePotPresetsTarget {
+                collection: self.collection.clone(),
+            },
         )])
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct BrowsePotPresetsTarget {}
+pub struct BrowsePotPresetsTarget {
+    /// If set, browses the presets of this preset collection instead of the currently filtered
+    /// presets.
+    pub collection: Option<String>,
+}
 
 impl RealearnTarget for BrowsePotPresetsTarget {
     fn control_type_and_character(
@@ -99,8 +107,8 @@ impl RealearnTarget for BrowsePotPresetsTarget {
         let preset_id = match preset_index {
             None => None,
             Some(i) => {
-                let id = pot_unit
-                    .find_preset_id_at_index(i)
+                let id = self
+                    .find_preset_id_at_index(&pot_unit, i)
                     .ok_or("no preset found for that index")?;
                 Some(id)
             }
@@ -164,7 +172,7 @@ impl RealearnTarget for BrowsePotPresetsTarget {
         let mut instance_state = context.instance().borrow_mut();
         let pot_unit = instance_state.pot_unit().ok()?;
         let pot_unit = blocking_lock_arc(&pot_unit, "PotUnit from BrowsePotPresetsTarget 5");
-        let preset_index = pot_unit.find_index_of_preset(pot_unit.preset_id()?)?;
+        let preset_index = self.find_index_of_preset(&pot_unit, pot_unit.preset_id()?)?;
         Some(NumericValue::Discrete(preset_index as i32 + 1))
     }
 
@@ -199,7 +207,7 @@ impl BrowsePotPresetsTarget {
         pot_unit: &RuntimePotUnit,
         preset_id: Option<PresetId>,
     ) -> AbsoluteValue {
-        let preset_index = preset_id.and_then(|id| pot_unit.find_index_of_preset(id));
+        let preset_index = preset_id.and_then(|id| self.find_index_of_preset(pot_unit, id));
         let actual = match preset_index {
             None => 0,
             Some(i) => i + 1,
@@ -209,7 +217,33 @@ impl BrowsePotPresetsTarget {
     }
 
     fn preset_count(&self, pot_unit: &RuntimePotUnit) -> u32 {
-        pot_unit.preset_count()
+        match &self.collection {
+            None => pot_unit.preset_count(),
+            Some(name) => pot_db().preset_collection_preset_ids(name).len() as u32,
+        }
+    }
+
+    fn find_preset_id_at_index(&self, pot_unit: &RuntimePotUnit, index: u32) -> Option<PresetId> {
+        match &self.collection {
+            None => pot_unit.find_preset_id_at_index(index),
+            Some(name) => pot_db()
+                .preset_collection_preset_ids(name)
+                .get(index as usize)
+                .copied(),
+        }
+    }
+
+    fn find_index_of_preset(&self, pot_unit: &RuntimePotUnit, id: PresetId) -> Option<u32> {
+        match &self.collection {
+            None => pot_unit.find_index_of_preset(id),
+            Some(name) => {
+                let index = pot_db()
+                    .preset_collection_preset_ids(name)
+                    .iter()
+                    .position(|i| *i == id)?;
+                Some(index as _)
+            }
+        }
     }
 
     fn convert_unit_value_to_preset_index(
@@ -0,0 +1,183 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value, convert_unit_to_discrete_value,
+    AdditionalFeedbackEvent, CompartmentKind, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef, UnresolvedReaperTargetDef,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{ChangeEvent, Project, Reaper};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedBrowseProjectsTarget;
+
+impl UnresolvedReaperTargetDef for UnresolvedBrowseProjectsTarget {
+    fn resolve(
+        &self,
+        _: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::BrowseProjects(BrowseProjectsTarget)])
+    }
+}
+
+/// Switches between the open project tabs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BrowseProjectsTarget;
+
+impl BrowseProjectsTarget {
+    fn project_count(&self) -> u32 {
+        Reaper::get().projects().count() as u32
+    }
+
+    fn current_project_index(&self) -> Option<u32> {
+        let current = Reaper::get().current_project();
+        let index = Reaper::get().projects().position(|p| p == current)?;
+        Some(index as u32)
+    }
+
+    fn select_project(&self, index: u32) -> Result<(), &'static str> {
+        let project = Reaper::get()
+            .projects()
+            .nth(index as usize)
+            .ok_or("project tab doesn't exist")?;
+        unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .SelectProjectInstance(project.raw().as_ptr());
+        }
+        Ok(())
+    }
+}
+
+impl RealearnTarget for BrowseProjectsTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(self.project_count()),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        Ok(convert_unit_to_discrete_value(input, self.project_count()))
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        let index = convert_unit_to_discrete_value(value, self.project_count());
+        match Reaper::get().projects().nth(index as usize) {
+            None => "<None>".to_string(),
+            Some(p) => get_project_name(p),
+        }
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => convert_unit_to_discrete_value(v, self.project_count()),
+            AbsoluteValue::Discrete(f) => f.actual(),
+        };
+        self.select_project(index)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Reaper(ChangeEvent::ProjectSwitched(_))
+            | CompoundChangeEvent::Additional(AdditionalFeedbackEvent::ProjectTabsChanged) => {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(Reaper::get().current_project())
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        Ok(convert_discrete_to_unit_value(value, self.project_count()))
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(get_project_name(Reaper::get().current_project()).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let index = self.current_project_index()?;
+        Some(NumericValue::Discrete(index as i32 + 1))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::BrowseProjects)
+    }
+}
+
+impl<'a> Target<'a> for BrowseProjectsTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let index = self.current_project_index()?;
+        let max_value = self.project_count().saturating_sub(1);
+        Some(AbsoluteValue::Discrete(Fraction::new(index, max_value)))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+fn get_project_name(project: Project) -> String {
+    match project.file() {
+        None => "<Unsaved project>".to_string(),
+        Some(f) => f.file_stem().unwrap_or_default().to_string(),
+    }
+}
+
+pub const BROWSE_PROJECTS_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Project,
+    name: "Browse project tabs",
+    short_name: "Browse project tabs",
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,152 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, AutomationEnvelope, CompartmentKind, ControlContext,
+    EnvelopeDescriptor, ExtendedProcessorContext, FeedbackResolution, FxParameterDescriptor,
+    HitResponse, MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType,
+    TargetCharacter, TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence::EnvelopeAction;
+use reaper_high::{Fx, Project, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedEnvelopeActionTarget {
+    pub envelope_descriptor: EnvelopeDescriptor,
+    pub action: EnvelopeAction,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedEnvelopeActionTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(self
+            .envelope_descriptor
+            .resolve(context, compartment)?
+            .into_iter()
+            .map(|envelope| {
+                ReaperTarget::EnvelopeAction(EnvelopeActionTarget {
+                    envelope,
+                    action: self.action,
+                })
+            })
+            .collect())
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified about envelope visibility or arm changes.
+        match self.action {
+            EnvelopeAction::Visibility | EnvelopeAction::Arm => Some(FeedbackResolution::High),
+            EnvelopeAction::InsertPoint => None,
+        }
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        self.envelope_descriptor.track_descriptor()
+    }
+
+    fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        self.envelope_descriptor.fx_parameter_descriptor()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvelopeActionTarget {
+    pub envelope: AutomationEnvelope,
+    pub action: EnvelopeAction,
+}
+
+impl RealearnTarget for EnvelopeActionTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        match self.action {
+            EnvelopeAction::Visibility | EnvelopeAction::Arm => {
+                (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
+            }
+            EnvelopeAction::InsertPoint => (
+                ControlType::AbsoluteContinuousRetriggerable,
+                TargetCharacter::Trigger,
+            ),
+        }
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let on = !value.to_unit_value()?.is_zero();
+        match self.action {
+            EnvelopeAction::Visibility => self.envelope.set_visible(on)?,
+            EnvelopeAction::Arm => self.envelope.set_armed(on)?,
+            EnvelopeAction::InsertPoint => {
+                if !on {
+                    return Ok(HitResponse::ignored());
+                }
+                let pos = self.envelope.cursor_position();
+                self.envelope.insert_point_at(pos)?;
+            }
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.envelope.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.envelope.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(self.envelope.track())
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        Some(self.envelope.fx_parameter()?.fx())
+    }
+
+    fn can_report_current_value(&self) -> bool {
+        self.action != EnvelopeAction::InsertPoint
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::EnvelopeAction)
+    }
+}
+
+impl<'a> Target<'a> for EnvelopeActionTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let on = match self.action {
+            EnvelopeAction::Visibility => self.envelope.is_visible(),
+            EnvelopeAction::Arm => self.envelope.is_armed(),
+            EnvelopeAction::InsertPoint => return None,
+        };
+        Some(AbsoluteValue::Continuous(convert_bool_to_unit_value(on)))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ENVELOPE_ACTION_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Envelope,
+    name: "Envelope action",
+    short_name: "Envelope action",
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
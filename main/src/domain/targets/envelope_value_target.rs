@@ -0,0 +1,112 @@
+use crate::domain::{
+    AutomationEnvelope, CompartmentKind, ControlContext, EnvelopeDescriptor,
+    ExtendedProcessorContext, FeedbackResolution, FxParameterDescriptor, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use reaper_high::{Fx, Project, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedEnvelopeValueTarget {
+    pub envelope_descriptor: EnvelopeDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedEnvelopeValueTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(self
+            .envelope_descriptor
+            .resolve(context, compartment)?
+            .into_iter()
+            .map(|envelope| ReaperTarget::EnvelopeValue(EnvelopeValueTarget { envelope }))
+            .collect())
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // The envelope value changes while the cursor moves. We don't get notified about that.
+        Some(FeedbackResolution::High)
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        self.envelope_descriptor.track_descriptor()
+    }
+
+    fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        self.envelope_descriptor.fx_parameter_descriptor()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvelopeValueTarget {
+    pub envelope: AutomationEnvelope,
+}
+
+impl RealearnTarget for EnvelopeValueTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (ControlType::AbsoluteContinuous, TargetCharacter::Continuous)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let pos = self.envelope.cursor_position();
+        self.envelope.set_value_at(pos, value.to_unit_value()?)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.envelope.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.envelope.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(self.envelope.track())
+    }
+
+    fn fx(&self) -> Option<&Fx> {
+        Some(self.envelope.fx_parameter()?.fx())
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        let value = self.current_value(context)?.to_unit_value();
+        Some(self.format_value(value, context).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::EnvelopeValue)
+    }
+}
+
+impl<'a> Target<'a> for EnvelopeValueTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let pos = self.envelope.cursor_position();
+        let value: UnitValue = self.envelope.value_at(pos);
+        Some(AbsoluteValue::Continuous(value))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ENVELOPE_VALUE_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Envelope,
+    name: "Value at cursor",
+    short_name: "Envelope value",
+    hint: "Reads/writes at play cursor (if playing) or edit cursor",
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,102 @@
+use crate::domain::{
+    CompartmentKind, ControlContext, ExtendedProcessorContext, HitResponse, MappingControlContext,
+    RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef,
+    UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target};
+use reaper_high::{Project, Reaper};
+use reaper_medium::CommandId;
+
+/// Number of screensets (window sets) offered by REAPER.
+pub const SCREENSET_COUNT: u32 = 10;
+
+#[derive(Debug)]
+pub struct UnresolvedLoadScreensetTarget {
+    pub index: u32,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedLoadScreensetTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        if self.index >= SCREENSET_COUNT {
+            return Err("screenset index out of range");
+        }
+        Ok(vec![ReaperTarget::LoadScreenset(LoadScreensetTarget {
+            project: context.context().project_or_current_project(),
+            index: self.index,
+        })])
+    }
+}
+
+/// Loads a screenset (window set).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoadScreensetTarget {
+    pub project: Project,
+    /// Zero-based, so 0 corresponds to window set #01.
+    pub index: u32,
+}
+
+impl RealearnTarget for LoadScreensetTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Trigger,
+        )
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if value.to_unit_value()?.is_zero() {
+            return Ok(HitResponse::ignored());
+        }
+        // "Screenset: Load window set #01" and following
+        Reaper::get()
+            .main_section()
+            .action_by_command_id(CommandId::new(40454 + self.index))
+            .invoke_as_trigger(Some(self.project), None)
+            .map_err(|_| "couldn't load screenset")?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.project.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project)
+    }
+
+    fn can_report_current_value(&self) -> bool {
+        false
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::LoadScreenset)
+    }
+}
+
+impl<'a> Target<'a> for LoadScreensetTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        None
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const LOAD_SCREENSET_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Global,
+    name: "Load screenset",
+    short_name: "Load screenset",
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,454 @@
+use crate::domain::{
+    CompartmentKind, ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, MappingKey, RealearnTarget, ReaperTarget, ReaperTargetType,
+    TargetCharacter, TargetSection, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence;
+use reaper_high::{Project, Reaper};
+use reaper_medium::PositionInSeconds;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ptr::null_mut;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct UnresolvedMacroTarget {
+    /// Shared with all resolved targets and the unit. That way, a running macro survives target
+    /// refreshes but stops as soon as the mapping is changed or removed.
+    pub state: SharedMacroState,
+}
+
+impl UnresolvedMacroTarget {
+    pub fn new(compartment: CompartmentKind, steps: Vec<MacroStep>) -> Self {
+        let state = MacroState {
+            compartment,
+            steps,
+            run: None,
+        };
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedMacroTarget {
+    fn resolve(
+        &self,
+        _: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::Macro(MacroTarget {
+            state: self.state.clone(),
+        })])
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // Progress changes with time, not via events.
+        Some(FeedbackResolution::High)
+    }
+}
+
+/// Executes a sequence of steps, one after the other.
+#[derive(Clone, Debug)]
+pub struct MacroTarget {
+    pub state: SharedMacroState,
+}
+
+impl PartialEq for MacroTarget {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl RealearnTarget for MacroTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Trigger,
+        )
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if value.to_unit_value()?.is_zero() {
+            return Ok(HitResponse::ignored());
+        }
+        let mut state = self.state.borrow_mut();
+        if state.is_running() {
+            state.cancel();
+        } else {
+            state.start();
+            context
+                .control_context
+                .unit
+                .borrow_mut()
+                .register_running_macro(&self.state);
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        true
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let state = self.state.borrow();
+        let text = match &state.run {
+            None => "Idle".to_string(),
+            Some(run) => format!("Step {} of {}", run.next_step_index, state.steps.len()),
+        };
+        Some(text.into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::Macro)
+    }
+}
+
+impl<'a> Target<'a> for MacroTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let progress = self.state.borrow().progress();
+        Some(AbsoluteValue::Continuous(progress))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const MACRO_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::ReaLearn,
+    name: "Run macro",
+    short_name: "Run macro",
+    ..DEFAULT_TARGET
+};
+
+pub type SharedMacroState = Rc<RefCell<MacroState>>;
+pub type WeakMacroState = Weak<RefCell<MacroState>>;
+
+#[derive(Debug)]
+pub struct MacroState {
+    compartment: CompartmentKind,
+    steps: Vec<MacroStep>,
+    /// Is `Some` while the macro is running.
+    run: Option<MacroRun>,
+}
+
+#[derive(Debug)]
+struct MacroRun {
+    next_step_index: usize,
+    waiting_until: Option<Instant>,
+}
+
+impl MacroState {
+    pub fn compartment(&self) -> CompartmentKind {
+        self.compartment
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// Returns the fraction of steps which have been executed already, or zero if not running.
+    pub fn progress(&self) -> UnitValue {
+        match &self.run {
+            Some(run) if !self.steps.is_empty() => {
+                UnitValue::new_clamped(run.next_step_index as f64 / self.steps.len() as f64)
+            }
+            _ => UnitValue::MIN,
+        }
+    }
+
+    fn start(&mut self) {
+        self.run = Some(MacroRun {
+            next_step_index: 0,
+            waiting_until: None,
+        });
+    }
+
+    fn cancel(&mut self) {
+        self.run = None;
+    }
+
+    /// Executes all steps which are due, up to the next wait step which is not over yet.
+    ///
+    /// Returns the mapping invocations that must be carried out by the caller, in the given order.
+    pub fn advance(&mut self, now: Instant, project: Project) -> Vec<MacroMappingInvocation> {
+        self.advance_internal(now, |wait| wait.duration(project))
+    }
+
+    fn advance_internal(
+        &mut self,
+        now: Instant,
+        wait_duration: impl Fn(&MacroWait) -> Duration,
+    ) -> Vec<MacroMappingInvocation> {
+        let mut invocations = vec![];
+        let Some(run) = &mut self.run else {
+            return invocations;
+        };
+        loop {
+            if let Some(waiting_until) = run.waiting_until {
+                if now < waiting_until {
+                    return invocations;
+                }
+                run.waiting_until = None;
+            }
+            let Some(step) = self.steps.get(run.next_step_index) else {
+                break;
+            };
+            run.next_step_index += 1;
+            match step {
+                MacroStep::SetTargetValue { mapping_key, value } => {
+                    invocations.push(MacroMappingInvocation {
+                        mapping_key: mapping_key.clone(),
+                        value: *value,
+                    });
+                }
+                MacroStep::InvokeMapping { mapping_key } => {
+                    invocations.push(MacroMappingInvocation {
+                        mapping_key: mapping_key.clone(),
+                        value: UnitValue::MAX,
+                    });
+                }
+                MacroStep::Wait(wait) => {
+                    run.waiting_until = Some(now + wait_duration(wait));
+                }
+            }
+        }
+        // All steps executed.
+        self.run = None;
+        invocations
+    }
+}
+
+/// Instruction to control another mapping.
+#[derive(Debug, PartialEq)]
+pub struct MacroMappingInvocation {
+    pub mapping_key: MappingKey,
+    pub value: UnitValue,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum MacroStep {
+    SetTargetValue {
+        mapping_key: MappingKey,
+        value: UnitValue,
+    },
+    InvokeMapping {
+        mapping_key: MappingKey,
+    },
+    Wait(MacroWait),
+}
+
+impl TryFrom<persistence::MacroStep> for MacroStep {
+    type Error = &'static str;
+
+    fn try_from(value: persistence::MacroStep) -> Result<Self, Self::Error> {
+        use persistence::MacroStep as S;
+        let step = match value {
+            S::SetTargetValue { mapping, value } => Self::SetTargetValue {
+                mapping_key: mapping.into(),
+                value: UnitValue::try_from(value)
+                    .map_err(|_| "macro target value must be between 0.0 and 1.0")?,
+            },
+            S::InvokeMapping { mapping } => Self::InvokeMapping {
+                mapping_key: mapping.into(),
+            },
+            S::WaitMillis { millis } => Self::Wait(MacroWait::Millis(millis)),
+            S::WaitBeats { beats } => {
+                if beats < 0.0 {
+                    return Err("macro wait beats must not be negative");
+                }
+                Self::Wait(MacroWait::Beats(beats))
+            }
+            S::WaitUntilNextBar => Self::Wait(MacroWait::UntilNextBar),
+        };
+        Ok(step)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MacroWait {
+    Millis(u32),
+    Beats(f64),
+    UntilNextBar,
+}
+
+impl MacroWait {
+    /// Musical durations are converted to wall-clock durations at the moment the wait starts,
+    /// based on the tempo map of the given project at the play (or edit) cursor position.
+    fn duration(&self, project: Project) -> Duration {
+        let seconds = match self {
+            MacroWait::Millis(millis) => return Duration::from_millis(*millis as u64),
+            MacroWait::Beats(beats) => {
+                let pos = project.play_or_edit_cursor_position().unwrap_or_default();
+                seconds_until_beats(project, pos, *beats)
+            }
+            MacroWait::UntilNextBar => {
+                let pos = project.play_or_edit_cursor_position().unwrap_or_default();
+                seconds_until_next_bar(project, pos)
+            }
+        };
+        Duration::from_secs_f64(seconds.max(0.0))
+    }
+}
+
+fn seconds_until_beats(project: Project, pos: PositionInSeconds, beats: f64) -> f64 {
+    let low = Reaper::get().medium_reaper().low();
+    let proj = project.raw().as_ptr();
+    unsafe {
+        let mut full_beats = 0.0;
+        low.TimeMap2_timeToBeats(
+            proj,
+            pos.get(),
+            null_mut(),
+            null_mut(),
+            &mut full_beats,
+            null_mut(),
+        );
+        low.TimeMap2_beatsToTime(proj, full_beats + beats, null_mut()) - pos.get()
+    }
+}
+
+fn seconds_until_next_bar(project: Project, pos: PositionInSeconds) -> f64 {
+    let low = Reaper::get().medium_reaper().low();
+    let proj = project.raw().as_ptr();
+    unsafe {
+        let mut measure_index = 0;
+        low.TimeMap2_timeToBeats(
+            proj,
+            pos.get(),
+            &mut measure_index,
+            null_mut(),
+            null_mut(),
+            null_mut(),
+        );
+        let next_measure_index = measure_index + 1;
+        low.TimeMap2_beatsToTime(proj, 0.0, &next_measure_index) - pos.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(steps: Vec<MacroStep>) -> MacroState {
+        MacroState {
+            compartment: CompartmentKind::Main,
+            steps,
+            run: None,
+        }
+    }
+
+    fn invocation(key: &str, value: f64) -> MacroMappingInvocation {
+        MacroMappingInvocation {
+            mapping_key: key.to_string().into(),
+            value: UnitValue::new(value),
+        }
+    }
+
+    fn wait_duration(wait: &MacroWait) -> Duration {
+        match wait {
+            MacroWait::Millis(millis) => Duration::from_millis(*millis as u64),
+            _ => Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn not_running() {
+        // Given
+        let mut state = state(vec![MacroStep::InvokeMapping {
+            mapping_key: "a".to_string().into(),
+        }]);
+        // When
+        let invocations = state.advance_internal(Instant::now(), wait_duration);
+        // Then
+        assert!(invocations.is_empty());
+        assert!(!state.is_running());
+        assert_eq!(state.progress(), UnitValue::MIN);
+    }
+
+    #[test]
+    fn steps_without_wait() {
+        // Given
+        let mut state = state(vec![
+            MacroStep::SetTargetValue {
+                mapping_key: "a".to_string().into(),
+                value: UnitValue::new(0.5),
+            },
+            MacroStep::InvokeMapping {
+                mapping_key: "b".to_string().into(),
+            },
+        ]);
+        state.start();
+        // When
+        let invocations = state.advance_internal(Instant::now(), wait_duration);
+        // Then
+        assert_eq!(
+            invocations,
+            vec![invocation("a", 0.5), invocation("b", 1.0)]
+        );
+        assert!(!state.is_running());
+    }
+
+    #[test]
+    fn steps_with_wait() {
+        // Given
+        let mut state = state(vec![
+            MacroStep::InvokeMapping {
+                mapping_key: "a".to_string().into(),
+            },
+            MacroStep::Wait(MacroWait::Millis(100)),
+            MacroStep::InvokeMapping {
+                mapping_key: "b".to_string().into(),
+            },
+        ]);
+        let start = Instant::now();
+        state.start();
+        // When
+        let first = state.advance_internal(start, wait_duration);
+        let during_wait = state.advance_internal(start + Duration::from_millis(50), wait_duration);
+        let progress_during_wait = state.progress();
+        let after_wait = state.advance_internal(start + Duration::from_millis(100), wait_duration);
+        // Then
+        assert_eq!(first, vec![invocation("a", 1.0)]);
+        assert!(during_wait.is_empty());
+        assert_eq!(progress_during_wait, UnitValue::new(2.0 / 3.0));
+        assert_eq!(after_wait, vec![invocation("b", 1.0)]);
+        assert!(!state.is_running());
+    }
+
+    #[test]
+    fn cancel_and_restart() {
+        // Given
+        let mut state = state(vec![
+            MacroStep::Wait(MacroWait::Millis(100)),
+            MacroStep::InvokeMapping {
+                mapping_key: "a".to_string().into(),
+            },
+        ]);
+        let start = Instant::now();
+        state.start();
+        state.advance_internal(start, wait_duration);
+        // When
+        state.cancel();
+        let after_cancel =
+            state.advance_internal(start + Duration::from_millis(200), wait_duration);
+        state.start();
+        let after_restart =
+            state.advance_internal(start + Duration::from_millis(200), wait_duration);
+        // Then
+        assert!(after_cancel.is_empty());
+        assert!(after_restart.is_empty());
+        assert!(state.is_running());
+        assert_eq!(state.progress(), UnitValue::new(0.5));
+    }
+}
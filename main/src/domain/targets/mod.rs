@@ -55,6 +55,15 @@ pub use automation_mode_override_target::*;
 mod fx_parameter_target;
 pub use fx_parameter_target::*;
 
+mod envelope_value_target;
+pub use envelope_value_target::*;
+
+mod envelope_action_target;
+pub use envelope_action_target::*;
+
+mod automation_item_action_target;
+pub use automation_item_action_target::*;
+
 mod fx_enable_target;
 pub use fx_enable_target::*;
 
@@ -88,6 +97,15 @@ pub use track_touch_state_target::*;
 mod go_to_bookmark_target;
 pub use go_to_bookmark_target::*;
 
+mod browse_projects_target;
+pub use browse_projects_target::*;
+
+mod load_screenset_target;
+pub use load_screenset_target::*;
+
+mod window_visibility_target;
+pub use window_visibility_target::*;
+
 mod seek_target;
 pub use seek_target::*;
 
@@ -121,6 +139,12 @@ pub use route_automation_mode_target::*;
 mod route_touch_state_target;
 pub use route_touch_state_target::*;
 
+mod route_existence_target;
+pub use route_existence_target::*;
+
+mod route_send_mode_target;
+pub use route_send_mode_target::*;
+
 mod track_pan_target;
 pub use track_pan_target::*;
 
@@ -148,12 +172,27 @@ pub use track_show_target::*;
 mod track_solo_target;
 pub use track_solo_target::*;
 
+mod track_name_target;
+pub use track_name_target::*;
+
+mod track_color_target;
+pub use track_color_target::*;
+
+mod track_layout_target;
+pub use track_layout_target::*;
+
 mod track_automation_mode_target;
 pub use track_automation_mode_target::*;
 
 mod track_monitoring_mode_target;
 pub use track_monitoring_mode_target::*;
 
+mod track_record_input_target;
+pub use track_record_input_target::*;
+
+mod track_record_mode_target;
+pub use track_record_mode_target::*;
+
 mod load_mapping_snapshot_target;
 pub use load_mapping_snapshot_target::*;
 
@@ -166,6 +205,9 @@ pub use enable_mappings_target::*;
 mod modify_mapping_target;
 pub use modify_mapping_target::*;
 
+mod macro_target;
+pub use macro_target::*;
+
 mod enable_instances_target;
 pub use enable_instances_target::*;
 
@@ -189,6 +231,10 @@ pub use browse_pot_presets_target::*;
 
 mod preview_pot_preset_target;
 pub use preview_pot_preset_target::*;
+mod rate_pot_preset_target;
+pub use rate_pot_preset_target::*;
+mod tag_pot_preset_target;
+pub use tag_pot_preset_target::*;
 
 mod load_pot_preset_target;
 pub use load_pot_preset_target::*;
@@ -0,0 +1,168 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value, convert_unit_to_discrete_value,
+    CompartmentKind, CompoundChangeEvent, ControlContext, ExtendedProcessorContext, HitResponse,
+    InstanceStateChanged, MappingControlContext, PotStateChangedEvent, RealearnTarget,
+    ReaperTarget, ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef,
+    UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use base::{blocking_lock, blocking_lock_arc};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use pot::MAX_PRESET_RATING;
+use std::borrow::Cow;
+
+/// "Not rated" plus all possible ratings.
+const RATING_COUNT: u32 = MAX_PRESET_RATING as u32 + 1;
+
+#[derive(Debug)]
+pub struct UnresolvedRatePotPresetTarget {}
+
+impl UnresolvedReaperTargetDef for UnresolvedRatePotPresetTarget {
+    fn resolve(
+        &self,
+        _: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::RatePotPreset(RatePotPresetTarget {})])
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RatePotPresetTarget {}
+
+impl RealearnTarget for RatePotPresetTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(RATING_COUNT),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        value: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        Ok(convert_unit_to_discrete_value(value, RATING_COUNT))
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        Ok(convert_discrete_to_unit_value(value, RATING_COUNT))
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let rating = convert_unit_to_discrete_value(value.to_unit_value()?, RATING_COUNT);
+        // Don't keep the instance state borrowed while updating, this emits events
+        let shared_pot_unit = context.control_context.instance().borrow_mut().pot_unit()?;
+        let mut pot_unit = blocking_lock(&*shared_pot_unit, "PotUnit from RatePotPresetTarget 1");
+        let preset_id = pot_unit.preset_id().ok_or("no Pot preset selected")?;
+        pot_unit
+            .update_user_metadata(preset_id, shared_pot_unit.clone(), |m| {
+                m.set_rating(rating as u8)
+            })
+            .map_err(|_| "couldn't rate preset")?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, context: ControlContext) -> bool {
+        let mut instance_state = context.instance().borrow_mut();
+        let pot_unit = match instance_state.pot_unit() {
+            Ok(u) => u,
+            Err(_) => return false,
+        };
+        let pot_unit = blocking_lock_arc(&pot_unit, "PotUnit from RatePotPresetTarget 2");
+        pot_unit.preset_id().is_some()
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Instance(InstanceStateChanged::PotStateChanged(
+                PotStateChangedEvent::PresetChanged { .. }
+                | PotStateChangedEvent::UserMetadataChanged { .. },
+            )) => (true, None),
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        let text = match self.current_rating(context)? {
+            0 => "Not rated".to_string(),
+            r => "★".repeat(r as usize),
+        };
+        Some(text.into())
+    }
+
+    fn numeric_value(&self, context: ControlContext) -> Option<NumericValue> {
+        Some(NumericValue::Discrete(self.current_rating(context)? as i32))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::RatePotPreset)
+    }
+}
+
+impl<'a> Target<'a> for RatePotPresetTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, context: Self::Context) -> Option<AbsoluteValue> {
+        let rating = self.current_rating(context)?;
+        Some(AbsoluteValue::Discrete(Fraction::new(
+            rating as u32,
+            MAX_PRESET_RATING as u32,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+impl RatePotPresetTarget {
+    /// Returns 0 if the currently selected preset is not rated.
+    fn current_rating(&self, context: ControlContext) -> Option<u8> {
+        let mut instance_state = context.instance().borrow_mut();
+        let pot_unit = instance_state.pot_unit().ok()?;
+        let pot_unit = blocking_lock_arc(&pot_unit, "PotUnit from RatePotPresetTarget 3");
+        let metadata = pot_unit.user_metadata(pot_unit.preset_id()?)?;
+        Some(metadata.rating.unwrap_or(0))
+    }
+}
+
+pub const RATE_POT_PRESET_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Pot,
+    name: "Rate preset",
+    short_name: "Rate Pot preset",
+    ..DEFAULT_TARGET
+};
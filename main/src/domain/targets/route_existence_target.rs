@@ -0,0 +1,217 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    find_route_by_related_track, format_value_as_on_off, get_effective_tracks, CompartmentKind,
+    ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackRouteDescriptor, TrackRouteType, UnresolvedReaperTargetDef,
+    DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence::RouteExistenceAction;
+use reaper_high::{Project, Reaper, Track, TrackRoute, TrackRoutePartner};
+use reaper_medium::TrackSendDirection;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedRouteExistenceTarget {
+    /// The route selector is interpreted as partner track selector.
+    pub descriptor: TrackRouteDescriptor,
+    pub action: RouteExistenceAction,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedRouteExistenceTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        if self.descriptor.route.r#type == TrackRouteType::HardwareOutput {
+            return Err("hardware outputs can't be created or removed");
+        }
+        let tracks = get_effective_tracks(
+            context,
+            &self.descriptor.track_descriptor.track,
+            compartment,
+        )?;
+        tracks
+            .into_iter()
+            .map(|track| {
+                let partner = self
+                    .descriptor
+                    .route
+                    .selector
+                    .resolve_partner_track(&track, context, compartment)
+                    .map_err(|_| "partner track doesn't exist")?;
+                let target = ReaperTarget::RouteExistence(RouteExistenceTarget {
+                    track,
+                    partner,
+                    route_type: self.descriptor.route.r#type,
+                    action: self.action,
+                });
+                Ok(target)
+            })
+            .collect()
+    }
+
+    fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified when routes are created or removed.
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteExistenceTarget {
+    pub track: Track,
+    pub partner: Track,
+    pub route_type: TrackRouteType,
+    pub action: RouteExistenceAction,
+}
+
+impl RouteExistenceTarget {
+    fn existing_route(&self) -> Option<TrackRoute> {
+        find_route_by_related_track(&self.track, &self.partner, self.route_type).ok()?
+    }
+
+    fn create_route(&self) -> Result<(), &'static str> {
+        if self.existing_route().is_some() {
+            return Ok(());
+        }
+        let (source, destination) = match self.route_type {
+            TrackRouteType::Send => (&self.track, &self.partner),
+            TrackRouteType::Receive => (&self.partner, &self.track),
+            TrackRouteType::HardwareOutput => return Err("hardware outputs not supported"),
+        };
+        let source = source.raw().map_err(|_| "track not available")?;
+        let destination = destination
+            .raw()
+            .map_err(|_| "partner track not available")?;
+        let index = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .CreateTrackSend(source.as_ptr(), destination.as_ptr())
+        };
+        if index < 0 {
+            return Err("couldn't create route");
+        }
+        Ok(())
+    }
+
+    fn remove_route(&self) -> Result<(), &'static str> {
+        let Some(route) = self.existing_route() else {
+            return Ok(());
+        };
+        let (category, index) = route_category_and_index(&route);
+        let track = route.track().raw().map_err(|_| "track not available")?;
+        let successful = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .RemoveTrackSend(track.as_ptr(), category, index)
+        };
+        if !successful {
+            return Err("couldn't remove route");
+        }
+        Ok(())
+    }
+}
+
+impl RealearnTarget for RouteExistenceTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        match self.action {
+            RouteExistenceAction::Toggle => {
+                (ControlType::AbsoluteContinuous, TargetCharacter::Switch)
+            }
+            RouteExistenceAction::Create | RouteExistenceAction::Remove => (
+                ControlType::AbsoluteContinuousRetriggerable,
+                TargetCharacter::Trigger,
+            ),
+        }
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let on = !value.to_unit_value()?.is_zero();
+        match self.action {
+            RouteExistenceAction::Toggle => {
+                if on {
+                    self.create_route()?;
+                } else {
+                    self.remove_route()?;
+                }
+            }
+            RouteExistenceAction::Create | RouteExistenceAction::Remove if !on => {
+                return Ok(HitResponse::ignored());
+            }
+            RouteExistenceAction::Create => self.create_route()?,
+            RouteExistenceAction::Remove => self.remove_route()?,
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available() && self.partner.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::RouteExistence)
+    }
+}
+
+impl<'a> Target<'a> for RouteExistenceTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = convert_bool_to_unit_value(self.existing_route().is_some());
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ROUTE_EXISTENCE_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Send,
+    name: "Create/remove",
+    short_name: "Send existence",
+    hint: "Addresses the partner track, not an existing route",
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
+
+/// Returns the send category and index as expected by the low-level REAPER send functions.
+pub fn route_category_and_index(route: &TrackRoute) -> (i32, i32) {
+    let category = match route.direction() {
+        TrackSendDirection::Receive => -1,
+        TrackSendDirection::Send => match route.partner() {
+            Some(TrackRoutePartner::HardwareOutput(_)) => 1,
+            _ => 0,
+        },
+    };
+    (category, route.index() as i32)
+}
@@ -0,0 +1,189 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, get_track_routes, route_category_and_index, CompartmentKind,
+    ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackRouteDescriptor, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence::SendMode;
+use reaper_high::{Project, Reaper, Track, TrackRoute};
+use reaper_medium::reaper_str;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedRouteSendModeTarget {
+    pub descriptor: TrackRouteDescriptor,
+    pub mode: SendMode,
+    pub poll_for_feedback: bool,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedRouteSendModeTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        let routes = get_track_routes(context, &self.descriptor, compartment)?;
+        let targets = routes
+            .into_iter()
+            .map(|route| {
+                ReaperTarget::RouteSendMode(RouteSendModeTarget {
+                    route,
+                    poll_for_feedback: self.poll_for_feedback,
+                    mode: self.mode,
+                })
+            })
+            .collect();
+        Ok(targets)
+    }
+
+    fn route_descriptor(&self) -> Option<&TrackRouteDescriptor> {
+        Some(&self.descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        if self.poll_for_feedback {
+            Some(FeedbackResolution::High)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteSendModeTarget {
+    pub route: TrackRoute,
+    pub poll_for_feedback: bool,
+    pub mode: SendMode,
+}
+
+impl RouteSendModeTarget {
+    fn send_mode(&self) -> Option<SendMode> {
+        let (category, index) = route_category_and_index(&self.route);
+        let track = self.route.track().raw().ok()?;
+        let value = unsafe {
+            Reaper::get().medium_reaper().low().GetTrackSendInfo_Value(
+                track.as_ptr(),
+                category,
+                index,
+                reaper_str!("I_SENDMODE").as_ptr(),
+            )
+        };
+        let mode = match value.round() as i32 {
+            0 => SendMode::PostFader,
+            1 => SendMode::PreFx,
+            // 2 is the deprecated post-FX mode
+            2 | 3 => SendMode::PostFx,
+            _ => return None,
+        };
+        Some(mode)
+    }
+
+    fn set_send_mode(&self, mode: SendMode) -> Result<(), &'static str> {
+        let (category, index) = route_category_and_index(&self.route);
+        let track = self
+            .route
+            .track()
+            .raw()
+            .map_err(|_| "track not available")?;
+        let value = match mode {
+            SendMode::PostFader => 0.0,
+            SendMode::PreFx => 1.0,
+            SendMode::PostFx => 3.0,
+        };
+        let successful = unsafe {
+            Reaper::get().medium_reaper().low().SetTrackSendInfo_Value(
+                track.as_ptr(),
+                category,
+                index,
+                reaper_str!("I_SENDMODE").as_ptr(),
+                value,
+            )
+        };
+        if !successful {
+            return Err("couldn't set send mode");
+        }
+        Ok(())
+    }
+}
+
+impl RealearnTarget for RouteSendModeTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        // Retriggerable because of #277
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Switch,
+        )
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if value.to_unit_value()?.is_zero() {
+            self.set_send_mode(SendMode::PostFader)?;
+        } else {
+            self.set_send_mode(self.mode)?;
+        }
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.route.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.route.track().project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(self.route.track())
+    }
+
+    fn route(&self) -> Option<&TrackRoute> {
+        Some(&self.route)
+    }
+
+    fn supports_automatic_feedback(&self) -> bool {
+        self.poll_for_feedback
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::RouteSendMode)
+    }
+}
+
+impl<'a> Target<'a> for RouteSendModeTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = convert_bool_to_unit_value(self.send_mode()? == self.mode);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const ROUTE_SEND_MODE_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Send,
+    name: "Send mode",
+    short_name: "Send mode",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_poll_for_feedback: true,
+    supports_track: true,
+    supports_send: true,
+    ..DEFAULT_TARGET
+};
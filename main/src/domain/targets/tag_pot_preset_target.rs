@@ -0,0 +1,135 @@
+use crate::domain::{
+    format_value_as_on_off, CompartmentKind, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, HitResponse, InstanceStateChanged, MappingControlContext,
+    PotStateChangedEvent, RealearnTarget, ReaperTarget, ReaperTargetType, Tag, TargetCharacter,
+    TargetSection, TargetTypeDef, UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use base::{blocking_lock, blocking_lock_arc};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTagPotPresetTarget {
+    pub tags: Vec<Tag>,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTagPotPresetTarget {
+    fn resolve(
+        &self,
+        _: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::TagPotPreset(TagPotPresetTarget {
+            tags: self.tags.clone(),
+        })])
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TagPotPresetTarget {
+    pub tags: Vec<Tag>,
+}
+
+impl RealearnTarget for TagPotPresetTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Switch,
+        )
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        context: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if self.tags.is_empty() {
+            return Err("no tags given");
+        }
+        let add = value.is_on();
+        // Don't keep the instance state borrowed while updating, this emits events
+        let shared_pot_unit = context.control_context.instance().borrow_mut().pot_unit()?;
+        let mut pot_unit = blocking_lock(&*shared_pot_unit, "PotUnit from TagPotPresetTarget 1");
+        let preset_id = pot_unit.preset_id().ok_or("no Pot preset selected")?;
+        pot_unit
+            .update_user_metadata(preset_id, shared_pot_unit.clone(), |m| {
+                for tag in &self.tags {
+                    let tag = tag.to_string();
+                    if add {
+                        m.add_tag(&tag);
+                    } else {
+                        m.remove_tag(&tag);
+                    }
+                }
+            })
+            .map_err(|_| "couldn't tag preset")?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, context: ControlContext) -> bool {
+        let mut instance_state = context.instance().borrow_mut();
+        let pot_unit = match instance_state.pot_unit() {
+            Ok(u) => u,
+            Err(_) => return false,
+        };
+        let pot_unit = blocking_lock_arc(&pot_unit, "PotUnit from TagPotPresetTarget 2");
+        pot_unit.preset_id().is_some()
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Instance(InstanceStateChanged::PotStateChanged(
+                PotStateChangedEvent::PresetChanged { .. }
+                | PotStateChangedEvent::UserMetadataChanged { .. },
+            )) => (true, None),
+            _ => (false, None),
+        }
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TagPotPreset)
+    }
+}
+
+impl<'a> Target<'a> for TagPotPresetTarget {
+    type Context = ControlContext<'a>;
+
+    /// "On" if the currently selected preset has all of the given tags.
+    fn current_value(&self, context: Self::Context) -> Option<AbsoluteValue> {
+        let mut instance_state = context.instance().borrow_mut();
+        let pot_unit = instance_state.pot_unit().ok()?;
+        let pot_unit = blocking_lock_arc(&pot_unit, "PotUnit from TagPotPresetTarget 3");
+        let metadata = pot_unit.user_metadata(pot_unit.preset_id()?)?;
+        let has_all_tags = !self.tags.is_empty()
+            && self
+                .tags
+                .iter()
+                .all(|tag| metadata.has_tag(&tag.to_string()));
+        let uv = if has_all_tags {
+            UnitValue::MAX
+        } else {
+            UnitValue::MIN
+        };
+        Some(AbsoluteValue::Continuous(uv))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TAG_POT_PRESET_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Pot,
+    name: "Tag preset",
+    short_name: "Tag Pot preset",
+    supports_tags: true,
+    ..DEFAULT_TARGET
+};
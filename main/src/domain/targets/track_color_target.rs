@@ -0,0 +1,326 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value_with_none,
+    convert_unit_to_discrete_value_with_none, get_effective_tracks, CompartmentKind,
+    ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{Project, Reaper, Track};
+use reaper_medium::{reaper_str, RgbColor};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackColorTarget {
+    pub track_descriptor: TrackDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackColorTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| ReaperTarget::TrackColor(TrackColorTarget { track }))
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified when the track color changes.
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackColorTarget {
+    pub track: Track,
+}
+
+/// The colors to cycle through. Discrete value 0 stands for "no custom color".
+const TRACK_COLOR_PALETTE: [(u8, u8, u8); 12] = [
+    (255, 0, 0),
+    (255, 128, 0),
+    (255, 255, 0),
+    (128, 255, 0),
+    (0, 255, 0),
+    (0, 255, 128),
+    (0, 255, 255),
+    (0, 128, 255),
+    (0, 0, 255),
+    (128, 0, 255),
+    (255, 0, 255),
+    (255, 0, 128),
+];
+
+const PALETTE_SIZE: u32 = TRACK_COLOR_PALETTE.len() as u32;
+
+impl TrackColorTarget {
+    fn palette_index(&self) -> Option<Option<u32>> {
+        match self.track.custom_color() {
+            None => Some(None),
+            Some(color) => {
+                let index = TRACK_COLOR_PALETTE
+                    .iter()
+                    .position(|(r, g, b)| (*r, *g, *b) == (color.r, color.g, color.b))?;
+                Some(Some(index as u32))
+            }
+        }
+    }
+
+    fn set_color(&self, color: Option<RgbColor>) -> Result<(), &'static str> {
+        let raw_track = self.track.raw().map_err(|_| "track not available")?;
+        let low = Reaper::get().medium_reaper().low();
+        let value = match color {
+            None => 0.0,
+            Some(RgbColor { r, g, b }) => {
+                // 0x1000000 marks the custom color as active
+                let native = unsafe { low.ColorToNative(r as _, g as _, b as _) };
+                (native | 0x1000000) as f64
+            }
+        };
+        let successful = unsafe {
+            low.SetMediaTrackInfo_Value(
+                raw_track.as_ptr(),
+                reaper_str!("I_CUSTOMCOLOR").as_ptr(),
+                value,
+            )
+        };
+        if !successful {
+            return Err("couldn't set track color");
+        }
+        Ok(())
+    }
+}
+
+impl RealearnTarget for TrackColorTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        // `+ 1` because "<Default>" is also a possible value.
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(PALETTE_SIZE + 1),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        let value = convert_unit_to_discrete_value_with_none(input, PALETTE_SIZE)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        Ok(value)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        match convert_unit_to_discrete_value_with_none(value, PALETTE_SIZE) {
+            None => "<Default>".to_string(),
+            Some(i) => format_color(palette_color(i)),
+        }
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => {
+                convert_unit_to_discrete_value_with_none(v, PALETTE_SIZE)
+            }
+            AbsoluteValue::Discrete(f) => {
+                if f.actual() == 0 {
+                    None
+                } else {
+                    Some(f.actual() - 1)
+                }
+            }
+        };
+        let color = index.map(palette_color);
+        self.set_color(color)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn supports_text_input(&self) -> bool {
+        true
+    }
+
+    fn hit_with_text(
+        &mut self,
+        text: &str,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let color = parse_color(text)?;
+        if color == self.track.custom_color() {
+            return Ok(HitResponse::ignored());
+        }
+        self.set_color(color)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        let index = if value == 0 { None } else { Some(value - 1) };
+        Ok(convert_discrete_to_unit_value_with_none(
+            index,
+            PALETTE_SIZE,
+        ))
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let text = match self.track.custom_color() {
+            None => "<Default>".to_string(),
+            Some(color) => format_color(color),
+        };
+        Some(text.into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let index = self.palette_index()?.map(|i| i + 1).unwrap_or(0);
+        Some(NumericValue::Discrete(index as i32))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackColor)
+    }
+}
+
+impl<'a> Target<'a> for TrackColorTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        // Custom colors which are not part of the palette don't have a value.
+        let actual_value = self.palette_index()?.map(|i| i + 1).unwrap_or(0);
+        Some(AbsoluteValue::Discrete(Fraction::new(
+            actual_value,
+            PALETTE_SIZE,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+fn palette_color(index: u32) -> RgbColor {
+    let (r, g, b) = TRACK_COLOR_PALETTE[index.min(PALETTE_SIZE - 1) as usize];
+    RgbColor { r, g, b }
+}
+
+/// Parses a color in the format `#RRGGBB`. An empty text resets the track to the default color.
+fn parse_color(text: &str) -> Result<Option<RgbColor>, &'static str> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    // `from_str_radix` would also accept a leading sign, e.g. "+12345".
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("color must have format #RRGGBB");
+    }
+    let rgb = u32::from_str_radix(hex, 16).map_err(|_| "color must have format #RRGGBB")?;
+    let color = RgbColor {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+    };
+    Ok(Some(color))
+}
+
+fn format_color(color: RgbColor) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b)
+}
+
+pub const TRACK_COLOR_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Track,
+    name: "Color",
+    short_name: "Track color",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_valid_colors() {
+        // When
+        let color = parse_color("#FF8000").unwrap();
+        // Then
+        assert_eq!(
+            color,
+            Some(RgbColor {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(
+            parse_color("00ff00").unwrap().map(format_color),
+            Some("#00FF00".to_string())
+        );
+        assert_eq!(parse_color("  ").unwrap(), None);
+    }
+
+    #[test]
+    fn reject_invalid_colors() {
+        // Given
+        let texts = [
+            "+12345", "#+12345", "#12345", "#1234567", "#GG0000", "-00000",
+        ];
+        for text in texts {
+            // When
+            let result = parse_color(text);
+            // Then
+            assert!(result.is_err(), "{text} should be rejected");
+        }
+    }
+}
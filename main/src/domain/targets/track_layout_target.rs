@@ -0,0 +1,328 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value_with_none,
+    convert_unit_to_discrete_value_with_none, get_effective_tracks, CompartmentKind,
+    ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use reaper_high::{Project, Reaper, Track};
+use reaper_medium::{reaper_str, ReaperStr, TrackArea};
+use std::borrow::Cow;
+use std::ffi::CString;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackLayoutTarget {
+    pub track_descriptor: TrackDescriptor,
+    pub area: TrackArea,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackLayoutTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        // Querying the layouts of the theme is not exactly cheap, so we do it once per
+        // resolve only, not on each poll.
+        let layouts: Rc<[String]> = query_available_layouts(self.area).into();
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| {
+                    ReaperTarget::TrackLayout(TrackLayoutTarget {
+                        track,
+                        area: self.area,
+                        layouts: layouts.clone(),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified when the track layout changes.
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackLayoutTarget {
+    pub track: Track,
+    pub area: TrackArea,
+    /// Layouts offered by the current theme for this area, not including the default layout.
+    ///
+    /// Determined when resolving the target.
+    pub layouts: Rc<[String]>,
+}
+
+impl TrackLayoutTarget {
+    fn layout_key(&self) -> &'static ReaperStr {
+        match self.area {
+            TrackArea::Tcp => reaper_str!("P_TCP_LAYOUT"),
+            TrackArea::Mcp => reaper_str!("P_MCP_LAYOUT"),
+        }
+    }
+
+    /// Returns the name of the current layout, empty if it's the default layout.
+    fn layout(&self) -> String {
+        let Ok(raw_track) = self.track.raw() else {
+            return String::new();
+        };
+        let mut buffer = [0u8; 256];
+        let successful = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetSetMediaTrackInfo_String(
+                    raw_track.as_ptr(),
+                    self.layout_key().as_ptr(),
+                    buffer.as_mut_ptr() as _,
+                    false,
+                )
+        };
+        if !successful {
+            return String::new();
+        }
+        buffer_to_string(&buffer)
+    }
+
+    fn set_layout(&self, name: &str) -> Result<(), &'static str> {
+        let raw_track = self.track.raw().map_err(|_| "track not available")?;
+        let c_name = CString::new(name).map_err(|_| "invalid layout name")?;
+        let successful = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .low()
+                .GetSetMediaTrackInfo_String(
+                    raw_track.as_ptr(),
+                    self.layout_key().as_ptr(),
+                    c_name.as_ptr() as _,
+                    true,
+                )
+        };
+        if !successful {
+            return Err("couldn't set track layout");
+        }
+        Ok(())
+    }
+
+    /// Returns `None` if the track uses the default layout.
+    fn layout_index(&self) -> Option<Option<u32>> {
+        let current = self.layout();
+        if current.is_empty() {
+            return Some(None);
+        }
+        let index = self.layouts.iter().position(|l| *l == current)?;
+        Some(Some(index as u32))
+    }
+}
+
+impl RealearnTarget for TrackLayoutTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        // `+ 1` because "<Default>" is also a possible value.
+        let layout_count = self.layouts.len() as u32;
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(layout_count + 1),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        let layout_count = self.layouts.len() as u32;
+        let value = convert_unit_to_discrete_value_with_none(input, layout_count)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        Ok(value)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        convert_unit_to_discrete_value_with_none(value, self.layouts.len() as u32)
+            .and_then(|i| self.layouts.get(i as usize).cloned())
+            .unwrap_or_else(|| "<Default>".to_string())
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => {
+                convert_unit_to_discrete_value_with_none(v, self.layouts.len() as u32)
+            }
+            AbsoluteValue::Discrete(f) => {
+                if f.actual() == 0 {
+                    None
+                } else {
+                    Some(f.actual() - 1)
+                }
+            }
+        };
+        let name = match index {
+            None => "",
+            Some(i) => self.layouts.get(i as usize).ok_or("layout doesn't exist")?,
+        };
+        self.set_layout(name)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn supports_text_input(&self) -> bool {
+        true
+    }
+
+    fn hit_with_text(
+        &mut self,
+        text: &str,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let name = text.trim();
+        if name == self.layout() {
+            return Ok(HitResponse::ignored());
+        }
+        if !name.is_empty() && !self.layouts.iter().any(|l| l == name) {
+            // The theme might have changed since resolving the target.
+            if !query_available_layouts(self.area).iter().any(|l| l == name) {
+                return Err("layout doesn't exist in current theme");
+            }
+        }
+        self.set_layout(name)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        let index = if value == 0 { None } else { Some(value - 1) };
+        let layout_count = self.layouts.len() as u32;
+        Ok(convert_discrete_to_unit_value_with_none(
+            index,
+            layout_count,
+        ))
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        let layout = self.layout();
+        let text = if layout.is_empty() {
+            "<Default>".to_string()
+        } else {
+            layout
+        };
+        Some(text.into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let index = self.layout_index()?.map(|i| i + 1).unwrap_or(0);
+        Some(NumericValue::Discrete(index as i32))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackLayout)
+    }
+}
+
+impl<'a> Target<'a> for TrackLayoutTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let actual_value = self.layout_index()?.map(|i| i + 1).unwrap_or(0);
+        Some(AbsoluteValue::Discrete(Fraction::new(
+            actual_value,
+            self.layouts.len() as u32,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+/// Returns the layouts offered by the current theme for the given area, not including the default
+/// layout.
+fn query_available_layouts(area: TrackArea) -> Vec<String> {
+    let section = match area {
+        TrackArea::Tcp => reaper_str!("tcp"),
+        TrackArea::Mcp => reaper_str!("mcp"),
+    };
+    let low = Reaper::get().medium_reaper().low();
+    let mut layouts = vec![];
+    for index in 0.. {
+        let mut buffer = [0u8; 256];
+        let successful = unsafe {
+            low.ThemeLayout_GetLayout(
+                section.as_ptr(),
+                index,
+                buffer.as_mut_ptr() as _,
+                buffer.len() as _,
+            )
+        };
+        if !successful {
+            break;
+        }
+        let name = buffer_to_string(&buffer);
+        if !name.is_empty() {
+            layouts.push(name);
+        }
+    }
+    layouts
+}
+
+fn buffer_to_string(buffer: &[u8]) -> String {
+    let len = buffer.iter().position(|b| *b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into_owned()
+}
+
+pub const TRACK_LAYOUT_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Track,
+    name: "Layout",
+    short_name: "Track layout",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
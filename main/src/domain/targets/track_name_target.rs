@@ -0,0 +1,142 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    get_effective_tracks, CompartmentKind, CompoundChangeEvent, ControlContext,
+    ExtendedProcessorContext, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef, TrackDescriptor,
+    UnresolvedReaperTargetDef, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target};
+use reaper_high::{ChangeEvent, Project, Track};
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackNameTarget {
+    pub track_descriptor: TrackDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackNameTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| ReaperTarget::TrackName(TrackNameTarget { track }))
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackNameTarget {
+    pub track: Track,
+}
+
+impl TrackNameTarget {
+    fn name(&self) -> String {
+        self.track
+            .name()
+            .map(|n| n.into_string())
+            .unwrap_or_default()
+    }
+}
+
+impl RealearnTarget for TrackNameTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Trigger,
+        )
+    }
+
+    fn hit(
+        &mut self,
+        _: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        // The track name can only be changed via text input.
+        Ok(HitResponse::ignored())
+    }
+
+    fn supports_text_input(&self) -> bool {
+        true
+    }
+
+    fn hit_with_text(
+        &mut self,
+        text: &str,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        if text == self.name() {
+            return Ok(HitResponse::ignored());
+        }
+        self.track.set_name(text);
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn process_change_event(
+        &self,
+        evt: CompoundChangeEvent,
+        _: ControlContext,
+    ) -> (bool, Option<AbsoluteValue>) {
+        match evt {
+            CompoundChangeEvent::Reaper(ChangeEvent::TrackNameChanged(e))
+                if e.track == self.track =>
+            {
+                (true, None)
+            }
+            _ => (false, None),
+        }
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(self.name().into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackName)
+    }
+}
+
+impl<'a> Target<'a> for TrackNameTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        // The numeric value just tells whether the track has a name at all. Textual feedback
+        // is where the name becomes visible.
+        let val = convert_bool_to_unit_value(!self.name().is_empty());
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TRACK_NAME_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Track,
+    name: "Set name",
+    short_name: "Track name",
+    hint: "Needs a text-producing source (e.g. OSC string argument)",
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
@@ -0,0 +1,275 @@
+use crate::domain::{
+    convert_count_to_step_size, convert_discrete_to_unit_value_with_none,
+    convert_unit_to_discrete_value_with_none, get_effective_tracks, CompartmentKind,
+    ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackDescriptor, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{
+    AbsoluteValue, ControlType, ControlValue, Fraction, NumericValue, Target, UnitValue,
+};
+use helgoboss_midi::Channel;
+use reaper_high::{Project, Reaper, Track};
+use reaper_medium::RecordingInput;
+use std::borrow::Cow;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackRecordInputTarget {
+    pub track_descriptor: TrackDescriptor,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackRecordInputTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        // Building the list of inputs is not exactly cheap, so we do it once per resolve only,
+        // not on each poll.
+        let inputs: Rc<[RecordingInput]> = browsable_recording_inputs().into();
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| {
+                    ReaperTarget::TrackRecordInput(TrackRecordInputTarget {
+                        track,
+                        inputs: inputs.clone(),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified when the recording input changes.
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackRecordInputTarget {
+    pub track: Track,
+    /// The browsable recording inputs, determined when resolving the target.
+    pub inputs: Rc<[RecordingInput]>,
+}
+
+impl TrackRecordInputTarget {
+    /// Returns `Some(None)` if the track has no input and `None` if it has an input which is not
+    /// part of the browsable inputs.
+    fn input_index(&self) -> Option<Option<u32>> {
+        let Some(current) = self.track.recording_input() else {
+            return Some(None);
+        };
+        let index = self.inputs.iter().position(|i| *i == current)?;
+        Some(Some(index as u32))
+    }
+}
+
+impl RealearnTarget for TrackRecordInputTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        // `+ 1` because "<None>" is also a possible value.
+        let input_count = self.inputs.len() as u32;
+        (
+            ControlType::AbsoluteDiscrete {
+                atomic_step_size: convert_count_to_step_size(input_count + 1),
+                is_retriggerable: false,
+            },
+            TargetCharacter::Discrete,
+        )
+    }
+
+    fn parse_as_value(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn parse_as_step_size(
+        &self,
+        text: &str,
+        context: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        self.parse_value_from_discrete_value(text, context)
+    }
+
+    fn convert_unit_value_to_discrete_value(
+        &self,
+        input: UnitValue,
+        _: ControlContext,
+    ) -> Result<u32, &'static str> {
+        let input_count = self.inputs.len() as u32;
+        let value = convert_unit_to_discrete_value_with_none(input, input_count)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        Ok(value)
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        let input = convert_unit_to_discrete_value_with_none(value, self.inputs.len() as u32)
+            .and_then(|i| self.inputs.get(i as usize).copied());
+        format_recording_input(input)
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let index = match value.to_absolute_value()? {
+            AbsoluteValue::Continuous(v) => {
+                convert_unit_to_discrete_value_with_none(v, self.inputs.len() as u32)
+            }
+            AbsoluteValue::Discrete(f) => {
+                if f.actual() == 0 {
+                    None
+                } else {
+                    Some(f.actual() - 1)
+                }
+            }
+        };
+        let input = match index {
+            None => None,
+            Some(i) => Some(
+                *self
+                    .inputs
+                    .get(i as usize)
+                    .ok_or("recording input doesn't exist")?,
+            ),
+        };
+        self.track.set_recording_input(input);
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn convert_discrete_value_to_unit_value(
+        &self,
+        value: u32,
+        _: ControlContext,
+    ) -> Result<UnitValue, &'static str> {
+        let index = if value == 0 { None } else { Some(value - 1) };
+        let input_count = self.inputs.len() as u32;
+        Ok(convert_discrete_to_unit_value_with_none(index, input_count))
+    }
+
+    fn text_value(&self, _: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_recording_input(self.track.recording_input()).into())
+    }
+
+    fn numeric_value(&self, _: ControlContext) -> Option<NumericValue> {
+        let index = self.input_index()?.map(|i| i + 1).unwrap_or(0);
+        Some(NumericValue::Discrete(index as i32))
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackRecordInput)
+    }
+}
+
+impl<'a> Target<'a> for TrackRecordInputTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let actual_value = self.input_index()?.map(|i| i + 1).unwrap_or(0);
+        Some(AbsoluteValue::Discrete(Fraction::new(
+            actual_value,
+            self.inputs.len() as u32,
+        )))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TRACK_RECORD_INPUT_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Track,
+    name: "Browse record inputs",
+    short_name: "Browse track record inputs",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    ..DEFAULT_TARGET
+};
+
+/// Returns the recording inputs that can be browsed, in this order:
+///
+/// 1. Mono audio inputs
+/// 2. Stereo audio inputs
+/// 3. All MIDI inputs (all channels, then each channel)
+/// 4. Each available MIDI input device (all channels)
+fn browsable_recording_inputs() -> Vec<RecordingInput> {
+    let reaper = Reaper::get();
+    let audio_input_count = reaper.input_channels().count() as u32;
+    let mono_inputs = (0..audio_input_count).map(RecordingInput::Mono);
+    let stereo_inputs = (0..audio_input_count.saturating_sub(1)).map(RecordingInput::Stereo);
+    let all_midi_inputs = std::iter::once(None)
+        .chain((0..16).map(|ch| Some(Channel::new(ch))))
+        .map(|channel| RecordingInput::Midi {
+            device_id: None,
+            channel,
+        });
+    let midi_device_inputs = reaper
+        .midi_input_devices()
+        .filter(|d| d.is_available())
+        .map(|d| RecordingInput::Midi {
+            device_id: Some(d.id()),
+            channel: None,
+        });
+    mono_inputs
+        .chain(stereo_inputs)
+        .chain(all_midi_inputs)
+        .chain(midi_device_inputs)
+        .collect()
+}
+
+fn format_recording_input(input: Option<RecordingInput>) -> String {
+    let Some(input) = input else {
+        return "<None>".to_string();
+    };
+    let format_channel = |channel: Option<Channel>| match channel {
+        None => "All channels".to_string(),
+        Some(ch) => format!("Channel {}", ch.get() + 1),
+    };
+    match input {
+        RecordingInput::Mono(ch) => format!("In {}", ch + 1),
+        RecordingInput::Stereo(ch) => format!("In {}/{}", ch + 1, ch + 2),
+        RecordingInput::MonoReaRoute(ch) => format!("ReaRoute {}", ch - 256 + 1),
+        RecordingInput::StereoReaRoute(ch) => {
+            format!("ReaRoute {}/{}", ch - 256 + 1, ch - 256 + 2)
+        }
+        RecordingInput::Midi {
+            device_id: None,
+            channel,
+        } => format!("MIDI: {}", format_channel(channel)),
+        RecordingInput::Midi {
+            device_id: Some(id),
+            channel,
+        } => {
+            let dev = Reaper::get().midi_input_device_by_id(id);
+            let dev_name = dev
+                .name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| format!("Device {}", id.get()));
+            format!("MIDI {dev_name}: {}", format_channel(channel))
+        }
+        _ => "<Other>".to_string(),
+    }
+}
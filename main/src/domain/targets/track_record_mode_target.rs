@@ -0,0 +1,193 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    change_track_prop, format_value_as_on_off, get_effective_tracks, CompartmentKind,
+    ControlContext, ExtendedProcessorContext, FeedbackResolution, HitResponse,
+    MappingControlContext, RealearnTarget, ReaperTarget, ReaperTargetType, TargetCharacter,
+    TargetSection, TargetTypeDef, TrackDescriptor, TrackExclusivity, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence::RecordMode;
+use reaper_high::{Project, Reaper, Track};
+use reaper_medium::reaper_str;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedTrackRecordModeTarget {
+    pub track_descriptor: TrackDescriptor,
+    pub exclusivity: TrackExclusivity,
+    pub mode: RecordMode,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedTrackRecordModeTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(
+            get_effective_tracks(context, &self.track_descriptor.track, compartment)?
+                .into_iter()
+                .map(|track| {
+                    ReaperTarget::TrackRecordMode(TrackRecordModeTarget {
+                        track,
+                        exclusivity: self.exclusivity,
+                        mode: self.mode,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        Some(&self.track_descriptor)
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified when the record mode changes.
+        Some(FeedbackResolution::High)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackRecordModeTarget {
+    pub track: Track,
+    pub exclusivity: TrackExclusivity,
+    pub mode: RecordMode,
+}
+
+impl RealearnTarget for TrackRecordModeTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        // Retriggerable because of #277
+        if self.exclusivity == TrackExclusivity::NonExclusive {
+            (
+                ControlType::AbsoluteContinuousRetriggerable,
+                TargetCharacter::Switch,
+            )
+        } else {
+            (
+                ControlType::AbsoluteContinuousRetriggerable,
+                TargetCharacter::Trigger,
+            )
+        }
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        change_track_prop(
+            &self.track,
+            self.exclusivity,
+            value.to_unit_value()?,
+            |t| {
+                let _ = set_record_mode(t, self.mode);
+            },
+            |t| {
+                let _ = set_record_mode(t, RecordMode::Input);
+            },
+        );
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.track.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.track.project())
+    }
+
+    fn track(&self) -> Option<&Track> {
+        Some(&self.track)
+    }
+
+    fn track_exclusivity(&self) -> Option<TrackExclusivity> {
+        Some(self.exclusivity)
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::TrackRecordMode)
+    }
+}
+
+impl<'a> Target<'a> for TrackRecordModeTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = convert_bool_to_unit_value(record_mode(&self.track)? == self.mode);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const TRACK_RECORD_MODE_TARGET: TargetTypeDef = TargetTypeDef {
+    section: TargetSection::Track,
+    name: "Set record mode",
+    short_name: "Track record mode",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    supports_track: true,
+    supports_track_exclusivity: true,
+    ..DEFAULT_TARGET
+};
+
+fn record_mode(track: &Track) -> Option<RecordMode> {
+    let raw_track = track.raw().ok()?;
+    let value = unsafe {
+        Reaper::get()
+            .medium_reaper()
+            .low()
+            .GetMediaTrackInfo_Value(raw_track.as_ptr(), reaper_str!("I_RECMODE").as_ptr())
+    };
+    let mode = match value.round() as i32 {
+        0 => RecordMode::Input,
+        1 => RecordMode::StereoOutput,
+        2 => RecordMode::Disabled,
+        3 => RecordMode::StereoOutputLatencyCompensated,
+        4 => RecordMode::MidiOutput,
+        5 => RecordMode::MonoOutput,
+        6 => RecordMode::MonoOutputLatencyCompensated,
+        7 => RecordMode::MidiOverdub,
+        8 => RecordMode::MidiReplace,
+        _ => return None,
+    };
+    Some(mode)
+}
+
+fn set_record_mode(track: &Track, mode: RecordMode) -> Result<(), &'static str> {
+    let raw_track = track.raw().map_err(|_| "track not available")?;
+    let value = match mode {
+        RecordMode::Input => 0.0,
+        RecordMode::StereoOutput => 1.0,
+        RecordMode::Disabled => 2.0,
+        RecordMode::StereoOutputLatencyCompensated => 3.0,
+        RecordMode::MidiOutput => 4.0,
+        RecordMode::MonoOutput => 5.0,
+        RecordMode::MonoOutputLatencyCompensated => 6.0,
+        RecordMode::MidiOverdub => 7.0,
+        RecordMode::MidiReplace => 8.0,
+    };
+    let successful = unsafe {
+        Reaper::get().medium_reaper().low().SetMediaTrackInfo_Value(
+            raw_track.as_ptr(),
+            reaper_str!("I_RECMODE").as_ptr(),
+            value,
+        )
+    };
+    if !successful {
+        return Err("couldn't set record mode");
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use crate::domain::ui_util::convert_bool_to_unit_value;
+use crate::domain::{
+    format_value_as_on_off, CompartmentKind, ControlContext, ExtendedProcessorContext,
+    FeedbackResolution, HitResponse, MappingControlContext, RealearnTarget, ReaperTarget,
+    ReaperTargetType, TargetCharacter, TargetSection, TargetTypeDef, UnresolvedReaperTargetDef,
+    AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY, DEFAULT_TARGET,
+};
+use helgoboss_learn::{AbsoluteValue, ControlType, ControlValue, Target, UnitValue};
+use helgobox_api::persistence::ReaperWindow;
+use reaper_high::{Project, Reaper};
+use reaper_medium::CommandId;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct UnresolvedWindowVisibilityTarget {
+    pub window: ReaperWindow,
+}
+
+impl UnresolvedReaperTargetDef for UnresolvedWindowVisibilityTarget {
+    fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        _: CompartmentKind,
+    ) -> Result<Vec<ReaperTarget>, &'static str> {
+        Ok(vec![ReaperTarget::WindowVisibility(
+            WindowVisibilityTarget {
+                project: context.context().project_or_current_project(),
+                window: self.window,
+            },
+        )])
+    }
+
+    fn feedback_resolution(&self) -> Option<FeedbackResolution> {
+        // We don't get notified when windows are opened or closed.
+        Some(FeedbackResolution::High)
+    }
+}
+
+/// Shows or hides a specific REAPER window.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WindowVisibilityTarget {
+    pub project: Project,
+    pub window: ReaperWindow,
+}
+
+impl WindowVisibilityTarget {
+    fn is_visible(&self) -> Option<bool> {
+        match self.window {
+            ReaperWindow::Mixer => toggle_action_state(MIXER_TOGGLE_ACTION),
+            ReaperWindow::FxBrowser => toggle_action_state(FX_BROWSER_TOGGLE_ACTION),
+            ReaperWindow::MidiEditor => {
+                let hwnd = unsafe { Reaper::get().medium_reaper().low().MIDIEditor_GetActive() };
+                Some(!hwnd.is_null())
+            }
+        }
+    }
+
+    fn set_visible(&self, visible: bool) -> Result<(), &'static str> {
+        if self.is_visible() == Some(visible) {
+            return Ok(());
+        }
+        match self.window {
+            ReaperWindow::Mixer => self.invoke_action(MIXER_TOGGLE_ACTION),
+            ReaperWindow::FxBrowser => self.invoke_action(FX_BROWSER_TOGGLE_ACTION),
+            ReaperWindow::MidiEditor => {
+                if visible {
+                    self.invoke_action(MIDI_EDITOR_OPEN_ACTION)
+                } else {
+                    let low = Reaper::get().medium_reaper().low();
+                    unsafe {
+                        let hwnd = low.MIDIEditor_GetActive();
+                        // "File: Close window"
+                        low.MIDIEditor_OnCommand(hwnd, 2);
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    fn invoke_action(&self, command_id: u32) -> Result<(), &'static str> {
+        Reaper::get()
+            .main_section()
+            .action_by_command_id(CommandId::new(command_id))
+            .invoke_as_trigger(Some(self.project), None)
+            .map_err(|_| "couldn't invoke window action")
+    }
+}
+
+/// "View: Toggle show mixer window"
+const MIXER_TOGGLE_ACTION: u32 = 40078;
+/// "View: Show FX browser window"
+const FX_BROWSER_TOGGLE_ACTION: u32 = 40271;
+/// "Item: Open in built-in MIDI editor (set default behavior in preferences)"
+const MIDI_EDITOR_OPEN_ACTION: u32 = 40153;
+
+fn toggle_action_state(command_id: u32) -> Option<bool> {
+    Reaper::get()
+        .main_section()
+        .action_by_command_id(CommandId::new(command_id))
+        .is_on()
+        .ok()?
+}
+
+impl RealearnTarget for WindowVisibilityTarget {
+    fn control_type_and_character(&self, _: ControlContext) -> (ControlType, TargetCharacter) {
+        (
+            ControlType::AbsoluteContinuousRetriggerable,
+            TargetCharacter::Switch,
+        )
+    }
+
+    fn format_value(&self, value: UnitValue, _: ControlContext) -> String {
+        format_value_as_on_off(value).to_string()
+    }
+
+    fn hit(
+        &mut self,
+        value: ControlValue,
+        _: MappingControlContext,
+    ) -> Result<HitResponse, &'static str> {
+        let visible = !value.to_unit_value()?.is_zero();
+        self.set_visible(visible)?;
+        Ok(HitResponse::processed_with_effect())
+    }
+
+    fn is_available(&self, _: ControlContext) -> bool {
+        self.project.is_available()
+    }
+
+    fn project(&self) -> Option<Project> {
+        Some(self.project)
+    }
+
+    fn text_value(&self, context: ControlContext) -> Option<Cow<'static, str>> {
+        Some(format_value_as_on_off(self.current_value(context)?.to_unit_value()).into())
+    }
+
+    fn reaper_target_type(&self) -> Option<ReaperTargetType> {
+        Some(ReaperTargetType::WindowVisibility)
+    }
+}
+
+impl<'a> Target<'a> for WindowVisibilityTarget {
+    type Context = ControlContext<'a>;
+
+    fn current_value(&self, _: Self::Context) -> Option<AbsoluteValue> {
+        let val = convert_bool_to_unit_value(self.is_visible()?);
+        Some(AbsoluteValue::Continuous(val))
+    }
+
+    fn control_type(&self, context: Self::Context) -> ControlType {
+        self.control_type_and_character(context).0
+    }
+}
+
+pub const WINDOW_VISIBILITY_TARGET: TargetTypeDef = TargetTypeDef {
+    lua_only: true,
+    section: TargetSection::Global,
+    name: "Show/hide window",
+    short_name: "Window visibility",
+    hint: AUTOMATIC_FEEDBACK_VIA_POLLING_ONLY,
+    ..DEFAULT_TARGET
+};
@@ -10,8 +10,9 @@ use serde_json::json;
 use crate::base::Prop;
 use crate::domain::{
     CompartmentKind, FxDescriptor, GlobalControlAndFeedbackState, GroupId, MappingId,
-    MappingSnapshotContainer, ParameterManager, QualifiedMappingId, SharedInstance, Tag, TagScope,
-    TrackDescriptor, UnitId, VirtualMappingSnapshotIdForLoad, WeakInstance,
+    MappingSnapshotContainer, ParameterManager, QualifiedMappingId, SharedInstance,
+    SharedMacroState, Tag, TagScope, TrackDescriptor, UnitId, VirtualMappingSnapshotIdForLoad,
+    WeakInstance, WeakMacroState,
 };
 use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
 use base::{serde_json_util, NamedChannelSender, SenderToNormalThread};
@@ -108,6 +109,13 @@ pub struct Unit {
     parameter_manager: Arc<ParameterManager>,
     custom_compartment_data: EnumMap<CompartmentKind, NonCryptoHashMap<String, serde_json::Value>>,
     control_unit_top_left_corner: playtime_api::persistence::SlotAddress,
+    /// Macros which have been started and might still be running.
+    ///
+    /// - Not persistent
+    /// - Set by target "ReaLearn: Run macro"
+    /// - Advanced by the main processor
+    /// - Weak so that changing or removing the mapping stops the macro.
+    running_macros: Vec<WeakMacroState>,
 }
 
 #[derive(Debug)]
@@ -143,6 +151,7 @@ impl Unit {
             parameter_manager: Arc::new(parameter_manager),
             custom_compartment_data: Default::default(),
             control_unit_top_left_corner: Default::default(),
+            running_macros: vec![],
         }
     }
 
@@ -334,6 +343,28 @@ impl Unit {
         }
     }
 
+    pub fn register_running_macro(&mut self, state: &SharedMacroState) {
+        let state = Rc::downgrade(state);
+        // The macro could have been started, cancelled and started again within one main loop
+        // cycle, before the main processor had the chance to forget about it.
+        if self.running_macros.iter().any(|m| m.ptr_eq(&state)) {
+            return;
+        }
+        self.running_macros.push(state);
+    }
+
+    /// Returns all macros which are still running and forgets about the ones which are not.
+    pub fn running_macros(&mut self) -> Vec<SharedMacroState> {
+        self.running_macros.retain(|m| {
+            m.upgrade()
+                .is_some_and(|m| m.try_borrow().map(|m| m.is_running()).unwrap_or(true))
+        });
+        self.running_macros
+            .iter()
+            .filter_map(|m| m.upgrade())
+            .collect()
+    }
+
     pub fn set_mapping_snapshot_container(
         &mut self,
         compartment: CompartmentKind,
@@ -1,29 +1,34 @@
 use crate::application::BookmarkAnchorType;
 use crate::domain::realearn_target::RealearnTarget;
 use crate::domain::{
-    scoped_track_index, Backbone, CompartmentKind, CompartmentParamIndex, CompartmentParams,
-    ControlContext, ExtendedProcessorContext, FeedbackResolution, ReaperTarget,
+    scoped_track_index, AutomationEnvelope, Backbone, CompartmentKind, CompartmentParamIndex,
+    CompartmentParams, ControlContext, ExtendedProcessorContext, FeedbackResolution, ReaperTarget,
     UnresolvedActionTarget, UnresolvedAllTrackFxEnableTarget, UnresolvedAnyOnTarget,
-    UnresolvedAutomationModeOverrideTarget, UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget,
-    UnresolvedBrowsePotFilterItemsTarget, UnresolvedBrowsePotPresetsTarget,
-    UnresolvedBrowseTracksTarget, UnresolvedCompartmentParameterValueTarget, UnresolvedDummyTarget,
-    UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget, UnresolvedFxEnableTarget,
+    UnresolvedAutomationItemActionTarget, UnresolvedAutomationModeOverrideTarget,
+    UnresolvedBrowseFxsTarget, UnresolvedBrowseGroupTarget, UnresolvedBrowsePotFilterItemsTarget,
+    UnresolvedBrowsePotPresetsTarget, UnresolvedBrowseProjectsTarget, UnresolvedBrowseTracksTarget,
+    UnresolvedCompartmentParameterValueTarget, UnresolvedDummyTarget,
+    UnresolvedEnableInstancesTarget, UnresolvedEnableMappingsTarget,
+    UnresolvedEnvelopeActionTarget, UnresolvedEnvelopeValueTarget, UnresolvedFxEnableTarget,
     UnresolvedFxOnlineTarget, UnresolvedFxOpenTarget, UnresolvedFxParameterTarget,
     UnresolvedFxParameterTouchStateTarget, UnresolvedFxPresetTarget, UnresolvedFxToolTarget,
     UnresolvedGoToBookmarkTarget, UnresolvedLastTouchedTarget, UnresolvedLoadFxSnapshotTarget,
-    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget, UnresolvedMidiSendTarget,
+    UnresolvedLoadMappingSnapshotTarget, UnresolvedLoadPotPresetTarget,
+    UnresolvedLoadScreensetTarget, UnresolvedMacroTarget, UnresolvedMidiSendTarget,
     UnresolvedModifyMappingTarget, UnresolvedMouseTarget, UnresolvedOscSendTarget,
-    UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget,
-    UnresolvedRouteAutomationModeTarget, UnresolvedRouteMonoTarget, UnresolvedRouteMuteTarget,
-    UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget, UnresolvedRouteTouchStateTarget,
-    UnresolvedRouteVolumeTarget, UnresolvedSeekTarget, UnresolvedStreamDeckBrightnessTarget,
+    UnresolvedPlayrateTarget, UnresolvedPreviewPotPresetTarget, UnresolvedRatePotPresetTarget,
+    UnresolvedRouteAutomationModeTarget, UnresolvedRouteExistenceTarget, UnresolvedRouteMonoTarget,
+    UnresolvedRouteMuteTarget, UnresolvedRoutePanTarget, UnresolvedRoutePhaseTarget,
+    UnresolvedRouteSendModeTarget, UnresolvedRouteTouchStateTarget, UnresolvedRouteVolumeTarget,
+    UnresolvedSeekTarget, UnresolvedStreamDeckBrightnessTarget, UnresolvedTagPotPresetTarget,
     UnresolvedTakeMappingSnapshotTarget, UnresolvedTempoTarget, UnresolvedTrackArmTarget,
-    UnresolvedTrackAutomationModeTarget, UnresolvedTrackMonitoringModeTarget,
-    UnresolvedTrackMuteTarget, UnresolvedTrackPanTarget, UnresolvedTrackParentSendTarget,
-    UnresolvedTrackPeakTarget, UnresolvedTrackPhaseTarget, UnresolvedTrackSelectionTarget,
-    UnresolvedTrackShowTarget, UnresolvedTrackSoloTarget, UnresolvedTrackToolTarget,
-    UnresolvedTrackTouchStateTarget, UnresolvedTrackVolumeTarget, UnresolvedTrackWidthTarget,
-    UnresolvedTransportTarget,
+    UnresolvedTrackAutomationModeTarget, UnresolvedTrackColorTarget, UnresolvedTrackLayoutTarget,
+    UnresolvedTrackMonitoringModeTarget, UnresolvedTrackMuteTarget, UnresolvedTrackNameTarget,
+    UnresolvedTrackPanTarget, UnresolvedTrackParentSendTarget, UnresolvedTrackPeakTarget,
+    UnresolvedTrackPhaseTarget, UnresolvedTrackRecordInputTarget, UnresolvedTrackRecordModeTarget,
+    UnresolvedTrackSelectionTarget, UnresolvedTrackShowTarget, UnresolvedTrackSoloTarget,
+    UnresolvedTrackToolTarget, UnresolvedTrackTouchStateTarget, UnresolvedTrackVolumeTarget,
+    UnresolvedTrackWidthTarget, UnresolvedTransportTarget, UnresolvedWindowVisibilityTarget,
 };
 use derive_more::{Display, Error};
 use enum_dispatch::enum_dispatch;
@@ -36,7 +41,7 @@ use helgobox_api::persistence::{
 use playtime_api::persistence::SlotAddress;
 use reaper_high::{
     BookmarkType, FindBookmarkResult, Fx, FxChain, FxParameter, Guid, Project, Reaper,
-    SendPartnerType, Track, TrackRoute,
+    SendPartnerType, Track, TrackRoute, TrackRoutePartner,
 };
 use reaper_medium::{BookmarkId, MasterTrackBehavior, TrackArea};
 use serde::{Deserialize, Serialize};
@@ -57,6 +62,9 @@ pub enum UnresolvedReaperTarget {
     Action(UnresolvedActionTarget),
     FxParameter(UnresolvedFxParameterTarget),
     FxParameterTouchState(UnresolvedFxParameterTouchStateTarget),
+    EnvelopeValue(UnresolvedEnvelopeValueTarget),
+    EnvelopeAction(UnresolvedEnvelopeActionTarget),
+    AutomationItemAction(UnresolvedAutomationItemActionTarget),
     TrackVolume(UnresolvedTrackVolumeTarget),
     TrackTool(UnresolvedTrackToolTarget),
     TrackPeak(UnresolvedTrackPeakTarget),
@@ -70,14 +78,21 @@ pub enum UnresolvedReaperTarget {
     TrackPhase(UnresolvedTrackPhaseTarget),
     TrackShow(UnresolvedTrackShowTarget),
     TrackSolo(UnresolvedTrackSoloTarget),
+    TrackName(UnresolvedTrackNameTarget),
+    TrackColor(UnresolvedTrackColorTarget),
+    TrackLayout(UnresolvedTrackLayoutTarget),
     TrackAutomationMode(UnresolvedTrackAutomationModeTarget),
     TrackMonitoringMode(UnresolvedTrackMonitoringModeTarget),
+    TrackRecordInput(UnresolvedTrackRecordInputTarget),
+    TrackRecordMode(UnresolvedTrackRecordModeTarget),
     RoutePan(UnresolvedRoutePanTarget),
     RouteMute(UnresolvedRouteMuteTarget),
     RoutePhase(UnresolvedRoutePhaseTarget),
     RouteMono(UnresolvedRouteMonoTarget),
     RouteAutomationMode(UnresolvedRouteAutomationModeTarget),
     RouteTouchState(UnresolvedRouteTouchStateTarget),
+    RouteExistence(UnresolvedRouteExistenceTarget),
+    RouteSendMode(UnresolvedRouteSendModeTarget),
     Tempo(UnresolvedTempoTarget),
     Playrate(UnresolvedPlayrateTarget),
     AutomationModeOverride(UnresolvedAutomationModeOverrideTarget),
@@ -93,6 +108,9 @@ pub enum UnresolvedReaperTarget {
     LoadFxPreset(UnresolvedLoadFxSnapshotTarget),
     TrackTouchState(UnresolvedTrackTouchStateTarget),
     GoToBookmark(UnresolvedGoToBookmarkTarget),
+    BrowseProjects(UnresolvedBrowseProjectsTarget),
+    LoadScreenset(UnresolvedLoadScreensetTarget),
+    WindowVisibility(UnresolvedWindowVisibilityTarget),
     Seek(UnresolvedSeekTarget),
     SendMidi(UnresolvedMidiSendTarget),
     SendOsc(UnresolvedOscSendTarget),
@@ -110,6 +128,7 @@ pub enum UnresolvedReaperTarget {
     TakeMappingSnapshot(UnresolvedTakeMappingSnapshotTarget),
     EnableMappings(UnresolvedEnableMappingsTarget),
     ModifyMapping(UnresolvedModifyMappingTarget),
+    Macro(UnresolvedMacroTarget),
     BrowseGroup(UnresolvedBrowseGroupTarget),
     EnableInstances(UnresolvedEnableInstancesTarget),
     AnyOn(UnresolvedAnyOnTarget),
@@ -118,6 +137,8 @@ pub enum UnresolvedReaperTarget {
     BrowsePotPresets(UnresolvedBrowsePotPresetsTarget),
     PreviewPotPreset(UnresolvedPreviewPotPresetTarget),
     LoadPotPreset(UnresolvedLoadPotPresetTarget),
+    RatePotPreset(UnresolvedRatePotPresetTarget),
+    TagPotPreset(UnresolvedTagPotPresetTarget),
     CompartmentParameterValue(UnresolvedCompartmentParameterValueTarget),
     StreamDeckBrightness(UnresolvedStreamDeckBrightnessTarget),
 }
@@ -545,6 +566,56 @@ pub struct FxParameterDescriptor {
     pub fx_parameter: VirtualFxParameter,
 }
 
+#[derive(Debug)]
+pub enum EnvelopeDescriptor {
+    Track {
+        track_descriptor: TrackDescriptor,
+        name: String,
+    },
+    FxParameter(FxParameterDescriptor),
+}
+
+impl EnvelopeDescriptor {
+    // Returns an error if the track or FX parameter doesn't exist. Envelopes which don't exist
+    // are silently skipped.
+    pub fn resolve(
+        &self,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Vec<AutomationEnvelope>, &'static str> {
+        let envelopes = match self {
+            EnvelopeDescriptor::Track {
+                track_descriptor,
+                name,
+            } => get_effective_tracks(context, &track_descriptor.track, compartment)?
+                .into_iter()
+                .flat_map(|track| AutomationEnvelope::from_track_by_name(track, name))
+                .collect(),
+            EnvelopeDescriptor::FxParameter(desc) => get_fx_params(context, desc, compartment)?
+                .into_iter()
+                .flat_map(AutomationEnvelope::from_fx_parameter)
+                .collect(),
+        };
+        Ok(envelopes)
+    }
+
+    pub fn track_descriptor(&self) -> Option<&TrackDescriptor> {
+        match self {
+            EnvelopeDescriptor::Track {
+                track_descriptor, ..
+            } => Some(track_descriptor),
+            EnvelopeDescriptor::FxParameter(_) => None,
+        }
+    }
+
+    pub fn fx_parameter_descriptor(&self) -> Option<&FxParameterDescriptor> {
+        match self {
+            EnvelopeDescriptor::Track { .. } => None,
+            EnvelopeDescriptor::FxParameter(desc) => Some(desc),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TrackRouteDescriptor {
     pub track_descriptor: TrackDescriptor,
@@ -576,6 +647,9 @@ pub enum TrackRouteSelector {
     ById(Guid),
     ByName(WildMatch),
     ByIndex(u32),
+    /// Addresses the route whose partner track carries the given tag in its name (see
+    /// [`track_name_has_tag`]).
+    ByTag(String),
 }
 
 impl TrackRouteSelector {
@@ -612,10 +686,47 @@ impl TrackRouteSelector {
                 }
             })?,
             ByIndex(i) => resolve_track_route_by_index(track, route_type, *i)?,
+            ByTag(tag) => find_route_by_tag(track, tag, route_type).ok_or_else(|| {
+                TrackRouteResolveError::TrackRouteNotFound {
+                    guid: None,
+                    name: Some(WildMatch::new(tag)),
+                    index: None,
+                }
+            })?,
         };
         Ok(route)
     }
 
+    /// Resolves the partner track of a route that doesn't necessarily exist yet.
+    ///
+    /// In contrast to [`Self::resolve`], indexes are interpreted as track indexes within the
+    /// project, not as route indexes.
+    pub fn resolve_partner_track(
+        &self,
+        track: &Track,
+        context: ExtendedProcessorContext,
+        compartment: CompartmentKind,
+    ) -> Result<Track, TrackRouteResolveError> {
+        use TrackRouteSelector::*;
+        let project = track.project();
+        let partner = match self {
+            Dynamic(evaluator) => {
+                let i = Self::evaluate_to_route_index(evaluator, context, compartment)?;
+                project.track_by_index(i)
+            }
+            ById(guid) => {
+                let partner = project
+                    .track_by_guid(guid)
+                    .map_err(|_| TrackRouteResolveError::ProjectNotAvailable)?;
+                partner.is_available().then_some(partner)
+            }
+            ByName(name) => find_track_by_name(project, name),
+            ByIndex(i) => project.track_by_index(*i),
+            ByTag(tag) => find_track_by_tag(project, tag),
+        };
+        partner.ok_or(TrackRouteResolveError::InvalidRoute)
+    }
+
     pub fn calculated_route_index(
         &self,
         context: ExtendedProcessorContext,
@@ -664,6 +775,7 @@ impl TrackRouteSelector {
         use TrackRouteSelector::*;
         match self {
             ByName(name) => Some(name.to_string()),
+            ByTag(tag) => Some(tag.clone()),
             _ => None,
         }
     }
@@ -677,6 +789,7 @@ impl fmt::Display for VirtualTrackRoute {
             ById(id) => write!(f, "{}", id.to_string_without_braces()),
             ByName(name) => write!(f, "\"{name}\""),
             ByIndex(i) => write!(f, "#{}", i + 1),
+            ByTag(tag) => write!(f, "Tagged \"{tag}\""),
         }
     }
 }
@@ -1694,6 +1807,28 @@ fn find_track_by_name(project: Project, name: &WildMatch) -> Option<Track> {
     })
 }
 
+fn find_track_by_tag(project: Project, tag: &str) -> Option<Track> {
+    project.tracks().find(|t| match t.name() {
+        None => false,
+        Some(n) => track_name_has_tag(n.to_str(), tag),
+    })
+}
+
+/// REAPER tracks don't have tags, so we treat words in the track name which start with `#` as
+/// tags. E.g. a track named "Reverb #fx #long" has the tags "fx" and "long".
+///
+/// Comparison is case-insensitive. The given tag may or may not start with `#`.
+pub fn track_name_has_tag(track_name: &str, tag: &str) -> bool {
+    let tag = tag.trim().trim_start_matches('#');
+    if tag.is_empty() {
+        return false;
+    }
+    track_name
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .any(|t| t.eq_ignore_ascii_case(tag))
+}
+
 fn find_tracks_by_name(project: Project, name: &WildMatch) -> impl Iterator<Item = Track> + '_ {
     project.tracks().filter(move |t| match t.name() {
         None => false,
@@ -2124,7 +2259,7 @@ pub fn find_bookmark(
     }
 }
 
-fn find_route_by_related_track(
+pub fn find_route_by_related_track(
     main_track: &Track,
     related_track: &Track,
     route_type: TrackRouteType,
@@ -2154,6 +2289,22 @@ fn find_route_by_name(
     }
 }
 
+fn find_route_by_tag(track: &Track, tag: &str, route_type: TrackRouteType) -> Option<TrackRoute> {
+    let matcher = |r: &TrackRoute| match r.partner() {
+        Some(TrackRoutePartner::Track(t)) => match t.name() {
+            None => false,
+            Some(n) => track_name_has_tag(n.to_str(), tag),
+        },
+        _ => false,
+    };
+    match route_type {
+        TrackRouteType::Send => track.typed_sends(SendPartnerType::Track).find(matcher),
+        TrackRouteType::Receive => track.receives().find(matcher),
+        // Hardware outputs don't have partner tracks.
+        TrackRouteType::HardwareOutput => None,
+    }
+}
+
 #[derive(Default)]
 struct Descriptors<'a> {
     track: Option<&'a TrackDescriptor>,
@@ -2314,3 +2465,21 @@ fn additional_playtime_vars(context: ControlContext) -> impl Fn(&str, &[f64]) ->
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_name_tags() {
+        // Given
+        let track_name = "Reverb #FX #long";
+        // Then
+        assert!(track_name_has_tag(track_name, "fx"));
+        assert!(track_name_has_tag(track_name, "#long"));
+        assert!(!track_name_has_tag(track_name, "reverb"));
+        assert!(!track_name_has_tag(track_name, "lon"));
+        assert!(!track_name_has_tag(track_name, ""));
+        assert!(!track_name_has_tag("Drums", "drums"));
+    }
+}
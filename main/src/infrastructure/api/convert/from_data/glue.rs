@@ -76,6 +76,7 @@ pub fn convert_glue(
             defaults::GLUE_ROUND_TARGET_VALUE,
         ),
         control_transformation: style.required_value(data.eel_control_transformation),
+        control_transformation_kind: style.required_value(data.control_transformation_kind),
         button_filter: {
             use persistence::ButtonFilter as T;
             use ButtonUsage::*;
@@ -126,6 +127,7 @@ pub fn convert_glue(
                         data.feedback_background_color,
                     )?,
                     transformation: style.required_value(data.eel_feedback_transformation),
+                    transformation_kind: style.required_value(data.feedback_transformation_kind),
                 }),
                 Text => T::Text(TextFeedback {
                     commons: convert_feedback_commons(
@@ -189,6 +191,8 @@ pub fn convert_glue(
             style.required_value(v)
         },
         feedback_value_table: data.feedback_value_table,
+        smoothing: data.smoothing,
+        response_curve: data.response_curve,
     };
     Ok(glue)
 }
@@ -209,7 +213,7 @@ fn convert_absolute_mode(
     style.required_value(mode)
 }
 
-fn convert_unit_interval(min: UnitValue, max: UnitValue) -> persistence::Interval<f64> {
+pub fn convert_unit_interval(min: UnitValue, max: UnitValue) -> persistence::Interval<f64> {
     persistence::Interval(min.get(), max.get())
 }
 
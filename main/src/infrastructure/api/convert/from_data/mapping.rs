@@ -1,12 +1,14 @@
 use crate::application::{
-    LifecycleMidiMessageModel, LifecycleModel, MappingExtensionModel, RawMidiMessage,
+    LifecycleMidiMessageModel, LifecycleModel, LifecycleOscArgumentModel, LifecycleOscMessageModel,
+    LifecycleTargetValueModel, MappingExtensionModel, RawMidiMessage,
 };
 use crate::infrastructure::api::convert::from_data::{
     convert_activation_condition, convert_glue, convert_source, convert_tags, convert_target,
-    ConversionStyle, NewSourceProps,
+    convert_unit_interval, ConversionStyle, NewSourceProps,
 };
 use crate::infrastructure::api::convert::{defaults, ConversionResult};
-use crate::infrastructure::data::MappingModelData;
+use crate::infrastructure::data::{AdditionalTargetModelData, MappingModelData};
+use helgoboss_learn::UnitValue;
 use helgobox_api::persistence;
 use helgobox_api::persistence::LifecycleHook;
 
@@ -45,12 +47,41 @@ pub fn convert_mapping(
         },
         glue: style.required_value(convert_glue(data.mode, style)?),
         target: style.required_value(convert_target(data.target, style)?),
+        additional_targets: {
+            let targets = data
+                .additional_targets
+                .into_iter()
+                .map(|t| convert_additional_target(t, style))
+                .collect::<ConversionResult<Vec<_>>>()?;
+            style.required_value(targets)
+        },
+        feedback_target_index: style.required_value(data.feedback_target_index),
         success_audio_feedback: data.success_audio_feedback,
         unprocessed: style.optional_value(advanced.unprocessed),
     };
     Ok(mapping)
 }
 
+fn convert_additional_target(
+    data: AdditionalTargetModelData,
+    style: ConversionStyle,
+) -> ConversionResult<persistence::AdditionalTarget> {
+    let target_interval = if data.min_target_value.is_some() || data.max_target_value.is_some() {
+        Some(convert_unit_interval(
+            data.min_target_value.unwrap_or(UnitValue::MIN),
+            data.max_target_value.unwrap_or(UnitValue::MAX),
+        ))
+    } else {
+        None
+    };
+    let target = persistence::AdditionalTarget {
+        target: convert_target(data.target, style)?,
+        target_interval,
+        control_transformation: data.eel_control_transformation,
+    };
+    Ok(target)
+}
+
 #[derive(Default)]
 struct AdvancedDesc {
     extension_desc: ExtensionDesc,
@@ -123,10 +154,64 @@ fn convert_lifecycle_model(
                 .collect();
             style.required_value(actions?)
         },
+        send_osc_feedback: {
+            let actions = lifecycle_model
+                .send_osc_feedback
+                .into_iter()
+                .map(convert_lifecycle_osc_message_model)
+                .collect();
+            style.required_value(actions)
+        },
+        run_lua: lifecycle_model.run_lua,
+        set_target_value: {
+            let actions = lifecycle_model
+                .set_target_value
+                .into_iter()
+                .map(convert_lifecycle_target_value_model)
+                .collect();
+            style.required_value(actions)
+        },
     };
     Ok(style.required_value(hook))
 }
 
+fn convert_lifecycle_osc_message_model(
+    model: LifecycleOscMessageModel,
+) -> persistence::SendOscFeedbackAction {
+    use persistence::OscFeedbackArgument as A;
+    use LifecycleOscArgumentModel as M;
+    let arguments: Vec<_> = model
+        .arguments
+        .into_iter()
+        .map(|arg| match arg {
+            M::Float(value) => A::Float { value },
+            M::Double(value) => A::Double { value },
+            M::Int(value) => A::Int { value },
+            M::Long(value) => A::Long { value },
+            M::Bool(value) => A::Bool { value },
+            M::String(value) => A::String { value },
+            M::Nil => A::Nil,
+        })
+        .collect();
+    persistence::SendOscFeedbackAction {
+        address: model.address,
+        arguments: if arguments.is_empty() {
+            None
+        } else {
+            Some(arguments)
+        },
+    }
+}
+
+fn convert_lifecycle_target_value_model(
+    model: LifecycleTargetValueModel,
+) -> persistence::SetTargetValueAction {
+    persistence::SetTargetValueAction {
+        mapping: model.mapping,
+        value: model.value.get(),
+    }
+}
+
 fn convert_lifecycle_midi_message_model(
     model: LifecycleMidiMessageModel,
 ) -> ConversionResult<persistence::SendMidiFeedbackAction> {
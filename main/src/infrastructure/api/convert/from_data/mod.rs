@@ -13,7 +13,7 @@ pub use compartment::*;
 mod target;
 
 use crate::application::ActivationType;
-use crate::domain::{Keystroke, Tag};
+use crate::domain::{Keystroke, PlayStateCondition, Tag};
 use crate::infrastructure::data::{
     ActivationConditionData, OscValueRange, VirtualControlElementIdData,
 };
@@ -193,5 +193,65 @@ fn convert_activation_condition(
             };
             Some(T::TargetValue(condition))
         }
+        PlayState => {
+            let condition = persistence::PlayStateActivationCondition {
+                state: convert_play_state(condition_data.play_state),
+            };
+            Some(T::PlayState(condition))
+        }
+        TrackSelected => {
+            let condition = persistence::TrackSelectedActivationCondition {
+                track_name: condition_data.eel_condition,
+            };
+            Some(T::TrackSelected(condition))
+        }
+        FxFocused => {
+            let condition = persistence::FxFocusedActivationCondition {
+                fx_name: condition_data.eel_condition,
+            };
+            Some(T::FxFocused(condition))
+        }
+        MidiEditorOpen => Some(T::MidiEditorOpen),
+        All => {
+            let condition = persistence::AllActivationCondition {
+                conditions: convert_activation_conditions(condition_data.conditions),
+            };
+            Some(T::All(condition))
+        }
+        Any => {
+            let condition = persistence::AnyActivationCondition {
+                conditions: convert_activation_conditions(condition_data.conditions),
+            };
+            Some(T::Any(condition))
+        }
+        Not => {
+            let child = condition_data.conditions.into_iter().next()?;
+            let condition = persistence::NotActivationCondition {
+                condition: Box::new(convert_activation_condition(child)?),
+            };
+            Some(T::Not(condition))
+        }
+    }
+}
+
+fn convert_play_state(s: PlayStateCondition) -> persistence::ActivationPlayState {
+    use persistence::ActivationPlayState as T;
+    use PlayStateCondition::*;
+    match s {
+        Stopped => T::Stopped,
+        Playing => T::Playing,
+        Paused => T::Paused,
+        Recording => T::Recording,
     }
 }
+
+/// Child conditions of type "Always" are left out because they don't have a persistence
+/// representation.
+fn convert_activation_conditions(
+    conditions: Vec<ActivationConditionData>,
+) -> Vec<persistence::ActivationCondition> {
+    conditions
+        .into_iter()
+        .filter_map(convert_activation_condition)
+        .collect()
+}
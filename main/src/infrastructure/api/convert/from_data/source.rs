@@ -245,6 +245,7 @@ pub fn convert_source(
                     })
                 }
                 Speech => persistence::Source::Speech,
+                Modulator => persistence::Source::Modulator(data.modulator),
             }
         }
         Virtual => {
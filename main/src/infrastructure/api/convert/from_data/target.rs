@@ -1,5 +1,5 @@
 use crate::application::{
-    AutomationModeOverrideType, BookmarkAnchorType, MappingModificationKind,
+    AutomationModeOverrideType, BookmarkAnchorType, EnvelopeType, MappingModificationKind,
     RealearnAutomationMode, RealearnTrackArea, TargetCategory, TargetUnit, TrackRouteSelectorType,
     VirtualFxParameterType, VirtualFxType, VirtualTrackType,
 };
@@ -19,25 +19,28 @@ use crate::infrastructure::data::{
 use base::hash_util::convert_into_other_hash_set;
 use helgobox_api::persistence;
 use helgobox_api::persistence::{
-    AllTrackFxOnOffStateTarget, AnyOnTarget, AutomationModeOverrideTarget,
-    BackwardCompatibleMappingSnapshotDescForTake, BookmarkDescriptor, BookmarkRef,
-    BrowseFxChainTarget, BrowseFxPresetsTarget, BrowseGroupMappingsTarget,
-    BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseTracksTarget,
+    AllTrackFxOnOffStateTarget, AnyOnTarget, AutomationItemActionTarget,
+    AutomationModeOverrideTarget, BackwardCompatibleMappingSnapshotDescForTake, BookmarkDescriptor,
+    BookmarkRef, BrowseFxChainTarget, BrowseFxPresetsTarget, BrowseGroupMappingsTarget,
+    BrowsePotFilterItemsTarget, BrowsePotPresetsTarget, BrowseProjectsTarget, BrowseTracksTarget,
     CompartmentParameterDescriptor, CompartmentParameterValueTarget, DummyTarget,
-    EnableInstancesTarget, EnableMappingsTarget, FxOnOffStateTarget, FxOnlineOfflineStateTarget,
-    FxParameterAutomationTouchStateTarget, FxParameterValueTarget, FxToolTarget,
-    FxVisibilityTarget, GoToBookmarkTarget, InputDeviceMidiDestination, LastTouchedTarget,
-    LearnTargetMappingModification, LoadFxSnapshotTarget, LoadMappingSnapshotTarget,
-    LoadPotPresetTarget, MappingModification, ModifyMappingTarget, MouseTarget, PlayRateTarget,
-    PreviewPotPresetTarget, ReaperActionTarget, RouteAutomationModeTarget, RouteMonoStateTarget,
-    RouteMuteStateTarget, RoutePanTarget, RoutePhaseTarget, RouteTouchStateTarget,
-    RouteVolumeTarget, SeekTarget, SendMidiTarget, SendOscTarget,
-    SetTargetToLastTouchedMappingModification, StreamDeckBrightnessTarget,
-    TakeMappingSnapshotTarget, TempoTarget, TrackArmStateTarget, TrackAutomationModeTarget,
-    TrackAutomationTouchStateTarget, TrackMonitoringModeTarget, TrackMuteStateTarget,
-    TrackPanTarget, TrackParentSendStateTarget, TrackPeakTarget, TrackPhaseTarget,
-    TrackSelectionStateTarget, TrackSoloStateTarget, TrackToolTarget, TrackVisibilityTarget,
-    TrackVolumeTarget, TrackWidthTarget, TransportActionTarget,
+    EnableInstancesTarget, EnableMappingsTarget, EnvelopeActionTarget, EnvelopeValueTarget,
+    FxOnOffStateTarget, FxOnlineOfflineStateTarget, FxParameterAutomationTouchStateTarget,
+    FxParameterValueTarget, FxToolTarget, FxVisibilityTarget, GoToBookmarkTarget,
+    InputDeviceMidiDestination, LastTouchedTarget, LearnTargetMappingModification,
+    LoadFxSnapshotTarget, LoadMappingSnapshotTarget, LoadPotPresetTarget, LoadScreensetTarget,
+    MacroTarget, MappingModification, ModifyMappingTarget, MouseTarget, PlayRateTarget,
+    PreviewPotPresetTarget, RatePotPresetTarget, ReaperActionTarget, RouteAutomationModeTarget,
+    RouteExistenceTarget, RouteMonoStateTarget, RouteMuteStateTarget, RoutePanTarget,
+    RoutePhaseTarget, RouteSendModeTarget, RouteTouchStateTarget, RouteVolumeTarget, SeekTarget,
+    SendMidiTarget, SendOscTarget, SetTargetToLastTouchedMappingModification,
+    StreamDeckBrightnessTarget, TagPotPresetTarget, TakeMappingSnapshotTarget, TempoTarget,
+    TrackArmStateTarget, TrackAutomationModeTarget, TrackAutomationTouchStateTarget,
+    TrackColorTarget, TrackLayoutTarget, TrackMonitoringModeTarget, TrackMuteStateTarget,
+    TrackNameTarget, TrackPanTarget, TrackParentSendStateTarget, TrackPeakTarget, TrackPhaseTarget,
+    TrackRecordInputTarget, TrackRecordModeTarget, TrackSelectionStateTarget, TrackSoloStateTarget,
+    TrackToolTarget, TrackVisibilityTarget, TrackVolumeTarget, TrackWidthTarget,
+    TransportActionTarget, WindowVisibilityTarget,
 };
 
 pub fn convert_target(
@@ -78,6 +81,14 @@ fn convert_real_target(
             };
             T::AutomationModeOverride(t)
         }
+        LoadScreenset => T::LoadScreenset(LoadScreensetTarget {
+            commons,
+            index: data.screenset_index,
+        }),
+        WindowVisibility => T::WindowVisibility(WindowVisibilityTarget {
+            commons,
+            window: data.reaper_window,
+        }),
         Action => T::ReaperAction(ReaperActionTarget {
             commons,
             scope: style.required_value(data.action_scope),
@@ -175,6 +186,26 @@ fn convert_real_target(
                 defaults::TARGET_USE_SELECTION_GANGING,
             ),
         }),
+        TrackRecordInput => T::TrackRecordInput(TrackRecordInputTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+        }),
+        TrackRecordMode => T::TrackRecordMode(TrackRecordModeTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+            exclusivity: convert_track_exclusivity(data.track_exclusivity),
+            mode: data.track_record_mode,
+        }),
         TrackTouchState => T::TrackAutomationTouchState(TrackAutomationTouchStateTarget {
             commons,
             track: convert_track_descriptor(
@@ -250,6 +281,20 @@ fn convert_real_target(
                 parameter: convert_fx_parameter_descriptor(data, style),
             })
         }
+        EnvelopeValue => T::EnvelopeValue(EnvelopeValueTarget {
+            commons,
+            envelope: convert_envelope_descriptor(data, style),
+        }),
+        EnvelopeAction => T::EnvelopeAction(EnvelopeActionTarget {
+            commons,
+            action: Some(data.envelope_action),
+            envelope: convert_envelope_descriptor(data, style),
+        }),
+        AutomationItemAction => T::AutomationItemAction(AutomationItemActionTarget {
+            commons,
+            action: Some(data.automation_item_action),
+            envelope: convert_envelope_descriptor(data, style),
+        }),
         RouteAutomationMode => T::RouteAutomationMode(RouteAutomationModeTarget {
             commons,
             mode: convert_automation_mode(data.track_automation_mode),
@@ -300,6 +345,20 @@ fn convert_real_target(
             },
             route: convert_route_descriptor(data, style),
         }),
+        RouteExistence => T::RouteExistence(RouteExistenceTarget {
+            commons,
+            action: Some(data.route_existence_action),
+            route: convert_route_descriptor(data, style),
+        }),
+        RouteSendMode => T::RouteSendMode(RouteSendModeTarget {
+            commons,
+            mode: data.send_mode,
+            poll_for_feedback: style.required_value_with_default(
+                data.poll_for_feedback,
+                defaults::TARGET_POLL_FOR_FEEDBACK,
+            ),
+            route: convert_route_descriptor(data, style),
+        }),
         PlaytimeSlotTransportAction => T::PlaytimeSlotTransportAction(
             helgobox_api::persistence::PlaytimeSlotTransportActionTarget {
                 commons,
@@ -383,6 +442,7 @@ fn convert_real_target(
             },
         }),
         Dummy => T::Dummy(DummyTarget { commons }),
+        BrowseProjects => T::BrowseProjects(BrowseProjectsTarget { commons }),
         BrowseTracks => T::BrowseTracks(BrowseTracksTarget {
             commons,
             scroll_arrange_view: style.required_value_with_default(
@@ -628,6 +688,39 @@ fn convert_real_target(
                 defaults::TARGET_USE_SELECTION_GANGING,
             ),
         }),
+        TrackName => T::TrackName(TrackNameTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+        }),
+        TrackColor => T::TrackColor(TrackColorTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+        }),
+        TrackLayout => T::TrackLayout(TrackLayoutTarget {
+            commons,
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+            area: {
+                match data.track_area {
+                    RealearnTrackArea::Tcp => persistence::TrackArea::Tcp,
+                    RealearnTrackArea::Mcp => persistence::TrackArea::Mcp,
+                }
+            },
+        }),
         FxTool => T::FxTool(FxToolTarget {
             commons,
             action: Some(data.fx_tool_action),
@@ -734,6 +827,10 @@ fn convert_real_target(
             session: data.session_id,
             mapping: data.mapping_key.map(|key| key.into()),
         }),
+        Macro => T::Macro(MacroTarget {
+            commons,
+            steps: data.macro_steps,
+        }),
         LoadMappingSnapshot => T::LoadMappingSnapshot(LoadMappingSnapshotTarget {
             commons,
             tags: convert_tags(&data.tags, style),
@@ -766,12 +863,20 @@ fn convert_real_target(
             commons,
             item_kind: style.required_value(data.pot_filter_item_kind),
         }),
-        BrowsePotPresets => T::BrowsePotPresets(BrowsePotPresetsTarget { commons }),
+        BrowsePotPresets => T::BrowsePotPresets(BrowsePotPresetsTarget {
+            commons,
+            collection: data.pot_collection,
+        }),
         PreviewPotPreset => T::PreviewPotPreset(PreviewPotPresetTarget { commons }),
         LoadPotPreset => T::LoadPotPreset(LoadPotPresetTarget {
             commons,
             fx: convert_fx_descriptor(data, style),
         }),
+        RatePotPreset => T::RatePotPreset(RatePotPresetTarget { commons }),
+        TagPotPreset => T::TagPotPreset(TagPotPresetTarget {
+            commons,
+            tags: convert_tags(&data.tags, style),
+        }),
         StreamDeckBrightness => T::StreamDeckBrightness(StreamDeckBrightnessTarget { commons }),
     };
     Ok(target)
@@ -968,6 +1073,27 @@ fn convert_fx_chain(
     style.required_value(chain)
 }
 
+fn convert_envelope_descriptor(
+    data: TargetModelData,
+    style: ConversionStyle,
+) -> persistence::EnvelopeDescriptor {
+    use persistence::EnvelopeDescriptor as T;
+    match data.envelope_type {
+        EnvelopeType::Track => T::Track {
+            track: convert_track_descriptor(
+                data.track_data,
+                data.enable_only_if_track_is_selected,
+                &data.clip_column,
+                style,
+            ),
+            name: data.envelope_name,
+        },
+        EnvelopeType::FxParameter => T::FxParameter {
+            parameter: convert_fx_parameter_descriptor(data, style),
+        },
+    }
+}
+
 fn convert_fx_parameter_descriptor(
     data: TargetModelData,
     style: ConversionStyle,
@@ -1037,6 +1163,10 @@ fn convert_route_descriptor(
             commons,
             index: props.index,
         },
+        ByTag => T::ByTag {
+            commons,
+            tag: props.name,
+        },
     }
 }
 
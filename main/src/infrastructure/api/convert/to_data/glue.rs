@@ -29,6 +29,7 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         feedback_type: helgoboss_learn::FeedbackType,
         commons: FbCommonsData,
         transformation: String,
+        transformation_kind: TransformationScriptKind,
     }
     let fb_data = {
         use helgoboss_learn::FeedbackType as T;
@@ -44,16 +45,19 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
                 feedback_type: T::Numeric,
                 commons: convert_fb_commons(fb.commons),
                 transformation: fb.transformation.unwrap_or_default(),
+                transformation_kind: fb.transformation_kind.unwrap_or_default(),
             },
             Text(fb) => FbData {
                 feedback_type: T::Text,
                 commons: convert_fb_commons(fb.commons),
                 transformation: fb.text_expression.unwrap_or_default(),
+                transformation_kind: Default::default(),
             },
             Dynamic(fb) => FbData {
                 feedback_type: T::Dynamic,
                 commons: convert_fb_commons(fb.commons),
                 transformation: fb.script.unwrap_or_default(),
+                transformation_kind: Default::default(),
             },
         }
     };
@@ -122,6 +126,8 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         },
         eel_control_transformation: g.control_transformation.unwrap_or_default(),
         eel_feedback_transformation: fb_data.transformation,
+        control_transformation_kind: g.control_transformation_kind.unwrap_or_default(),
+        feedback_transformation_kind: fb_data.transformation_kind,
         reverse_is_enabled: g.reverse.unwrap_or(defaults::GLUE_REVERSE),
         feedback_color: fb_data.commons.color,
         feedback_background_color: fb_data.commons.background_color,
@@ -211,6 +217,8 @@ pub fn convert_glue(g: Glue) -> ConversionResult<ModeModelData> {
         },
         feedback_type: fb_data.feedback_type,
         feedback_value_table: g.feedback_value_table,
+        smoothing: g.smoothing,
+        response_curve: g.response_curve,
     };
     Ok(data)
 }
@@ -238,7 +246,7 @@ fn convert_step_size_interval(
     Ok(result)
 }
 
-fn convert_unit_value_interval(
+pub fn convert_unit_value_interval(
     interval: Interval<f64>,
 ) -> ConversionResult<helgoboss_learn::Interval<UnitValue>> {
     let result = helgoboss_learn::Interval::try_new(
@@ -1,16 +1,20 @@
 use super::convert_source;
 use crate::application;
 use crate::application::{
-    LifecycleMidiMessageModel, LifecycleModel, MappingExtensionModel, RawByteArrayMidiMessage,
+    LifecycleMidiMessageModel, LifecycleModel, LifecycleOscArgumentModel, LifecycleOscMessageModel,
+    LifecycleTargetValueModel, MappingExtensionModel, RawByteArrayMidiMessage,
 };
 use crate::domain::Tag;
-use crate::infrastructure::api::convert::to_data::glue::convert_glue;
+use crate::infrastructure::api::convert::to_data::glue::{
+    convert_glue, convert_unit_value_interval,
+};
 use crate::infrastructure::api::convert::to_data::target::convert_target;
 use crate::infrastructure::api::convert::to_data::{
     convert_activation, ApiToDataConversionContext,
 };
 use crate::infrastructure::api::convert::{defaults, ConversionResult};
-use crate::infrastructure::data::{EnabledData, MappingModelData};
+use crate::infrastructure::data::{AdditionalTargetModelData, EnabledData, MappingModelData};
+use anyhow::bail;
 use helgobox_api::persistence::*;
 use std::convert::TryInto;
 use std::str::FromStr;
@@ -42,6 +46,18 @@ pub fn convert_mapping(
         } else {
             (false, false)
         };
+    let additional_targets = m.additional_targets.unwrap_or_default();
+    if !additional_targets.is_empty() {
+        if matches!(m.target, Some(Target::Virtual(_))) {
+            bail!("additional targets are not supported for mappings with a virtual target");
+        }
+        if additional_targets
+            .iter()
+            .any(|t| matches!(t.target, Target::Virtual(_)))
+        {
+            bail!("additional targets can't be virtual targets");
+        }
+    }
     let v = MappingModelData {
         id: m.id.map(|id| id.into()),
         name: m.name.unwrap_or_default(),
@@ -50,6 +66,11 @@ pub fn convert_mapping(
         source: convert_source(m.source.unwrap_or_default())?,
         mode: convert_glue(m.glue.unwrap_or_default())?,
         target: convert_target(m.target.unwrap_or_default())?,
+        additional_targets: additional_targets
+            .into_iter()
+            .map(convert_additional_target)
+            .collect::<ConversionResult<_>>()?,
+        feedback_target_index: m.feedback_target_index.unwrap_or_default(),
         is_enabled: m.enabled.unwrap_or(defaults::MAPPING_ENABLED),
         enabled_data: {
             EnabledData {
@@ -77,6 +98,20 @@ pub fn convert_mapping(
     Ok(v)
 }
 
+fn convert_additional_target(t: AdditionalTarget) -> ConversionResult<AdditionalTargetModelData> {
+    let target_interval = t
+        .target_interval
+        .map(convert_unit_value_interval)
+        .transpose()?;
+    let v = AdditionalTargetModelData {
+        target: convert_target(t.target)?,
+        min_target_value: target_interval.map(|i| i.min_val()),
+        max_target_value: target_interval.map(|i| i.max_val()),
+        eel_control_transformation: t.control_transformation,
+    };
+    Ok(v)
+}
+
 pub fn convert_tags(tag_strings: Vec<String>) -> ConversionResult<Vec<Tag>> {
     tag_strings.into_iter().map(convert_tag).collect()
 }
@@ -118,10 +153,10 @@ fn convert_advanced(
 }
 
 fn convert_lifecycle_hook(hook: Option<LifecycleHook>) -> ConversionResult<LifecycleModel> {
+    let hook = hook.unwrap_or_default();
     let v = LifecycleModel {
         send_midi_feedback: {
             let actions: Result<Vec<_>, _> = hook
-                .unwrap_or_default()
                 .send_midi_feedback
                 .unwrap_or_default()
                 .into_iter()
@@ -129,6 +164,54 @@ fn convert_lifecycle_hook(hook: Option<LifecycleHook>) -> ConversionResult<Lifec
                 .collect();
             actions?
         },
+        send_osc_feedback: hook
+            .send_osc_feedback
+            .unwrap_or_default()
+            .into_iter()
+            .map(convert_send_osc_feedback_action)
+            .collect(),
+        run_lua: hook.run_lua,
+        set_target_value: {
+            let actions: Result<Vec<_>, _> = hook
+                .set_target_value
+                .unwrap_or_default()
+                .into_iter()
+                .map(convert_set_target_value_action)
+                .collect();
+            actions?
+        },
+    };
+    Ok(v)
+}
+
+fn convert_send_osc_feedback_action(action: SendOscFeedbackAction) -> LifecycleOscMessageModel {
+    use LifecycleOscArgumentModel as M;
+    use OscFeedbackArgument as A;
+    LifecycleOscMessageModel {
+        address: action.address,
+        arguments: action
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|arg| match arg {
+                A::Float { value } => M::Float(value),
+                A::Double { value } => M::Double(value),
+                A::Int { value } => M::Int(value),
+                A::Long { value } => M::Long(value),
+                A::Bool { value } => M::Bool(value),
+                A::String { value } => M::String(value),
+                A::Nil => M::Nil,
+            })
+            .collect(),
+    }
+}
+
+fn convert_set_target_value_action(
+    action: SetTargetValueAction,
+) -> ConversionResult<LifecycleTargetValueModel> {
+    let v = LifecycleTargetValueModel {
+        mapping: action.mapping,
+        value: action.value.try_into().map_err(anyhow::Error::msg)?,
     };
     Ok(v)
 }
@@ -8,8 +8,8 @@ use anyhow::anyhow;
 pub use compartment::*;
 use enumflags2::BitFlags;
 use helgobox_api::persistence::{
-    ActivationCondition, Interval, Keystroke, ModifierState, OscArgKind, ParamRef,
-    VirtualControlElementId,
+    ActivationCondition, ActivationPlayState, Interval, Keystroke, ModifierState, OscArgKind,
+    ParamRef, VirtualControlElementId,
 };
 pub use mapping::*;
 use reaper_medium::AcceleratorKeyCode;
@@ -132,10 +132,65 @@ fn convert_activation(
             eel_condition: c.condition,
             ..Default::default()
         },
+        PlayState(c) => ActivationConditionData {
+            activation_type: ActivationType::PlayState,
+            play_state: convert_play_state(c.state),
+            ..Default::default()
+        },
+        TrackSelected(c) => ActivationConditionData {
+            activation_type: ActivationType::TrackSelected,
+            eel_condition: c.track_name,
+            ..Default::default()
+        },
+        FxFocused(c) => ActivationConditionData {
+            activation_type: ActivationType::FxFocused,
+            eel_condition: c.fx_name,
+            ..Default::default()
+        },
+        MidiEditorOpen => ActivationConditionData {
+            activation_type: ActivationType::MidiEditorOpen,
+            ..Default::default()
+        },
+        All(c) => ActivationConditionData {
+            activation_type: ActivationType::All,
+            conditions: convert_activations(c.conditions, param_index_by_key)?,
+            ..Default::default()
+        },
+        Any(c) => ActivationConditionData {
+            activation_type: ActivationType::Any,
+            conditions: convert_activations(c.conditions, param_index_by_key)?,
+            ..Default::default()
+        },
+        Not(c) => ActivationConditionData {
+            activation_type: ActivationType::Not,
+            conditions: vec![convert_activation(*c.condition, param_index_by_key)?],
+            ..Default::default()
+        },
     };
     Ok(data)
 }
 
+fn convert_play_state(s: ActivationPlayState) -> domain::PlayStateCondition {
+    use domain::PlayStateCondition as T;
+    use ActivationPlayState::*;
+    match s {
+        Stopped => T::Stopped,
+        Playing => T::Playing,
+        Paused => T::Paused,
+        Recording => T::Recording,
+    }
+}
+
+fn convert_activations(
+    conditions: Vec<ActivationCondition>,
+    param_index_by_key: &impl Fn(&str) -> Option<CompartmentParamIndex>,
+) -> ConversionResult<Vec<ActivationConditionData>> {
+    conditions
+        .into_iter()
+        .map(|c| convert_activation(c, param_index_by_key))
+        .collect()
+}
+
 fn resolve_parameter_ref(
     param_ref: &ParamRef,
     param_index_by_key: &impl Fn(&str) -> Option<CompartmentParamIndex>,
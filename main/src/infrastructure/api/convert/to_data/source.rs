@@ -1,4 +1,5 @@
 use crate::application::{MidiSourceType, ReaperSourceType, SourceCategory};
+use crate::domain::CompartmentParamIndex;
 use crate::infrastructure::api::convert::to_data::{
     convert_control_element_id, convert_keystroke, convert_osc_arg_type, convert_osc_value_range,
 };
@@ -147,6 +148,7 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
             Source::Timer(_) => ReaperSourceType::Timer,
             Source::RealearnParameter(_) => ReaperSourceType::RealearnParameter,
             Source::RealearnCompartmentLoaded => ReaperSourceType::RealearnCompartmentLoaded,
+            Source::Modulator(_) => ReaperSourceType::Modulator,
             _ => Default::default(),
         },
         timer_millis: match &s {
@@ -159,10 +161,69 @@ pub fn convert_source(s: Source) -> ConversionResult<SourceModelData> {
             }
             _ => Default::default(),
         },
+        modulator: match s {
+            Source::Modulator(m) => {
+                validate_modulator(&m)?;
+                m
+            }
+            _ => Default::default(),
+        },
     };
     Ok(data)
 }
 
+fn validate_modulator(m: &ModulatorSource) -> ConversionResult<()> {
+    let validate_param = |index: Option<u32>| -> ConversionResult<()> {
+        if let Some(i) = index {
+            CompartmentParamIndex::try_from(i).map_err(anyhow::Error::msg)?;
+        }
+        Ok(())
+    };
+    let validate_unit = |name: &str, value: Option<f64>| -> ConversionResult<()> {
+        if value.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+            bail!("modulator {name} must be between 0.0 and 1.0");
+        }
+        Ok(())
+    };
+    let validate_rate = |rate: &ModulatorRate| -> ConversionResult<()> {
+        match rate {
+            ModulatorRate::Free(r) => {
+                if !r.hertz.is_finite() || r.hertz < 0.0 {
+                    bail!("modulator rate in hertz must not be negative");
+                }
+            }
+            ModulatorRate::TempoSynced(r) => {
+                if !r.beats.is_finite() || r.beats <= 0.0 {
+                    bail!("modulator rate in beats must be positive");
+                }
+            }
+        }
+        Ok(())
+    };
+    validate_param(m.depth_parameter_index)?;
+    validate_param(m.rate_parameter_index)?;
+    validate_unit("depth", m.depth)?;
+    match &m.modulator {
+        Modulator::Lfo(m) => {
+            validate_rate(&m.rate)?;
+            if m.phase.is_some_and(|p| !p.is_finite()) {
+                bail!("modulator phase must be a finite number");
+            }
+        }
+        Modulator::Envelope(m) => {
+            if m.gate_mapping.is_empty() {
+                bail!("envelope modulator needs a gate mapping");
+            }
+            validate_unit("sustain", m.sustain)?;
+        }
+        Modulator::RandomWalk(m) => {
+            validate_rate(&m.rate)?;
+            validate_unit("step size", m.step_size)?;
+        }
+    }
+    Ok(())
+}
+
 fn convert_category(s: &Source) -> SourceCategory {
     use Source::*;
     match s {
@@ -172,7 +233,8 @@ fn convert_category(s: &Source) -> SourceCategory {
         | RealearnCompartmentLoaded
         | Timer(_)
         | RealearnParameter(_)
-        | Speech => SourceCategory::Reaper,
+        | Speech
+        | Modulator(_) => SourceCategory::Reaper,
         MidiNoteVelocity(_)
         | MidiNoteKeyNumber(_)
         | MidiPolyphonicKeyPressureAmount(_)
@@ -1,8 +1,8 @@
 use crate::application::{
-    AutomationModeOverrideType, BookmarkAnchorType, FxParameterPropValues, FxPropValues,
-    MappingModificationKind, RealearnAutomationMode, RealearnTrackArea, TargetCategory,
-    TrackPropValues, TrackRoutePropValues, TrackRouteSelectorType, VirtualFxParameterType,
-    VirtualFxType, VirtualTrackType,
+    AutomationModeOverrideType, BookmarkAnchorType, EnvelopeType, FxParameterPropValues,
+    FxPropValues, MappingModificationKind, RealearnAutomationMode, RealearnTrackArea,
+    TargetCategory, TrackPropValues, TrackRoutePropValues, TrackRouteSelectorType,
+    VirtualFxParameterType, VirtualFxType, VirtualTrackType, DEFAULT_TRACK_ENVELOPE_NAME,
 };
 use crate::domain::{
     ActionInvocationType, Exclusivity, FxDisplayType, ReaperTargetType, SeekOptions,
@@ -59,6 +59,18 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::LoadScreenset(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::LoadScreenset,
+            screenset_index: d.index,
+            ..init(d.commons)
+        },
+        Target::WindowVisibility(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::WindowVisibility,
+            reaper_window: d.window,
+            ..init(d.commons)
+        },
         #[allow(unused_mut)]
         Target::ReaperAction(d) => {
             let mut track_desc = if let Some(td) = d.track {
@@ -109,6 +121,11 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
             any_on_parameter: convert_any_on_parameter(d.parameter),
             ..init(d.commons)
         },
+        Target::BrowseProjects(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::BrowseProjects,
+            ..init(d.commons)
+        },
         Target::BrowseTracks(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::BrowseTracks,
@@ -337,6 +354,30 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::TrackRecordInput(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackRecordInput,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
+        Target::TrackRecordMode(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackRecordMode,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_exclusivity: convert_track_exclusivity(d.exclusivity),
+                track_record_mode: d.mode,
+                ..init(d.commons)
+            }
+        }
         Target::TrackAutomationTouchState(d) => {
             let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
             TargetModelData {
@@ -454,6 +495,45 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::TrackName(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackName,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
+        Target::TrackColor(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackColor,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
+        Target::TrackLayout(d) => {
+            let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::TrackLayout,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_area: {
+                    match d.area {
+                        TrackArea::Tcp => RealearnTrackArea::Tcp,
+                        TrackArea::Mcp => RealearnTrackArea::Mcp,
+                    }
+                },
+                ..init(d.commons)
+            }
+        }
         Target::TrackSoloState(d) => {
             let track_desc = convert_track_desc(d.track.unwrap_or_default())?;
             TargetModelData {
@@ -646,6 +726,21 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::EnvelopeValue(d) => {
+            convert_envelope_target(ReaperTargetType::EnvelopeValue, d.envelope, d.commons)?
+        }
+        Target::EnvelopeAction(d) => TargetModelData {
+            envelope_action: d.action.unwrap_or_default(),
+            ..convert_envelope_target(ReaperTargetType::EnvelopeAction, d.envelope, d.commons)?
+        },
+        Target::AutomationItemAction(d) => TargetModelData {
+            automation_item_action: d.action.unwrap_or_default(),
+            ..convert_envelope_target(
+                ReaperTargetType::AutomationItemAction,
+                d.envelope,
+                d.commons,
+            )?
+        },
         Target::RouteAutomationMode(d) => {
             let route_desc = convert_route_desc(d.route)?;
             let track_desc = route_desc.track_desc;
@@ -754,6 +849,37 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::RouteExistence(d) => {
+            let route_desc = convert_route_desc(d.route)?;
+            let track_desc = route_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::RouteExistence,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_route_data: route_desc.track_route_data,
+                route_existence_action: d.action.unwrap_or_default(),
+                ..init(d.commons)
+            }
+        }
+        Target::RouteSendMode(d) => {
+            let route_desc = convert_route_desc(d.route)?;
+            let track_desc = route_desc.track_desc;
+            TargetModelData {
+                category: TargetCategory::Reaper,
+                r#type: ReaperTargetType::RouteSendMode,
+                track_data: track_desc.track_data,
+                enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+                clip_column: track_desc.clip_column.unwrap_or_default(),
+                track_route_data: route_desc.track_route_data,
+                poll_for_feedback: d
+                    .poll_for_feedback
+                    .unwrap_or(defaults::TARGET_POLL_FOR_FEEDBACK),
+                send_mode: d.mode,
+                ..init(d.commons)
+            }
+        }
         Target::PlaytimeSlotTransportAction(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::PlaytimeSlotTransportAction,
@@ -920,6 +1046,12 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
             mapping_key: d.mapping.map(|id| id.into()),
             ..init(d.commons)
         },
+        Target::Macro(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::Macro,
+            macro_steps: d.steps,
+            ..init(d.commons)
+        },
         Target::LoadMappingSnapshot(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::LoadMappingSnapshot,
@@ -972,6 +1104,7 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
         Target::BrowsePotPresets(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::BrowsePotPresets,
+            pot_collection: d.collection,
             ..init(d.commons)
         },
         Target::PreviewPotPreset(d) => TargetModelData {
@@ -993,6 +1126,17 @@ pub fn convert_target(t: Target) -> ConversionResult<TargetModelData> {
                 ..init(d.commons)
             }
         }
+        Target::RatePotPreset(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::RatePotPreset,
+            ..init(d.commons)
+        },
+        Target::TagPotPreset(d) => TargetModelData {
+            category: TargetCategory::Reaper,
+            r#type: ReaperTargetType::TagPotPreset,
+            tags: convert_tags(d.tags.unwrap_or_default())?,
+            ..init(d.commons)
+        },
         Target::StreamDeckBrightness(d) => TargetModelData {
             category: TargetCategory::Reaper,
             r#type: ReaperTargetType::StreamDeckBrightness,
@@ -1019,10 +1163,36 @@ fn init(commons: TargetCommons) -> TargetModelData {
                 Percent => T::Percent,
             }
         },
+        envelope_name: DEFAULT_TRACK_ENVELOPE_NAME.to_string(),
         ..Default::default()
     }
 }
 
+fn convert_envelope_target(
+    r#type: ReaperTargetType,
+    envelope: EnvelopeDescriptor,
+    commons: TargetCommons,
+) -> ConversionResult<TargetModelData> {
+    let envelope_desc = convert_envelope_desc(envelope)?;
+    let fx_parameter_desc = envelope_desc.fx_parameter_desc;
+    let fx_desc = fx_parameter_desc.fx_desc;
+    let track_desc = fx_desc.chain_desc.track_desc;
+    let data = TargetModelData {
+        category: TargetCategory::Reaper,
+        r#type,
+        track_data: track_desc.track_data,
+        enable_only_if_track_is_selected: track_desc.track_must_be_selected,
+        clip_column: track_desc.clip_column.unwrap_or_default(),
+        fx_data: fx_desc.fx_data,
+        enable_only_if_fx_has_focus: fx_desc.fx_must_have_focus,
+        fx_parameter_data: fx_parameter_desc.fx_parameter_data,
+        envelope_type: envelope_desc.envelope_type,
+        envelope_name: envelope_desc.envelope_name,
+        ..init(commons)
+    };
+    Ok(data)
+}
+
 fn convert_automation_mode(mode: AutomationMode) -> RealearnAutomationMode {
     use AutomationMode::*;
     use RealearnAutomationMode as T;
@@ -1068,6 +1238,12 @@ struct FxParameterDesc {
     fx_parameter_data: FxParameterData,
 }
 
+struct EnvelopeDesc {
+    fx_parameter_desc: FxParameterDesc,
+    envelope_type: EnvelopeType,
+    envelope_name: String,
+}
+
 fn convert_track_desc(t: TrackDescriptor) -> ConversionResult<TrackDesc> {
     use TrackDescriptor::*;
     let (props, track_must_be_selected) = match t {
@@ -1264,6 +1440,15 @@ fn convert_route_desc(t: RouteDescriptor) -> ConversionResult<RouteDesc> {
                 ..Default::default()
             },
         ),
+        ByTag { commons, tag } => (
+            convert_track_desc(commons.track.unwrap_or_default())?,
+            TrackRoutePropValues {
+                selector_type: TrackRouteSelectorType::ByTag,
+                r#type: convert_route_kind(commons.route_kind.unwrap_or_default()),
+                name: tag,
+                ..Default::default()
+            },
+        ),
     };
     let desc = RouteDesc {
         track_desc,
@@ -1433,6 +1618,31 @@ fn convert_fx_parameter_desc(t: FxParameterDescriptor) -> ConversionResult<FxPar
     Ok(desc)
 }
 
+fn convert_envelope_desc(t: EnvelopeDescriptor) -> ConversionResult<EnvelopeDesc> {
+    let desc = match t {
+        EnvelopeDescriptor::Track { track, name } => EnvelopeDesc {
+            fx_parameter_desc: FxParameterDesc {
+                fx_desc: FxDesc {
+                    chain_desc: FxChainDesc {
+                        track_desc: convert_track_desc(track.unwrap_or_default())?,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            envelope_type: EnvelopeType::Track,
+            envelope_name: name,
+        },
+        EnvelopeDescriptor::FxParameter { parameter } => EnvelopeDesc {
+            fx_parameter_desc: convert_fx_parameter_desc(parameter)?,
+            envelope_type: EnvelopeType::FxParameter,
+            envelope_name: DEFAULT_TRACK_ENVELOPE_NAME.to_string(),
+        },
+    };
+    Ok(desc)
+}
+
 fn convert_transport_action(transport_action: TransportAction) -> domain::TransportAction {
     use domain::TransportAction as T;
     use TransportAction::*;
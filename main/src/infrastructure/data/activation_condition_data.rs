@@ -2,7 +2,7 @@ use crate::application::{
     ActivationConditionCommand, ActivationConditionModel, ActivationType, BankConditionModel,
     Change, ModifierConditionModel,
 };
-use crate::domain::MappingKey;
+use crate::domain::{MappingKey, PlayStateCondition};
 use crate::infrastructure::data::{DataToModelConversionContext, ModelToDataConversionContext};
 use base::default_util::{deserialize_null_default, is_default};
 use serde::{Deserialize, Serialize};
@@ -46,6 +46,18 @@ pub struct ActivationConditionData {
         skip_serializing_if = "is_default"
     )]
     pub mapping_key: Option<MappingKey>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub conditions: Vec<ActivationConditionData>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub play_state: PlayStateCondition,
 }
 
 impl ActivationConditionData {
@@ -62,6 +74,12 @@ impl ActivationConditionData {
             mapping_key: model
                 .mapping_id()
                 .and_then(|id| conversion_context.mapping_key_by_id(id)),
+            conditions: model
+                .conditions()
+                .iter()
+                .map(|c| ActivationConditionData::from_model(c, conversion_context))
+                .collect(),
+            play_state: model.play_state(),
         }
     }
 
@@ -81,5 +99,16 @@ impl ActivationConditionData {
             .as_ref()
             .and_then(|key| conversion_context.mapping_id_by_key(key));
         model.change(V::SetMappingId(mapping_id));
+        let conditions = self
+            .conditions
+            .iter()
+            .map(|data| {
+                let mut model = ActivationConditionModel::default();
+                data.apply_to_model(&mut model, conversion_context);
+                model
+            })
+            .collect();
+        model.change(V::SetConditions(conditions));
+        model.change(V::SetPlayState(self.play_state));
     }
 }
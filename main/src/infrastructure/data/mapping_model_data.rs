@@ -1,4 +1,6 @@
-use crate::application::{Change, MappingCommand, MappingModel};
+use crate::application::{
+    AdditionalTargetModel, Change, MappingCommand, MappingModel, TargetModel,
+};
 use crate::domain::{
     CompartmentKind, ExtendedProcessorContext, FeedbackSendBehavior, GroupId, GroupKey, MappingId,
     MappingKey, Tag,
@@ -8,6 +10,7 @@ use crate::infrastructure::data::{
     ModeModelData, ModelToDataConversionContext, SourceModelData, TargetModelData,
 };
 use base::default_util::{bool_true, deserialize_null_default, is_bool_true, is_default};
+use helgoboss_learn::{Interval, UnitValue};
 use helgobox_api::persistence::SuccessAudioFeedback;
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -47,6 +50,18 @@ pub struct MappingModelData {
     pub source: SourceModelData,
     pub mode: ModeModelData,
     pub target: TargetModelData,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub additional_targets: Vec<AdditionalTargetModelData>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub feedback_target_index: u32,
     #[serde(default = "bool_true", skip_serializing_if = "is_bool_true")]
     pub is_enabled: bool,
     #[serde(flatten)]
@@ -81,6 +96,30 @@ pub struct MappingModelData {
     pub success_audio_feedback: Option<SuccessAudioFeedback>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdditionalTargetModelData {
+    pub target: TargetModelData,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub min_target_value: Option<UnitValue>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub max_target_value: Option<UnitValue>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub eel_control_transformation: Option<String>,
+}
+
 impl MappingModelData {
     pub fn from_model(
         model: &MappingModel,
@@ -98,6 +137,17 @@ impl MappingModelData {
             source: SourceModelData::from_model(&model.source_model),
             mode: ModeModelData::from_model(&model.mode_model),
             target: TargetModelData::from_model(&model.target_model, conversion_context),
+            additional_targets: model
+                .additional_targets()
+                .iter()
+                .map(|t| AdditionalTargetModelData {
+                    target: TargetModelData::from_model(&t.target_model, conversion_context),
+                    min_target_value: t.target_value_interval.map(|i| i.min_val()),
+                    max_target_value: t.target_value_interval.map(|i| i.max_val()),
+                    eel_control_transformation: t.eel_control_transformation.clone(),
+                })
+                .collect(),
+            feedback_target_index: model.feedback_target_index(),
             is_enabled: model.is_enabled(),
             enabled_data: EnabledData {
                 control_is_enabled: model.control_is_enabled(),
@@ -238,6 +288,38 @@ impl MappingModelData {
             conversion_context,
             migration_descriptor,
         )?;
+        let additional_targets = self
+            .additional_targets
+            .iter()
+            .map(|t| {
+                let mut target_model = TargetModel::default_for_compartment(compartment);
+                t.target.apply_to_model_flexible(
+                    &mut target_model,
+                    processor_context,
+                    preset_version,
+                    compartment,
+                    conversion_context,
+                    migration_descriptor,
+                )?;
+                let target_value_interval =
+                    if t.min_target_value.is_some() || t.max_target_value.is_some() {
+                        Some(Interval::new_auto(
+                            t.min_target_value.unwrap_or(UnitValue::MIN),
+                            t.max_target_value.unwrap_or(UnitValue::MAX),
+                        ))
+                    } else {
+                        None
+                    };
+                let model = AdditionalTargetModel {
+                    target_model,
+                    target_value_interval,
+                    eel_control_transformation: t.eel_control_transformation.clone(),
+                };
+                Ok(model)
+            })
+            .collect::<anyhow::Result<_>>()?;
+        model.change(P::SetAdditionalTargets(additional_targets));
+        model.change(P::SetFeedbackTargetIndex(self.feedback_target_index));
         model.change(P::SetIsEnabled(self.is_enabled));
         model.change(P::SetControlIsEnabled(self.enabled_data.control_is_enabled));
         model.change(P::SetFeedbackIsEnabled(
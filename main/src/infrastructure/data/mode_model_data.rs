@@ -6,7 +6,9 @@ use helgoboss_learn::{
     GroupInteraction, Interval, OutOfRangeBehavior, SoftSymmetricUnitValue, TakeoverMode,
     UnitValue, ValueSequence, VirtualColor,
 };
-use helgobox_api::persistence::FeedbackValueTable;
+use helgobox_api::persistence::{
+    FeedbackValueTable, ResponseCurve, Smoothing, TransformationScriptKind,
+};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::debug;
@@ -107,6 +109,18 @@ pub struct ModeModelData {
         deserialize_with = "deserialize_null_default",
         skip_serializing_if = "is_default"
     )]
+    pub control_transformation_kind: TransformationScriptKind,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub feedback_transformation_kind: TransformationScriptKind,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
     pub reverse_is_enabled: bool,
     #[serde(
         default,
@@ -208,6 +222,18 @@ pub struct ModeModelData {
         skip_serializing_if = "is_default"
     )]
     pub feedback_value_table: Option<FeedbackValueTable>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub smoothing: Option<Smoothing>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub response_curve: Option<ResponseCurve>,
 }
 
 fn default_step_size() -> SoftSymmetricUnitValue {
@@ -247,6 +273,8 @@ impl ModeModelData {
             } else {
                 model.eel_feedback_transformation().to_owned()
             },
+            control_transformation_kind: model.control_transformation_kind(),
+            feedback_transformation_kind: model.feedback_transformation_kind(),
             feedback_color: model.feedback_color().cloned(),
             feedback_background_color: model.feedback_background_color().cloned(),
             reverse_is_enabled: model.reverse(),
@@ -266,6 +294,8 @@ impl ModeModelData {
             target_value_sequence: model.target_value_sequence().clone(),
             feedback_type: model.feedback_type(),
             feedback_value_table: model.feedback_value_table().cloned(),
+            smoothing: model.smoothing().cloned(),
+            response_curve: model.response_curve().cloned(),
         }
     }
 
@@ -371,6 +401,12 @@ impl ModeModelData {
         };
         model.change(P::SetEelFeedbackTransformation(eel_fb_transformation));
         model.change(P::SetTextualFeedbackExpression(textual_fb_expression));
+        model.change(P::SetControlTransformationKind(
+            self.control_transformation_kind,
+        ));
+        model.change(P::SetFeedbackTransformationKind(
+            self.feedback_transformation_kind,
+        ));
         model.change(P::SetFeedbackColor(self.feedback_color.clone()));
         model.change(P::SetFeedbackBackgroundColor(
             self.feedback_background_color.clone(),
@@ -407,6 +443,8 @@ impl ModeModelData {
         ));
         model.change(P::SetFeedbackType(self.feedback_type));
         model.change(P::SetFeedbackValueTable(self.feedback_value_table.clone()));
+        model.change(P::SetSmoothing(self.smoothing.clone()));
+        model.change(P::SetResponseCurve(self.response_curve.clone()));
     }
 }
 
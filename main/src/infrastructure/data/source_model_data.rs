@@ -10,8 +10,8 @@ use base::default_util::{deserialize_null_default, is_default};
 use helgoboss_learn::{DisplayType, MidiClockTransportMessage, OscTypeTag, SourceCharacter};
 use helgoboss_midi::{Channel, U14, U7};
 use helgobox_api::persistence::{
-    MidiScriptKind, StreamDeckButtonBackground, StreamDeckButtonDesign, StreamDeckButtonForeground,
-    VirtualControlElementCharacter,
+    MidiScriptKind, ModulatorSource, StreamDeckButtonBackground, StreamDeckButtonDesign,
+    StreamDeckButtonForeground, VirtualControlElementCharacter,
 };
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -189,6 +189,12 @@ pub struct SourceModelData {
         skip_serializing_if = "is_default"
     )]
     pub parameter_index: CompartmentParamIndex,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub modulator: ModulatorSource,
 }
 
 impl SourceModelData {
@@ -228,6 +234,7 @@ impl SourceModelData {
             reaper_source_type: model.reaper_source_type(),
             timer_millis: model.timer_millis(),
             parameter_index: model.parameter_index(),
+            modulator: model.modulator().clone(),
         }
     }
 
@@ -308,6 +315,7 @@ impl SourceModelData {
         model.change(P::SetReaperSourceType(self.reaper_source_type));
         model.change(P::SetTimerMillis(self.timer_millis));
         model.change(P::SetParameterIndex(self.parameter_index));
+        model.change(P::SetModulator(self.modulator.clone()));
         model.change(P::SetKeystroke(self.keystroke));
         model.change(P::SetButtonIndex(self.button_index));
         model.change(P::SetButtonBackgroundType(
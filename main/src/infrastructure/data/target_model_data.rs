@@ -1,11 +1,12 @@
 use super::f32_as_u32;
 use super::none_if_minus_one;
 use crate::application::{
-    AutomationModeOverrideType, BookmarkAnchorType, Change, FxParameterPropValues, FxPropValues,
-    FxSnapshot, MappingModificationKind, MappingRefModel, MappingSnapshotTypeForLoad,
+    AutomationModeOverrideType, BookmarkAnchorType, Change, EnvelopeType, FxParameterPropValues,
+    FxPropValues, FxSnapshot, MappingModificationKind, MappingRefModel, MappingSnapshotTypeForLoad,
     MappingSnapshotTypeForTake, RealearnAutomationMode, RealearnTrackArea, TargetCategory,
     TargetCommand, TargetModel, TargetUnit, TrackPropValues, TrackRoutePropValues,
     TrackRouteSelectorType, VirtualFxParameterType, VirtualFxType, VirtualTrackType,
+    DEFAULT_TRACK_ENVELOPE_NAME,
 };
 use crate::domain::{
     get_fx_chains, ActionInvocationType, AnyOnParameter, CompartmentKind, Exclusivity,
@@ -27,9 +28,10 @@ use base::default_util::{
 use base::hash_util::NonCryptoHashSet;
 use helgoboss_learn::{AbsoluteValue, Fraction, OscTypeTag, UnitValue};
 use helgobox_api::persistence::{
-    ActionScope, Axis, BrowseTracksMode, FxToolAction, LearnableTargetKind,
-    MappingSnapshotDescForLoad, MappingSnapshotDescForTake, MonitoringMode, MouseAction,
-    PotFilterKind, SeekBehavior, TargetTouchCause, TargetValue, TrackScope, TrackToolAction,
+    ActionScope, AutomationItemAction, Axis, BrowseTracksMode, EnvelopeAction, FxToolAction,
+    LearnableTargetKind, MacroStep, MappingSnapshotDescForLoad, MappingSnapshotDescForTake,
+    MonitoringMode, MouseAction, PotFilterKind, ReaperWindow, RecordMode, RouteExistenceAction,
+    SeekBehavior, SendMode, TargetTouchCause, TargetValue, TrackScope, TrackToolAction,
     VirtualControlElementCharacter,
 };
 use helgobox_api::persistence::{
@@ -171,6 +173,30 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub fx_tool_action: FxToolAction,
+    // Envelope targets
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub envelope_type: EnvelopeType,
+    #[serde(
+        default = "default_envelope_name",
+        skip_serializing_if = "is_default_envelope_name"
+    )]
+    pub envelope_name: String,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub envelope_action: EnvelopeAction,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub automation_item_action: AutomationItemAction,
     // Transport target
     #[serde(
         default,
@@ -217,6 +243,20 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub touched_route_parameter_type: TouchedRouteParameterType,
+    // Route existence target
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub route_existence_action: RouteExistenceAction,
+    // Route send mode target
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub send_mode: SendMode,
     // Bookmark target
     #[serde(flatten)]
     pub bookmark_data: BookmarkData,
@@ -244,6 +284,34 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub track_monitoring_mode: MonitoringMode,
+    // Track record mode target
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub track_record_mode: RecordMode,
+    // Load screenset target
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub screenset_index: u32,
+    // Window visibility target
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub reaper_window: ReaperWindow,
+    // Macro target
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub macro_steps: Vec<MacroStep>,
     // Automation mode override target
     #[serde(
         default,
@@ -489,6 +557,12 @@ pub struct TargetModelData {
         skip_serializing_if = "is_default"
     )]
     pub pot_filter_item_kind: PotFilterKind,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "is_default"
+    )]
+    pub pot_collection: Option<String>,
     /// New since ReaLearn v2.15.0-pre.1
     #[serde(
         default,
@@ -529,6 +603,14 @@ pub struct TargetModelData {
     pub touch_cause: TargetTouchCause,
 }
 
+fn default_envelope_name() -> String {
+    DEFAULT_TRACK_ENVELOPE_NAME.to_string()
+}
+
+fn is_default_envelope_name(v: &str) -> bool {
+    v == DEFAULT_TRACK_ENVELOPE_NAME
+}
+
 impl TargetModelData {
     pub fn from_model(
         model: &TargetModel,
@@ -571,6 +653,10 @@ impl TargetModelData {
             track_exclusivity: model.track_exclusivity(),
             track_tool_action: model.track_tool_action(),
             fx_tool_action: model.fx_tool_action(),
+            envelope_type: model.envelope_type(),
+            envelope_name: model.envelope_name().to_owned(),
+            envelope_action: model.envelope_action(),
+            automation_item_action: model.automation_item_action(),
             transport_action: model.transport_action(),
             any_on_parameter: model.any_on_parameter(),
             control_element_type: model.control_element_character(),
@@ -581,6 +667,8 @@ impl TargetModelData {
             fx_snapshot: model.fx_snapshot().cloned(),
             touched_parameter_type: model.touched_track_parameter_type(),
             touched_route_parameter_type: model.touched_route_parameter_type(),
+            route_existence_action: model.route_existence_action(),
+            send_mode: model.send_mode(),
             bookmark_data: BookmarkData {
                 anchor: model.bookmark_anchor_type(),
                 r#ref: model.bookmark_ref(),
@@ -590,6 +678,10 @@ impl TargetModelData {
             track_area: model.track_area(),
             track_automation_mode: model.automation_mode(),
             track_monitoring_mode: model.monitoring_mode(),
+            track_record_mode: model.record_mode(),
+            screenset_index: model.screenset_index(),
+            reaper_window: model.reaper_window(),
+            macro_steps: model.macro_steps().to_vec(),
             automation_mode_override_type: model.automation_mode_override_type(),
             browse_tracks_mode: model.browse_tracks_mode(),
             fx_display_type: model.fx_display_type(),
@@ -644,6 +736,7 @@ impl TargetModelData {
             axis: model.axis(),
             mouse_action: model.mouse_action(),
             pot_filter_item_kind: model.pot_filter_item_kind(),
+            pot_collection: model.pot_collection().map(|c| c.to_string()),
             mapping_modification_kind: model.mapping_modification_kind(),
             session_id,
             mapping_key,
@@ -816,6 +909,8 @@ impl TargetModelData {
         model.change(C::SetTouchedRouteParameterType(
             self.touched_route_parameter_type,
         ));
+        model.change(C::SetRouteExistenceAction(self.route_existence_action));
+        model.change(C::SetSendMode(self.send_mode));
         let bookmark_type = if self.bookmark_data.is_region {
             BookmarkType::Region
         } else {
@@ -828,6 +923,10 @@ impl TargetModelData {
         model.change(C::SetTrackArea(self.track_area));
         model.change(C::SetAutomationMode(self.track_automation_mode));
         model.change(C::SetMonitoringMode(self.track_monitoring_mode));
+        model.change(C::SetRecordMode(self.track_record_mode));
+        model.change(C::SetScreensetIndex(self.screenset_index));
+        model.change(C::SetReaperWindow(self.reaper_window));
+        model.change(C::SetMacroSteps(self.macro_steps.clone()));
         model.change(C::SetAutomationModeOverrideType(
             self.automation_mode_override_type,
         ));
@@ -909,6 +1008,10 @@ impl TargetModelData {
         }
         model.change(C::SetTrackToolAction(self.track_tool_action));
         model.change(C::SetFxToolAction(self.fx_tool_action));
+        model.change(C::SetEnvelopeType(self.envelope_type));
+        model.change(C::SetEnvelopeName(self.envelope_name.clone()));
+        model.change(C::SetEnvelopeAction(self.envelope_action));
+        model.change(C::SetAutomationItemAction(self.automation_item_action));
         // "Load mapping snapshot" stuff
         let mapping_snapshot_id_for_load = {
             let (mapping_snapshot_type, mapping_snapshot_id) = match &self.mapping_snapshot {
@@ -959,6 +1062,7 @@ impl TargetModelData {
             model.set_mouse_action_without_notification(self.mouse_action);
         }
         model.change(C::SetPotFilterItemKind(self.pot_filter_item_kind));
+        model.change(C::SetPotCollection(self.pot_collection.clone()));
         model.change(C::SetMappingModificationKind(
             self.mapping_modification_kind,
         ));
@@ -1229,7 +1333,7 @@ pub fn serialize_track_route(route: TrackRoutePropValues) -> TrackRouteData {
             name: None,
             expression: None,
         },
-        ByName => TrackRouteData {
+        ByName | ByTag => TrackRouteData {
             selector_type: Some(route.selector_type),
             r#type: route.r#type,
             index: None,
@@ -1801,12 +1905,16 @@ pub fn deserialize_track_route(data: &TrackRouteData) -> TrackRoutePropValues {
             ..Default::default()
         },
         TrackRouteData {
-            selector_type: Some(TrackRouteSelectorType::ByName),
+            selector_type:
+                Some(
+                    selector_type
+                    @ (TrackRouteSelectorType::ByName | TrackRouteSelectorType::ByTag),
+                ),
             r#type: t,
             name: Some(name),
             ..
         } => TrackRoutePropValues {
-            selector_type: TrackRouteSelectorType::ByName,
+            selector_type: *selector_type,
             r#type: *t,
             name: name.clone(),
             ..Default::default()
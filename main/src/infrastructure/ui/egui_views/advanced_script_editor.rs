@@ -278,7 +278,10 @@ impl Toolbox {
                             output_value: prev_y.get(),
                             rel_time: Duration::from_millis(rel_time_millis as u64),
                         },
-                        additional_input: AdditionalTransformationInput { y_last: 0.0 },
+                        additional_input: AdditionalTransformationInput {
+                            y_last: 0.0,
+                            lua: None,
+                        },
                     };
                     let Some(output) = script.evaluate(input).ok() else {
                         // No sample for that point
@@ -18,8 +18,9 @@ use crate::application::{
 use crate::base::when;
 use crate::domain::{
     convert_compartment_param_index_range_to_iter, Backbone, CompartmentKind,
-    CompartmentParamIndex, ControlInput, FeedbackOutput, GroupId, MessageCaptureEvent, OscDeviceId,
-    ParamSetting, ReaperTarget, StayActiveWhenProjectInBackground, COMPARTMENT_PARAMETER_COUNT,
+    CompartmentParamIndex, ControlInput, FeedbackOutput, GroupId, MappingId, MappingIssue,
+    MessageCaptureEvent, OscDeviceId, ParamSetting, ReaperTarget,
+    StayActiveWhenProjectInBackground, COMPARTMENT_PARAMETER_COUNT,
 };
 use crate::domain::{MidiControlInput, MidiDestination};
 use crate::infrastructure::data::{
@@ -170,6 +171,66 @@ impl HeaderPanel {
         self.open_extra_panel(editor);
     }
 
+    fn analyze_mappings(&self) {
+        let msg = self.analyze_mappings_internal();
+        self.view.require_window().alert("ReaLearn", msg);
+    }
+
+    fn analyze_mappings_internal(&self) -> String {
+        let compartment = self.active_compartment();
+        let session = self.session();
+        let session = session.borrow();
+        let issues = session.analyze_mappings(compartment);
+        if issues.is_empty() {
+            "No issues found.".to_string()
+        } else {
+            let mapping_name = |id: MappingId| {
+                session
+                    .find_mapping_by_id(compartment, id)
+                    .map(|m| m.borrow().effective_name())
+                    .unwrap_or_else(|| id.to_string())
+            };
+            let lines: Vec<_> = issues
+                .iter()
+                .map(|issue| match issue {
+                    MappingIssue::SourceCollision { mappings: [a, b] } => format!(
+                        "- Mappings \"{}\" and \"{}\" react to the same source",
+                        mapping_name(*a),
+                        mapping_name(*b)
+                    ),
+                    MappingIssue::FeedbackAddressCollision {
+                        mappings: [a, b], ..
+                    } => format!(
+                        "- Mappings \"{}\" and \"{}\" send feedback to the same destination",
+                        mapping_name(*a),
+                        mapping_name(*b)
+                    ),
+                    MappingIssue::Unreachable { mapping } => format!(
+                        "- Mapping \"{}\" can never be active",
+                        mapping_name(*mapping)
+                    ),
+                    MappingIssue::MissingGroup { mapping, .. } => format!(
+                        "- Mapping \"{}\" refers to a group that doesn't exist",
+                        mapping_name(*mapping)
+                    ),
+                    MappingIssue::MissingLeadMapping { mapping, .. } => format!(
+                        "- Mapping \"{}\" has an activation condition that refers to a mapping that doesn't exist",
+                        mapping_name(*mapping)
+                    ),
+                    MappingIssue::UnknownSnapshotTag { mapping, tag } => format!(
+                        "- Mapping \"{}\" refers to snapshot tag \"{tag}\" which no mapping has",
+                        mapping_name(*mapping)
+                    ),
+                })
+                .collect();
+            format!(
+                "Found {} potential issues:\n\n{}",
+                issues.len(),
+                lines.join("\n")
+            )
+        }
+    }
+
     fn open_extra_panel(&self, panel: impl View + 'static) {
         open_child_panel_dyn(&self.extra_panel, panel, self.view.require_window());
     }
@@ -601,6 +662,7 @@ impl HeaderPanel {
                     "Edit compartment-wide Lua code",
                     MainMenuAction::EditCompartmentWideLuaCode,
                 ),
+                item("Analyze mappings", MainMenuAction::AnalyzeMappings),
                 labeled_separator("Unit-related"),
                 // Unit scope
                 menu(
@@ -910,6 +972,7 @@ impl HeaderPanel {
             }
             MainMenuAction::ReloadAllCompartmentPresets => self.reload_all_compartment_presets(),
             MainMenuAction::EditCompartmentWideLuaCode => self.edit_compartment_common_lua(),
+            MainMenuAction::AnalyzeMappings => self.analyze_mappings(),
             MainMenuAction::OpenPotBrowser => {
                 self.show_pot_browser();
             }
@@ -3237,6 +3300,7 @@ enum MainMenuAction {
     SendFeedbackNow,
     LogDebugInfo,
     EditCompartmentWideLuaCode,
+    AnalyzeMappings,
     CreateCompartmentPresetWorkspace,
     CreateCompartmentPresetWorkspaceIncludingFactoryPresets,
     ConvertToolbarToStreamDeckMappings(String),
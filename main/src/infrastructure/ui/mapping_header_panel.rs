@@ -12,7 +12,7 @@ use crate::application::{
     SharedUnitModel, UnitModel, WeakUnitModel,
 };
 use crate::domain::ui_util::format_tags_as_csv;
-use crate::domain::{CompartmentKind, MappingId, Tag};
+use crate::domain::{CompartmentKind, MappingId, PlayStateCondition, Tag};
 use crate::infrastructure::ui::menus;
 use derivative::Derivative;
 use std::fmt::Debug;
@@ -57,6 +57,8 @@ pub trait Item: Debug {
     fn set_script(&mut self, session: WeakUnitModel, value: String, initiator: u32);
     fn mapping_id(&self) -> Option<MappingId>;
     fn set_mapping_id(&mut self, session: WeakUnitModel, value: Option<MappingId>);
+    fn play_state(&self) -> PlayStateCondition;
+    fn set_play_state(&mut self, session: WeakUnitModel, value: PlayStateCondition);
 }
 
 pub enum ItemProp {
@@ -70,6 +72,8 @@ pub enum ItemProp {
     BankCondition,
     Script,
     MappingId,
+    Conditions,
+    PlayState,
 }
 
 impl ItemProp {
@@ -82,6 +86,8 @@ impl ItemProp {
             S::BankCondition => Self::BankCondition,
             S::Script => Self::Script,
             S::MappingId => Self::MappingId,
+            S::Conditions => Self::Conditions,
+            S::PlayState => Self::PlayState,
         }
     }
 }
@@ -269,6 +275,12 @@ impl MappingHeaderPanel {
                 button.set_text(text);
                 Some("Mapping")
             }
+            PlayState => {
+                button.show();
+                check_box.hide();
+                button.set_text(item.play_state().to_string());
+                Some("State")
+            }
             _ => {
                 button.hide();
                 check_box.hide();
@@ -343,7 +355,22 @@ impl MappingHeaderPanel {
                 edit_control.set_text(item.script());
                 Some("Ex: p[0] == 2")
             }
-            Always => {
+            TrackSelected => {
+                button.hide();
+                check_box.hide();
+                edit_control.show();
+                edit_control.set_text(item.script());
+                Some("Ex: Drums*")
+            }
+            FxFocused => {
+                button.hide();
+                check_box.hide();
+                edit_control.show();
+                edit_control.set_text(item.script());
+                Some("Ex: *Pro-Q*")
+            }
+            // Composite conditions can only be edited via the Lua API
+            Always | All | Any | Not | PlayState | MidiEditorOpen => {
                 button.hide();
                 check_box.hide();
                 edit_control.hide();
@@ -495,6 +522,16 @@ impl MappingHeaderPanel {
                     item.borrow_mut().set_mapping_id(session, mapping_id);
                 }
             }
+            PlayState => {
+                let menu = menus::play_state_condition_menu(item.borrow().play_state());
+                let result = self
+                    .view
+                    .require_window()
+                    .open_popup_menu(menu, Window::cursor_pos());
+                if let Some(play_state) = result {
+                    item.borrow_mut().set_play_state(session, play_state);
+                }
+            }
             _ => {}
         }
     }
@@ -605,6 +642,9 @@ impl MappingHeaderPanel {
                     }
                     Script => self.invalidate_activation_setting_2_controls(item, initiator),
                     MappingId => self.invalidate_activation_setting_1_controls(item),
+                    // Child conditions are not displayed
+                    Conditions => {}
+                    PlayState => self.invalidate_activation_setting_1_controls(item),
                 };
             });
         });
@@ -877,6 +917,21 @@ impl Item for MappingModel {
             None,
         );
     }
+
+    fn play_state(&self) -> PlayStateCondition {
+        self.activation_condition_model().play_state()
+    }
+
+    fn set_play_state(&mut self, session: WeakUnitModel, value: PlayStateCondition) {
+        UnitModel::change_mapping_from_ui_simple(
+            session,
+            self,
+            MappingCommand::ChangeActivationCondition(ActivationConditionCommand::SetPlayState(
+                value,
+            )),
+            None,
+        );
+    }
 }
 
 impl Item for GroupModel {
@@ -1027,4 +1082,19 @@ impl Item for GroupModel {
             None,
         );
     }
+
+    fn play_state(&self) -> PlayStateCondition {
+        self.activation_condition_model().play_state()
+    }
+
+    fn set_play_state(&mut self, session: WeakUnitModel, value: PlayStateCondition) {
+        UnitModel::change_group_from_ui_simple(
+            session,
+            self,
+            GroupCommand::ChangeActivationCondition(ActivationConditionCommand::SetPlayState(
+                value,
+            )),
+            None,
+        );
+    }
 }
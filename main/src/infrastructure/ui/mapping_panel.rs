@@ -32,8 +32,9 @@ use helgobox_api::persistence::{
     MidiScriptKind, MonitoringMode, MouseButton, PlaytimeColumnAction, PlaytimeColumnDescriptor,
     PlaytimeColumnDescriptorKind, PlaytimeMatrixAction, PlaytimeRowAction, PlaytimeRowDescriptor,
     PlaytimeRowDescriptorKind, PlaytimeSlotDescriptor, PlaytimeSlotDescriptorKind,
-    PlaytimeSlotManagementAction, PlaytimeSlotTransportAction, PotFilterKind, SeekBehavior,
-    TrackToolAction, VirtualControlElementCharacter,
+    PlaytimeSlotManagementAction, PlaytimeSlotTransportAction, PotFilterKind, RecordMode,
+    SeekBehavior, SendMode, TrackToolAction, TransformationScriptKind,
+    VirtualControlElementCharacter,
 };
 use swell_ui::{
     DeviceContext, DialogUnits, Point, SharedView, SwellStringArg, View, ViewContext, WeakView,
@@ -89,7 +90,8 @@ use crate::infrastructure::ui::util::{
 };
 use crate::infrastructure::ui::{
     menus, EelControlTransformationEngine, EelFeedbackTransformationEngine, EelMidiScriptEngine,
-    ItemProp, LuaFeedbackScriptEngine, LuaMidiScriptEngine, MappingHeaderPanel, MappingRowsPanel,
+    ItemProp, LuaControlTransformationEngine, LuaFeedbackScriptEngine,
+    LuaFeedbackTransformationEngine, LuaMidiScriptEngine, MappingHeaderPanel, MappingRowsPanel,
     OscFeedbackArgumentsEngine, PlainTextEngine, RawMidiScriptEngine, ScriptEditorInput,
     ScriptEngine, SimpleScriptEditorPanel, TextualFeedbackExpressionEngine, UnitPanel,
     YamlEditorPanel,
@@ -253,6 +255,9 @@ impl MappingPanel {
                                 P::BeepOnSuccess => {
                                     view.invalidate_beep_on_success_checkbox();
                                 }
+                                P::AdditionalTargets | P::FeedbackTargetIndex => {
+                                    // No representation in GUI at the moment.
+                                }
                                 P::IsEnabled => {
                                     view.invalidate_mapping_enabled_check_box();
                                 }
@@ -363,6 +368,9 @@ impl MappingPanel {
                                             P::ButtonStaticText => {
                                                 view.invalidate_source_line_7_edit_control(initiator);
                                             }
+                                            P::Modulator => {
+                                                // No representation in GUI at the moment.
+                                            }
                                         }
                                     }
                                 }
@@ -414,6 +422,9 @@ impl MappingPanel {
                                             P::EelControlTransformation => {
                                                 view.invalidate_mode_controls_internal(initiator);
                                             }
+                                            P::ControlTransformationKind | P::FeedbackTransformationKind => {
+                                                // No representation in GUI at the moment.
+                                            }
                                             P::EelFeedbackTransformation | P::TextualFeedbackExpression => {
                                                 view.invalidate_mode_eel_feedback_transformation_edit_control(initiator);
                                             }
@@ -438,7 +449,9 @@ impl MappingPanel {
                                             P::FeedbackColor | P::FeedbackBackgroundColor => {
                                                 view.invalidate_mode_feedback_type_button();
                                             }
-                                            P::FeedbackValueTable => {
+                                            P::FeedbackValueTable
+                                            | P::Smoothing
+                                            | P::ResponseCurve => {
                                                 // No representation in GUI at the moment.
                                             }
                                             P::LegacyJumpInterval => {
@@ -513,7 +526,7 @@ impl MappingPanel {
                                                 view.invalidate_target_value_controls();
                                                 view.invalidate_mode_controls();
                                             }
-                                            P::SeekBehavior | P::TouchedTrackParameterType | P::AutomationMode | P::MonitoringMode | P::TrackArea => {
+                                            P::SeekBehavior | P::TouchedTrackParameterType | P::AutomationMode | P::MonitoringMode | P::RecordMode | P::SendMode | P::TrackArea => {
                                                 view.invalidate_target_line_3(None);
                                             }
                                             P::SoloBehavior => {
@@ -1306,7 +1319,15 @@ impl MappingPanel {
 
     fn edit_control_transformation(&self) {
         let session = self.session.clone();
-        let engine = Box::new(EelControlTransformationEngine);
+        let engine: Box<dyn ScriptEngine> = match self
+            .mapping()
+            .borrow()
+            .mode_model
+            .control_transformation_kind()
+        {
+            TransformationScriptKind::Eel => Box::new(EelControlTransformationEngine),
+            TransformationScriptKind::Lua => Box::new(LuaControlTransformationEngine::new()),
+        };
         let help_url = "https://docs.helgoboss.org/realearn/goto#control-transformation";
         let get_value = |m: &MappingModel| m.mode_model.eel_control_transformation().to_owned();
         let set_value = move |m: &mut MappingModel, eel: String| {
@@ -1335,8 +1356,17 @@ impl MappingPanel {
 
     fn edit_feedback_transformation(&self) {
         let session = self.session.clone();
+        let engine: Box<dyn ScriptEngine> = match self
+            .mapping()
+            .borrow()
+            .mode_model
+            .feedback_transformation_kind()
+        {
+            TransformationScriptKind::Eel => Box::new(EelFeedbackTransformationEngine),
+            TransformationScriptKind::Lua => Box::new(LuaFeedbackTransformationEngine::new()),
+        };
         self.edit_script_in_simple_editor(
-            Box::new(EelFeedbackTransformationEngine),
+            engine,
             "https://docs.helgoboss.org/realearn/goto#numeric-feedback-type",
             |m| m.mode_model.eel_feedback_transformation().to_owned(),
             move |m, eel| {
@@ -3517,6 +3547,13 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetMonitoringMode(v),
                     ));
                 }
+                ReaperTargetType::TrackRecordMode => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid record mode");
+                    self.change_mapping(MappingCommand::ChangeTarget(
+                        TargetCommand::SetRecordMode(v),
+                    ));
+                }
                 ReaperTargetType::TrackTouchState => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid touched track parameter type");
@@ -3531,6 +3568,13 @@ impl<'a> MutableMappingPanel<'a> {
                         TargetCommand::SetTouchedRouteParameterType(v),
                     ));
                 }
+                ReaperTargetType::RouteSendMode => {
+                    let i = combo.selected_combo_box_item_index();
+                    let v = i.try_into().expect("invalid send mode");
+                    self.change_mapping(MappingCommand::ChangeTarget(TargetCommand::SetSendMode(
+                        v,
+                    )));
+                }
                 _ if self.mapping.target_model.supports_axis() => {
                     let i = combo.selected_combo_box_item_index();
                     let v = i.try_into().expect("invalid axis type");
@@ -3919,7 +3963,7 @@ impl<'a> MutableMappingPanel<'a> {
                             Some(edit_control_id),
                         );
                     }
-                    TrackRouteSelectorType::ByName => {
+                    TrackRouteSelectorType::ByName | TrackRouteSelectorType::ByTag => {
                         let name = control.text().unwrap_or_default();
                         self.change_mapping_with_initiator(
                             MappingCommand::ChangeTarget(TargetCommand::SetRouteName(name)),
@@ -5503,7 +5547,9 @@ impl<'a> ImmutableMappingPanel<'a> {
                         TrackRouteSelectorType::Dynamic => {
                             Some(self.target.route_expression().to_owned())
                         }
-                        TrackRouteSelectorType::ByName => Some(self.target.route_name().to_owned()),
+                        TrackRouteSelectorType::ByName | TrackRouteSelectorType::ByTag => {
+                            Some(self.target.route_name().to_owned())
+                        }
                         TrackRouteSelectorType::ByIndex => {
                             let index = self.target.route_index();
                             Some((index + 1).to_string())
@@ -5611,6 +5657,7 @@ impl<'a> ImmutableMappingPanel<'a> {
                 ReaperTargetType::TrackTouchState => Some("Type"),
                 ReaperTargetType::SendOsc => Some("Address"),
                 ReaperTargetType::TrackMonitoringMode => Some("Mode"),
+                ReaperTargetType::TrackRecordMode => Some("Mode"),
                 ReaperTargetType::LoadMappingSnapshot => Some("Default"),
                 ReaperTargetType::ModifyMapping => Some("Unit"),
                 ReaperTargetType::SendMidi
@@ -5962,11 +6009,21 @@ impl<'a> ImmutableMappingPanel<'a> {
                         self.target.touched_route_parameter_type().into(),
                     );
                 }
+                ReaperTargetType::RouteSendMode => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(SendMode::iter());
+                    combo.select_combo_box_item_by_index(self.target.send_mode().into());
+                }
                 ReaperTargetType::TrackMonitoringMode => {
                     combo.show();
                     combo.fill_combo_box_indexed(MonitoringMode::iter());
                     combo.select_combo_box_item_by_index(self.target.monitoring_mode().into());
                 }
+                ReaperTargetType::TrackRecordMode => {
+                    combo.show();
+                    combo.fill_combo_box_indexed(RecordMode::iter());
+                    combo.select_combo_box_item_by_index(self.target.record_mode().into());
+                }
                 _ if self.target.supports_automation_mode() => {
                     combo.show();
                     combo.fill_combo_box_indexed(RealearnAutomationMode::iter());
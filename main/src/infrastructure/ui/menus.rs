@@ -2,8 +2,8 @@ use crate::application::{UnitModel, WeakUnitModel};
 use crate::domain::{
     compartment_param_index_iter, probe_stream_deck_devices, CompartmentKind,
     CompartmentParamIndex, CompartmentParams, ControlInput, FeedbackOutput, MappingId,
-    MidiControlInput, MidiDestination, OscDeviceId, ProbedStreamDeckDevice, ReaperTargetType,
-    StreamDeckDeviceId, TargetSection,
+    MidiControlInput, MidiDestination, OscDeviceId, PlayStateCondition, ProbedStreamDeckDevice,
+    ReaperTargetType, StreamDeckDeviceId, TargetSection,
 };
 use crate::infrastructure::data::{CommonPresetInfo, OscDevice};
 use crate::infrastructure::plugin::{ActionSection, BackboneShell, ACTION_DEFS};
@@ -419,6 +419,20 @@ pub fn virtual_control_element_character_menu(
     anonymous_menu(entries.collect())
 }
 
+pub fn play_state_condition_menu(current_value: PlayStateCondition) -> Menu<PlayStateCondition> {
+    let entries = PlayStateCondition::iter().map(|s| {
+        item_with_opts(
+            s.to_string(),
+            ItemOpts {
+                enabled: true,
+                checked: s == current_value,
+            },
+            s,
+        )
+    });
+    anonymous_menu(entries.collect())
+}
+
 pub fn menu_containing_realearn_params(
     session: &WeakUnitModel,
     compartment: CompartmentKind,
@@ -1,5 +1,6 @@
 use crate::domain::{
-    EelMidiSourceScript, EelTransformation, LuaFeedbackScript, LuaMidiSourceScript, SafeLua, Script,
+    EelMidiSourceScript, EelTransformation, LuaFeedbackScript, LuaMidiSourceScript,
+    LuaTransformation, SafeLua, Script,
 };
 use crate::infrastructure::ui::bindings::root;
 use crate::infrastructure::ui::bindings::root::ID_YAML_HELP_BUTTON;
@@ -184,6 +185,54 @@ impl ScriptEngine for EelFeedbackTransformationEngine {
     }
 }
 
+pub struct LuaControlTransformationEngine {
+    lua: SafeLua,
+}
+
+impl LuaControlTransformationEngine {
+    pub fn new() -> Self {
+        Self {
+            lua: SafeLua::new().unwrap(),
+        }
+    }
+}
+
+impl ScriptEngine for LuaControlTransformationEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let transformation = LuaTransformation::compile_for_control(&self.lua, code)?;
+        transformation.evaluate(Default::default())?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
+pub struct LuaFeedbackTransformationEngine {
+    lua: SafeLua,
+}
+
+impl LuaFeedbackTransformationEngine {
+    pub fn new() -> Self {
+        Self {
+            lua: SafeLua::new().unwrap(),
+        }
+    }
+}
+
+impl ScriptEngine for LuaFeedbackTransformationEngine {
+    fn compile(&self, code: &str) -> Result<Box<dyn Script>, Box<dyn Error>> {
+        let transformation = LuaTransformation::compile_for_feedback(&self.lua, code)?;
+        transformation.evaluate(Default::default())?;
+        Ok(Box::new(()))
+    }
+
+    fn file_extension(&self) -> &'static str {
+        ".lua"
+    }
+}
+
 pub struct TextualFeedbackExpressionEngine;
 
 impl ScriptEngine for TextualFeedbackExpressionEngine {
@@ -28,13 +28,14 @@ use pot::preview_recorder::{
 };
 use pot::providers::projects::{ProjectDatabase, ProjectDbConfig};
 use pot::{
-    create_plugin_factory_preset, find_preview_file, pot_db, spawn_in_pot_worker, ChangeHint,
-    CurrentPreset, Debounce, DestinationTrackDescriptor, FiledBasedPotPresetKind, Filters,
-    LoadAudioSampleBehavior, LoadPresetError, LoadPresetOptions, LoadPresetWindowBehavior,
-    MacroParam, MainThreadDispatcher, MainThreadSpawner, OptFilter, PersistentDatabaseId,
-    PotFavorites, PotFilterExcludes, PotFxParamId, PotPreset, PotPresetKind, PotWorkerDispatcher,
-    PotWorkerSpawner, PresetWithId, RuntimePotUnit, SearchField, SharedRuntimePotUnit,
-    WorkerDispatcher,
+    create_duplicate_report, create_plugin_factory_preset, find_preview_file, pot_db,
+    spawn_in_pot_worker, ChangeHint, CurrentPreset, Debounce, DestinationTrackDescriptor,
+    DuplicateReport, FiledBasedPotPresetKind, Filters, LoadAudioSampleBehavior, LoadPresetError,
+    LoadPresetOptions, LoadPresetWindowBehavior, MacroParam, MainThreadDispatcher,
+    MainThreadSpawner, OptFilter, PersistentDatabaseId, PotFavorites, PotFilterExcludes,
+    PotFxParamId, PotPreset, PotPresetKind, PotWorkerDispatcher, PotWorkerSpawner,
+    PresetUserMetadata, PresetWithId, RuntimePotUnit, SearchField, SharedRuntimePotUnit,
+    WorkerDispatcher, MAX_PRESET_RATING,
 };
 use pot::{FilterItemId, PresetId};
 use reaper_high::{Fx, FxParameter, Reaper, SliderVolume, Track};
@@ -128,6 +129,11 @@ enum Dialog {
         folder: String,
         name: String,
     },
+    EditUserMetadata {
+        preset_id: PresetId,
+        tags: String,
+        notes: String,
+    },
     PresetCrawlerIntro,
     PresetCrawlerBasics,
     PresetCrawlerMouse {
@@ -172,6 +178,28 @@ enum Dialog {
         page: PreviewRecorderDonePage,
         output_config: PreviewOutputConfig,
     },
+    DuplicateFinderIntro,
+    DuplicateFinderSearching,
+    DuplicateFinderReport {
+        report: DuplicateReport,
+        rows: Vec<DuplicateReportRow>,
+    },
+    CreatePresetCollection {
+        name: String,
+    },
+    ImportPresetCollection {
+        file: String,
+    },
+    ImportingPresetCollection,
+}
+
+/// Flattened entry of a duplicate report, ready to be displayed in a table.
+#[derive(Debug)]
+struct DuplicateReportRow {
+    cluster: String,
+    name: String,
+    database: String,
+    location: Option<String>,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -197,6 +225,14 @@ impl Dialog {
         }
     }
 
+    fn edit_user_metadata(preset_id: PresetId, metadata: &PresetUserMetadata) -> Self {
+        Self::EditUserMetadata {
+            preset_id,
+            tags: Vec::from_iter(metadata.tags.iter().map(String::as_str)).join(", "),
+            notes: metadata.notes.clone(),
+        }
+    }
+
     fn add_project_database(folder: String) -> Self {
         let suggested_name = Path::new(&folder)
             .file_name()
@@ -280,6 +316,37 @@ impl Dialog {
         }
     }
 
+    fn duplicate_finder_report(report: DuplicateReport) -> Self {
+        let rows = report
+            .clusters
+            .iter()
+            .enumerate()
+            .flat_map(|(i, cluster)| {
+                cluster.entries.iter().map(move |entry| DuplicateReportRow {
+                    cluster: (i + 1).to_string(),
+                    name: entry.preset.preset.common.name.clone(),
+                    database: entry.database_name.clone(),
+                    location: entry.location(),
+                })
+            })
+            // The item table shows the last item first
+            .rev()
+            .collect();
+        Self::DuplicateFinderReport { report, rows }
+    }
+
+    fn create_preset_collection() -> Self {
+        Self::CreatePresetCollection {
+            name: String::new(),
+        }
+    }
+
+    fn import_preset_collection() -> Self {
+        Self::ImportPresetCollection {
+            file: format!("{}/", preset_collection_dir()),
+        }
+    }
+
     fn preview_recorder_recording(state: SharedPreviewRecorderState) -> Self {
         Self::PreviewRecorderRecording { state }
     }
@@ -369,6 +436,8 @@ fn run_warning_ui(ctx: &Context, state: &mut State) {
 
 const PRESET_CRAWLER_TITLE: &str = "Preset Crawler";
 const PREVIEW_RECORDER_TITLE: &str = "Preview Recorder";
+const DUPLICATE_FINDER_TITLE: &str = "Duplicate Finder";
+const PRESET_COLLECTIONS_TITLE: &str = "Preset collections";
 const PRESET_CRAWLER_COUNTDOWN_DURATION: Duration = Duration::from_secs(10);
 
 fn run_main_ui<I: PotBrowserIntegration>(
@@ -386,6 +455,8 @@ fn run_main_ui<I: PotBrowserIntegration>(
     );
     // Query commonly used stuff
     let background_task_elapsed = pot_unit.background_task_elapsed();
+    // Pick up changes in watched directories
+    pot_unit.rebuild_collections_if_pot_db_changed(state.main_state.pot_unit.clone());
     // Integrate cache worker results into local cache
     state
         .main_state
@@ -559,7 +630,7 @@ fn run_main_ui<I: PotBrowserIntegration>(
                             ui,
                             pot_unit,
                             TOOLBAR_HEIGHT_WITH_MARGIN,
-                            80.0,
+                            160.0,
                             // Left side: Toolbar
                             |ui, pot_unit| {
                                 // Actions
@@ -573,6 +644,35 @@ fn run_main_ui<I: PotBrowserIntegration>(
                                             Some(Dialog::PreviewRecorderIntro);
                                         ui.close_menu();
                                     }
+                                    if ui.button(DUPLICATE_FINDER_TITLE).clicked() {
+                                        state.main_state.dialog =
+                                            Some(Dialog::DuplicateFinderIntro);
+                                        ui.close_menu();
+                                    }
+                                    ui.menu_button(PRESET_COLLECTIONS_TITLE, |ui| {
+                                        add_preset_collections_menu(
+                                            &mut state.main_state.dialog,
+                                            ui,
+                                        );
+                                    });
+                                    let revert_button = ui
+                                        .add_enabled(
+                                            pot_unit.can_revert_last_preset_load(),
+                                            Button::new("Revert last preset load"),
+                                        )
+                                        .on_hover_text(
+                                            "Restores the FX chain into which the last preset \
+                                            was loaded to its previous state",
+                                        );
+                                    if revert_button.clicked() {
+                                        if let Err(e) = pot_unit.revert_last_preset_load() {
+                                            state.main_state.dialog = Some(Dialog::general_error(
+                                                "Couldn't revert preset load",
+                                                e.to_string(),
+                                            ));
+                                        }
+                                        ui.close_menu();
+                                    }
                                 });
                                 // Options
                                 let input = RightOptionsDropdownInput {
@@ -593,8 +693,18 @@ fn run_main_ui<I: PotBrowserIntegration>(
                                 .font(TextStyle::Monospace);
                                 ui.add_enabled(false, text_edit).on_disabled_hover_text(
                                     "Type anywhere to search!\nUse backspace to clear \
-                        the last character\nand (Ctrl+Alt)/(Cmd)+Backspace to clear all.",
+                        the last character\nand (Ctrl+Alt)/(Cmd)+Backspace to clear all.\n\n\
+                        Supports queries such as:\nbass NOT sub product:Serum\n\
+                        \"warm pad\" OR (tag:favorite size>1MB date>=2023-06)",
                                 );
+                                // Search query error
+                                if let Some(error) = pot_unit.search_query_error() {
+                                    ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                                        .on_hover_text(format!(
+                                            "Invalid search query, falling back to plain \
+                                            text search:\n{error}"
+                                        ));
+                                }
                                 // Preset count
                                 let preset_count = pot_unit.preset_count();
                                 ui.label(format!("➡ {preset_count} presets"));
@@ -607,6 +717,13 @@ fn run_main_ui<I: PotBrowserIntegration>(
                                     PotFilterKind::HasPreview,
                                     ui,
                                 );
+                                ui.separator();
+                                add_filter_view_content_as_icons(
+                                    &state.main_state.pot_unit,
+                                    pot_unit,
+                                    PotFilterKind::IsDuplicate,
+                                    ui,
+                                );
                             },
                         );
                     });
@@ -697,7 +814,7 @@ fn run_main_ui<I: PotBrowserIntegration>(
                             );
                         })
                         .body(|ui| {
-                            if let Some((_, preset_data)) = current_preset_id_and_data {
+                            if let Some((preset_id, preset_data)) = current_preset_id_and_data {
                                 let metadata = &preset_data.preset.common.metadata;
                                 ui.horizontal(|ui| {
                                     ui.strong("Vendor:");
@@ -731,6 +848,58 @@ fn run_main_ui<I: PotBrowserIntegration>(
                                         metadata.comment.as_ref().map(|c| c.replace("\\n", ""));
                                     ui.label(optional_string(text.as_deref()));
                                 });
+                                // User metadata
+                                let user_metadata = pot_db()
+                                    .user_metadata(&preset_data.preset.common.persistent_id);
+                                ui.horizontal(|ui| {
+                                    ui.strong("Rating:");
+                                    let current_rating = user_metadata.rating.unwrap_or(0);
+                                    for rating in 1..=MAX_PRESET_RATING {
+                                        let icon = if rating <= current_rating {
+                                            "★"
+                                        } else {
+                                            "☆"
+                                        };
+                                        if ui.small_button(icon).clicked() {
+                                            // Clicking the current rating again removes it
+                                            let new_rating =
+                                                if rating == current_rating { 0 } else { rating };
+                                            let result = pot_unit.update_user_metadata(
+                                                preset_id,
+                                                state.main_state.pot_unit.clone(),
+                                                |m| m.set_rating(new_rating),
+                                            );
+                                            if let Err(e) = result {
+                                                show_error_toast(e.to_string(), &mut toasts);
+                                            }
+                                        }
+                                    }
+                                    ui.strong("Tags:");
+                                    let tags = Vec::from_iter(
+                                        user_metadata.tags.iter().map(String::as_str),
+                                    )
+                                    .join(", ");
+                                    ui.label(optional_string(
+                                        (!tags.is_empty()).then_some(tags.as_str()),
+                                    ));
+                                    if ui
+                                        .small_button("Edit...")
+                                        .on_hover_text("Edit tags and notes")
+                                        .clicked()
+                                    {
+                                        state.main_state.dialog = Some(Dialog::edit_user_metadata(
+                                            preset_id,
+                                            &user_metadata,
+                                        ));
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.strong("Notes:");
+                                    let notes = &user_metadata.notes;
+                                    ui.label(optional_string(
+                                        (!notes.is_empty()).then_some(notes.as_str()),
+                                    ));
+                                });
                             }
                         });
                     // Destination info
@@ -860,6 +1029,53 @@ fn process_dialogs<I: PotBrowserIntegration>(input: ProcessDialogsInput<I>, ctx:
                 },
             );
         }
+        Dialog::EditUserMetadata {
+            preset_id,
+            tags,
+            notes,
+        } => {
+            show_dialog(
+                ctx,
+                "Edit tags and notes",
+                &mut (input.change_dialog, tags, notes),
+                |ui, (_, tags, notes)| {
+                    ui.horizontal(|ui| {
+                        ui.strong("Tags:");
+                        ui.text_edit_singleline(*tags)
+                            .on_hover_text("Comma-separated list of tags");
+                    });
+                    ui.strong("Notes:");
+                    ui.text_edit_multiline(*notes);
+                },
+                |ui, (change_dialog, tags, notes)| {
+                    if ui.button("Cancel").clicked() {
+                        **change_dialog = Some(None);
+                    };
+                    if ui.button("Save").clicked() {
+                        let result = input.pot_unit.update_user_metadata(
+                            *preset_id,
+                            input.shared_pot_unit.clone(),
+                            |m| {
+                                m.tags.clear();
+                                for tag in tags.split(',') {
+                                    m.add_tag(tag);
+                                }
+                                m.notes = notes.trim().to_string();
+                            },
+                        );
+                        match result {
+                            Ok(_) => {
+                                **change_dialog = Some(None);
+                            }
+                            Err(e) => {
+                                let error_dialog = Dialog::general_error(e.to_string(), "");
+                                **change_dialog = Some(Some(error_dialog));
+                            }
+                        }
+                    }
+                },
+            );
+        }
         Dialog::PresetCrawlerIntro => show_dialog(
             ctx,
             PRESET_CRAWLER_TITLE,
@@ -1374,9 +1590,241 @@ fn process_dialogs<I: PotBrowserIntegration>(input: ProcessDialogsInput<I>, ctx:
                 },
             )
         }
+        Dialog::DuplicateFinderIntro => show_dialog(
+            ctx,
+            DUPLICATE_FINDER_TITLE,
+            &mut (input.change_dialog, input.pot_worker_dispatcher),
+            |ui, _| {
+                add_markdown(ui, DUPLICATE_FINDER_INTRO_TEXT, DIALOG_CONTENT_MAX_HEIGHT);
+            },
+            |ui, (change_dialog, pot_worker_dispatcher)| {
+                if ui.button("Cancel").clicked() {
+                    **change_dialog = Some(None);
+                };
+                if !ui.button("Find duplicates").clicked() {
+                    return;
+                }
+                let build_input = input.pot_unit.create_build_input();
+                pot_worker_dispatcher.do_in_background_and_then(
+                    async move { create_duplicate_report(build_input) },
+                    |context, report| {
+                        if matches!(context.dialog, Some(Dialog::DuplicateFinderSearching)) {
+                            context.dialog = Some(Dialog::duplicate_finder_report(report));
+                        }
+                    },
+                );
+                **change_dialog = Some(Some(Dialog::DuplicateFinderSearching));
+            },
+        ),
+        Dialog::DuplicateFinderSearching => show_dialog(
+            ctx,
+            DUPLICATE_FINDER_TITLE,
+            input.change_dialog,
+            |ui, _| {
+                ui.label("Searching for duplicates (this may take a while)...");
+                ui.spinner();
+            },
+            |ui, change_dialog| {
+                if ui.button("Cancel").clicked() {
+                    *change_dialog = Some(None);
+                };
+            },
+        ),
+        Dialog::DuplicateFinderReport { report, rows } => show_dialog(
+            ctx,
+            DUPLICATE_FINDER_TITLE,
+            input.change_dialog,
+            |ui, _| {
+                if report.clusters.is_empty() {
+                    ui.strong("No duplicates found!");
+                    return;
+                }
+                ui.label(format!(
+                    "Found {} clusters of duplicates, containing {} redundant presets:",
+                    report.clusters.len(),
+                    report.redundant_preset_count()
+                ));
+                add_item_table(ui, rows, DIALOG_CONTENT_MAX_HEIGHT);
+            },
+            |ui, change_dialog| {
+                if ui.button("Close").clicked() {
+                    *change_dialog = Some(None);
+                };
+                if report.clusters.is_empty() || !ui.button("Save report").clicked() {
+                    return;
+                }
+                match save_duplicate_report(report) {
+                    Ok(path) => reveal_path(path.as_std_path()),
+                    Err(e) => {
+                        *change_dialog = Some(Some(Dialog::general_error(
+                            DUPLICATE_FINDER_TITLE,
+                            e.to_string(),
+                        )));
+                    }
+                }
+            },
+        ),
+        Dialog::CreatePresetCollection { name } => show_dialog(
+            ctx,
+            "New preset collection",
+            &mut (input.change_dialog, name),
+            |ui, (_, name)| {
+                ui.horizontal(|ui| {
+                    ui.strong("Name:");
+                    ui.text_edit_singleline(*name);
+                });
+            },
+            |ui, (change_dialog, name)| {
+                if ui.button("Cancel").clicked() {
+                    **change_dialog = Some(None);
+                };
+                if ui.button("Create").clicked() {
+                    match pot_db().create_preset_collection(name) {
+                        Ok(_) => {
+                            **change_dialog = Some(None);
+                        }
+                        Err(e) => {
+                            let error_dialog = Dialog::general_error(e.to_string(), "");
+                            **change_dialog = Some(Some(error_dialog));
+                        }
+                    }
+                }
+            },
+        ),
+        Dialog::ImportPresetCollection { file } => show_dialog(
+            ctx,
+            "Import preset collection",
+            &mut (input.change_dialog, input.pot_worker_dispatcher, file),
+            |ui, (_, _, file)| {
+                ui.label(
+                    "Presets which can't be found by their IDs (e.g. because the collection has \
+                    been exported on another machine) are looked up by name and product.",
+                );
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.strong("File:");
+                    ui.text_edit_singleline(*file);
+                });
+            },
+            |ui, (change_dialog, pot_worker_dispatcher, file)| {
+                if ui.button("Cancel").clicked() {
+                    **change_dialog = Some(None);
+                };
+                if !ui.button("Import").clicked() {
+                    return;
+                }
+                let file_path = Utf8PathBuf::from(file.trim());
+                pot_worker_dispatcher.do_in_background_and_then(
+                    async move { pot_db().import_preset_collection(&file_path) },
+                    |context, result| {
+                        if !matches!(context.dialog, Some(Dialog::ImportingPresetCollection)) {
+                            return;
+                        }
+                        context.dialog = match result {
+                            Ok(_) => None,
+                            Err(e) => Some(Dialog::general_error(
+                                "Couldn't import preset collection",
+                                e.to_string(),
+                            )),
+                        };
+                    },
+                );
+                **change_dialog = Some(Some(Dialog::ImportingPresetCollection));
+            },
+        ),
+        Dialog::ImportingPresetCollection => show_dialog(
+            ctx,
+            "Import preset collection",
+            input.change_dialog,
+            |ui, _| {
+                ui.label("Importing preset collection (this may take a while)...");
+                ui.spinner();
+            },
+            |ui, change_dialog| {
+                if ui.button("Close").clicked() {
+                    *change_dialog = Some(None);
+                };
+            },
+        ),
+    }
+}
+
+/// Saves the given report as text file and returns its path.
+fn save_duplicate_report(report: &DuplicateReport) -> anyhow::Result<Utf8PathBuf> {
+    let file_name = Local::now()
+        .format("Duplicates %Y-%m-%d %H-%M-%S.txt")
+        .to_string();
+    let path = Reaper::get()
+        .resource_path()
+        .join("Helgoboss/Pot/Reports")
+        .join(file_name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, report.to_text())?;
+    Ok(path)
+}
+
+/// Directory into which preset collections are exported by default.
+fn preset_collection_dir() -> Utf8PathBuf {
+    os_document_or_reaper_resource_dir().join("Helgobox/SoundPot/Collections")
+}
+
+fn add_preset_collections_menu(dialog: &mut Option<Dialog>, ui: &mut Ui) {
+    if ui.button("New collection...").clicked() {
+        *dialog = Some(Dialog::create_preset_collection());
+        ui.close_menu();
+    }
+    if ui.button("Import collection...").clicked() {
+        *dialog = Some(Dialog::import_preset_collection());
+        ui.close_menu();
+    }
+    let collection_names = pot_db().preset_collection_names();
+    if !collection_names.is_empty() {
+        ui.separator();
+    }
+    for name in collection_names {
+        ui.menu_button(&name, |ui| {
+            let export_button = ui
+                .button("Export")
+                .on_hover_text("Saves the collection as file which can be imported elsewhere");
+            if export_button.clicked() {
+                match pot_db().export_preset_collection(&name, &preset_collection_dir()) {
+                    Ok(path) => reveal_path(path.as_std_path()),
+                    Err(e) => {
+                        *dialog = Some(Dialog::general_error(
+                            "Couldn't export preset collection",
+                            e.to_string(),
+                        ));
+                    }
+                }
+                ui.close_menu();
+            }
+            if ui.button("Delete").clicked() {
+                if let Err(e) = pot_db().delete_preset_collection(&name) {
+                    *dialog = Some(Dialog::general_error(
+                        "Couldn't delete preset collection",
+                        e.to_string(),
+                    ));
+                }
+                ui.close_menu();
+            }
+        });
     }
 }
 
+/// Returns the name of the preset collection which is currently selected as filter, if any.
+fn selected_preset_collection(pot_unit: &RuntimePotUnit) -> Option<String> {
+    let filter_item_id = pot_unit.get_filter(PotFilterKind::Collection)?;
+    pot_unit
+        .filter_item_collections
+        .get(PotFilterKind::Collection)
+        .iter()
+        .find(|item| item.id == filter_item_id)?
+        .name
+        .clone()
+}
+
 fn add_crawl_presets_stopped_dialog_contents(
     stop_reason: PresetCrawlerStopReason,
     cs: &PresetCrawlingState,
@@ -1518,6 +1966,7 @@ struct PresetTableInput<'a> {
 fn add_preset_table(mut input: PresetTableInput, ui: &mut Ui, preset_cache: &mut PresetCache) {
     let text_height = get_text_height(ui);
     let preset_count = input.pot_unit.preset_count();
+    let selected_collection = selected_preset_collection(input.pot_unit);
     let mut table = TableBuilder::new(ui)
         .striped(true)
         .resizable(true)
@@ -1625,6 +2074,18 @@ fn add_preset_table(mut input: PresetTableInput, ui: &mut Ui, preset_cache: &mut
                                     create_product_plugin_menu(&mut input, data, ui);
                                 });
                             });
+                            // Preset collections
+                            ui.menu_button("Add to collection", |ui| {
+                                create_add_to_collection_menu(&data.preset, input.toasts, ui);
+                            });
+                            if let Some(collection_name) = &selected_collection {
+                                create_collection_entry_menu_items(
+                                    collection_name,
+                                    &data.preset,
+                                    input.toasts,
+                                    ui,
+                                );
+                            }
                             // Reveal preset in file manager
                             #[cfg(any(
                             all(target_os = "windows", target_arch = "x86_64"),
@@ -1727,6 +2188,74 @@ impl DisplayItem for PreviewRecorderFailure {
     }
 }
 
+fn create_add_to_collection_menu(preset: &PotPreset, toasts: &mut Toasts, ui: &mut Ui) {
+    let collection_names = pot_db().preset_collection_names();
+    if collection_names.is_empty() {
+        ui.label("No collections yet (create one via the \"Tools\" menu)");
+    }
+    for name in collection_names {
+        if ui.button(&name).clicked() {
+            match pot_db().add_preset_to_collection(&name, preset) {
+                Ok(true) => {}
+                Ok(false) => show_info_toast("Preset is in this collection already", toasts),
+                Err(e) => show_error_toast(e.to_string(), toasts),
+            }
+            ui.close_menu();
+        }
+    }
+}
+
+fn create_collection_entry_menu_items(
+    collection_name: &str,
+    preset: &PotPreset,
+    toasts: &mut Toasts,
+    ui: &mut Ui,
+) {
+    let mut result = None;
+    if ui.button("Move up in collection").clicked() {
+        result = Some(pot_db().move_preset_in_collection(collection_name, preset, -1));
+    }
+    if ui.button("Move down in collection").clicked() {
+        result = Some(pot_db().move_preset_in_collection(collection_name, preset, 1));
+    }
+    if ui.button("Remove from collection").clicked() {
+        result = Some(pot_db().remove_preset_from_collection(collection_name, preset));
+    }
+    let Some(result) = result else {
+        return;
+    };
+    if let Err(e) = result {
+        show_error_toast(e.to_string(), toasts);
+    }
+    ui.close_menu();
+}
+
+impl DisplayItem for DuplicateReportRow {
+    fn prop_count() -> u32 {
+        4
+    }
+
+    fn prop_label(prop_index: u32) -> &'static str {
+        match prop_index {
+            0 => "Cluster",
+            1 => "Name",
+            2 => "Database",
+            3 => "Location",
+            _ => "",
+        }
+    }
+
+    fn prop_value(&self, prop_index: u32) -> Option<Cow<str>> {
+        match prop_index {
+            0 => Some(self.cluster.as_str().into()),
+            1 => Some(shorten_preset_name(&self.name)),
+            2 => Some(self.database.as_str().into()),
+            3 => self.location.as_deref().map(|s| s.into()),
+            _ => None,
+        }
+    }
+}
+
 impl DisplayItem for PresetWithId {
     fn prop_count() -> u32 {
         3
@@ -1944,6 +2473,33 @@ fn add_stats_panel(
         )
         .on_hover_text("Checking previews");
         ui.label(" + ");
+        ui.label(
+            pot_unit
+                .stats
+                .user_metadata_filter_duration
+                .as_millis()
+                .to_string(),
+        )
+        .on_hover_text("Checking tags and ratings");
+        ui.label(" + ");
+        ui.label(
+            pot_unit
+                .stats
+                .search_query_filter_duration
+                .as_millis()
+                .to_string(),
+        )
+        .on_hover_text("Evaluating search query");
+        ui.label(" + ");
+        ui.label(
+            pot_unit
+                .stats
+                .duplicate_filter_duration
+                .as_millis()
+                .to_string(),
+        )
+        .on_hover_text("Detecting duplicates");
+        ui.label(" + ");
         ui.label(pot_unit.stats.sort_duration.as_millis().to_string())
             .on_hover_text("Sorting filters and presets");
         ui.label(" + ");
@@ -2005,13 +2561,58 @@ fn add_right_options_dropdown(input: RightOptionsDropdownInput, ui: &mut Ui) {
             "Allows more accurate search by enabling wildcards: Use * to match any \
         string and ? to match any letter!",
         );
-        if input.pot_unit.runtime_state.search_options.use_wildcards != old_wildcard_setting {
+        // Fuzzy search
+        let old_fuzzy_setting = input.pot_unit.runtime_state.search_options.fuzzy;
+        ui.checkbox(
+            &mut input.pot_unit.runtime_state.search_options.fuzzy,
+            "Typo tolerance",
+        )
+        .on_hover_text(
+            "Also finds presets if the search text contains small typos. Makes searching \
+        large databases slower. Not applied when using wildcards.",
+        );
+        // Relevance
+        let old_relevance_setting = input
+            .pot_unit
+            .runtime_state
+            .search_options
+            .sort_by_relevance;
+        ui.checkbox(
+            &mut input
+                .pot_unit
+                .runtime_state
+                .search_options
+                .sort_by_relevance,
+            "Sort by relevance",
+        )
+        .on_hover_text(
+            "While searching, shows the best matches first (e.g. names starting with the \
+        search text) instead of sorting alphabetically",
+        );
+        let search_options = &input.pot_unit.runtime_state.search_options;
+        if search_options.use_wildcards != old_wildcard_setting
+            || search_options.fuzzy != old_fuzzy_setting
+            || search_options.sort_by_relevance != old_relevance_setting
+        {
             input.pot_unit.rebuild_collections(
                 input.shared_pot_unit.clone(),
                 ChangeHint::SearchExpression,
                 Debounce::No,
             );
         }
+        // Watching
+        let mut watching_enabled = pot_db().is_watching_enabled();
+        if ui
+            .checkbox(&mut watching_enabled, "Watch folders for changes")
+            .on_hover_text(
+                "Gets notified about changes in the FX chain, track template, FX preset and \
+            project folders and updates the presets automatically. Stays enabled after \
+            restarting REAPER.",
+            )
+            .changed()
+        {
+            pot_db().set_watching_enabled(watching_enabled);
+        }
         // Stats
         ui.checkbox(input.show_stats, "Display stats")
             .on_hover_text("Show query statistics");
@@ -2116,6 +2717,7 @@ fn add_filter_panels<I: PotBrowserIntegration>(
     // Add dependent filter views
     ui.separator();
     let show_projects = pot_unit.supports_filter_kind(PotFilterKind::Project);
+    let show_collections = pot_unit.supports_filter_kind(PotFilterKind::Collection);
     let show_banks = pot_unit.supports_filter_kind(PotFilterKind::Bank);
     let show_sub_banks = show_banks
         && pot_unit.supports_filter_kind(PotFilterKind::SubBank)
@@ -2133,10 +2735,19 @@ fn add_filter_panels<I: PotBrowserIntegration>(
                 .is_set_to_concrete_value(PotFilterKind::Category)
                 || pot_unit.get_filter(PotFilterKind::SubCategory).is_some()));
     let show_modes = pot_unit.supports_filter_kind(PotFilterKind::Mode);
-    let mut remaining_kind_count = 6;
+    let show_tempos = pot_unit.supports_filter_kind(PotFilterKind::Tempo);
+    let show_keys = pot_unit.supports_filter_kind(PotFilterKind::Key);
+    // Only show tags if the user has tagged at least one preset (there's always the <None> item)
+    let show_tags = pot_unit.supports_filter_kind(PotFilterKind::Tag)
+        && pot_unit.count_filter_items(PotFilterKind::Tag) > 1;
+    let show_ratings = pot_unit.supports_filter_kind(PotFilterKind::Rating);
+    let mut remaining_kind_count = 11;
     if !show_projects {
         remaining_kind_count -= 1;
     }
+    if !show_collections {
+        remaining_kind_count -= 1;
+    }
     if !show_banks {
         remaining_kind_count -= 1;
     }
@@ -2152,6 +2763,18 @@ fn add_filter_panels<I: PotBrowserIntegration>(
     if !show_modes {
         remaining_kind_count -= 1;
     }
+    if !show_tempos {
+        remaining_kind_count -= 1;
+    }
+    if !show_keys {
+        remaining_kind_count -= 1;
+    }
+    if !show_tags {
+        remaining_kind_count -= 1;
+    }
+    if !show_ratings {
+        remaining_kind_count -= 1;
+    }
     let mut added_one_view_already = false;
     let mut needs_separator = || {
         if added_one_view_already {
@@ -2176,6 +2799,19 @@ fn add_filter_panels<I: PotBrowserIntegration>(
                 integration,
             );
         }
+        if show_collections {
+            add_filter_view(
+                ui,
+                filter_view_height,
+                shared_unit,
+                pot_unit,
+                PotFilterKind::Collection,
+                needs_separator(),
+                false,
+                last_filters.get(PotFilterKind::Collection),
+                integration,
+            );
+        }
         if show_banks {
             add_filter_view(
                 ui,
@@ -2241,6 +2877,58 @@ fn add_filter_panels<I: PotBrowserIntegration>(
                 integration,
             );
         }
+        if show_tempos {
+            add_filter_view(
+                ui,
+                filter_view_height,
+                shared_unit,
+                pot_unit,
+                PotFilterKind::Tempo,
+                needs_separator(),
+                false,
+                last_filters.get(PotFilterKind::Tempo),
+                integration,
+            );
+        }
+        if show_keys {
+            add_filter_view(
+                ui,
+                filter_view_height,
+                shared_unit,
+                pot_unit,
+                PotFilterKind::Key,
+                needs_separator(),
+                false,
+                last_filters.get(PotFilterKind::Key),
+                integration,
+            );
+        }
+        if show_tags {
+            add_filter_view(
+                ui,
+                filter_view_height,
+                shared_unit,
+                pot_unit,
+                PotFilterKind::Tag,
+                needs_separator(),
+                false,
+                last_filters.get(PotFilterKind::Tag),
+                integration,
+            );
+        }
+        if show_ratings {
+            add_filter_view(
+                ui,
+                filter_view_height,
+                shared_unit,
+                pot_unit,
+                PotFilterKind::Rating,
+                needs_separator(),
+                false,
+                last_filters.get(PotFilterKind::Rating),
+                integration,
+            );
+        }
     }
 }
 
@@ -3322,6 +4010,20 @@ When you are ready, press "Start crawling". No worries, at this point, Preset Cr
 - Try not to move the mouse during crawling!
 "#;
 
+const DUPLICATE_FINDER_INTRO_TEXT: &str = r#"
+## Welcome to Pot Duplicate Finder!
+
+Over time, the same preset often ends up in several places: Copied into another folder, imported twice or available both as FX chain and as plug-in preset.
+
+Duplicate Finder looks for presets that either have the same content or have a similar name (ignoring case, punctuation and suffixes such as "(2)" or "Copy") and belong to the same product. Names are only compared if the content of at least one of the presets is unknown.
+
+- It considers only the presets that match the currently selected filters, so you can narrow down the search, e.g. to one database.
+- The result is a list of duplicate clusters, which you can save as text file and use as guide for cleaning up your preset folders. Duplicate Finder itself never deletes anything.
+- If you just want to hide duplicates in the preset list, use the "Hide duplicates" filter (🗋) in the toolbar instead.
+
+Press "Find duplicates" to start!
+"#;
+
 const PREVIEW_RECORDER_READY_TEXT: &str = r#"
 ## Ready!
 
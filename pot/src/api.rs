@@ -1,7 +1,7 @@
 use crate::plugins::ProductKind;
 use crate::provider_database::{
-    DatabaseId, FIL_HAS_PREVIEW_TRUE, FIL_IS_FAVORITE_TRUE, FIL_IS_USER_PRESET_FALSE,
-    FIL_IS_USER_PRESET_TRUE,
+    DatabaseId, FIL_HAS_PREVIEW_TRUE, FIL_IS_DUPLICATE_TRUE, FIL_IS_FAVORITE_TRUE,
+    FIL_IS_USER_PRESET_FALSE, FIL_IS_USER_PRESET_TRUE,
 };
 use crate::{FilterItem, PotPreset};
 use enum_map::EnumMap;
@@ -10,7 +10,8 @@ use helgobox_api::persistence::PotFilterKind;
 use once_cell::sync::Lazy;
 use std::collections::HashSet;
 
-use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
+use base::hash_util;
+use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet, PersistentHash};
 use std::fmt;
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
@@ -93,6 +94,27 @@ pub enum Fil {
     ///
     /// Not suitable for persistence because project IDs are created at runtime.
     Project(ProjectId),
+    /// Refers to a textual filter value such as a category name, identified by its hash.
+    ///
+    /// Makes sense for filter kinds like "Category" or "Mode" in databases which don't have
+    /// integer IDs for their filter items.
+    ///
+    /// Suitable for persistence.
+    Text(PersistentHash),
+    /// Refers to a minimum rating.
+    ///
+    /// Only makes sense for the pot filter kind "Rating".
+    ///
+    /// Suitable for persistence.
+    Rating(u8),
+}
+
+impl Fil {
+    pub fn text(text: &str) -> Self {
+        Self::Text(hash_util::calculate_persistent_non_crypto_hash_one_shot(
+            text.as_bytes(),
+        ))
+    }
 }
 
 /// Runtime ID for a [`Product`].
@@ -170,7 +192,7 @@ impl<T: HasFilterItemId> GenericFilterItemCollections<T> {
 /// `None` means no filter is set (`<Any>`).
 pub type OptFilter = Option<FilterItemId>;
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
 pub struct Filters(EnumMap<PotFilterKind, OptFilter>);
 
 impl Filters {
@@ -186,6 +208,22 @@ impl Filters {
         }
     }
 
+    pub fn wants_duplicates(&self) -> Option<bool> {
+        if let Some(FilterItemId(Some(fil))) = self.get(PotFilterKind::IsDuplicate) {
+            Some(fil == FIL_IS_DUPLICATE_TRUE)
+        } else {
+            None
+        }
+    }
+
+    pub fn wants_min_rating(&self) -> Option<u8> {
+        if let Some(FilterItemId(Some(Fil::Rating(rating)))) = self.get(PotFilterKind::Rating) {
+            Some(rating)
+        } else {
+            None
+        }
+    }
+
     pub fn database_matches(&self, db_id: DatabaseId) -> bool {
         self.matches(PotFilterKind::Database, Fil::Database(db_id))
     }
@@ -359,7 +397,7 @@ impl PotFavorites {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
 pub struct PotFilterExcludes {
     exluded_items: EnumMap<PotFilterKind, NonCryptoHashSet<FilterItemId>>,
 }
@@ -618,6 +656,10 @@ impl PersistentPresetId {
             inner_preset_id,
         }
     }
+
+    pub fn db_id(&self) -> &PersistentDatabaseId {
+        &self.db_id
+    }
 }
 
 impl Display for PersistentPresetId {
@@ -0,0 +1,732 @@
+//! Extraction of technical and musical metadata from audio sample files.
+//!
+//! Only the headers and metadata chunks are read, never the audio data itself, so this is fast
+//! enough for indexing large sample libraries.
+
+use base::regex;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Chunks larger than this are not read into memory (e.g. ID3 tags with huge embedded artwork).
+const MAX_METADATA_CHUNK_SIZE: u32 = 1024 * 1024;
+
+/// Sample rates outside of this range are considered corrupt.
+const VALID_SAMPLE_RATE_RANGE: RangeInclusive<f64> = 1.0..=10_000_000.0;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AudioMetadata {
+    pub duration: Option<Duration>,
+    pub channel_count: Option<u16>,
+    pub sample_rate: Option<u32>,
+    /// Tempo in beats per minute.
+    pub tempo: Option<f64>,
+    pub key: Option<MusicalKey>,
+    /// `Some(true)` if the file declares itself as loop, `Some(false)` if it declares itself as
+    /// one-shot and `None` if it doesn't tell.
+    pub is_loop: Option<bool>,
+}
+
+impl AudioMetadata {
+    /// Reads the metadata from the given audio file contents.
+    ///
+    /// The file extension determines the expected format. Formats that are not supported yield
+    /// empty metadata.
+    pub fn read(reader: &mut (impl Read + Seek), file_ext: &str) -> Result<Self, Box<dyn Error>> {
+        let metadata = match file_ext.to_lowercase().as_str() {
+            "wav" => read_wav(reader)?,
+            "aif" | "aiff" => read_aiff(reader)?,
+            "flac" => read_flac(reader)?,
+            "ogg" => read_ogg(reader)?,
+            _ => Self::default(),
+        };
+        Ok(metadata)
+    }
+
+    /// Fills in tempo, key and loop info from hints in the file name or path, if the file itself
+    /// doesn't provide them.
+    ///
+    /// Examples: `Funky Beat 120bpm Am.wav`, `Loops/Drums/Beat 03.wav`, `One Shots/Kick.wav`
+    pub fn complement_from_path(&mut self, relative_path: &str) {
+        let file_name = relative_path
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(relative_path);
+        let file_stem = match file_name.rfind('.') {
+            None => file_name,
+            Some(i) => &file_name[..i],
+        };
+        if self.tempo.is_none() {
+            self.tempo = parse_tempo_from_file_name(file_stem);
+        }
+        if self.key.is_none() {
+            self.key = parse_key_from_file_name(file_stem);
+        }
+        if self.is_loop.is_none() {
+            self.is_loop = parse_loop_hint_from_path(relative_path);
+        }
+    }
+
+    /// Returns whether this sample should be treated as loop.
+    ///
+    /// If there's no explicit information, samples with a tempo are considered loops.
+    pub fn is_loop(&self) -> bool {
+        self.is_loop.unwrap_or(self.tempo.is_some())
+    }
+
+    /// Returns the tempo rounded to whole beats per minute.
+    pub fn rounded_tempo(&self) -> Option<u32> {
+        self.tempo.map(|t| t.round() as u32)
+    }
+}
+
+/// The key of a sample, as far as it's known.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MusicalKey {
+    /// Pitch class of the root note (0 = C, 1 = C#, ..., 11 = B).
+    pub root: u8,
+    /// `None` if the mode is unknown.
+    pub is_minor: Option<bool>,
+}
+
+const PITCH_CLASS_NAMES: [&str; 12] = [
+    "C", "C#", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+
+impl MusicalKey {
+    /// Parses keys such as "C", "F#", "Bbm", "A minor" or "Eb maj".
+    pub fn parse(text: &str) -> Option<Self> {
+        let captures = regex!(r"^([A-Ga-g])\s*([#b♯♭]?)\s*(m|min|minor|M|maj|major)?$")
+            .captures(text.trim())?;
+        let letter = captures.get(1)?.as_str().chars().next()?;
+        let accidental = captures.get(2).map(|m| m.as_str()).unwrap_or_default();
+        let mode = captures.get(3).map(|m| m.as_str());
+        Some(Self::from_parts(letter, accidental, mode))
+    }
+
+    /// Creates a key from the given MIDI note number, mode unknown.
+    pub fn from_midi_note(note: u8) -> Self {
+        Self {
+            root: note % 12,
+            is_minor: None,
+        }
+    }
+
+    fn from_parts(letter: char, accidental: &str, mode: Option<&str>) -> Self {
+        let natural: i8 = match letter.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            _ => 11,
+        };
+        let offset = match accidental {
+            "#" | "♯" => 1,
+            "b" | "♭" => -1,
+            _ => 0,
+        };
+        Self {
+            root: (natural + offset).rem_euclid(12) as u8,
+            is_minor: mode.map(|m| m.starts_with("min") || m == "m"),
+        }
+    }
+}
+
+/// Keys with unknown mode are displayed like major keys.
+impl Display for MusicalKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(PITCH_CLASS_NAMES[self.root as usize])?;
+        if self.is_minor == Some(true) {
+            f.write_str("m")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_tempo_from_file_name(file_stem: &str) -> Option<f64> {
+    let captures = regex!(r"(?i)(?:^|[^0-9.])(\d{2,3}(?:[.,]\d{1,2})?)\s*[-_]?\s*bpm")
+        .captures(file_stem)
+        .or_else(|| regex!(r"(?i)bpm\s*[-_]?\s*(\d{2,3}(?:[.,]\d{1,2})?)").captures(file_stem))?;
+    let tempo: f64 = captures.get(1)?.as_str().replace(',', ".").parse().ok()?;
+    is_plausible_tempo(tempo).then_some(tempo)
+}
+
+/// Only considers tokens that are clearly keys, i.e. have an accidental or a mode suffix. A single
+/// letter such as "A" or "E" is too ambiguous.
+fn parse_key_from_file_name(file_stem: &str) -> Option<MusicalKey> {
+    regex!(r"(?:^|[^A-Za-z0-9#])([A-G])([#b]?)(m|min|minor|maj|major)?(?:$|[^A-Za-z0-9#])")
+        .captures_iter(file_stem)
+        .filter_map(|captures| {
+            let letter = captures.get(1)?.as_str().chars().next()?;
+            let accidental = captures.get(2).map(|m| m.as_str()).unwrap_or_default();
+            let mode = captures.get(3).map(|m| m.as_str());
+            if accidental.is_empty() && mode.is_none() {
+                return None;
+            }
+            Some(MusicalKey::from_parts(letter, accidental, mode))
+        })
+        .last()
+}
+
+fn parse_loop_hint_from_path(relative_path: &str) -> Option<bool> {
+    let lowercase_path = relative_path.to_lowercase();
+    if regex!(r"one[\s_-]?shot").is_match(&lowercase_path) {
+        Some(false)
+    } else if lowercase_path.contains("loop") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+fn is_plausible_tempo(tempo: f64) -> bool {
+    (30.0..=300.0).contains(&tempo)
+}
+
+/// Reads a RIFF WAVE file.
+///
+/// Relevant chunks: `fmt `, `data`, `acid` (ACIDized loops), `smpl` (sampler loops) and
+/// `id3 ` (ID3v2 tag).
+fn read_wav(reader: &mut (impl Read + Seek)) -> Result<AudioMetadata, Box<dyn Error>> {
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err("not a WAV file".into());
+    }
+    let mut metadata = AudioMetadata::default();
+    let mut byte_rate = None;
+    let mut data_size = None;
+    while let Some((id, size)) = read_chunk_header(reader, Endianness::Little)? {
+        let next_chunk_pos = reader.stream_position()? + padded_chunk_size(size);
+        match &id {
+            b"fmt " => {
+                let fmt = read_chunk_data(reader, size.min(16))?;
+                metadata.channel_count = read_u16_le(&fmt, 2);
+                metadata.sample_rate = read_u32_le(&fmt, 4);
+                byte_rate = read_u32_le(&fmt, 8);
+            }
+            b"data" => {
+                data_size = Some(size);
+            }
+            b"acid" => {
+                let acid = read_chunk_data(reader, size.min(24))?;
+                if let Some(flags) = read_u32_le(&acid, 0) {
+                    let is_one_shot = flags & 0x01 != 0;
+                    let has_root_note = flags & 0x02 != 0;
+                    metadata.is_loop = Some(!is_one_shot);
+                    if has_root_note {
+                        metadata.key = read_u16_le(&acid, 4)
+                            .map(|note| MusicalKey::from_midi_note(note as u8));
+                    }
+                    if !is_one_shot {
+                        metadata.tempo = read_u32_le(&acid, 20)
+                            .map(|bits| f32::from_bits(bits) as f64)
+                            .filter(|t| is_plausible_tempo(*t));
+                    }
+                }
+            }
+            b"smpl" => {
+                let smpl = read_chunk_data(reader, size.min(36))?;
+                if metadata.is_loop.is_none() {
+                    metadata.is_loop = read_u32_le(&smpl, 28).map(|loop_count| loop_count > 0);
+                }
+            }
+            b"id3 " | b"ID3 " if size <= MAX_METADATA_CHUNK_SIZE => {
+                let tag = read_chunk_data(reader, size)?;
+                apply_id3_tag(&mut metadata, &tag);
+            }
+            _ => {}
+        }
+        reader.seek(SeekFrom::Start(next_chunk_pos))?;
+    }
+    if let (Some(data_size), Some(byte_rate)) = (data_size, byte_rate) {
+        if byte_rate > 0 {
+            metadata.duration = Some(Duration::from_secs_f64(data_size as f64 / byte_rate as f64));
+        }
+    }
+    Ok(metadata)
+}
+
+/// Reads an AIFF or AIFF-C file.
+///
+/// Relevant chunks: `COMM`, `INST` (sustain loop) and `ID3 ` (ID3v2 tag).
+fn read_aiff(reader: &mut (impl Read + Seek)) -> Result<AudioMetadata, Box<dyn Error>> {
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != b"FORM" || !matches!(&header[8..12], b"AIFF" | b"AIFC") {
+        return Err("not an AIFF file".into());
+    }
+    let mut metadata = AudioMetadata::default();
+    while let Some((id, size)) = read_chunk_header(reader, Endianness::Big)? {
+        let next_chunk_pos = reader.stream_position()? + padded_chunk_size(size);
+        match &id {
+            b"COMM" => {
+                let comm = read_chunk_data(reader, size.min(18))?;
+                metadata.channel_count = read_u16_be(&comm, 0);
+                let frame_count = read_u32_be(&comm, 2);
+                let sample_rate = comm
+                    .get(8..18)
+                    .and_then(|b| b.try_into().ok())
+                    .map(extended_to_f64)
+                    .filter(|r| VALID_SAMPLE_RATE_RANGE.contains(r));
+                metadata.sample_rate = sample_rate.map(|r| r.round() as u32);
+                if let (Some(frame_count), Some(sample_rate)) = (frame_count, sample_rate) {
+                    metadata.duration =
+                        Duration::try_from_secs_f64(frame_count as f64 / sample_rate).ok();
+                }
+            }
+            b"INST" => {
+                let inst = read_chunk_data(reader, size.min(20))?;
+                if metadata.is_loop.is_none() {
+                    // Play mode of the sustain loop: 0 = no looping
+                    metadata.is_loop = read_u16_be(&inst, 8).map(|play_mode| play_mode != 0);
+                }
+            }
+            b"ID3 " | b"id3 " if size <= MAX_METADATA_CHUNK_SIZE => {
+                let tag = read_chunk_data(reader, size)?;
+                apply_id3_tag(&mut metadata, &tag);
+            }
+            _ => {}
+        }
+        reader.seek(SeekFrom::Start(next_chunk_pos))?;
+    }
+    Ok(metadata)
+}
+
+/// Reads a FLAC file (`STREAMINFO` and `VORBIS_COMMENT` metadata blocks).
+fn read_flac(reader: &mut (impl Read + Seek)) -> Result<AudioMetadata, Box<dyn Error>> {
+    let mut marker = [0u8; 4];
+    reader.read_exact(&mut marker)?;
+    if &marker != b"fLaC" {
+        return Err("not a FLAC file".into());
+    }
+    let mut metadata = AudioMetadata::default();
+    loop {
+        let mut block_header = [0u8; 4];
+        reader.read_exact(&mut block_header)?;
+        let is_last = block_header[0] & 0x80 != 0;
+        let block_type = block_header[0] & 0x7f;
+        let size = u32::from_be_bytes([0, block_header[1], block_header[2], block_header[3]]);
+        let next_block_pos = reader.stream_position()? + size as u64;
+        match block_type {
+            0 => {
+                let info = read_chunk_data(reader, size.min(34))?;
+                if info.len() >= 18 {
+                    let sample_rate = ((info[10] as u32) << 12)
+                        | ((info[11] as u32) << 4)
+                        | ((info[12] as u32) >> 4);
+                    let channel_count = ((info[12] >> 1) & 0x07) as u16 + 1;
+                    let sample_count = (((info[13] & 0x0f) as u64) << 32)
+                        | u32::from_be_bytes([info[14], info[15], info[16], info[17]]) as u64;
+                    metadata.sample_rate = Some(sample_rate);
+                    metadata.channel_count = Some(channel_count);
+                    if sample_rate > 0 && sample_count > 0 {
+                        metadata.duration = Some(Duration::from_secs_f64(
+                            sample_count as f64 / sample_rate as f64,
+                        ));
+                    }
+                }
+            }
+            4 if size <= MAX_METADATA_CHUNK_SIZE => {
+                let comments = read_chunk_data(reader, size)?;
+                apply_vorbis_comments(&mut metadata, &comments);
+            }
+            _ => {}
+        }
+        if is_last {
+            break;
+        }
+        reader.seek(SeekFrom::Start(next_block_pos))?;
+    }
+    Ok(metadata)
+}
+
+/// Reads an Ogg Vorbis file.
+///
+/// The identification and comment headers are taken from the first packets, the duration from
+/// the granule position of the last page.
+fn read_ogg(reader: &mut (impl Read + Seek)) -> Result<AudioMetadata, Box<dyn Error>> {
+    let mut metadata = AudioMetadata::default();
+    let mut packets: Vec<Vec<u8>> = vec![];
+    let mut current_packet = vec![];
+    // The comment header is the second packet and usually ends within the first few pages
+    const MAX_HEADER_PAGE_COUNT: usize = 16;
+    for _ in 0..MAX_HEADER_PAGE_COUNT {
+        if packets.len() >= 2 {
+            break;
+        }
+        let mut page_header = [0u8; 27];
+        reader.read_exact(&mut page_header)?;
+        if &page_header[0..4] != b"OggS" {
+            return Err("not an Ogg file".into());
+        }
+        let mut segment_table = vec![0u8; page_header[26] as usize];
+        reader.read_exact(&mut segment_table)?;
+        for segment_size in segment_table {
+            let mut segment = vec![0u8; segment_size as usize];
+            reader.read_exact(&mut segment)?;
+            current_packet.extend(segment);
+            if current_packet.len() > MAX_METADATA_CHUNK_SIZE as usize {
+                return Err("Ogg header packet too large".into());
+            }
+            if segment_size < 255 {
+                packets.push(std::mem::take(&mut current_packet));
+            }
+        }
+    }
+    let identification = packets.first().ok_or("Ogg identification header missing")?;
+    if identification.get(0..7) != Some(&b"\x01vorbis"[..]) {
+        return Err("Ogg file doesn't contain Vorbis".into());
+    }
+    metadata.channel_count = identification.get(11).map(|c| *c as u16);
+    metadata.sample_rate = read_u32_le(identification, 12);
+    if let Some(comment_header) = packets.get(1) {
+        if let Some(comments) = comment_header.strip_prefix(b"\x03vorbis") {
+            apply_vorbis_comments(&mut metadata, comments);
+        }
+    }
+    // Find last page
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    let tail_size = file_size.min(64 * 1024);
+    reader.seek(SeekFrom::Start(file_size - tail_size))?;
+    let mut tail = vec![0u8; tail_size as usize];
+    reader.read_exact(&mut tail)?;
+    let last_page_pos = tail.windows(4).rposition(|w| w == b"OggS");
+    let sample_count = last_page_pos
+        .and_then(|pos| tail.get(pos + 6..pos + 14))
+        .and_then(|bytes| Some(i64::from_le_bytes(bytes.try_into().ok()?)));
+    if let (Some(sample_count), Some(sample_rate)) = (sample_count, metadata.sample_rate) {
+        if sample_count > 0 && sample_rate > 0 {
+            metadata.duration = Some(Duration::from_secs_f64(
+                sample_count as f64 / sample_rate as f64,
+            ));
+        }
+    }
+    Ok(metadata)
+}
+
+/// Applies the tempo and key of a Vorbis comment block (as used in FLAC and Ogg Vorbis).
+fn apply_vorbis_comments(metadata: &mut AudioMetadata, data: &[u8]) {
+    let mut pos = 0;
+    let read_string = |pos: &mut usize| -> Option<String> {
+        let len = read_u32_le(data, *pos)? as usize;
+        let bytes = data.get(*pos + 4..*pos + 4 + len)?;
+        *pos += 4 + len;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    };
+    // Vendor string
+    if read_string(&mut pos).is_none() {
+        return;
+    }
+    let Some(comment_count) = read_u32_le(data, pos) else {
+        return;
+    };
+    pos += 4;
+    for _ in 0..comment_count {
+        let Some(comment) = read_string(&mut pos) else {
+            return;
+        };
+        let Some((key, value)) = comment.split_once('=') else {
+            continue;
+        };
+        match key.to_uppercase().as_str() {
+            "BPM" | "TEMPO" | "TBPM" => apply_tempo_text(metadata, value),
+            "KEY" | "INITIALKEY" | "TKEY" => apply_key_text(metadata, value),
+            _ => {}
+        }
+    }
+}
+
+/// Applies the tempo (`TBPM`) and key (`TKEY`) frames of an ID3v2 tag.
+fn apply_id3_tag(metadata: &mut AudioMetadata, tag: &[u8]) {
+    if tag.len() < 10 || &tag[0..3] != b"ID3" {
+        return;
+    }
+    let major_version = tag[3];
+    let flags = tag[5];
+    let tag_size = read_syncsafe_u32(&tag[6..10]) as usize;
+    let end = (10 + tag_size).min(tag.len());
+    let mut pos = 10;
+    if flags & 0x40 != 0 {
+        // Extended header
+        let ext_size = match major_version {
+            3 => read_u32_be(tag, pos).map(|s| s as usize + 4),
+            4 => tag.get(pos..pos + 4).map(|b| read_syncsafe_u32(b) as usize),
+            _ => None,
+        };
+        let Some(ext_size) = ext_size else {
+            return;
+        };
+        pos += ext_size;
+    }
+    let (id_len, header_len) = if major_version == 2 { (3, 6) } else { (4, 10) };
+    while pos + header_len <= end {
+        let id = &tag[pos..pos + id_len];
+        if id[0] == 0 {
+            // Padding
+            break;
+        }
+        let size_bytes = &tag[pos + id_len..pos + id_len * 2];
+        let size = match major_version {
+            2 => u32::from_be_bytes([0, size_bytes[0], size_bytes[1], size_bytes[2]]),
+            3 => u32::from_be_bytes(size_bytes.try_into().unwrap()),
+            _ => read_syncsafe_u32(size_bytes),
+        } as usize;
+        let content_start = pos + header_len;
+        let Some(content) = tag.get(content_start..content_start + size) else {
+            break;
+        };
+        match id {
+            b"TBPM" | b"TBP" => {
+                if let Some(text) = decode_id3_text(content) {
+                    apply_tempo_text(metadata, &text);
+                }
+            }
+            b"TKEY" | b"TKE" => {
+                if let Some(text) = decode_id3_text(content) {
+                    apply_key_text(metadata, &text);
+                }
+            }
+            _ => {}
+        }
+        pos = content_start + size;
+    }
+}
+
+fn apply_tempo_text(metadata: &mut AudioMetadata, text: &str) {
+    if metadata.tempo.is_some() {
+        return;
+    }
+    if let Ok(tempo) = text.trim().trim_matches('\0').replace(',', ".").parse() {
+        if is_plausible_tempo(tempo) {
+            metadata.tempo = Some(tempo);
+        }
+    }
+}
+
+fn apply_key_text(metadata: &mut AudioMetadata, text: &str) {
+    if metadata.key.is_some() {
+        return;
+    }
+    metadata.key = MusicalKey::parse(text.trim_matches('\0'));
+}
+
+/// Decodes the content of an ID3v2 text frame.
+fn decode_id3_text(content: &[u8]) -> Option<String> {
+    let (encoding, text) = content.split_first()?;
+    let decoded = match encoding {
+        // ISO-8859-1
+        0 => text.iter().map(|b| *b as char).collect(),
+        // UTF-16 with BOM or UTF-16BE
+        1 | 2 => {
+            let (is_little_endian, text) = match text {
+                [0xff, 0xfe, rest @ ..] => (true, rest),
+                [0xfe, 0xff, rest @ ..] => (false, rest),
+                _ => (false, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| {
+                    if is_little_endian {
+                        u16::from_le_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_be_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        // UTF-8
+        _ => String::from_utf8_lossy(text).into_owned(),
+    };
+    Some(decoded)
+}
+
+#[derive(Copy, Clone)]
+enum Endianness {
+    Little,
+    Big,
+}
+
+/// Returns `None` at the end of the file.
+fn read_chunk_header(
+    reader: &mut impl Read,
+    endianness: Endianness,
+) -> io::Result<Option<([u8; 4], u32)>> {
+    let mut header = [0u8; 8];
+    match reader.read_exact(&mut header) {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let id = [header[0], header[1], header[2], header[3]];
+    let size_bytes = [header[4], header[5], header[6], header[7]];
+    let size = match endianness {
+        Endianness::Little => u32::from_le_bytes(size_bytes),
+        Endianness::Big => u32::from_be_bytes(size_bytes),
+    };
+    Ok(Some((id, size)))
+}
+
+/// RIFF and IFF chunks are padded to an even size.
+fn padded_chunk_size(size: u32) -> u64 {
+    size as u64 + (size & 1) as u64
+}
+
+/// Reads up to `size` bytes. Truncated files result in shorter data, not in an error.
+fn read_chunk_data(reader: &mut impl Read, size: u32) -> io::Result<Vec<u8>> {
+    let mut data = vec![];
+    reader.take(size as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn read_u16_le(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32_le(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_u16_be(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32_be(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_syncsafe_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .take(4)
+        .fold(0, |acc, b| (acc << 7) | (*b as u32 & 0x7f))
+}
+
+/// Converts an 80-bit IEEE 754 extended precision number (as used for the AIFF sample rate).
+fn extended_to_f64(bytes: [u8; 10]) -> f64 {
+    let exponent = (((bytes[0] & 0x7f) as i32) << 8) | bytes[1] as i32;
+    let mantissa = u64::from_be_bytes(bytes[2..10].try_into().unwrap());
+    if exponent == 0 && mantissa == 0 {
+        return 0.0;
+    }
+    let value = mantissa as f64 * 2f64.powi(exponent - 16383 - 63);
+    if bytes[0] & 0x80 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn wav_with_acid_chunk() {
+        // Given
+        let mut fmt = vec![];
+        fmt.extend(1u16.to_le_bytes()); // PCM
+        fmt.extend(2u16.to_le_bytes()); // Channels
+        fmt.extend(44100u32.to_le_bytes()); // Sample rate
+        fmt.extend((44100u32 * 4).to_le_bytes()); // Byte rate
+        fmt.extend(4u16.to_le_bytes()); // Block align
+        fmt.extend(16u16.to_le_bytes()); // Bits per sample
+        let mut acid = vec![];
+        acid.extend(0x02u32.to_le_bytes()); // Root note set, not one-shot
+        acid.extend(57u16.to_le_bytes()); // A
+        acid.extend([0u8; 14]);
+        acid.extend(120f32.to_le_bytes());
+        let data = vec![0u8; 44100 * 4 * 2];
+        let mut body = b"WAVE".to_vec();
+        for (id, content) in [(b"fmt ", &fmt), (b"acid", &acid), (b"data", &data)] {
+            body.extend(id);
+            body.extend((content.len() as u32).to_le_bytes());
+            body.extend(content);
+        }
+        let mut file = b"RIFF".to_vec();
+        file.extend((body.len() as u32).to_le_bytes());
+        file.extend(body);
+        // When
+        let metadata = AudioMetadata::read(&mut Cursor::new(file), "WAV").unwrap();
+        // Then
+        assert_eq!(
+            metadata,
+            AudioMetadata {
+                duration: Some(Duration::from_secs(2)),
+                channel_count: Some(2),
+                sample_rate: Some(44100),
+                tempo: Some(120.0),
+                key: Some(MusicalKey {
+                    root: 9,
+                    is_minor: None
+                }),
+                is_loop: Some(true),
+            }
+        );
+    }
+
+    fn aiff_file(sample_rate: f64) -> Vec<u8> {
+        let exponent = sample_rate.log2().floor() as i32;
+        let mantissa = (sample_rate / 2f64.powi(exponent) * 2f64.powi(63)) as u64;
+        let mut comm = vec![];
+        comm.extend(2u16.to_be_bytes()); // Channels
+        comm.extend(88200u32.to_be_bytes()); // Frames
+        comm.extend(16u16.to_be_bytes()); // Bits per sample
+        comm.extend(((exponent + 16383) as u16).to_be_bytes());
+        comm.extend(mantissa.to_be_bytes());
+        let mut body = b"AIFF".to_vec();
+        body.extend(b"COMM");
+        body.extend((comm.len() as u32).to_be_bytes());
+        body.extend(comm);
+        let mut file = b"FORM".to_vec();
+        file.extend((body.len() as u32).to_be_bytes());
+        file.extend(body);
+        file
+    }
+
+    #[test]
+    fn aiff() {
+        // Given
+        let file = aiff_file(44100.0);
+        // When
+        let metadata = AudioMetadata::read(&mut Cursor::new(file), "aiff").unwrap();
+        // Then
+        assert_eq!(metadata.channel_count, Some(2));
+        assert_eq!(metadata.sample_rate, Some(44100));
+        assert_eq!(metadata.duration, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn aiff_with_invalid_sample_rate() {
+        for sample_rate in [0.5, 1e300] {
+            // Given
+            let file = aiff_file(sample_rate);
+            // When
+            let metadata = AudioMetadata::read(&mut Cursor::new(file), "aiff").unwrap();
+            // Then
+            assert_eq!(metadata.channel_count, Some(2));
+            assert_eq!(metadata.sample_rate, None);
+            assert_eq!(metadata.duration, None);
+        }
+    }
+
+    #[test]
+    fn hints_from_path() {
+        let mut metadata = AudioMetadata::default();
+        metadata.complement_from_path("Funk Kit/Loops/Groove_98.5BPM_F#m.wav");
+        assert_eq!(metadata.tempo, Some(98.5));
+        assert_eq!(metadata.key.unwrap().to_string(), "F#m");
+        assert!(metadata.is_loop());
+        let mut metadata = AudioMetadata::default();
+        metadata.complement_from_path("Funk Kit/One Shots/Kick A.wav");
+        assert_eq!(metadata.tempo, None);
+        assert_eq!(metadata.key, None);
+        assert!(!metadata.is_loop());
+    }
+}
@@ -0,0 +1,440 @@
+//! Detection of presets which exist multiple times, within one database or across databases.
+
+use crate::{pot_db, BuildInput, PotPreset, PotPresetKind, PresetWithId, ProductId};
+use base::hash_util::PersistentHash;
+use helgobox_api::persistence::PotFilterKind;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Same normalized preset name for the same product.
+///
+/// Makes a preset without content hash count as duplicate of presets with the same name key. It's
+/// linked to at most one content, so presets with different content never end up as duplicates,
+/// no matter how they are named.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+struct NameKey {
+    name: String,
+    product: DuplicateProduct,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum DuplicateProduct {
+    Id(ProductId),
+    Name(String),
+}
+
+/// Returns the given preset name in a form that ignores differences which usually don't matter
+/// when comparing preset names: Case, punctuation, whitespace and copy suffixes such as "(2)" or
+/// "Copy".
+pub fn normalize_name(name: &str) -> String {
+    strip_copy_suffix(name)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn strip_copy_suffix(name: &str) -> &str {
+    let trimmed = name.trim_end();
+    // "Bass (2)"
+    if let Some((base, number)) = trimmed
+        .strip_suffix(')')
+        .and_then(|without_paren| without_paren.rsplit_once('('))
+    {
+        if !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+            && !base.trim().is_empty()
+        {
+            return base;
+        }
+    }
+    // "Bass copy", "Bass - Copy"
+    let suffix_start = trimmed.len().saturating_sub(4);
+    if trimmed.is_char_boundary(suffix_start)
+        && trimmed[suffix_start..].eq_ignore_ascii_case("copy")
+    {
+        let base = &trimmed[..suffix_start];
+        if base.ends_with([' ', '-', '_']) && !base.trim_matches([' ', '-', '_']).is_empty() {
+            return base;
+        }
+    }
+    trimmed
+}
+
+fn name_key(preset: &PotPreset) -> Option<NameKey> {
+    // Without knowing the product, equal names are too weak an indicator
+    let product = match preset.common.product_ids.first() {
+        Some(id) => Some(DuplicateProduct::Id(*id)),
+        None => preset
+            .common
+            .product_name
+            .as_deref()
+            .map(|name| DuplicateProduct::Name(normalize_name(name))),
+    };
+    product.map(|product| NameKey {
+        name: normalize_name(&preset.common.name),
+        product,
+    })
+}
+
+/// Presets with a particular name key.
+#[derive(Default)]
+struct NameKeyOccurrences {
+    content: NameKeyContent,
+    /// Index of the first preset without content hash.
+    first_without_content_hash: Option<usize>,
+}
+
+/// Content of the presets with a particular name key, as far as it's known.
+#[derive(Default)]
+enum NameKeyContent {
+    /// No preset has a content hash.
+    #[default]
+    Unknown,
+    /// All presets which have a content hash have the same one.
+    Unique {
+        content_hash: PersistentHash,
+        first_preset_index: usize,
+    },
+    /// Presets have different content hashes, so it's unclear which content a preset without
+    /// content hash has.
+    Ambiguous,
+}
+
+impl NameKeyContent {
+    fn add(&mut self, content_hash: PersistentHash, preset_index: usize) {
+        match self {
+            NameKeyContent::Unknown => {
+                *self = NameKeyContent::Unique {
+                    content_hash,
+                    first_preset_index: preset_index,
+                };
+            }
+            NameKeyContent::Unique {
+                content_hash: existing_content_hash,
+                ..
+            } => {
+                if *existing_content_hash != content_hash {
+                    *self = NameKeyContent::Ambiguous;
+                }
+            }
+            NameKeyContent::Ambiguous => {}
+        }
+    }
+}
+
+/// Groups a list of presets into clusters of duplicates.
+///
+/// Presets which don't have any duplicates end up in a cluster of their own.
+#[derive(Debug)]
+pub struct DuplicateClusters {
+    /// Cluster ID for each preset index.
+    cluster_ids: Vec<usize>,
+    /// First preset index for each cluster ID.
+    first_preset_indexes: Vec<usize>,
+    /// Number of presets for each cluster ID.
+    cluster_sizes: Vec<usize>,
+}
+
+impl DuplicateClusters {
+    /// Detects duplicates among the given presets.
+    ///
+    /// Cluster IDs are assigned in order of first occurrence.
+    pub fn detect<'a>(presets: impl IntoIterator<Item = &'a PotPreset>) -> Self {
+        // Union-find, so that transitive duplicates end up in the same cluster
+        let mut parents: Vec<usize> = Vec::new();
+        let mut first_index_by_content_hash: HashMap<PersistentHash, usize> = HashMap::new();
+        let mut occurrences_by_name_key: HashMap<NameKey, NameKeyOccurrences> = HashMap::new();
+        for (i, preset) in presets.into_iter().enumerate() {
+            parents.push(i);
+            let content_hash = preset.common.content_hash;
+            if let Some(content_hash) = content_hash {
+                match first_index_by_content_hash.entry(content_hash) {
+                    Entry::Occupied(e) => union(&mut parents, *e.get(), i),
+                    Entry::Vacant(e) => {
+                        e.insert(i);
+                    }
+                }
+            }
+            let Some(name_key) = name_key(preset) else {
+                continue;
+            };
+            let occurrences = occurrences_by_name_key.entry(name_key).or_default();
+            match content_hash {
+                Some(content_hash) => occurrences.content.add(content_hash, i),
+                None => match occurrences.first_without_content_hash {
+                    None => occurrences.first_without_content_hash = Some(i),
+                    Some(first) => union(&mut parents, first, i),
+                },
+            }
+        }
+        // Presets without content hash can only be linked to a content once all presets are known
+        for occurrences in occurrences_by_name_key.values() {
+            if let (
+                Some(first_without_content_hash),
+                NameKeyContent::Unique {
+                    first_preset_index, ..
+                },
+            ) = (occurrences.first_without_content_hash, &occurrences.content)
+            {
+                union(
+                    &mut parents,
+                    first_without_content_hash,
+                    *first_preset_index,
+                );
+            }
+        }
+        let mut cluster_id_by_root: HashMap<usize, usize> = HashMap::new();
+        let mut clusters = Self {
+            cluster_ids: Vec::with_capacity(parents.len()),
+            first_preset_indexes: Vec::new(),
+            cluster_sizes: Vec::new(),
+        };
+        for i in 0..parents.len() {
+            let root = find_root(&mut parents, i);
+            let cluster_id = *cluster_id_by_root.entry(root).or_insert_with(|| {
+                clusters.first_preset_indexes.push(i);
+                clusters.cluster_sizes.push(0);
+                clusters.first_preset_indexes.len() - 1
+            });
+            clusters.cluster_sizes[cluster_id] += 1;
+            clusters.cluster_ids.push(cluster_id);
+        }
+        clusters
+    }
+
+    pub fn cluster_count(&self) -> usize {
+        self.cluster_sizes.len()
+    }
+
+    pub fn cluster_id(&self, preset_index: usize) -> usize {
+        self.cluster_ids[preset_index]
+    }
+
+    /// Returns whether the preset at the given index has at least one duplicate.
+    pub fn has_duplicates(&self, preset_index: usize) -> bool {
+        self.cluster_sizes[self.cluster_id(preset_index)] > 1
+    }
+
+    /// Returns whether the preset at the given index is the first one of its cluster.
+    ///
+    /// Hiding duplicates means keeping only those.
+    pub fn is_first_of_cluster(&self, preset_index: usize) -> bool {
+        self.first_preset_indexes[self.cluster_id(preset_index)] == preset_index
+    }
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let root_a = find_root(parents, a);
+    let root_b = find_root(parents, b);
+    // Always let the lower index win, so the root is the first occurrence
+    if root_a < root_b {
+        parents[root_b] = root_a;
+    } else if root_b < root_a {
+        parents[root_a] = root_b;
+    }
+}
+
+/// Report of all duplicate clusters, useful for cleaning up preset folders.
+#[derive(Debug, Default)]
+pub struct DuplicateReport {
+    pub clusters: Vec<DuplicateCluster>,
+}
+
+#[derive(Debug)]
+pub struct DuplicateCluster {
+    /// `true` if all presets in this cluster have the same content, `false` if at least one of
+    /// them is just similar by name and product.
+    pub identical_content: bool,
+    pub entries: Vec<DuplicateReportEntry>,
+}
+
+#[derive(Debug)]
+pub struct DuplicateReportEntry {
+    pub database_name: String,
+    pub preset: PresetWithId,
+}
+
+impl DuplicateReportEntry {
+    /// Returns the file in which the preset is saved, if it's saved in a file at all.
+    pub fn location(&self) -> Option<String> {
+        match &self.preset.preset.kind {
+            PotPresetKind::FileBased(k) => Some(k.path.to_string()),
+            PotPresetKind::ProjectBased(k) => Some(k.path_to_rpp.to_string_lossy().to_string()),
+            PotPresetKind::Internal(_) | PotPresetKind::DefaultFactory(_) => None,
+        }
+    }
+}
+
+impl DuplicateReport {
+    /// Number of presets which are duplicates of another preset in the same cluster.
+    pub fn redundant_preset_count(&self) -> usize {
+        self.clusters.iter().map(|c| c.entries.len() - 1).sum()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(
+            text,
+            "{} duplicate clusters, {} redundant presets",
+            self.clusters.len(),
+            self.redundant_preset_count()
+        );
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            let match_kind = if cluster.identical_content {
+                "identical content"
+            } else {
+                "similar name"
+            };
+            let _ = writeln!(text, "\nCluster {} ({match_kind})", i + 1);
+            for entry in &cluster.entries {
+                let common = &entry.preset.preset.common;
+                let _ = write!(text, "- {} [{}]", common.name, entry.database_name);
+                if let Some(product_name) = &common.product_name {
+                    let _ = write!(text, " ({product_name})");
+                }
+                if let Some(location) = entry.location() {
+                    let _ = write!(text, ": {location}");
+                }
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+/// Creates a report of all duplicates among the presets matching the given build input.
+///
+/// Can take long.
+pub fn create_duplicate_report(mut build_input: BuildInput) -> DuplicateReport {
+    // We want to see all duplicates, not just the remaining ones
+    build_input.filters.set(PotFilterKind::IsDuplicate, None);
+    let presets = pot_db().gather_presets(build_input);
+    let clusters = DuplicateClusters::detect(presets.iter().map(|p| &p.preset));
+    let mut report_clusters: Vec<Option<DuplicateCluster>> =
+        (0..clusters.cluster_count()).map(|_| None).collect();
+    for (i, preset) in presets.into_iter().enumerate() {
+        if !clusters.has_duplicates(i) {
+            continue;
+        }
+        let database_name = pot_db()
+            .try_with_db(preset.id.database_id, |db| db.name().to_string())
+            .unwrap_or_default();
+        let cluster =
+            report_clusters[clusters.cluster_id(i)].get_or_insert_with(|| DuplicateCluster {
+                identical_content: true,
+                entries: vec![],
+            });
+        if let Some(first_entry) = cluster.entries.first() {
+            let first_hash = first_entry.preset.preset.common.content_hash;
+            if first_hash.is_none() || preset.preset.common.content_hash != first_hash {
+                cluster.identical_content = false;
+            }
+        }
+        cluster.entries.push(DuplicateReportEntry {
+            database_name,
+            preset,
+        });
+    }
+    DuplicateReport {
+        clusters: report_clusters.into_iter().flatten().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        InternalPotPresetKind, PersistentDatabaseId, PersistentInnerPresetId, PersistentPresetId,
+        PotPresetCommon,
+    };
+    use base::hash_util::calculate_persistent_non_crypto_hash_one_shot;
+
+    #[test]
+    fn normalize_names() {
+        assert_eq!(normalize_name("Warm  Pad"), "warm pad");
+        assert_eq!(normalize_name("Warm-Pad (2)"), "warm pad");
+        assert_eq!(normalize_name("WARM PAD - Copy"), "warm pad");
+        assert_eq!(normalize_name("Warm Pad 2"), "warm pad 2");
+        assert_eq!(normalize_name("Copy"), "copy");
+        assert_eq!(normalize_name("(2)"), "2");
+    }
+
+    #[test]
+    fn detect_clusters() {
+        // Given
+        let presets = [
+            preset("Warm Pad", Some("Synth"), Some("a")),
+            preset("Bass", Some("Synth"), Some("b")),
+            preset("Warm Pad (2)", Some("synth"), None),
+            preset("Lead", None, Some("a")),
+            preset("Bass", None, None),
+            preset("Strings", Some("Synth"), Some("c")),
+            preset("Strings", Some("Synth"), Some("d")),
+            preset("Strings", Some("Synth"), None),
+        ];
+        // When
+        let clusters = DuplicateClusters::detect(&presets);
+        // Then
+        assert_eq!(clusters.cluster_count(), 6);
+        assert_eq!(clusters.cluster_id(2), clusters.cluster_id(0));
+        assert_eq!(clusters.cluster_id(3), clusters.cluster_id(0));
+        assert!(clusters.is_first_of_cluster(0));
+        assert!(!clusters.is_first_of_cluster(2));
+        assert!(!clusters.has_duplicates(1));
+        assert!(!clusters.has_duplicates(4));
+        // Different content, and it's unclear which one the preset without content hash has
+        assert!(!clusters.has_duplicates(5));
+        assert!(!clusters.has_duplicates(6));
+        assert!(!clusters.has_duplicates(7));
+    }
+
+    #[test]
+    fn link_presets_without_content_hash_to_unique_content() {
+        // Given
+        let presets = [
+            preset("Strings (2)", Some("Synth"), None),
+            preset("Strings", Some("Synth"), Some("c")),
+            preset("Strings - Copy", Some("Synth"), None),
+            preset("Strings", Some("Synth"), Some("c")),
+        ];
+        // When
+        let clusters = DuplicateClusters::detect(&presets);
+        // Then
+        assert_eq!(clusters.cluster_count(), 1);
+        assert!(clusters.is_first_of_cluster(0));
+    }
+
+    fn preset(name: &str, product_name: Option<&str>, content: Option<&str>) -> PotPreset {
+        PotPreset::new(
+            PotPresetCommon {
+                persistent_id: PersistentPresetId::new(
+                    PersistentDatabaseId::new("test".to_string()),
+                    PersistentInnerPresetId::new(name.to_string()),
+                ),
+                name: name.to_string(),
+                context_name: None,
+                plugin_ids: vec![],
+                product_ids: vec![],
+                product_name: product_name.map(|n| n.to_string()),
+                content_hash: content
+                    .map(|c| calculate_persistent_non_crypto_hash_one_shot(c.as_bytes())),
+                db_specific_preview_file: None,
+                is_supported: true,
+                is_available: true,
+                metadata: Default::default(),
+            },
+            PotPresetKind::Internal(InternalPotPresetKind { plugin_id: None }),
+        )
+    }
+}
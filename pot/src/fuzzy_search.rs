@@ -0,0 +1,157 @@
+//! Typo-tolerant matching and relevance scoring for plain search expressions.
+
+/// Score for each query word which occurs literally in the text.
+const WORD_MATCH_SCORE: u32 = 30;
+/// Additional score if the word occurs at the very beginning of the text.
+const PREFIX_BONUS: u32 = 30;
+/// Additional score if the word occurs at the beginning of a word within the text.
+const WORD_BOUNDARY_BONUS: u32 = 20;
+/// Score for each query word which occurs in the text with typos (minus a penalty per typo).
+const TYPO_MATCH_SCORE: u32 = 15;
+const TYPO_PENALTY: u32 = 5;
+/// Additional score if the complete query occurs literally in the text.
+const EXACT_PHRASE_BONUS: u32 = 50;
+/// Maximum additional score for texts which are not much longer than the query.
+const MAX_LENGTH_BONUS: usize = 20;
+
+/// Returns a relevance score if the text matches the query, `None` otherwise.
+///
+/// Both query and text must be lowercase. Each whitespace-separated query word must occur in the
+/// text. If typos are tolerated, a query word also matches the beginning of a text word if the
+/// edit distance is small enough (depending on the length of the query word).
+pub fn fuzzy_match(query: &str, text: &str, tolerate_typos: bool) -> Option<u32> {
+    let mut score = 0;
+    for query_word in query.split_whitespace() {
+        score += score_query_word(query_word, text, tolerate_typos)?;
+    }
+    if let Some(pos) = text.find(query) {
+        score += EXACT_PHRASE_BONUS + position_bonus(text, pos);
+    }
+    let text_len = text.chars().count().max(1);
+    let length_bonus = (query.chars().count() * MAX_LENGTH_BONUS / text_len).min(MAX_LENGTH_BONUS);
+    Some(score + length_bonus as u32)
+}
+
+fn score_query_word(query_word: &str, text: &str, tolerate_typos: bool) -> Option<u32> {
+    if let Some(pos) = text.find(query_word) {
+        return Some(WORD_MATCH_SCORE + position_bonus(text, pos));
+    }
+    if !tolerate_typos {
+        return None;
+    }
+    let query_chars: Vec<char> = query_word.chars().collect();
+    let max_typos = max_typos(query_chars.len());
+    if max_typos == 0 {
+        return None;
+    }
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|text_word| {
+            let text_chars: Vec<char> = text_word.chars().collect();
+            prefix_edit_distance(&query_chars, &text_chars)
+        })
+        .filter(|distance| *distance <= max_typos)
+        .map(|distance| TYPO_MATCH_SCORE - distance as u32 * TYPO_PENALTY)
+        .max()
+}
+
+fn position_bonus(text: &str, byte_pos: usize) -> u32 {
+    if byte_pos == 0 {
+        return PREFIX_BONUS;
+    }
+    let at_word_boundary = text[..byte_pos]
+        .chars()
+        .next_back()
+        .is_some_and(|c| !c.is_alphanumeric());
+    if at_word_boundary {
+        WORD_BOUNDARY_BONUS
+    } else {
+        0
+    }
+}
+
+/// Short words must match exactly, otherwise almost everything would match.
+fn max_typos(char_count: usize) -> usize {
+    match char_count {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Returns the smallest edit distance between the query and any prefix of the text
+/// (optimal string alignment distance, so swapping two adjacent characters counts as one typo).
+fn prefix_edit_distance(query: &[char], text: &[char]) -> usize {
+    let width = text.len() + 1;
+    // d[i * width + j] = distance between first i query chars and first j text chars
+    let mut d = vec![0; (query.len() + 1) * width];
+    for (i, row) in d.chunks_mut(width).enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+    for i in 1..=query.len() {
+        for j in 1..=text.len() {
+            let cost = usize::from(query[i - 1] != text[j - 1]);
+            let mut value = (d[(i - 1) * width + j] + 1)
+                .min(d[i * width + j - 1] + 1)
+                .min(d[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && query[i - 1] == text[j - 2] && query[i - 2] == text[j - 1] {
+                value = value.min(d[(i - 2) * width + j - 2] + 1);
+            }
+            d[i * width + j] = value;
+        }
+    }
+    let last_row = &d[query.len() * width..];
+    last_row.iter().copied().min().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_word_boundary_higher() {
+        // Given
+        let query = "pads";
+        // When
+        let epic_pads = fuzzy_match(query, "epic pads 01", true).unwrap();
+        let drumpads = fuzzy_match(query, "drumpads kit", true).unwrap();
+        let pads_first = fuzzy_match(query, "pads of fury", true).unwrap();
+        // Then
+        assert!(pads_first > epic_pads);
+        assert!(epic_pads > drumpads);
+    }
+
+    #[test]
+    fn tolerate_typos() {
+        assert!(fuzzy_match("strngs", "lush strings", true).is_some());
+        assert!(fuzzy_match("strimgs", "lush strings", true).is_some());
+        assert!(fuzzy_match("strngs", "lush strings", false).is_none());
+        // Short words must match exactly
+        assert!(fuzzy_match("pda", "epic pads", true).is_none());
+        // All query words must match
+        assert!(fuzzy_match("warm pda", "warm bass", true).is_none());
+    }
+
+    #[test]
+    fn prefer_exact_matches() {
+        let exact = fuzzy_match("strings", "lush strings", true).unwrap();
+        let with_typo = fuzzy_match("strigns", "lush strings", true).unwrap();
+        assert!(exact > with_typo);
+    }
+
+    #[test]
+    fn compute_prefix_edit_distance() {
+        let distance = |a: &str, b: &str| {
+            let a: Vec<_> = a.chars().collect();
+            let b: Vec<_> = b.chars().collect();
+            prefix_edit_distance(&a, &b)
+        };
+        assert_eq!(distance("pads", "padsynth"), 0);
+        assert_eq!(distance("pasd", "pads"), 1);
+        assert_eq!(distance("bsas", "bass"), 1);
+        assert_eq!(distance("xyz", "pads"), 3);
+    }
+}
@@ -0,0 +1,257 @@
+use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
+use camino::Utf8PathBuf;
+use reaper_high::Reaper;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+/// Increase whenever the format of the cached data changes. Old caches will be discarded then.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// Identifies a certain state of a file on disk.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub modified_millis: u64,
+    pub size: u64,
+}
+
+impl FileStamp {
+    pub fn from_metadata(metadata: &fs::Metadata) -> Option<Self> {
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        let stamp = Self {
+            modified_millis: modified.as_millis() as u64,
+            size: metadata.len(),
+        };
+        Some(stamp)
+    }
+}
+
+/// Returns the location of the index cache file with the given name.
+pub fn index_cache_file(name: &str) -> Utf8PathBuf {
+    Reaper::get()
+        .resource_path()
+        .join("Helgoboss/Pot/IndexCache")
+        .join(format!("{name}.msgpack"))
+}
+
+/// A file found when scanning a database root directory.
+pub struct ScannedFile {
+    pub path: PathBuf,
+    /// Path relative to the root directory. Used as cache key.
+    pub relative_path: String,
+    pub stamp: FileStamp,
+}
+
+/// Lists all files in the given root directory which satisfy the given predicate.
+///
+/// Files whose relative paths can't be represented as valid UTF-8 are skipped.
+pub fn scan_files(
+    root_dir: &Path,
+    max_depth: Option<usize>,
+    include: impl Fn(&Path) -> bool,
+) -> Vec<ScannedFile> {
+    let mut walk_dir = WalkDir::new(root_dir).follow_links(true);
+    if let Some(max_depth) = max_depth {
+        walk_dir = walk_dir.max_depth(max_depth);
+    }
+    walk_dir
+        .into_iter()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            if !entry.file_type().is_file() || !include(entry.path()) {
+                return None;
+            }
+            let relative_path = entry.path().strip_prefix(root_dir).ok()?;
+            let scanned_file = ScannedFile {
+                relative_path: relative_path.to_str()?.to_string(),
+                stamp: FileStamp::from_metadata(&entry.metadata().ok()?)?,
+                path: entry.into_path(),
+            };
+            Some(scanned_file)
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile<T> {
+    stamp: FileStamp,
+    /// `None` if the file couldn't be processed. We don't try again until the file changes.
+    data: Option<T>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFileContent<T> {
+    version: u32,
+    files: NonCryptoHashMap<String, CachedFile<T>>,
+}
+
+/// Persistent index data of a file-based database provider, one entry per file.
+///
+/// Makes it possible to refresh databases incrementally: Only new or changed files (judged by
+/// modification time and size) need to be processed.
+///
+/// The cached data must not depend on the plug-in database, otherwise newly installed plug-ins
+/// wouldn't be picked up.
+pub struct FileIndexCache<T> {
+    file_path: Utf8PathBuf,
+    files: NonCryptoHashMap<String, CachedFile<T>>,
+    /// Loading is deferred until the first refresh, which usually happens in the Pot worker.
+    loaded: bool,
+    dirty: bool,
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> FileIndexCache<T> {
+    pub fn new(file_path: Utf8PathBuf) -> Self {
+        Self {
+            file_path,
+            files: Default::default(),
+            loaded: false,
+            dirty: false,
+        }
+    }
+
+    /// Updates the cache so that it contains exactly the given files and returns their data.
+    ///
+    /// Invokes `process` only for files that are new or have changed. Saves the cache if
+    /// anything changed.
+    pub fn update<'a>(
+        &mut self,
+        scanned_files: &'a [ScannedFile],
+        mut process: impl FnMut(&ScannedFile) -> Option<T>,
+    ) -> Vec<(&'a ScannedFile, T)> {
+        self.ensure_loaded();
+        let result = scanned_files
+            .iter()
+            .filter_map(|scanned_file| {
+                let data = match self.files.get(&scanned_file.relative_path) {
+                    Some(cached_file) if cached_file.stamp == scanned_file.stamp => {
+                        cached_file.data.clone()
+                    }
+                    _ => {
+                        let data = process(scanned_file);
+                        let cached_file = CachedFile {
+                            stamp: scanned_file.stamp,
+                            data: data.clone(),
+                        };
+                        self.files
+                            .insert(scanned_file.relative_path.clone(), cached_file);
+                        self.dirty = true;
+                        data
+                    }
+                };
+                Some((scanned_file, data?))
+            })
+            .collect();
+        // Forget removed files
+        let existing_paths: NonCryptoHashSet<&str> = scanned_files
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        let count_before = self.files.len();
+        self.files
+            .retain(|path, _| existing_paths.contains(path.as_str()));
+        if self.files.len() != count_before {
+            self.dirty = true;
+        }
+        if self.dirty {
+            match self.save() {
+                Ok(_) => self.dirty = false,
+                Err(e) => tracing::warn!("Couldn't save Pot index cache: {e}"),
+            }
+        }
+        result
+    }
+
+    fn ensure_loaded(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+        let Ok(bytes) = fs::read(&self.file_path) else {
+            return;
+        };
+        match rmp_serde::from_slice::<CacheFileContent<T>>(&bytes) {
+            Ok(content) if content.version == CACHE_FORMAT_VERSION => {
+                self.files = content.files;
+            }
+            Ok(_) => {
+                tracing::debug!("Discarding Pot index cache with outdated format");
+            }
+            Err(e) => {
+                tracing::warn!("Couldn't read Pot index cache: {e}");
+            }
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        if let Some(dir) = self.file_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = CacheFileContentRef {
+            version: CACHE_FORMAT_VERSION,
+            files: &self.files,
+        };
+        let bytes = rmp_serde::to_vec(&content)?;
+        fs::write(&self.file_path, bytes)?;
+        Ok(())
+    }
+}
+
+/// Replaces the content of the given file with garbage of the same size and restores the
+/// modification time, so that only the index cache knows the original content.
+#[cfg(test)]
+pub fn corrupt_file_keeping_stamp(path: &Path) {
+    let metadata = fs::metadata(path).unwrap();
+    fs::write(path, vec![0xff; metadata.len() as usize]).unwrap();
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(metadata.modified().unwrap())
+        .unwrap();
+}
+
+#[derive(Serialize)]
+struct CacheFileContentRef<'a, T> {
+    version: u32,
+    files: &'a NonCryptoHashMap<String, CachedFile<T>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn only_process_changed_files() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache_file = Utf8PathBuf::from_path_buf(dir.path().join("cache.msgpack")).unwrap();
+        let file = |relative_path: &str, modified_millis| ScannedFile {
+            path: dir.path().join(relative_path),
+            relative_path: relative_path.to_string(),
+            stamp: FileStamp {
+                modified_millis,
+                size: 10,
+            },
+        };
+        let process_count = Cell::new(0);
+        let process = |f: &ScannedFile| {
+            process_count.set(process_count.get() + 1);
+            Some(f.relative_path.to_uppercase())
+        };
+        let mut cache = FileIndexCache::<String>::new(cache_file.clone());
+        cache.update(&[file("a", 1), file("b", 1)], process);
+        // When
+        let mut reloaded_cache = FileIndexCache::<String>::new(cache_file);
+        let files = [file("a", 1), file("b", 2), file("c", 1)];
+        let result = reloaded_cache.update(&files, process);
+        // Then
+        assert_eq!(process_count.get(), 4);
+        let data: Vec<_> = result.into_iter().map(|(_, data)| data).collect();
+        assert_eq!(data, vec!["A", "B", "C"]);
+    }
+}
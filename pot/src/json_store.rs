@@ -0,0 +1,112 @@
+//! Persistence of the small local Pot stores such as user metadata, load history and preset
+//! collections.
+
+use anyhow::Context;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::Local;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+
+/// Reads the content of the given JSON store file.
+///
+/// A missing or unreadable file results in empty content (we don't want Pot to fail just because
+/// of that). An unreadable file is moved aside first, so that saving the store doesn't overwrite
+/// data which might still be recoverable, e.g. if it has been written by a newer version.
+/// `label` describes the content in log messages.
+pub fn load_json_store<T: DeserializeOwned + Default>(file_path: &Utf8Path, label: &str) -> T {
+    if !file_path.exists() {
+        return T::default();
+    }
+    let read = || -> anyhow::Result<T> {
+        let json = fs::read_to_string(file_path)?;
+        Ok(serde_json::from_str(&json)?)
+    };
+    match read() {
+        Ok(content) => content,
+        Err(e) => {
+            let corrupt_file_path = corrupt_file_path(file_path);
+            // Copying is the fallback if the file can't be moved, e.g. because it's locked
+            let move_result = fs::rename(file_path, &corrupt_file_path)
+                .or_else(|_| fs::copy(file_path, &corrupt_file_path).map(|_| ()));
+            match move_result {
+                Ok(_) => tracing::warn!(
+                    "Couldn't read {label}, backed up file as {corrupt_file_path} and starting empty: {e}"
+                ),
+                Err(move_error) => tracing::error!(
+                    "Couldn't read {label} ({e}) and couldn't back up file: {move_error}"
+                ),
+            }
+            T::default()
+        }
+    }
+}
+
+/// Writes the given content to the given JSON store file, creating the Pot directory if necessary.
+///
+/// Writes to a temporary file first and then replaces the store file with it, so that a crash
+/// during writing doesn't leave a truncated store file.
+pub fn save_json_store(
+    file_path: &Utf8Path,
+    label: &str,
+    content: &impl Serialize,
+) -> anyhow::Result<()> {
+    if let Some(dir) = file_path.parent() {
+        fs::create_dir_all(dir).context("couldn't create Pot directory")?;
+    }
+    let json = serde_json::to_string_pretty(content)?;
+    let temp_file_path = Utf8PathBuf::from(format!("{file_path}.tmp"));
+    fs::write(&temp_file_path, json).with_context(|| format!("couldn't write {label} file"))?;
+    fs::rename(&temp_file_path, file_path)
+        .with_context(|| format!("couldn't replace {label} file"))?;
+    Ok(())
+}
+
+fn corrupt_file_path(file_path: &Utf8Path) -> Utf8PathBuf {
+    let stem = file_path.file_stem().unwrap_or("store");
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+    file_path.with_file_name(format!("{stem}.corrupt-{timestamp}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn save_and_load() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = Utf8PathBuf::from_path_buf(dir.path().join("Pot/store.json")).unwrap();
+        let content = BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        // When
+        save_json_store(&file_path, "test store", &content).unwrap();
+        let loaded: BTreeMap<String, i32> = load_json_store(&file_path, "test store");
+        // Then
+        assert_eq!(loaded, content);
+    }
+
+    #[test]
+    fn keep_unreadable_file_when_saving() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = Utf8PathBuf::from_path_buf(dir.path().join("store.json")).unwrap();
+        fs::write(&file_path, "{ invalid").unwrap();
+        // When
+        let mut content: BTreeMap<String, i32> = load_json_store(&file_path, "test store");
+        content.insert("a".to_string(), 1);
+        save_json_store(&file_path, "test store", &content).unwrap();
+        // Then
+        let other_files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.as_path() != file_path.as_std_path())
+            .collect();
+        assert_eq!(other_files.len(), 1);
+        let file_name = other_files[0].file_name().unwrap().to_str().unwrap();
+        assert!(file_name.starts_with("store.corrupt-"));
+        assert_eq!(fs::read_to_string(&other_files[0]).unwrap(), "{ invalid");
+        let loaded: BTreeMap<String, i32> = load_json_store(&file_path, "test store");
+        assert_eq!(loaded, content);
+    }
+}
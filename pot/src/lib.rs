@@ -8,7 +8,7 @@
 
 use base::{blocking_lock, blocking_lock_arc, blocking_write_lock, hash_util, SoundPlayer};
 
-use enumset::EnumSet;
+use enumset::{enum_set, EnumSet};
 use helgobox_api::persistence::PotFilterKind;
 use reaper_high::{Chunk, Fx, FxChain, GroupingBehavior, Project, Reaper, Track};
 use reaper_medium::{
@@ -16,7 +16,7 @@ use reaper_medium::{
     MasterTrackBehavior, ParamId, ReaperNormalizedFxParamValue, ReaperVolumeValue, RecordingInput,
 };
 use std::borrow::Cow;
-use std::cell::{Ref, RefMut};
+use std::cell::{OnceCell, Ref, RefMut};
 use std::error::Error;
 use std::ffi::CString;
 use std::fs;
@@ -33,9 +33,12 @@ use wildmatch::WildMatch;
 
 mod api;
 pub use api::*;
+mod audio_metadata;
+mod lv2;
 mod nks;
 mod pot_database;
 use crate::providers::komplete::NksFile;
+use crate::providers::vst3::Vst3PresetFile;
 pub use pot_database::*;
 
 mod plugin_id;
@@ -44,8 +47,27 @@ use crate::plugins::{PluginCommon, PluginCore};
 use crate::provider_database::{DatabaseId, FIL_IS_AVAILABLE_FALSE, FIL_IS_AVAILABLE_TRUE};
 pub use plugin_id::*;
 
+mod duplicates;
+pub use duplicates::*;
+mod index_cache;
+mod json_store;
+mod load_history;
+pub use load_history::*;
+mod preset_collections;
+pub use preset_collections::*;
 mod provider_database;
 pub mod providers;
+mod turtle;
+mod user_metadata;
+pub use user_metadata::*;
+
+mod settings;
+pub use settings::*;
+mod search_query;
+pub use search_query::*;
+
+mod fuzzy_search;
+pub use fuzzy_search::*;
 mod worker;
 pub use worker::*;
 mod escape_catcher;
@@ -141,6 +163,7 @@ pub trait PotIntegration {
     fn notify_preset_changed(&self, id: Option<PresetId>);
     fn notify_filter_changed(&self, kind: PotFilterKind, filter: OptFilter);
     fn notify_indexes_rebuilt(&self);
+    fn notify_user_metadata_changed(&self, id: PresetId);
     /// Returns an FX instance which must not be removed (e.g. in the process of loading a preset).
     ///
     /// This should be the FX holding the ReaLearn instance which controls the pot.
@@ -153,11 +176,15 @@ pub struct RuntimePotUnit {
     pub runtime_state: RuntimeState,
     pub filter_item_collections: FilterItemCollections,
     pub supported_filter_kinds: EnumSet<PotFilterKind>,
-    pub preset_collection: PresetCollection,
+    pub preset_collection: PresetIdSet,
     pub wasted_runs: u32,
     pub wasted_duration: Duration,
     pub stats: Stats,
     build_counter: u64,
+    /// Revision of the Pot database which the current collections were built from.
+    pot_db_revision: u8,
+    /// Load history revision of the Pot database which the current collections were built from.
+    load_history_revision: u8,
     sound_player: SoundPlayer,
     preview_volume: ReaperVolumeValue,
     pub default_load_preset_window_behavior: LoadPresetWindowBehavior,
@@ -165,10 +192,32 @@ pub struct RuntimePotUnit {
     pub name_track_after_preset: bool,
     show_excluded_filter_items: bool,
     running_background_task: Option<RunningBackgroundTask>,
+    /// Error of the search expression which the current collections were built from.
+    search_query_error: Option<SearchQueryError>,
+    #[derivative(Debug = "ignore")]
+    last_load_undo_point: Option<PresetLoadUndoPoint>,
     #[derivative(Debug = "ignore")]
     integration: BoxedPotIntegration,
 }
 
+/// State of an FX chain before a preset has been loaded into it.
+struct PresetLoadUndoPoint {
+    chain: FxChain,
+    /// `None` if the chain didn't contain any FX.
+    chain_chunk: Option<String>,
+}
+
+impl PresetLoadUndoPoint {
+    fn capture(chain: &FxChain) -> Result<Self, Box<dyn Error>> {
+        let chain_chunk = chain.chunk()?.map(|region| region.content().to_string());
+        let undo_point = Self {
+            chain: chain.clone(),
+            chain_chunk,
+        };
+        Ok(undo_point)
+    }
+}
+
 #[derive(Debug)]
 struct RunningBackgroundTask {
     start_time: Instant,
@@ -256,6 +305,9 @@ pub struct Stats {
     pub filter_query_duration: Duration,
     pub preset_query_duration: Duration,
     pub preview_filter_duration: Duration,
+    pub user_metadata_filter_duration: Duration,
+    pub search_query_filter_duration: Duration,
+    pub duplicate_filter_duration: Duration,
     pub sort_duration: Duration,
     pub index_duration: Duration,
 }
@@ -266,6 +318,9 @@ impl Stats {
             + self.filter_query_duration
             + self.preset_query_duration
             + self.preview_filter_duration
+            + self.user_metadata_filter_duration
+            + self.search_query_filter_duration
+            + self.duplicate_filter_duration
             + self.sort_duration
             + self.index_duration
     }
@@ -374,12 +429,24 @@ pub struct SearchEvaluator {
     processed_search_expression: String,
     options: SearchOptions,
     wild_match: Option<WildMatch>,
+    /// Set if the search expression uses query syntax. In this case, the processed search
+    /// expression is empty (so the providers deliver everything) and the query is evaluated by
+    /// the pot database itself.
+    query: Option<SearchQuery>,
+    query_error: Option<SearchQueryError>,
 }
 
 #[derive(Clone, Debug)]
 pub struct SearchOptions {
     pub use_wildcards: bool,
     pub search_fields: EnumSet<SearchField>,
+    /// Tolerates typos in plain search expressions. Ignored if wildcards are used.
+    ///
+    /// Off by default because it makes searching large databases considerably slower (Komplete
+    /// has to evaluate each preset instead of using SQL `LIKE`).
+    pub fuzzy: bool,
+    /// Sorts presets by relevance (instead of by name) while searching.
+    pub sort_by_relevance: bool,
 }
 
 impl Default for SearchOptions {
@@ -387,6 +454,8 @@ impl Default for SearchOptions {
         Self {
             use_wildcards: false,
             search_fields: EnumSet::all(),
+            fuzzy: false,
+            sort_by_relevance: true,
         }
     }
 }
@@ -399,17 +468,57 @@ pub enum SearchField {
     ProductName,
     #[strum(serialize = "Extension")]
     FileExtension,
+    /// Author, vendor, comment and database-specific metadata such as tempo and key of samples.
+    #[strum(serialize = "Metadata")]
+    Metadata,
+    /// Notes attached by the user. Evaluated by the pot database itself, not by the providers.
+    #[strum(serialize = "Notes")]
+    Notes,
 }
 
 pub trait SearchInput {
     fn preset_name(&self) -> &str;
     fn product_name(&self) -> Option<Cow<str>>;
     fn file_extension(&self) -> Option<&str>;
+
+    /// Additional text that should be searchable via [`SearchField::Metadata`].
+    fn metadata(&self) -> Option<Cow<str>> {
+        None
+    }
 }
 
 impl SearchEvaluator {
     pub fn new(raw_search_expression: &str, options: SearchOptions) -> Self {
-        let processed_search_expression = raw_search_expression.trim().to_lowercase();
+        Self::new_internal(raw_search_expression, options, true)
+    }
+
+    /// Creates an evaluator which treats the search expression as plain text, even if it contains
+    /// query syntax (useful for looking up presets by name).
+    pub fn new_plain(raw_search_expression: &str, options: SearchOptions) -> Self {
+        Self::new_internal(raw_search_expression, options, false)
+    }
+
+    fn new_internal(
+        raw_search_expression: &str,
+        options: SearchOptions,
+        support_queries: bool,
+    ) -> Self {
+        let trimmed_search_expression = raw_search_expression.trim();
+        let (query, query_error) =
+            if support_queries && SearchQuery::is_structured(trimmed_search_expression) {
+                match SearchQuery::parse(trimmed_search_expression, options.use_wildcards) {
+                    Ok(q) => (Some(q), None),
+                    // Fall back to plain search
+                    Err(e) => (None, Some(e)),
+                }
+            } else {
+                (None, None)
+            };
+        let processed_search_expression = if query.is_some() {
+            String::new()
+        } else {
+            trimmed_search_expression.to_lowercase()
+        };
         Self {
             wild_match: if options.use_wildcards {
                 Some(WildMatch::new(&processed_search_expression))
@@ -418,9 +527,21 @@ impl SearchEvaluator {
             },
             processed_search_expression,
             options,
+            query,
+            query_error,
         }
     }
 
+    /// Returns the structured query if the search expression uses query syntax.
+    pub fn query(&self) -> Option<&SearchQuery> {
+        self.query.as_ref()
+    }
+
+    /// Returns the error if the search expression looks like a query but couldn't be parsed.
+    pub fn query_error(&self) -> Option<&SearchQueryError> {
+        self.query_error.as_ref()
+    }
+
     pub fn processed_search_expression(&self) -> &str {
         &self.processed_search_expression
     }
@@ -433,6 +554,28 @@ impl SearchEvaluator {
         self.wild_match.is_some()
     }
 
+    /// Returns whether typos should be tolerated when matching.
+    pub fn use_fuzzy_matching(&self) -> bool {
+        self.options.fuzzy && !self.use_wildcards()
+    }
+
+    /// Returns whether presets should be sorted by [`Self::relevance`].
+    pub fn sorts_by_relevance(&self) -> bool {
+        self.options.sort_by_relevance && !self.processed_search_expression.is_empty()
+    }
+
+    /// Returns how well the given preset name matches the search expression (higher is better).
+    ///
+    /// Presets which only match because of other search fields get the lowest score.
+    pub fn relevance(&self, preset_name: &str) -> u32 {
+        fuzzy_match(
+            &self.processed_search_expression,
+            &preset_name.to_lowercase(),
+            self.use_fuzzy_matching(),
+        )
+        .unwrap_or(0)
+    }
+
     pub fn matches(&self, input: impl SearchInput) -> bool {
         if self.processed_search_expression.is_empty() {
             return true;
@@ -456,13 +599,44 @@ impl SearchEvaluator {
                         false
                     }
                 }
+                SearchField::Metadata => {
+                    if let Some(metadata) = input.metadata() {
+                        self.matches_internal(&metadata)
+                    } else {
+                        false
+                    }
+                }
+                // Providers don't know about user notes
+                SearchField::Notes => false,
             })
     }
 
+    /// Returns whether the search expression is non-empty and user notes should be searched.
+    pub fn wants_notes_search(&self) -> bool {
+        !self.processed_search_expression.is_empty()
+            && self.options.search_fields.contains(SearchField::Notes)
+    }
+
+    /// Returns an evaluator with the same options but an empty search expression, which matches
+    /// everything.
+    pub fn without_search_expression(&self) -> Self {
+        Self::new("", self.options.clone())
+    }
+
+    pub fn matches_notes(&self, notes: &str) -> bool {
+        !notes.is_empty() && self.matches_internal(notes)
+    }
+
     fn matches_internal(&self, text: &str) -> bool {
         let lowercase_text = text.to_lowercase();
         match &self.wild_match {
-            None => lowercase_text.contains(&self.processed_search_expression),
+            None => {
+                if self.options.fuzzy {
+                    fuzzy_match(&self.processed_search_expression, &lowercase_text, true).is_some()
+                } else {
+                    lowercase_text.contains(&self.processed_search_expression)
+                }
+            }
             Some(wild_match) => wild_match.matches(&lowercase_text),
         }
     }
@@ -475,6 +649,8 @@ pub enum ChangeHint {
     Normal,
     Filter(PotFilterKind),
     SearchExpression,
+    /// Tags, ratings or notes of a preset have changed.
+    UserMetadata,
 }
 
 impl ChangeHint {
@@ -483,6 +659,7 @@ impl ChangeHint {
         match self {
             Normal | TotalRefresh => EnumSet::all(),
             SearchExpression => EnumSet::empty(),
+            UserMetadata => enum_set!(PotFilterKind::Tag),
             Filter(changed_kind) => changed_kind.dependent_kinds().collect(),
         }
     }
@@ -552,7 +729,7 @@ pub struct PersistentState {
     preset_id: Option<String>,
 }
 
-type PresetCollection = NonCryptoIndexSet<PresetId>;
+type PresetIdSet = NonCryptoIndexSet<PresetId>;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct PersistentFilterSettings {
@@ -574,12 +751,16 @@ impl RuntimePotUnit {
             wasted_duration: Default::default(),
             stats: Default::default(),
             build_counter: 0,
+            pot_db_revision: 0,
+            load_history_revision: 0,
             preview_volume: sound_player.volume().unwrap_or_default(),
             sound_player,
             destination_descriptor: Default::default(),
             name_track_after_preset: true,
             show_excluded_filter_items: false,
             running_background_task: None,
+            search_query_error: None,
+            last_load_undo_point: None,
             integration,
             default_load_preset_window_behavior: Default::default(),
         };
@@ -650,6 +831,31 @@ impl RuntimePotUnit {
         );
     }
 
+    /// Returns the user metadata (tags, rating, notes) of the given preset.
+    pub fn user_metadata(&self, preset_id: PresetId) -> Option<PresetUserMetadata> {
+        let preset = pot_db().find_preset_by_id(preset_id)?;
+        Some(pot_db().user_metadata(&preset.common.persistent_id))
+    }
+
+    /// Modifies the user metadata (tags, rating, notes) of the given preset and rebuilds the
+    /// collections because tag and rating filters might be affected.
+    ///
+    /// Saving happens a bit later in the Pot worker.
+    pub fn update_user_metadata<R>(
+        &mut self,
+        preset_id: PresetId,
+        shared_self: SharedRuntimePotUnit,
+        f: impl FnOnce(&mut PresetUserMetadata) -> R,
+    ) -> anyhow::Result<R> {
+        let preset = pot_db()
+            .find_preset_by_id(preset_id)
+            .context("couldn't find preset")?;
+        let result = pot_db().update_user_metadata(preset_id, &preset, f);
+        self.integration.notify_user_metadata_changed(preset_id);
+        self.rebuild_collections(shared_self, ChangeHint::UserMetadata, Debounce::No);
+        Ok(result)
+    }
+
     pub fn play_preview(&mut self, preset_id: PresetId) -> anyhow::Result<()> {
         let preset = pot_db()
             .find_preset_by_id(preset_id)
@@ -729,10 +935,40 @@ impl RuntimePotUnit {
         let window_behavior = options
             .window_behavior_override
             .unwrap_or(self.default_load_preset_window_behavior);
-        match self.load_preset_at_internal(
+        // Remember the state of the destination chain so the load can be reverted. The shim
+        // fallback builds the destination again after the first attempt, so only the state before
+        // the first attempt counts.
+        let undo_point = OnceCell::new();
+        let build_destination = |pot_unit: &mut RuntimePotUnit| -> Result<_, &'static str> {
+            let destination = build_destination(pot_unit)?;
+            undo_point.get_or_init(|| PresetLoadUndoPoint::capture(&destination.chain).ok());
+            Ok(destination)
+        };
+        let result = self.load_preset_at_with_shim_fallback(
             preset,
             window_behavior,
             options.audio_sample_behavior,
+            &build_destination,
+        );
+        if result.is_ok() {
+            if let Some(undo_point) = undo_point.into_inner().flatten() {
+                self.last_load_undo_point = Some(undo_point);
+            }
+        }
+        result
+    }
+
+    fn load_preset_at_with_shim_fallback(
+        &mut self,
+        preset: &PotPreset,
+        window_behavior: LoadPresetWindowBehavior,
+        audio_sample_behavior: LoadAudioSampleBehavior,
+        build_destination: &impl Fn(&mut RuntimePotUnit) -> Result<Destination, &'static str>,
+    ) -> Result<Fx, LoadPresetError> {
+        match self.load_preset_at_internal(
+            preset,
+            window_behavior,
+            audio_sample_behavior,
             build_destination,
         ) {
             Ok(fx) => Ok(fx),
@@ -753,7 +989,7 @@ impl RuntimePotUnit {
                     &shim_file_path,
                     build_destination,
                     window_behavior,
-                    options.audio_sample_behavior,
+                    audio_sample_behavior,
                     true,
                     &protected_fx,
                 )?;
@@ -798,6 +1034,15 @@ impl RuntimePotUnit {
                     protected_fx,
                 )?
             }
+            "vstpreset" => {
+                let dest = build_destination(self)?;
+                load_vst3_preset(
+                    preset_file.as_std_path(),
+                    &dest,
+                    window_behavior,
+                    protected_fx,
+                )?
+            }
             "rfxchain" => {
                 let dest = build_destination(self)?;
                 load_rfx_chain_preset_using_chunks(
@@ -887,9 +1132,39 @@ impl RuntimePotUnit {
         };
         self.integration
             .set_current_fx_preset(outcome.fx.clone(), current_preset);
+        pot_db().record_preset_load(preset, create_load_history_entry(preset, &outcome.fx));
         outcome.fx
     }
 
+    /// Returns whether there's a preset load which can be reverted.
+    pub fn can_revert_last_preset_load(&self) -> bool {
+        self.last_load_undo_point.is_some()
+    }
+
+    /// Restores the FX chain into which the last preset has been loaded to its previous state.
+    pub fn revert_last_preset_load(&mut self) -> Result<(), Box<dyn Error>> {
+        let undo_point = self
+            .last_load_undo_point
+            .take()
+            .ok_or("there's no preset load to revert")?;
+        let _ = self.sound_player.stop();
+        let chain = &undo_point.chain;
+        if chain.fxs().any(|fx| &fx == self.protected_fx()) {
+            return Err(CANT_REMOVE_PROTECTED_FX.into());
+        }
+        match &undo_point.chain_chunk {
+            None => {
+                for fx in chain.fxs().rev() {
+                    chain.remove_fx(&fx)?;
+                }
+            }
+            Some(chunk) => {
+                chain.set_chunk(chunk)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn state(&self) -> &RuntimeState {
         &self.runtime_state
     }
@@ -976,6 +1251,12 @@ impl RuntimePotUnit {
         self.rebuild_collections(shared_self, ChangeHint::TotalRefresh, Debounce::No);
     }
 
+    /// Returns the error if the current search expression looks like a structured query but
+    /// couldn't be parsed.
+    pub fn search_query_error(&self) -> Option<&SearchQueryError> {
+        self.search_query_error.as_ref()
+    }
+
     pub fn create_build_input(&self) -> BuildInput {
         BuildInput {
             filters: self.runtime_state.filters,
@@ -1003,6 +1284,7 @@ impl RuntimePotUnit {
             return;
         }
         let build_input = self.create_build_input();
+        self.search_query_error = build_input.search_evaluator.query_error().cloned();
         self.build_counter += 1;
         let build_number = self.build_counter;
         let affected_kinds = change_hint.affected_kinds();
@@ -1033,6 +1315,8 @@ impl RuntimePotUnit {
                 pot_db().refresh();
             }
             let refresh_duration = refresh_start.elapsed();
+            let pot_db_revision = pot_db().revision();
+            let load_history_revision = pot_db().load_history_revision();
             // Build (expensive)
             let build_output = pot_db().build_collections(build_input, affected_kinds);
             // Set result (cheap)
@@ -1045,11 +1329,32 @@ impl RuntimePotUnit {
                 pot_unit.wasted_runs += 1;
                 return Ok(());
             }
+            pot_unit.pot_db_revision = pot_db_revision;
+            pot_unit.load_history_revision = load_history_revision;
             pot_unit.notify_build_outcome_ready(build_output, affected_kinds, refresh_duration);
             Ok(())
         });
     }
 
+    /// Rebuilds the collections if the Pot database has been refreshed in the meantime, e.g.
+    /// because watched directories have changed, or if a shown load history has changed.
+    pub fn rebuild_collections_if_pot_db_changed(&mut self, shared_self: SharedRuntimePotUnit) {
+        if self.running_background_task.is_some() {
+            return;
+        }
+        let current_revision = pot_db().revision();
+        let current_load_history_revision = pot_db().load_history_revision();
+        let load_history_changed = current_load_history_revision != self.load_history_revision
+            && pot_db().load_history_database_is_selected(&self.runtime_state.filters);
+        if current_revision == self.pot_db_revision && !load_history_changed {
+            return;
+        }
+        // Prevents triggering the rebuild again until it's finished
+        self.pot_db_revision = current_revision;
+        self.load_history_revision = current_load_history_revision;
+        self.rebuild_collections(shared_self, ChangeHint::Normal, Debounce::No);
+    }
+
     pub fn is_refreshing(&self) -> bool {
         self.running_background_task
             .as_ref()
@@ -1275,6 +1580,18 @@ pub struct PotPresetMetaData {
     pub modification_date: Option<NaiveDateTime>,
 }
 
+impl PotPresetMetaData {
+    /// Returns the text which is searched via [`SearchField::Metadata`].
+    ///
+    /// Both plain search and query search use this, so they find the same presets.
+    pub fn searchable_text(&self) -> Option<String> {
+        let texts = [&self.author, &self.vendor, &self.comment];
+        let mut texts = texts.into_iter().flatten().peekable();
+        texts.peek()?;
+        Some(texts.join("\n"))
+    }
+}
+
 impl PotPreset {
     pub fn new(common: PotPresetCommon, kind: PotPresetKind) -> Self {
         Self { common, kind }
@@ -1582,6 +1899,27 @@ fn load_internal_preset(
     )
 }
 
+fn load_vst3_preset(
+    path: &Path,
+    destination: &Destination,
+    window_behavior: LoadPresetWindowBehavior,
+    protected_fx: &Fx,
+) -> Result<LoadPresetOutcome, Box<dyn Error>> {
+    let plugin_id = Vst3PresetFile::load_plugin_id(path)?;
+    let path_str = path.to_str().ok_or("VST3 preset path is not valid UTF-8")?;
+    load_preset_single_fx(
+        plugin_id,
+        destination,
+        window_behavior,
+        protected_fx,
+        |fx| {
+            // REAPER accepts full paths to ".vstpreset" files as preset name
+            fx.activate_preset_by_name(path_str)?;
+            Ok(Default::default())
+        },
+    )
+}
+
 fn load_default_factory_preset(
     plugin_id: PluginId,
     destination: &Destination,
@@ -1725,6 +2063,25 @@ struct LoadPresetOutcome {
     banks: Vec<MacroParamBank>,
 }
 
+fn create_load_history_entry(preset: &PotPreset, fx: &Fx) -> PresetLoadHistoryEntry {
+    let track = fx.track();
+    PresetLoadHistoryEntry {
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        preset_id: preset.common.persistent_id.to_string(),
+        preset_name: preset.name().to_string(),
+        track_id: track.as_ref().map(|t| t.guid().to_string_without_braces()),
+        track_name: track
+            .as_ref()
+            .and_then(|t| t.name())
+            .map(|n| n.into_string()),
+        fx_id: fx
+            .get_or_query_guid()
+            .ok()
+            .map(|guid| guid.to_string_without_braces()),
+        fx_name: fx.name().into_string(),
+    }
+}
+
 pub struct FxEnsureOutput {
     pub fx: Fx,
     pub op: FxEnsureOp,
@@ -1930,8 +2287,13 @@ pub enum Debounce {
     Yes,
 }
 
+/// File extensions of audio files that REAPER can load and that Pot treats as audio samples.
+pub const AUDIO_FILE_EXTENSIONS: &[&str] = &["wav", "aif", "aiff", "flac", "ogg", "mp3"];
+
 fn is_audio_file_extension(ext: &str) -> bool {
-    matches!(ext, "wav" | "aif" | "ogg" | "mp3")
+    AUDIO_FILE_EXTENSIONS
+        .iter()
+        .any(|e| e.eq_ignore_ascii_case(ext))
 }
 
 pub fn preview_exists(preset: &PotPreset, reaper_resource_dir: &Utf8Path) -> bool {
@@ -1997,3 +2359,115 @@ pub fn create_plugin_factory_preset(
 }
 
 const CANT_REMOVE_PROTECTED_FX: &str = "Can't replace ReaLearn itself. Either set \"Load into\" correctly or put ReaLearn on the monitoring FX chain!";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestSearchInput {
+        preset_name: &'static str,
+        product_name: Option<&'static str>,
+    }
+
+    impl SearchInput for TestSearchInput {
+        fn preset_name(&self) -> &str {
+            self.preset_name
+        }
+
+        fn product_name(&self) -> Option<Cow<str>> {
+            self.product_name.map(Cow::Borrowed)
+        }
+
+        fn file_extension(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    fn input(preset_name: &'static str, product_name: Option<&'static str>) -> TestSearchInput {
+        TestSearchInput {
+            preset_name,
+            product_name,
+        }
+    }
+
+    #[test]
+    fn tolerate_typos_only_if_enabled() {
+        // Given
+        let exact = SearchEvaluator::new("strngs", SearchOptions::default());
+        let fuzzy = SearchEvaluator::new(
+            "strngs",
+            SearchOptions {
+                fuzzy: true,
+                ..Default::default()
+            },
+        );
+        let fuzzy_with_wildcards = SearchEvaluator::new(
+            "strngs",
+            SearchOptions {
+                fuzzy: true,
+                use_wildcards: true,
+                ..Default::default()
+            },
+        );
+        // Then
+        assert!(!exact.use_fuzzy_matching());
+        assert!(!exact.matches(input("Lush Strings", None)));
+        assert!(fuzzy.use_fuzzy_matching());
+        assert!(fuzzy.matches(input("Lush Strings", None)));
+        assert!(!fuzzy_with_wildcards.use_fuzzy_matching());
+        assert!(!fuzzy_with_wildcards.matches(input("Lush Strings", None)));
+    }
+
+    #[test]
+    fn respect_search_fields() {
+        // Given
+        let name_only = SearchEvaluator::new(
+            "serum",
+            SearchOptions {
+                search_fields: enum_set!(SearchField::PresetName),
+                ..Default::default()
+            },
+        );
+        let all_fields = SearchEvaluator::new("serum", SearchOptions::default());
+        // Then
+        assert!(!name_only.matches(input("Warm Pad", Some("Serum"))));
+        assert!(all_fields.matches(input("Warm Pad", Some("Serum"))));
+        assert!(all_fields
+            .without_search_expression()
+            .matches(input("Bass", None)));
+        assert!(all_fields.wants_notes_search());
+        assert!(!name_only.wants_notes_search());
+        assert!(all_fields.matches_notes("Sounds like Serum"));
+        assert!(!all_fields.matches_notes(""));
+    }
+
+    #[test]
+    fn distinguish_queries_from_plain_text() {
+        // Given
+        let query = SearchEvaluator::new("bass -sub", SearchOptions::default());
+        let plain = SearchEvaluator::new_plain("bass -sub", SearchOptions::default());
+        let invalid_query = SearchEvaluator::new("(bass", SearchOptions::default());
+        // Then
+        assert!(query.query().is_some());
+        assert_eq!(query.processed_search_expression(), "");
+        assert!(plain.query().is_none());
+        assert_eq!(plain.processed_search_expression(), "bass -sub");
+        assert!(invalid_query.query().is_none());
+        assert!(invalid_query.query_error().is_some());
+        assert!(invalid_query.matches(input("(Bass) Lead", None)));
+    }
+
+    #[test]
+    fn rank_by_relevance() {
+        // Given
+        let evaluator = SearchEvaluator::new("pad", SearchOptions::default());
+        // When
+        let pad_first = evaluator.relevance("Pad Strings");
+        let pad_later = evaluator.relevance("Warm Pad");
+        let no_match = evaluator.relevance("Bass");
+        // Then
+        assert!(evaluator.sorts_by_relevance());
+        assert!(pad_first > pad_later);
+        assert_eq!(no_match, 0);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::json_store::{load_json_store, save_json_store};
+use crate::PersistentPresetId;
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Maximum number of loads which are remembered. Older loads are forgotten.
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Records that a preset has been loaded into a particular FX.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PresetLoadHistoryEntry {
+    /// Unix timestamp in milliseconds.
+    pub timestamp: i64,
+    /// String representation of the persistent preset ID.
+    pub preset_id: String,
+    pub preset_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fx_id: Option<String>,
+    pub fx_name: String,
+}
+
+/// Local, persistent history of preset loads.
+#[derive(Debug)]
+pub struct PresetLoadHistory {
+    file_path: Utf8PathBuf,
+    /// Oldest entry first.
+    entries: Vec<PresetLoadHistoryEntry>,
+}
+
+#[derive(Default, Deserialize)]
+struct PresetLoadHistoryFileContent {
+    #[serde(default)]
+    loads: Vec<PresetLoadHistoryEntry>,
+}
+
+#[derive(Serialize)]
+struct PresetLoadHistoryFileContentRef<'a> {
+    loads: &'a [PresetLoadHistoryEntry],
+}
+
+impl PresetLoadHistory {
+    pub fn load(file_path: Utf8PathBuf) -> Self {
+        let content: PresetLoadHistoryFileContent =
+            load_json_store(&file_path, "Pot preset load history");
+        Self {
+            file_path,
+            entries: content.loads,
+        }
+    }
+
+    /// Returns the last load of each of the most recently loaded presets, most recent first.
+    pub fn recently_loaded(&self, max_count: usize) -> Vec<&PresetLoadHistoryEntry> {
+        let mut entries: Vec<&PresetLoadHistoryEntry> = Vec::new();
+        for entry in self.entries.iter().rev() {
+            if entries.len() == max_count {
+                break;
+            }
+            if !entries.iter().any(|e| e.preset_id == entry.preset_id) {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    /// Returns the last load of each of the most often loaded presets together with the number
+    /// of loads, most often loaded first. Presets with the same number of loads are ordered by
+    /// recency.
+    pub fn most_used(&self, max_count: usize) -> Vec<(&PresetLoadHistoryEntry, usize)> {
+        // Preset ID => (load count, index of last load)
+        let mut stats: HashMap<&str, (usize, usize)> = HashMap::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            let (count, last_index) = stats.entry(&entry.preset_id).or_default();
+            *count += 1;
+            *last_index = i;
+        }
+        let mut stats: Vec<_> = stats.into_values().collect();
+        stats.sort_by(|(count_1, index_1), (count_2, index_2)| {
+            count_2.cmp(count_1).then(index_2.cmp(index_1))
+        });
+        stats
+            .into_iter()
+            .take(max_count)
+            .map(|(count, last_index)| (&self.entries[last_index], count))
+            .collect()
+    }
+
+    /// Adds the given entry in memory. Call [`Self::save`] to persist it.
+    pub fn add(&mut self, entry: PresetLoadHistoryEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_HISTORY_ENTRIES {
+            let excess = self.entries.len() - MAX_HISTORY_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = PresetLoadHistoryFileContentRef {
+            loads: &self.entries,
+        };
+        save_json_store(&self.file_path, "Pot preset load history", &content)
+    }
+}
+
+impl PresetLoadHistoryEntry {
+    pub fn persistent_preset_id(&self) -> Option<PersistentPresetId> {
+        self.preset_id.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recently_loaded_and_most_used() {
+        // Given
+        let entry = |preset_id: &str| PresetLoadHistoryEntry {
+            timestamp: 0,
+            preset_id: preset_id.to_string(),
+            preset_name: preset_id.to_string(),
+            track_id: None,
+            track_name: None,
+            fx_id: None,
+            fx_name: "ReaSynth".to_string(),
+        };
+        let history = PresetLoadHistory {
+            file_path: Default::default(),
+            entries: ["a", "b", "a", "c", "b", "d"]
+                .into_iter()
+                .map(entry)
+                .collect(),
+        };
+        // When
+        let recently_loaded: Vec<_> = history
+            .recently_loaded(3)
+            .into_iter()
+            .map(|e| e.preset_id.as_str())
+            .collect();
+        let most_used: Vec<_> = history
+            .most_used(3)
+            .into_iter()
+            .map(|(e, count)| (e.preset_id.as_str(), count))
+            .collect();
+        // Then
+        assert_eq!(recently_loaded, vec!["d", "b", "c"]);
+        assert_eq!(most_used, vec![("b", 2), ("a", 2), ("d", 1)]);
+    }
+}
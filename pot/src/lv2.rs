@@ -0,0 +1,274 @@
+//! Discovery of LV2 bundles, shared by the plug-in database and the LV2 preset database.
+
+use crate::turtle::{parse_turtle, Term, Triple, RDF_TYPE};
+use base::hash_util::NonCryptoHashMap;
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use std::error::Error;
+use std::fs;
+
+pub const LV2_PLUGIN: &str = "http://lv2plug.in/ns/lv2core#Plugin";
+pub const LV2_INSTRUMENT_PLUGIN: &str = "http://lv2plug.in/ns/lv2core#InstrumentPlugin";
+pub const LV2_APPLIES_TO: &str = "http://lv2plug.in/ns/lv2core#appliesTo";
+pub const LV2_PORT: &str = "http://lv2plug.in/ns/lv2core#port";
+pub const LV2_SYMBOL: &str = "http://lv2plug.in/ns/lv2core#symbol";
+pub const PSET_PRESET: &str = "http://lv2plug.in/ns/ext/presets#Preset";
+pub const PSET_BANK: &str = "http://lv2plug.in/ns/ext/presets#bank";
+pub const PSET_BANK_CLASS: &str = "http://lv2plug.in/ns/ext/presets#Bank";
+pub const PSET_VALUE: &str = "http://lv2plug.in/ns/ext/presets#value";
+pub const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+pub const RDFS_SEE_ALSO: &str = "http://www.w3.org/2000/01/rdf-schema#seeAlso";
+pub const DOAP_NAME: &str = "http://usefulinc.com/ns/doap#name";
+
+/// A directory in the LV2 path which contains bundles.
+pub struct Lv2PathDir {
+    pub path: Utf8PathBuf,
+    /// `true` for the directory in the user's home, where hosts save user presets.
+    pub is_user: bool,
+}
+
+/// Returns the directories that make up the LV2 path.
+///
+/// Respects the `LV2_PATH` environment variable and falls back to the default Linux locations.
+/// On other operating systems, LV2 isn't considered.
+pub fn lv2_path() -> Vec<Lv2PathDir> {
+    if !cfg!(target_os = "linux") {
+        return vec![];
+    }
+    let home_dir = dirs::home_dir().and_then(|d| Utf8PathBuf::from_path_buf(d).ok());
+    let paths: Vec<Utf8PathBuf> = match std::env::var_os("LV2_PATH") {
+        Some(var) => std::env::split_paths(&var)
+            .filter_map(|p| Utf8PathBuf::from_path_buf(p).ok())
+            .collect(),
+        None => home_dir
+            .iter()
+            .map(|d| d.join(".lv2"))
+            .chain(["/usr/local/lib/lv2".into(), "/usr/lib/lv2".into()])
+            .collect(),
+    };
+    paths
+        .into_iter()
+        .map(|path| Lv2PathDir {
+            is_user: home_dir.as_ref().is_some_and(|h| path.starts_with(h)),
+            path,
+        })
+        .collect()
+}
+
+/// Loads all bundles in the given LV2 path directories, together with the index of their
+/// directory.
+///
+/// Only the manifests are parsed. Use [`Lv2Bundle::load_see_also_files`] to load more data.
+pub fn crawl_lv2_bundles(dirs: &[Lv2PathDir]) -> impl Iterator<Item = (usize, Lv2Bundle)> + '_ {
+    dirs.iter().enumerate().flat_map(|(dir_index, dir)| {
+        let bundle_dirs = fs::read_dir(&dir.path).into_iter().flatten();
+        bundle_dirs.filter_map(move |entry| {
+            let entry = entry.ok()?;
+            let path = Utf8PathBuf::from_path_buf(entry.path()).ok()?;
+            if !path.is_dir() {
+                return None;
+            }
+            let bundle = Lv2Bundle::load(path).ok()?;
+            Some((dir_index, bundle))
+        })
+    })
+}
+
+/// An LV2 bundle, that is, a directory with a `manifest.ttl` file.
+pub struct Lv2Bundle {
+    pub path: Utf8PathBuf,
+    triples: Vec<Triple>,
+    /// Indexes into `triples`, for looking up the statements about a subject quickly.
+    triple_indexes_by_subject: NonCryptoHashMap<String, Vec<usize>>,
+}
+
+impl Lv2Bundle {
+    pub fn load(path: Utf8PathBuf) -> Result<Self, Box<dyn Error>> {
+        let triples = load_turtle_file(&path.join("manifest.ttl"))?;
+        Ok(Self::new(path, triples))
+    }
+
+    fn new(path: Utf8PathBuf, triples: Vec<Triple>) -> Self {
+        let mut bundle = Self {
+            path,
+            triples: vec![],
+            triple_indexes_by_subject: Default::default(),
+        };
+        bundle.add_triples(triples);
+        bundle
+    }
+
+    fn add_triples(&mut self, triples: Vec<Triple>) {
+        for triple in triples {
+            self.triple_indexes_by_subject
+                .entry(triple.subject.clone())
+                .or_default()
+                .push(self.triples.len());
+            self.triples.push(triple);
+        }
+    }
+
+    /// Loads the files that the given subjects refer to via `rdfs:seeAlso` and merges their data.
+    ///
+    /// Files outside of the bundle and files which fail to parse are ignored.
+    pub fn load_see_also_files(&mut self, subjects: &[String]) {
+        let files: Vec<_> = subjects
+            .iter()
+            .flat_map(|s| self.nodes(s, RDFS_SEE_ALSO))
+            .filter_map(file_uri_to_path)
+            .filter(|p| p.starts_with(&self.path) && p.file_name() != Some("manifest.ttl"))
+            .collect();
+        for file in files.into_iter().unique() {
+            if let Ok(triples) = load_turtle_file(&file) {
+                self.add_triples(triples);
+            }
+        }
+    }
+
+    pub fn subjects_of_type<'a>(&'a self, type_iri: &'a str) -> impl Iterator<Item = &'a str> {
+        self.triples
+            .iter()
+            .filter(move |t| t.predicate == RDF_TYPE && t.object.as_node() == Some(type_iri))
+            .map(|t| t.subject.as_str())
+    }
+
+    pub fn has_type(&self, subject: &str, type_iri: &str) -> bool {
+        self.nodes(subject, RDF_TYPE).any(|n| n == type_iri)
+    }
+
+    pub fn objects<'a>(
+        &'a self,
+        subject: &'a str,
+        predicate: &'a str,
+    ) -> impl Iterator<Item = &'a Term> {
+        self.triple_indexes_by_subject
+            .get(subject)
+            .into_iter()
+            .flatten()
+            .map(|i| &self.triples[*i])
+            .filter(move |t| t.predicate == predicate)
+            .map(|t| &t.object)
+    }
+
+    pub fn nodes<'a>(
+        &'a self,
+        subject: &'a str,
+        predicate: &'a str,
+    ) -> impl Iterator<Item = &'a str> {
+        self.objects(subject, predicate).filter_map(|o| o.as_node())
+    }
+
+    pub fn node(&self, subject: &str, predicate: &str) -> Option<&str> {
+        self.nodes(subject, predicate).next()
+    }
+
+    pub fn literal(&self, subject: &str, predicate: &str) -> Option<&str> {
+        self.objects(subject, predicate)
+            .find_map(|o| o.as_literal())
+    }
+}
+
+fn load_turtle_file(path: &Utf8Path) -> Result<Vec<Triple>, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let triples = parse_turtle(&text, &path_to_file_uri(path))?;
+    Ok(triples)
+}
+
+/// Example: `/usr/lib/lv2/My Synth.lv2/manifest.ttl` => `file:///usr/lib/lv2/My%20Synth.lv2/manifest.ttl`
+pub fn path_to_file_uri(path: &Utf8Path) -> String {
+    let mut uri = "file://".to_string();
+    for b in path.as_str().bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    uri
+}
+
+/// Returns `None` if the given IRI is not a `file://` URI.
+pub fn file_uri_to_path(iri: &str) -> Option<Utf8PathBuf> {
+    let encoded = iri.strip_prefix("file://")?;
+    let mut bytes = vec![];
+    let mut iter = encoded.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    Some(String::from_utf8(bytes).ok()?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_bundle_with_see_also_files() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let bundle_path = Utf8PathBuf::from_path_buf(dir.path().join("My Presets.lv2")).unwrap();
+        fs::create_dir(&bundle_path).unwrap();
+        fs::write(
+            bundle_path.join("manifest.ttl"),
+            r#"
+                @prefix lv2: <http://lv2plug.in/ns/lv2core#> .
+                @prefix pset: <http://lv2plug.in/ns/ext/presets#> .
+                @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+                <Warm%20Pad.ttl> a pset:Preset ;
+                    lv2:appliesTo <http://example.org/synth> ;
+                    rdfs:seeAlso <Warm%20Pad.ttl> .
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            bundle_path.join("Warm Pad.ttl"),
+            r#"
+                @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+                <> rdfs:label "Warm Pad" .
+            "#,
+        )
+        .unwrap();
+        // When
+        let mut bundle = Lv2Bundle::load(bundle_path.clone()).unwrap();
+        let presets: Vec<_> = bundle
+            .subjects_of_type(PSET_PRESET)
+            .map(|s| s.to_string())
+            .collect();
+        let label_before = bundle
+            .literal(&presets[0], RDFS_LABEL)
+            .map(|l| l.to_string());
+        bundle.load_see_also_files(&presets);
+        // Then
+        let preset_uri = path_to_file_uri(&bundle_path.join("Warm Pad.ttl"));
+        assert_eq!(presets, vec![preset_uri.clone()]);
+        assert_eq!(label_before, None);
+        assert_eq!(bundle.literal(&preset_uri, RDFS_LABEL), Some("Warm Pad"));
+        assert_eq!(
+            bundle.node(&preset_uri, LV2_APPLIES_TO),
+            Some("http://example.org/synth")
+        );
+        assert!(bundle.has_type(&preset_uri, PSET_PRESET));
+        assert_eq!(
+            bundle.node("http://example.org/unknown", LV2_APPLIES_TO),
+            None
+        );
+    }
+
+    #[test]
+    fn convert_file_uris() {
+        // Given
+        let path = Utf8Path::new("/usr/lib/lv2/My Synth.lv2/manifest.ttl");
+        // When
+        let uri = path_to_file_uri(path);
+        // Then
+        assert_eq!(uri, "file:///usr/lib/lv2/My%20Synth.lv2/manifest.ttl");
+        assert_eq!(file_uri_to_path(&uri).as_deref(), Some(path));
+        assert_eq!(file_uri_to_path("http://example.org"), None);
+    }
+}
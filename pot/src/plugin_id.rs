@@ -8,6 +8,7 @@ pub enum PluginId {
     Vst3 { vst_uid: [u32; 4] },
     Clap { clap_id: LimitedAsciiString<100> },
     Js { js_id: LimitedAsciiString<100> },
+    Lv2 { lv2_uri: LimitedAsciiString<100> },
 }
 
 impl PluginId {
@@ -36,6 +37,13 @@ impl PluginId {
         Ok(id)
     }
 
+    pub fn lv2(uri: &str) -> Result<Self, &'static str> {
+        let id = Self::Lv2 {
+            lv2_uri: LimitedAsciiString::try_from_str(uri)?,
+        };
+        Ok(id)
+    }
+
     pub fn parse_from_rxml_line(line: &str) -> Result<PluginId, &'static str> {
         let line = line.trim();
         let mut tokens = splitty::split_unquoted_whitespace(line).unwrap_quotes(true);
@@ -87,6 +95,7 @@ impl PluginId {
             PluginId::Vst3 { .. } => PluginKind::Vst3,
             PluginId::Clap { .. } => PluginKind::Clap,
             PluginId::Js { .. } => PluginKind::Js,
+            PluginId::Lv2 { .. } => PluginKind::Lv2,
         }
     }
 
@@ -103,6 +112,7 @@ impl Display for PluginIdContentInReaperFormat<'_> {
         match self.0 {
             PluginId::Clap { clap_id } => clap_id.fmt(f),
             PluginId::Js { js_id } => js_id.fmt(f),
+            PluginId::Lv2 { lv2_uri } => lv2_uri.fmt(f),
             PluginId::Vst2 { vst_magic_number } => vst_magic_number.fmt(f),
             PluginId::Vst3 { vst_uid } => {
                 // D39D5B69 D6AF42FA 12345678 534D4433
@@ -140,6 +150,8 @@ pub enum PluginKind {
     Clap,
     #[strum(serialize = "js")]
     Js,
+    #[strum(serialize = "lv2")]
+    Lv2,
 }
 
 impl PluginKind {
@@ -149,6 +161,7 @@ impl PluginKind {
             Self::Vst3 => "VST3",
             Self::Clap => "CLAP",
             Self::Js => "JS",
+            Self::Lv2 => "LV2",
         }
     }
 
@@ -157,7 +170,7 @@ impl PluginKind {
     pub fn reaper_add_by_name_prefix_fix(&self) -> &'static str {
         match self {
             Self::Vst2 | Self::Vst3 => "i7zh34z",
-            Self::Clap | Self::Js => "",
+            Self::Clap | Self::Js | Self::Lv2 => "",
         }
     }
 
@@ -165,7 +178,7 @@ impl PluginKind {
         match self {
             Self::Vst2 => "<",
             Self::Vst3 => "{",
-            Self::Clap | Self::Js => "",
+            Self::Clap | Self::Js | Self::Lv2 => "",
         }
     }
 }
@@ -1,7 +1,9 @@
+use crate::lv2;
+use crate::lv2::Lv2PathDir;
 use crate::{parse_vst2_magic_number, parse_vst3_uid, PluginId, ProductId};
 use base::file_util;
-use base::hash_util::NonCryptoHashMap;
-use camino::Utf8Path;
+use base::hash_util::{NonCryptoHashMap, NonCryptoIndexMap};
+use camino::{Utf8Path, Utf8PathBuf};
 use ini::Ini;
 use regex::Match;
 use std::fmt;
@@ -82,9 +84,11 @@ impl PluginDatabase {
         );
         let js_root_dir = reaper_resource_dir.join("Effects");
         let js_plugins = crawl_js_plugins(&mut product_accumulator, &js_root_dir);
+        let lv2_plugins = crawl_lv2_plugins(&mut product_accumulator, &lv2::lv2_path());
         let plugin_map = shared_library_plugins
             .into_iter()
             .chain(js_plugins)
+            .chain(lv2_plugins)
             .map(|p| (p.common.core.id, p))
             .collect();
         Self {
@@ -118,15 +122,33 @@ impl PluginDatabase {
     }
 
     pub fn detect_plugin_from_rxml_line(&self, line: &str) -> Option<&Plugin> {
-        let is_fx_line = ["<VST ", "<CLAP ", "<JS "]
-            .into_iter()
-            .any(|suffix| line.starts_with(suffix));
-        if !is_fx_line {
+        if !is_fx_rxml_line(line) {
             return None;
         }
         let plugin_id = PluginId::parse_from_rxml_line(line).ok()?;
         self.find_plugin_by_id(&plugin_id)
     }
+
+    /// Detects the plug-ins used in the given FX lines (see [`is_fx_rxml_line`]).
+    pub fn detect_plugins_from_rxml_lines<'a>(
+        &self,
+        lines: impl IntoIterator<Item = &'a str>,
+    ) -> NonCryptoIndexMap<PluginId, PluginCore> {
+        lines
+            .into_iter()
+            .filter_map(|line| {
+                let plugin = self.detect_plugin_from_rxml_line(line)?;
+                Some((plugin.common.core.id, plugin.common.core))
+            })
+            .collect()
+    }
+}
+
+/// Returns whether the given (trimmed) line of a REAPER-XML-like file declares an FX.
+pub fn is_fx_rxml_line(line: &str) -> bool {
+    ["<VST ", "<CLAP ", "<JS "]
+        .into_iter()
+        .any(|prefix| line.starts_with(prefix))
 }
 
 /// A product - an abstraction over related plug-ins.
@@ -183,6 +205,7 @@ pub enum SuperPluginKind {
     Vst(VstPlugin),
     Clap(ClapPlugin),
     Js(JsPlugin),
+    Lv2(Lv2Plugin),
 }
 
 #[derive(Clone, Debug)]
@@ -204,6 +227,13 @@ pub struct JsPlugin {
     pub path: String,
 }
 
+#[derive(Clone, Debug)]
+pub struct Lv2Plugin {
+    /// Directory of the bundle which contains the plug-in.
+    pub bundle_path: Utf8PathBuf,
+    pub uri: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct VstPlugin {
     /// Safe means: Each space and special character is replaced with an underscore.
@@ -296,6 +326,52 @@ fn crawl_js_plugins(
         .collect()
 }
 
+fn crawl_lv2_plugins(
+    product_accumulator: &mut ProductAccumulator,
+    lv2_path: &[Lv2PathDir],
+) -> Vec<Plugin> {
+    lv2::crawl_lv2_bundles(lv2_path)
+        .flat_map(|(_, mut bundle)| {
+            let plugin_uris: Vec<_> = bundle
+                .subjects_of_type(lv2::LV2_PLUGIN)
+                .map(|s| s.to_string())
+                .collect();
+            // The plug-in name and more specific types are usually in a separate file
+            bundle.load_see_also_files(&plugin_uris);
+            plugin_uris
+                .into_iter()
+                .filter_map(|uri| {
+                    let product_kind = if bundle.has_type(&uri, lv2::LV2_INSTRUMENT_PLUGIN) {
+                        Some(ProductKind::Instrument)
+                    } else {
+                        Some(ProductKind::Effect)
+                    };
+                    let name = bundle
+                        .literal(&uri, lv2::DOAP_NAME)
+                        .unwrap_or(uri.as_str())
+                        .to_string();
+                    let plugin = Plugin {
+                        common: PluginCommon {
+                            core: PluginCore {
+                                id: PluginId::lv2(&uri).ok()?,
+                                product_id: product_accumulator
+                                    .get_or_add_plugin_product(&name, product_kind),
+                                product_kind,
+                            },
+                            name,
+                        },
+                        kind: SuperPluginKind::Lv2(Lv2Plugin {
+                            bundle_path: bundle.path.clone(),
+                            uri,
+                        }),
+                    };
+                    Some(plugin)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 fn crawl_shared_library_plugins(
     product_accumulator: &mut ProductAccumulator,
     reaper_resource_dir: &Utf8Path,
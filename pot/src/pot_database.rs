@@ -1,34 +1,59 @@
+use crate::index_cache::index_cache_file;
 use crate::provider_database::{
     Database, DatabaseId, InnerFilterItem, ProviderContext, SortablePresetId,
     FIL_HAS_PREVIEW_FALSE, FIL_HAS_PREVIEW_TRUE, FIL_IS_AVAILABLE_FALSE, FIL_IS_AVAILABLE_TRUE,
-    FIL_IS_FAVORITE_FALSE, FIL_IS_FAVORITE_TRUE, FIL_IS_SUPPORTED_FALSE, FIL_IS_SUPPORTED_TRUE,
-    FIL_IS_USER_PRESET_FALSE, FIL_IS_USER_PRESET_TRUE, FIL_PRODUCT_KIND_EFFECT,
-    FIL_PRODUCT_KIND_INSTRUMENT, FIL_PRODUCT_KIND_LOOP, FIL_PRODUCT_KIND_ONE_SHOT,
+    FIL_IS_DUPLICATE_FALSE, FIL_IS_DUPLICATE_TRUE, FIL_IS_FAVORITE_FALSE, FIL_IS_FAVORITE_TRUE,
+    FIL_IS_SUPPORTED_FALSE, FIL_IS_SUPPORTED_TRUE, FIL_IS_USER_PRESET_FALSE,
+    FIL_IS_USER_PRESET_TRUE, FIL_PRODUCT_KIND_EFFECT, FIL_PRODUCT_KIND_INSTRUMENT,
+    FIL_PRODUCT_KIND_LOOP, FIL_PRODUCT_KIND_ONE_SHOT,
 };
 use crate::providers::directory::{DirectoryDatabase, DirectoryDbConfig};
 use crate::providers::komplete::KompleteDatabase;
+use crate::providers::load_history::{
+    LoadHistoryDatabase, LoadHistoryDatabaseKind, ResolvedLoadHistory, SharedResolvedLoadHistory,
+};
+use crate::providers::preset_collections::{
+    PresetCollectionDatabase, ResolvedPresetCollection, ResolvedPresetCollectionEntry,
+    ResolvedPresetCollections, SharedResolvedPresetCollections,
+};
+use crate::worker::spawn_in_pot_worker;
 use crate::{
-    preview_exists, BuildInput, Fil, FilterItem, FilterItemCollections, FilterItemId, Filters,
-    InnerBuildInput, PersistentDatabaseId, PersistentPresetId, PluginId, PotFavorites, PotPreset,
-    PresetId, PresetWithId, Stats,
+    preview_exists, BuildInput, DuplicateClusters, Fil, FilterItem, FilterItemCollections,
+    FilterItemId, Filters, InnerBuildInput, PersistentDatabaseId, PersistentPresetId, PluginId,
+    PotFavorites, PotFilterExcludes, PotPreset, PotSettings, PotSettingsStore,
+    PotUserMetadataStore, PresetCollection, PresetCollectionEntry, PresetCollectionStore, PresetId,
+    PresetLoadHistory, PresetLoadHistoryEntry, PresetUserMetadata, PresetWithId, SearchEvaluator,
+    SearchField, SearchOptions, SearchQueryCandidate, Stats, MAX_PRESET_RATING,
 };
-use base::{blocking_read_lock, blocking_write_lock};
+use crate::{user_metadata_filters_are_set, user_metadata_matches_filters};
+use anyhow::Context;
+use base::{blocking_lock, blocking_read_lock, blocking_write_lock};
+use camino::{Utf8Path, Utf8PathBuf};
 
+use crate::lv2;
 use crate::plugins::PluginDatabase;
 use crate::providers::defaults::DefaultsDatabase;
 use crate::providers::ini::IniDatabase;
+use crate::providers::lv2::Lv2PresetDatabase;
+use crate::providers::samples::SampleDatabase;
+use crate::providers::vst3::{standard_vst3_preset_root_dirs, Vst3PresetDatabase};
 
 use enumset::{enum_set, EnumSet};
 use helgobox_api::persistence::PotFilterKind;
 use reaper_high::Reaper;
-use std::collections::{BTreeMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 use std::fmt::Debug;
+use std::iter;
+use std::mem;
 use std::ops::Deref;
 
-use base::hash_util::NonCryptoIndexSet;
+use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet, NonCryptoIndexSet};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::{RwLock, RwLockReadGuard};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, Weak};
 use std::time::{Duration, Instant};
 
 pub fn pot_db() -> &'static PotDatabase {
@@ -64,6 +89,116 @@ pub struct PotDatabase {
     databases: RwLock<Databases>,
     revision: AtomicU8,
     detected_legacy_vst3_scan: AtomicBool,
+    /// `Some` if watching the database directories is enabled.
+    watcher: Mutex<Option<DatabaseWatcher>>,
+    settings: RwLock<PotSettingsStore>,
+    user_metadata: RwLock<PotUserMetadataStore>,
+    user_metadata_index: RwLock<UserMetadataIndex>,
+    /// Keys of user metadata entries whose presets still need to be added to the index.
+    unindexed_user_metadata_keys: RwLock<NonCryptoHashSet<String>>,
+    user_metadata_save_pending: AtomicBool,
+    notes_search_candidates: RwLock<Option<NotesSearchCandidates>>,
+    load_history: RwLock<PresetLoadHistory>,
+    load_history_save_pending: AtomicBool,
+    resolved_load_history: SharedResolvedLoadHistory,
+    /// Increased with each recorded preset load. Only affects the load history databases.
+    load_history_revision: AtomicU8,
+    load_history_db_ids: NonCryptoHashSet<DatabaseId>,
+    preset_collections: RwLock<PresetCollectionStore>,
+    resolved_preset_collections: SharedResolvedPresetCollections,
+    /// Increased whenever the preset collections have been resolved.
+    preset_collections_revision: AtomicU8,
+    preset_collection_ids_cache: RwLock<PresetCollectionIdsCache>,
+    preset_collection_db_id: Option<DatabaseId>,
+}
+
+/// Maximum number of presets in each of the load history databases.
+const MAX_LOAD_HISTORY_PRESETS: usize = 100;
+
+/// How often the databases whose directories have changed are refreshed if watching is enabled.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Watches the directories of the file-based databases.
+struct DatabaseWatcher {
+    watcher: RecommendedWatcher,
+    /// The only strong reference. Event handler and refresh task stop as soon as the watcher is
+    /// dropped.
+    state: Arc<Mutex<DatabaseWatcherState>>,
+}
+
+#[derive(Default)]
+struct DatabaseWatcherState {
+    watched_dirs: Vec<(DatabaseId, PathBuf)>,
+    changed_db_ids: NonCryptoHashSet<DatabaseId>,
+}
+
+impl DatabaseWatcher {
+    fn new() -> notify::Result<Self> {
+        let state: Arc<Mutex<DatabaseWatcherState>> = Default::default();
+        let weak_state = Arc::downgrade(&state);
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if event.kind.is_access() {
+                return;
+            }
+            let Some(state) = weak_state.upgrade() else {
+                return;
+            };
+            let mut state = blocking_lock(&state, "pot db watcher event");
+            let changed_db_ids: Vec<_> = state
+                .watched_dirs
+                .iter()
+                .filter(|(_, dir)| event.paths.iter().any(|p| p.starts_with(dir)))
+                .map(|(db_id, _)| *db_id)
+                .collect();
+            state.changed_db_ids.extend(changed_db_ids);
+        })?;
+        Ok(Self { watcher, state })
+    }
+
+    fn watch_database(&mut self, db_id: DatabaseId, db: &dyn Database) {
+        for dir in db.watched_dirs() {
+            if let Err(e) = self.watcher.watch(&dir, RecursiveMode::Recursive) {
+                tracing::warn!("Couldn't watch Pot database directory {dir:?}: {e}");
+                continue;
+            }
+            blocking_lock(&self.state, "pot db watch database")
+                .watched_dirs
+                .push((db_id, dir));
+        }
+    }
+}
+
+/// How long to wait before saving modified user metadata or load history. Quickly repeated
+/// modifications (e.g. rating or loading presets via controller) then result in just one save.
+const STORE_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Maps the IDs of presets in non-virtual databases to the keys of their user metadata.
+///
+/// Saves us from looking up each preset when evaluating the user metadata filters.
+type UserMetadataIndex = NonCryptoHashMap<PresetId, String>;
+
+/// All presets matching particular filters, regardless of the search expression.
+///
+/// Notes search needs these and without caching, we would query them on each keystroke.
+struct NotesSearchCandidates {
+    pot_db_revision: u8,
+    filters: Filters,
+    filter_excludes: PotFilterExcludes,
+    db_ids: NonCryptoHashSet<DatabaseId>,
+    preset_ids: Vec<(DatabaseId, SortablePresetId)>,
+}
+
+/// IDs of the presets in each preset collection.
+///
+/// Targets which browse a collection need these on each control event and without caching, we
+/// would collect them each time.
+#[derive(Default)]
+struct PresetCollectionIdsCache {
+    preset_collections_revision: u8,
+    preset_ids_by_collection_name: NonCryptoHashMap<String, Arc<Vec<PresetId>>>,
 }
 
 type Databases = BTreeMap<DatabaseId, RwLock<BoxedDatabase>>;
@@ -91,6 +226,7 @@ impl PotDatabase {
                 valid_extensions: &["RfxChain"],
                 name: "FX chains",
                 description: "All the RfxChain files in your FXChains directory",
+                index_cache_file: index_cache_file("fx-chains"),
             };
             DirectoryDatabase::open(config)
         };
@@ -102,32 +238,105 @@ impl PotDatabase {
                 name: "Track templates",
                 description: "All the RTrackTemplate files in your TrackTemplates directory.\n\
                 Doesn't load the complete track, only its FX chain!",
+                index_cache_file: index_cache_file("track-templates"),
             };
             DirectoryDatabase::open(config)
         };
         let ini_db = IniDatabase::open(
             PersistentDatabaseId::new("fx-presets".to_string()),
             resource_path.join("presets"),
+            index_cache_file("fx-presets"),
+        );
+        let vst3_preset_db = Vst3PresetDatabase::open(
+            PersistentDatabaseId::new("vst3-presets".to_string()),
+            standard_vst3_preset_root_dirs(),
+        );
+        let lv2_preset_db = Lv2PresetDatabase::open(
+            PersistentDatabaseId::new("lv2-presets".to_string()),
+            lv2::lv2_path(),
+        );
+        let sample_db = SampleDatabase::open(
+            PersistentDatabaseId::new("samples".to_string()),
+            resource_path.join("Helgoboss/Pot/Samples"),
         );
         let defaults_db = DefaultsDatabase::open();
+        let settings = PotSettingsStore::load(resource_path.join("Helgoboss/Pot/settings.json"));
+        let user_metadata =
+            PotUserMetadataStore::load(resource_path.join("Helgoboss/Pot/user-metadata.json"));
+        let load_history =
+            PresetLoadHistory::load(resource_path.join("Helgoboss/Pot/load-history.json"));
+        let resolved_load_history = SharedResolvedLoadHistory::default();
+        let recently_loaded_db = LoadHistoryDatabase::open(
+            LoadHistoryDatabaseKind::RecentlyLoaded,
+            resolved_load_history.clone(),
+        );
+        let most_used_db = LoadHistoryDatabase::open(
+            LoadHistoryDatabaseKind::MostUsed,
+            resolved_load_history.clone(),
+        );
+        let load_history_db_persistent_ids = [
+            recently_loaded_db.persistent_id().clone(),
+            most_used_db.persistent_id().clone(),
+        ];
+        let preset_collections =
+            PresetCollectionStore::load(resource_path.join("Helgoboss/Pot/collections.json"));
+        let resolved_preset_collections = SharedResolvedPresetCollections::default();
+        let preset_collection_db =
+            PresetCollectionDatabase::open(resolved_preset_collections.clone());
+        let preset_collection_db_persistent_id = preset_collection_db.persistent_id().clone();
         let databases = [
             box_db_result(komplete_db),
             box_db_result(rfx_chain_db),
             box_db_result(track_template_db),
             box_db_result(ini_db),
+            box_db_result(vst3_preset_db),
+            box_db_result(lv2_preset_db),
+            box_db_result(sample_db),
             box_db_result(Ok(defaults_db)),
+            box_db_result(Ok(recently_loaded_db)),
+            box_db_result(Ok(most_used_db)),
+            box_db_result(Ok(preset_collection_db)),
         ];
-        let databases = databases
+        let databases: Databases = databases
             .into_iter()
             .flatten()
             .enumerate()
             .map(|(i, db)| (DatabaseId(i as _), RwLock::new(db)))
             .collect();
+        let load_history_db_ids = databases
+            .iter()
+            .filter(|(_, db)| {
+                let db = blocking_read_lock(db, "pot db open");
+                load_history_db_persistent_ids.contains(db.persistent_id())
+            })
+            .map(|(db_id, _)| *db_id)
+            .collect();
+        let preset_collection_db_id = databases.iter().find_map(|(db_id, db)| {
+            let db = blocking_read_lock(db, "pot db open");
+            (db.persistent_id() == &preset_collection_db_persistent_id).then_some(*db_id)
+        });
         Self {
             plugin_db: Default::default(),
             databases: RwLock::new(databases),
             revision: Default::default(),
             detected_legacy_vst3_scan: Default::default(),
+            watcher: Default::default(),
+            settings: RwLock::new(settings),
+            user_metadata: RwLock::new(user_metadata),
+            user_metadata_index: Default::default(),
+            unindexed_user_metadata_keys: Default::default(),
+            user_metadata_save_pending: Default::default(),
+            notes_search_candidates: Default::default(),
+            load_history: RwLock::new(load_history),
+            load_history_save_pending: Default::default(),
+            resolved_load_history,
+            load_history_revision: Default::default(),
+            load_history_db_ids,
+            preset_collections: RwLock::new(preset_collections),
+            resolved_preset_collections,
+            preset_collections_revision: Default::default(),
+            preset_collection_ids_cache: Default::default(),
+            preset_collection_db_id,
         }
     }
 
@@ -136,7 +345,20 @@ impl PotDatabase {
         self.revision.load(Ordering::Relaxed)
     }
 
-    pub fn refresh(&self) {
+    /// Returns a number that will be increased with each recorded preset load.
+    pub fn load_history_revision(&self) -> u8 {
+        self.load_history_revision.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the given filters show one of the load history databases, which change
+    /// with each recorded preset load.
+    pub fn load_history_database_is_selected(&self, filters: &Filters) -> bool {
+        self.load_history_db_ids
+            .iter()
+            .any(|db_id| database_is_selected_explicitly(filters, *db_id))
+    }
+
+    pub fn refresh(&'static self) {
         // Build provider context
         let resource_path = Reaper::get().resource_path();
         // Crawl plug-ins
@@ -151,10 +373,101 @@ impl PotDatabase {
             let mut db = blocking_write_lock(db, "pot db refresh provider db");
             let _ = db.refresh(&provider_context);
         }
+        // Resolve load history, preset collections and user metadata (needs the refreshed
+        // databases)
+        self.resolve_load_history(Some(&provider_context), vec![]);
+        self.resolve_preset_collections(Some(&provider_context), vec![]);
+        self.resolve_user_metadata_index(&provider_context);
         // Memorize plug-ins
         *blocking_write_lock(&self.plugin_db, "pot db refresh plugin db") = plugin_db;
         // Increment revision
         self.revision.fetch_add(1, Ordering::Relaxed);
+        // Resume watching after a restart
+        if self.settings().watch_folders {
+            self.apply_watching_enabled(true);
+        }
+    }
+
+    /// Returns the global Pot settings.
+    pub fn settings(&self) -> PotSettings {
+        blocking_read_lock(&self.settings, "pot db settings")
+            .settings()
+            .clone()
+    }
+
+    /// Refreshes only the given databases, e.g. because their files changed on disk.
+    ///
+    /// Thanks to the index caches, this usually just processes the changed files.
+    fn refresh_databases(&self, db_ids: &NonCryptoHashSet<DatabaseId>) {
+        let plugin_db = self.read_lock_plugin_db();
+        let provider_context = ProviderContext::new(&plugin_db);
+        let mut refreshed_any = false;
+        for (db_id, db) in self.read_lock_databases().iter() {
+            if !db_ids.contains(db_id) {
+                continue;
+            }
+            let mut db = blocking_write_lock(db, "pot db refresh changed provider db");
+            let _ = db.refresh(&provider_context);
+            refreshed_any = true;
+        }
+        if refreshed_any {
+            self.resolve_load_history(Some(&provider_context), vec![]);
+            self.resolve_preset_collections(Some(&provider_context), vec![]);
+            self.resolve_user_metadata_index(&provider_context);
+            self.revision.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_watching_enabled(&self) -> bool {
+        blocking_lock(&self.watcher, "pot db is_watching_enabled").is_some()
+    }
+
+    /// Enables or disables watching the database directories for changes and remembers this
+    /// setting, so that watching resumes after a restart.
+    ///
+    /// If enabled, the file system notifies us about changed files and the Pot worker regularly
+    /// refreshes the affected databases incrementally.
+    pub fn set_watching_enabled(&'static self, enabled: bool) {
+        self.apply_watching_enabled(enabled);
+        let result = blocking_write_lock(&self.settings, "pot db set_watching_enabled")
+            .update(|s| s.watch_folders = enabled);
+        if let Err(e) = result {
+            tracing::warn!("Couldn't save Pot settings: {e}");
+        }
+    }
+
+    fn apply_watching_enabled(&'static self, enabled: bool) {
+        let mut watcher = blocking_lock(&self.watcher, "pot db set_watching_enabled");
+        if enabled == watcher.is_some() {
+            return;
+        }
+        if !enabled {
+            *watcher = None;
+            return;
+        }
+        let mut new_watcher = match DatabaseWatcher::new() {
+            Ok(w) => w,
+            Err(e) => {
+                tracing::warn!("Couldn't watch Pot database directories: {e}");
+                return;
+            }
+        };
+        for (db_id, db) in self.read_lock_databases().iter() {
+            new_watcher.watch_database(*db_id, &**blocking_read_lock(db, "pot db watch"));
+        }
+        let state = Arc::downgrade(&new_watcher.state);
+        *watcher = Some(new_watcher);
+        spawn_in_pot_worker(async move {
+            // Each watcher has its own task, so disabling and quickly enabling again can't
+            // leave us with two tasks refreshing the same changes.
+            while let Some(changed_db_ids) = take_changed_db_ids(&state) {
+                if !changed_db_ids.is_empty() {
+                    self.refresh_databases(&changed_db_ids);
+                }
+                tokio::time::sleep(WATCH_INTERVAL).await;
+            }
+            Ok(())
+        });
     }
 
     pub fn detected_legacy_vst3_scan(&self) -> bool {
@@ -169,9 +482,600 @@ impl PotDatabase {
         blocking_read_lock(&self.plugin_db, "read-lock plug-in database")
     }
 
+    fn read_lock_user_metadata(&self) -> RwLockReadGuard<PotUserMetadataStore> {
+        blocking_read_lock(&self.user_metadata, "read-lock pot user metadata")
+    }
+
+    /// Returns the user metadata of the given preset (empty if the user hasn't added any).
+    pub fn user_metadata(&self, preset_id: &PersistentPresetId) -> PresetUserMetadata {
+        self.read_lock_user_metadata()
+            .get(preset_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn read_lock_user_metadata_index(&self) -> RwLockReadGuard<UserMetadataIndex> {
+        blocking_read_lock(
+            &self.user_metadata_index,
+            "read-lock pot user metadata index",
+        )
+    }
+
+    /// Modifies the user metadata of the given preset.
+    ///
+    /// Saving happens a bit later in the Pot worker.
+    pub fn update_user_metadata<R>(
+        &'static self,
+        preset_id: PresetId,
+        preset: &PotPreset,
+        f: impl FnOnce(&mut PresetUserMetadata) -> R,
+    ) -> R {
+        let persistent_id = &preset.common.persistent_id;
+        let (result, has_metadata) = {
+            let mut user_metadata =
+                blocking_write_lock(&self.user_metadata, "update pot user metadata");
+            let result = user_metadata.update(persistent_id, &preset.common.name, f);
+            (result, user_metadata.get(persistent_id).is_some())
+        };
+        let key = persistent_id.to_string();
+        if self.is_virtual_database(preset_id.database_id) {
+            // The index only contains presets of non-virtual databases. Looking up the preset in
+            // its original database would block if a refresh is running, so the Pot worker does
+            // it. Removed metadata doesn't need any index update, index entries just point to the
+            // metadata.
+            if has_metadata {
+                blocking_write_lock(
+                    &self.unindexed_user_metadata_keys,
+                    "update pot user metadata",
+                )
+                .insert(key);
+            }
+        } else {
+            let mut index =
+                blocking_write_lock(&self.user_metadata_index, "update pot user metadata");
+            if has_metadata {
+                index.insert(preset_id, key);
+            } else {
+                index.remove(&preset_id);
+            }
+        }
+        self.schedule_user_metadata_save();
+        result
+    }
+
+    fn schedule_user_metadata_save(&'static self) {
+        if self
+            .user_metadata_save_pending
+            .swap(true, Ordering::Relaxed)
+        {
+            return;
+        }
+        spawn_in_pot_worker(async move {
+            tokio::time::sleep(STORE_SAVE_DELAY).await;
+            // Reset before saving, so that modifications made while saving trigger another save
+            self.user_metadata_save_pending
+                .store(false, Ordering::Relaxed);
+            if let Err(e) = self.read_lock_user_metadata().save() {
+                tracing::warn!("Couldn't save Pot user metadata: {e}");
+            }
+            self.index_unindexed_user_metadata();
+            Ok(())
+        });
+    }
+
+    /// Adds the presets of user metadata which has been modified via virtual databases to the
+    /// index.
+    fn index_unindexed_user_metadata(&self) {
+        let keys = mem::take(&mut *blocking_write_lock(
+            &self.unindexed_user_metadata_keys,
+            "index unindexed pot user metadata",
+        ));
+        if keys.is_empty() {
+            return;
+        }
+        let plugin_db = self.read_lock_plugin_db();
+        let provider_context = ProviderContext::new(&plugin_db);
+        let index_entries = self.resolve_user_metadata_index_entries(&provider_context, keys);
+        if index_entries.is_empty() {
+            return;
+        }
+        blocking_write_lock(
+            &self.user_metadata_index,
+            "index unindexed pot user metadata",
+        )
+        .extend(index_entries);
+        // Let the Pot units rebuild their collections
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Builds the user metadata index from scratch.
+    fn resolve_user_metadata_index(&self, provider_context: &ProviderContext) {
+        blocking_write_lock(
+            &self.unindexed_user_metadata_keys,
+            "resolve pot user metadata index",
+        )
+        .clear();
+        let keys = self
+            .read_lock_user_metadata()
+            .entries()
+            .map(|(key, _)| key.to_string())
+            .collect();
+        let index = self
+            .resolve_user_metadata_index_entries(provider_context, keys)
+            .into_iter()
+            .collect();
+        *blocking_write_lock(&self.user_metadata_index, "resolve pot user metadata index") = index;
+    }
+
+    /// Looks up the presets of the user metadata entries with the given keys.
+    fn resolve_user_metadata_index_entries(
+        &self,
+        provider_context: &ProviderContext,
+        keys: impl IntoIterator<Item = String>,
+    ) -> Vec<(PresetId, String)> {
+        // Don't keep the user metadata locked while looking up presets, this can take a while
+        let preset_names: Vec<(String, String)> = {
+            let user_metadata = self.read_lock_user_metadata();
+            keys.into_iter()
+                .filter_map(|key| {
+                    let preset_name = user_metadata.preset_name_by_key(&key)?.to_string();
+                    Some((key, preset_name))
+                })
+                .collect()
+        };
+        preset_names
+            .into_iter()
+            .filter_map(|(key, preset_name)| {
+                let persistent_preset_id: PersistentPresetId = key.parse().ok()?;
+                let preset = self.find_preset_by_persistent_id_and_name(
+                    provider_context,
+                    &persistent_preset_id,
+                    &preset_name,
+                )?;
+                Some((preset.id, key))
+            })
+            .collect()
+    }
+
+    /// Adds the given load to the preset load history and updates the load history databases.
+    ///
+    /// The history is saved a bit later in the Pot worker, so file I/O doesn't slow down loading.
+    pub fn record_preset_load(&'static self, preset: &PotPreset, entry: PresetLoadHistoryEntry) {
+        blocking_write_lock(&self.load_history, "record pot preset load").add(entry);
+        self.schedule_load_history_save();
+        // All other presets in the load history databases have been resolved already. Looking up
+        // presets would block if a refresh is running.
+        let known_presets = {
+            let resolved_load_history =
+                blocking_read_lock(&self.resolved_load_history, "record pot preset load");
+            resolved_load_history
+                .recently_loaded
+                .iter()
+                .chain(resolved_load_history.most_used.iter())
+                .cloned()
+                .chain(iter::once(preset.clone()))
+                .collect()
+        };
+        self.resolve_load_history(None, known_presets);
+        self.load_history_revision.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn schedule_load_history_save(&'static self) {
+        if self.load_history_save_pending.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        spawn_in_pot_worker(async move {
+            tokio::time::sleep(STORE_SAVE_DELAY).await;
+            // Reset before saving, so that loads recorded while saving trigger another save
+            self.load_history_save_pending
+                .store(false, Ordering::Relaxed);
+            let result = blocking_read_lock(&self.load_history, "save pot load history").save();
+            if let Err(e) = result {
+                tracing::warn!("Couldn't save Pot preset load history: {e}");
+            }
+            Ok(())
+        });
+    }
+
+    /// Resolves the entries of the load history databases to actual presets.
+    ///
+    /// Presets which are not among the given known presets are looked up in their databases if a
+    /// provider context is given, otherwise they are left out.
+    fn resolve_load_history(
+        &self,
+        provider_context: Option<&ProviderContext>,
+        known_presets: Vec<PotPreset>,
+    ) {
+        let mut known_presets: HashMap<String, PotPreset> = known_presets
+            .into_iter()
+            .map(|p| (p.common.persistent_id.to_string(), p))
+            .collect();
+        let mut resolve = |entry: &PresetLoadHistoryEntry| {
+            if let Some(preset) = known_presets.get(&entry.preset_id) {
+                return Some(preset.clone());
+            }
+            let preset = self.find_preset_by_load_history_entry(provider_context?, entry)?;
+            known_presets.insert(entry.preset_id.clone(), preset.clone());
+            Some(preset)
+        };
+        // Don't keep the history locked while looking up presets, this can take a while
+        let (recently_loaded, most_used) = {
+            let load_history = blocking_read_lock(&self.load_history, "resolve pot load history");
+            let recently_loaded: Vec<_> = load_history
+                .recently_loaded(MAX_LOAD_HISTORY_PRESETS)
+                .into_iter()
+                .cloned()
+                .collect();
+            let most_used: Vec<_> = load_history
+                .most_used(MAX_LOAD_HISTORY_PRESETS)
+                .into_iter()
+                .map(|(entry, _)| entry.clone())
+                .collect();
+            (recently_loaded, most_used)
+        };
+        let resolved_load_history = ResolvedLoadHistory {
+            recently_loaded: recently_loaded.iter().filter_map(&mut resolve).collect(),
+            most_used: most_used.iter().filter_map(&mut resolve).collect(),
+        };
+        *blocking_write_lock(&self.resolved_load_history, "resolve pot load history") =
+            resolved_load_history;
+    }
+
+    /// Looks up the preset of the given load history entry.
+    ///
+    /// Databases can't look up presets by persistent ID, so this queries the preset name without
+    /// typo tolerance and picks the preset with the recorded persistent ID.
+    fn find_preset_by_load_history_entry(
+        &self,
+        provider_context: &ProviderContext,
+        entry: &PresetLoadHistoryEntry,
+    ) -> Option<PotPreset> {
+        let persistent_preset_id = entry.persistent_preset_id()?;
+        let preset = self.find_preset_by_persistent_id_and_name(
+            provider_context,
+            &persistent_preset_id,
+            &entry.preset_name,
+        )?;
+        Some(preset.preset)
+    }
+
+    /// Looks up a preset by searching for its name in the database which it belongs to.
+    fn find_preset_by_persistent_id_and_name(
+        &self,
+        provider_context: &ProviderContext,
+        persistent_preset_id: &PersistentPresetId,
+        preset_name: &str,
+    ) -> Option<PresetWithId> {
+        self.find_presets_by_name(provider_context, preset_name, |db| {
+            db.persistent_id() == persistent_preset_id.db_id()
+        })
+        .find(|p| &p.preset.common.persistent_id == persistent_preset_id)
+    }
+
+    /// Returns all presets with exactly the given name in the non-virtual databases for which
+    /// the given predicate returns `true`.
+    fn find_presets_by_name<'a>(
+        &'a self,
+        provider_context: &'a ProviderContext,
+        preset_name: &'a str,
+        include_db: impl Fn(&dyn Database) -> bool + 'a,
+    ) -> impl Iterator<Item = PresetWithId> + 'a {
+        let input = BuildInput {
+            filters: Default::default(),
+            filter_excludes: Default::default(),
+            search_evaluator: SearchEvaluator::new_plain(
+                preset_name,
+                SearchOptions {
+                    search_fields: enum_set!(SearchField::PresetName),
+                    fuzzy: false,
+                    sort_by_relevance: false,
+                    ..Default::default()
+                },
+            ),
+        };
+        let favorites = PotFavorites::default();
+        let db_ids: Vec<DatabaseId> = self.read_lock_databases().keys().copied().collect();
+        db_ids.into_iter().flat_map(move |db_id| {
+            let databases = self.read_lock_databases();
+            let Some(db) = databases.get(&db_id) else {
+                return vec![];
+            };
+            let db = blocking_read_lock(db, "pot db find_presets_by_name");
+            if db.is_virtual() || !include_db(&**db) {
+                return vec![];
+            }
+            let inner_input = InnerBuildInput::new(&input, &favorites, db_id);
+            let Ok(preset_ids) = db.query_presets(provider_context, inner_input) else {
+                return vec![];
+            };
+            preset_ids
+                .into_iter()
+                .filter(|p| p.preset_name == preset_name)
+                .filter_map(|p| {
+                    let preset = db.find_preset_by_id(provider_context, p.inner_preset_id)?;
+                    let preset_id = PresetId::new(db_id, p.inner_preset_id);
+                    Some(PresetWithId::new(preset_id, preset))
+                })
+                .collect()
+        })
+    }
+
+    fn read_lock_preset_collections(&self) -> RwLockReadGuard<PresetCollectionStore> {
+        blocking_read_lock(&self.preset_collections, "read-lock pot preset collections")
+    }
+
+    /// Returns the names of all preset collections.
+    pub fn preset_collection_names(&self) -> Vec<String> {
+        self.read_lock_preset_collections()
+            .collections()
+            .iter()
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    /// Creates a new empty preset collection and returns its final name (which gets a number
+    /// suffix if the name is taken already).
+    pub fn create_preset_collection(&self, name: &str) -> anyhow::Result<String> {
+        let name = blocking_write_lock(&self.preset_collections, "create preset collection")
+            .add(PresetCollection::new(name.to_string()))?;
+        self.notify_preset_collections_changed(vec![]);
+        Ok(name)
+    }
+
+    pub fn delete_preset_collection(&self, name: &str) -> anyhow::Result<()> {
+        blocking_write_lock(&self.preset_collections, "delete preset collection").remove(name)?;
+        self.notify_preset_collections_changed(vec![]);
+        Ok(())
+    }
+
+    /// Adds the given preset to the end of the given collection. Returns `false` if it's
+    /// contained already.
+    pub fn add_preset_to_collection(
+        &self,
+        collection_name: &str,
+        preset: &PotPreset,
+    ) -> anyhow::Result<bool> {
+        let entry = PresetCollectionEntry::from_preset(preset);
+        let added = blocking_write_lock(&self.preset_collections, "add preset to collection")
+            .update(collection_name, |c| c.add(entry))?;
+        self.notify_preset_collections_changed(vec![preset.clone()]);
+        Ok(added)
+    }
+
+    pub fn remove_preset_from_collection(
+        &self,
+        collection_name: &str,
+        preset: &PotPreset,
+    ) -> anyhow::Result<()> {
+        let entry_preset_id = self.find_collection_entry_preset_id(collection_name, preset);
+        blocking_write_lock(&self.preset_collections, "remove preset from collection")
+            .update(collection_name, |c| c.remove(&entry_preset_id))?;
+        self.notify_preset_collections_changed(vec![]);
+        Ok(())
+    }
+
+    /// Moves the given preset within the given collection by the given number of positions
+    /// (negative = towards the start).
+    pub fn move_preset_in_collection(
+        &self,
+        collection_name: &str,
+        preset: &PotPreset,
+        delta: isize,
+    ) -> anyhow::Result<()> {
+        let entry_preset_id = self.find_collection_entry_preset_id(collection_name, preset);
+        blocking_write_lock(&self.preset_collections, "move preset in collection")
+            .update(collection_name, |c| c.move_preset(&entry_preset_id, delta))?;
+        self.notify_preset_collections_changed(vec![]);
+        Ok(())
+    }
+
+    /// Writes the given collection to a portable file in the given directory and returns the
+    /// path of that file.
+    pub fn export_preset_collection(
+        &self,
+        collection_name: &str,
+        dir: &Utf8Path,
+    ) -> anyhow::Result<Utf8PathBuf> {
+        let preset_collections = self.read_lock_preset_collections();
+        let collection = preset_collections
+            .find(collection_name)
+            .context("preset collection not found")?;
+        let file_name = format!("{}.json", sanitize_filename::sanitize(&collection.name));
+        let file_path = dir.join(file_name);
+        collection.export(&file_path)?;
+        Ok(file_path)
+    }
+
+    /// Imports a collection from a portable file and returns its final name.
+    ///
+    /// Presets are resolved by their persistent IDs. If that fails, e.g. because the file has been
+    /// exported on another machine, presets with the same name and product are taken instead.
+    ///
+    /// Can take long.
+    pub fn import_preset_collection(&self, file_path: &Utf8Path) -> anyhow::Result<String> {
+        let collection = PresetCollection::import(file_path)?;
+        let name = blocking_write_lock(&self.preset_collections, "import preset collection")
+            .add(collection)?;
+        let plugin_db = self.read_lock_plugin_db();
+        let provider_context = ProviderContext::new(&plugin_db);
+        let known_presets = self.resolved_preset_collection_entries();
+        self.resolve_preset_collections(Some(&provider_context), known_presets);
+        self.revision.fetch_add(1, Ordering::Relaxed);
+        Ok(name)
+    }
+
+    /// Returns the IDs of the presets in the given collection, in collection order.
+    ///
+    /// Makes it possible to browse a collection without affecting the filters.
+    pub fn preset_collection_preset_ids(&self, collection_name: &str) -> Arc<Vec<PresetId>> {
+        let preset_collections_revision = self.preset_collections_revision.load(Ordering::Relaxed);
+        {
+            let cache = blocking_read_lock(
+                &self.preset_collection_ids_cache,
+                "pot db preset_collection_preset_ids 1",
+            );
+            if cache.preset_collections_revision == preset_collections_revision {
+                if let Some(preset_ids) = cache.preset_ids_by_collection_name.get(collection_name) {
+                    return preset_ids.clone();
+                }
+            }
+        }
+        let preset_ids: Vec<_> = match self.preset_collection_db_id {
+            None => vec![],
+            Some(db_id) => blocking_read_lock(
+                &self.resolved_preset_collections,
+                "pot db preset_collection_preset_ids 2",
+            )
+            .preset_ids_of_collection(collection_name)
+            .into_iter()
+            .map(|inner_preset_id| PresetId::new(db_id, inner_preset_id))
+            .collect(),
+        };
+        let preset_ids = Arc::new(preset_ids);
+        let mut cache = blocking_write_lock(
+            &self.preset_collection_ids_cache,
+            "pot db preset_collection_preset_ids 3",
+        );
+        if cache.preset_collections_revision != preset_collections_revision {
+            cache.preset_collections_revision = preset_collections_revision;
+            cache.preset_ids_by_collection_name.clear();
+        }
+        cache
+            .preset_ids_by_collection_name
+            .insert(collection_name.to_string(), preset_ids.clone());
+        preset_ids
+    }
+
+    /// The collection entries refer to presets by the preset IDs at the time of adding them. If
+    /// the preset has been resolved by name and product, its persistent ID differs.
+    fn find_collection_entry_preset_id(&self, collection_name: &str, preset: &PotPreset) -> String {
+        let preset_id = preset.common.persistent_id.to_string();
+        let resolved_preset_collections = blocking_read_lock(
+            &self.resolved_preset_collections,
+            "pot db find_collection_entry_preset_id",
+        );
+        resolved_preset_collections
+            .collections
+            .iter()
+            .filter(|c| c.name == collection_name)
+            .flat_map(|c| &c.presets)
+            .find(|e| e.preset.common.persistent_id == preset.common.persistent_id)
+            .map(|e| e.entry_preset_id.clone())
+            .unwrap_or(preset_id)
+    }
+
+    /// Re-resolves the preset collections after a modification, without looking up presets (which
+    /// could block if a refresh is running). The given presets are known in addition to the
+    /// already resolved ones.
+    fn notify_preset_collections_changed(&self, additional_known_presets: Vec<PotPreset>) {
+        let mut known_presets = self.resolved_preset_collection_entries();
+        known_presets.extend(
+            additional_known_presets
+                .into_iter()
+                .map(|p| (p.common.persistent_id.to_string(), p)),
+        );
+        self.resolve_preset_collections(None, known_presets);
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns all currently resolved presets, keyed by the preset ID of the collection entry.
+    fn resolved_preset_collection_entries(&self) -> Vec<(String, PotPreset)> {
+        blocking_read_lock(
+            &self.resolved_preset_collections,
+            "pot db resolved_preset_collection_entries",
+        )
+        .collections
+        .iter()
+        .flat_map(|c| &c.presets)
+        .map(|e| (e.entry_preset_id.clone(), e.preset.clone()))
+        .collect()
+    }
+
+    /// Resolves the entries of all preset collections to actual presets.
+    ///
+    /// Entries which are not among the given known presets (keyed by entry preset ID) are looked
+    /// up in the databases if a provider context is given, otherwise they are left out.
+    fn resolve_preset_collections(
+        &self,
+        provider_context: Option<&ProviderContext>,
+        known_presets: Vec<(String, PotPreset)>,
+    ) {
+        let mut known_presets: HashMap<String, PotPreset> = known_presets.into_iter().collect();
+        // Don't keep the collections locked while looking up presets, this can take a while
+        let collections = self.read_lock_preset_collections().collections().to_vec();
+        let resolved_collections = collections
+            .into_iter()
+            .map(|collection| {
+                let presets = collection
+                    .presets
+                    .into_iter()
+                    .filter_map(|entry| {
+                        let preset = match known_presets.get(&entry.preset_id) {
+                            Some(preset) => preset.clone(),
+                            None => {
+                                let preset = self
+                                    .find_preset_by_collection_entry(provider_context?, &entry)?;
+                                known_presets.insert(entry.preset_id.clone(), preset.clone());
+                                preset
+                            }
+                        };
+                        let resolved_entry = ResolvedPresetCollectionEntry {
+                            entry_preset_id: entry.preset_id,
+                            preset,
+                        };
+                        Some(resolved_entry)
+                    })
+                    .collect();
+                ResolvedPresetCollection {
+                    name: collection.name,
+                    presets,
+                }
+            })
+            .collect();
+        *blocking_write_lock(
+            &self.resolved_preset_collections,
+            "resolve pot preset collections",
+        ) = ResolvedPresetCollections {
+            collections: resolved_collections,
+        };
+        self.preset_collections_revision
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Looks up the preset of the given collection entry, first by persistent ID and then by name
+    /// and product.
+    fn find_preset_by_collection_entry(
+        &self,
+        provider_context: &ProviderContext,
+        entry: &PresetCollectionEntry,
+    ) -> Option<PotPreset> {
+        if let Some(persistent_preset_id) = entry.persistent_preset_id() {
+            let preset = self.find_preset_by_persistent_id_and_name(
+                provider_context,
+                &persistent_preset_id,
+                &entry.preset_name,
+            );
+            if let Some(preset) = preset {
+                return Some(preset.preset);
+            }
+        }
+        self.find_presets_by_name(provider_context, &entry.preset_name, |_| true)
+            .map(|p| p.preset)
+            .find(|p| match (&entry.product_name, &p.common.product_name) {
+                (None, _) => true,
+                (Some(wanted), Some(actual)) => wanted.eq_ignore_ascii_case(actual),
+                (Some(_), None) => false,
+            })
+    }
+
     pub fn add_database(&self, db: impl Database + Send + Sync + 'static) -> DatabaseId {
+        // Same locking order as in set_watching_enabled
+        let mut watcher = blocking_lock(&self.watcher, "add_database");
         let mut databases = blocking_write_lock(&self.databases, "add_database");
         let new_db_id = DatabaseId(databases.len() as u32);
+        if let Some(watcher) = watcher.as_mut() {
+            watcher.watch_database(new_db_id, &db);
+        }
         databases.insert(new_db_id, RwLock::new(Box::new(db)));
         new_db_id
     }
@@ -193,11 +1097,20 @@ impl PotDatabase {
                     | PotFilterKind::IsUser
                     | PotFilterKind::IsFavorite
                     | PotFilterKind::ProductKind
+                    | PotFilterKind::Tag
+                    | PotFilterKind::Rating
+                    | PotFilterKind::IsDuplicate
             ),
             ..Default::default()
         };
         measure_duration(&mut total_output.stats.filter_query_duration, || {
             add_constant_filter_items(affected_kinds, &mut total_output.filter_item_collections);
+            if affected_kinds.contains(PotFilterKind::Tag) {
+                total_output.filter_item_collections.set(
+                    PotFilterKind::Tag,
+                    create_filter_items_tag(&self.read_lock_user_metadata()),
+                );
+            }
             // Let all databases build filter collections and accumulate them
             let mut database_filter_items = Vec::new();
             let mut used_product_ids = HashSet::new();
@@ -224,6 +1137,10 @@ impl PotDatabase {
                 if !input.filters.database_matches(*db_id) {
                     continue;
                 }
+                // Virtual databases contain presets of other databases. Prevent duplicates.
+                if db.is_virtual() && !database_is_selected_explicitly(&input.filters, *db_id) {
+                    continue;
+                }
                 // Add supported filter kinds
                 total_output.supported_filter_kinds |= db.supported_advanced_filter_kinds();
                 // Build and accumulate filters collections
@@ -286,6 +1203,22 @@ impl PotDatabase {
         measure_duration(&mut total_output.stats.preview_filter_duration, || {
             self.apply_has_preview_filter(&input.filters, &mut sortable_preset_ids);
         });
+        // Apply user metadata filters if necessary (expensive!)
+        measure_duration(
+            &mut total_output.stats.user_metadata_filter_duration,
+            || {
+                self.apply_user_metadata_filters(&input.filters, &mut sortable_preset_ids);
+            },
+        );
+        // Apply structured search query if necessary (expensive!)
+        measure_duration(&mut total_output.stats.search_query_filter_duration, || {
+            self.apply_search_query_filter(&input.search_evaluator, &mut sortable_preset_ids);
+        });
+        // Apply duplicate filter if necessary (expensive!). Done last, so that the
+        // surviving preset of each cluster is one that matches all other filters.
+        measure_duration(&mut total_output.stats.duplicate_filter_duration, || {
+            self.apply_duplicate_filter(&input.filters, &mut sortable_preset_ids);
+        });
         // Sort filter items and presets
         measure_duration(&mut total_output.stats.sort_duration, || {
             for (kind, collection) in total_output.filter_item_collections.iter_mut() {
@@ -295,9 +1228,18 @@ impl PotDatabase {
                     });
                 }
             }
-            sortable_preset_ids.sort_by(|(_, p1), (_, p2)| {
-                lexical_sort::lexical_cmp(&p1.preset_name, &p2.preset_name)
-            });
+            // Virtual databases deliver their presets in a meaningful order already
+            if !self.virtual_database_is_selected(&input.filters) {
+                sortable_preset_ids.sort_by(|(_, p1), (_, p2)| {
+                    lexical_sort::lexical_cmp(&p1.preset_name, &p2.preset_name)
+                });
+            }
+            if input.search_evaluator.sorts_by_relevance() {
+                // Stable sort, so presets with the same relevance stay sorted by name
+                sortable_preset_ids.sort_by_cached_key(|(_, p)| {
+                    Reverse(input.search_evaluator.relevance(&p.preset_name))
+                });
+            }
         });
         // Index presets. Because later, we look up the preset index by the preset ID and vice versa
         // and we want that to happen without complexity O(n)! There can be tons of presets!
@@ -310,6 +1252,28 @@ impl PotDatabase {
         total_output
     }
 
+    fn is_virtual_database(&self, db_id: DatabaseId) -> bool {
+        self.read_lock_databases()
+            .get(&db_id)
+            .is_some_and(|db| blocking_read_lock(db, "pot db is_virtual_database").is_virtual())
+    }
+
+    fn virtual_database_ids(&self) -> NonCryptoHashSet<DatabaseId> {
+        self.read_lock_databases()
+            .iter()
+            .filter(|(_, db)| blocking_read_lock(db, "pot db virtual_database_ids").is_virtual())
+            .map(|(db_id, _)| *db_id)
+            .collect()
+    }
+
+    fn virtual_database_is_selected(&self, filters: &Filters) -> bool {
+        let Some(FilterItemId(Some(Fil::Database(db_id)))) = filters.get(PotFilterKind::Database)
+        else {
+            return false;
+        };
+        self.is_virtual_database(db_id)
+    }
+
     fn apply_has_preview_filter(
         &self,
         filters: &Filters,
@@ -329,13 +1293,105 @@ impl PotDatabase {
         }
     }
 
+    fn apply_user_metadata_filters(
+        &self,
+        filters: &Filters,
+        sortable_preset_ids: &mut Vec<(DatabaseId, SortablePresetId)>,
+    ) {
+        if !user_metadata_filters_are_set(filters) {
+            return;
+        }
+        let virtual_db_ids = self.virtual_database_ids();
+        let user_metadata = self.read_lock_user_metadata();
+        let index = self.read_lock_user_metadata_index();
+        sortable_preset_ids.retain(|(db_id, sortable_preset_id)| {
+            let preset_id = PresetId::new(*db_id, sortable_preset_id.inner_preset_id);
+            let metadata = if virtual_db_ids.contains(db_id) {
+                // Virtual databases contain just a few presets, so looking them up is cheap
+                self.find_preset_by_id(preset_id)
+                    .and_then(|preset| user_metadata.get(&preset.common.persistent_id))
+            } else {
+                index
+                    .get(&preset_id)
+                    .and_then(|key| user_metadata.get_by_key(key))
+            };
+            user_metadata_matches_filters(metadata, filters)
+        });
+    }
+
+    /// Hides presets which are duplicates of other presets or shows only those which have
+    /// duplicates.
+    ///
+    /// When hiding duplicates, the first preset of each cluster survives. Because database order is
+    /// preserved at this point, databases registered earlier win.
+    fn apply_duplicate_filter(
+        &self,
+        filters: &Filters,
+        sortable_preset_ids: &mut Vec<(DatabaseId, SortablePresetId)>,
+    ) {
+        let Some(wants_duplicates) = filters.wants_duplicates() else {
+            return;
+        };
+        let presets: Vec<Option<PotPreset>> = sortable_preset_ids
+            .iter()
+            .map(|(db_id, sortable_preset_id)| {
+                self.find_preset_by_id(PresetId::new(*db_id, sortable_preset_id.inner_preset_id))
+            })
+            .collect();
+        let clusters = DuplicateClusters::detect(presets.iter().flatten());
+        let mut presets_exist = presets.iter().map(|p| p.is_some());
+        let mut cluster_preset_index = 0;
+        sortable_preset_ids.retain(|_| {
+            // Preset doesn't exist? Shouldn't happen, but then it's not shown anyway.
+            if !presets_exist.next().unwrap_or(false) {
+                return false;
+            }
+            let i = cluster_preset_index;
+            cluster_preset_index += 1;
+            if wants_duplicates {
+                clusters.has_duplicates(i)
+            } else {
+                clusters.is_first_of_cluster(i)
+            }
+        });
+    }
+
+    /// Evaluates the structured search query (if any).
+    ///
+    /// The providers only understand plain search expressions, so they deliver all presets
+    /// matching the filters and we check the query ourselves.
+    fn apply_search_query_filter(
+        &self,
+        search_evaluator: &SearchEvaluator,
+        sortable_preset_ids: &mut Vec<(DatabaseId, SortablePresetId)>,
+    ) {
+        let Some(query) = search_evaluator.query() else {
+            return;
+        };
+        let user_metadata = self.read_lock_user_metadata();
+        sortable_preset_ids.retain(|(db_id, sortable_preset_id)| {
+            let preset_id = PresetId::new(*db_id, sortable_preset_id.inner_preset_id);
+            let Some(preset) = self.find_preset_by_id(preset_id) else {
+                return false;
+            };
+            let candidate = SearchQueryCandidate {
+                preset: &preset,
+                user_metadata: user_metadata.get(&preset.common.persistent_id),
+                default_fields: search_evaluator.options().search_fields,
+            };
+            query.matches(&candidate)
+        });
+    }
+
     /// Gathers an unsorted list of preset respecting all pre-filters.
     pub fn gather_presets(&self, input: BuildInput) -> Vec<PresetWithId> {
         // TODO-high-pot Implement correctly as soon as favorites writable
         let favorites = PotFavorites::default();
         let plugin_db = self.read_lock_plugin_db();
         let provider_context = ProviderContext::new(&plugin_db);
-        self.gather_preset_ids_internal(&input, &provider_context, &favorites)
+        let mut preset_ids = self.gather_preset_ids_internal(&input, &provider_context, &favorites);
+        self.apply_search_query_filter(&input.search_evaluator, &mut preset_ids);
+        preset_ids
             .into_iter()
             .filter_map(|(db_id, sortable_preset_id)| {
                 let preset_id = PresetId::new(db_id, sortable_preset_id.inner_preset_id);
@@ -350,6 +1406,109 @@ impl PotDatabase {
         input: &BuildInput,
         provider_context: &ProviderContext,
         favorites: &PotFavorites,
+    ) -> Vec<(DatabaseId, SortablePresetId)> {
+        let mut preset_ids = self.query_preset_ids(input, provider_context, favorites, None);
+        if input.search_evaluator.wants_notes_search() {
+            let matches_by_notes =
+                self.gather_preset_ids_matching_notes(input, provider_context, favorites);
+            let existing: HashSet<_> = preset_ids
+                .iter()
+                .map(|(db_id, p)| (*db_id, p.inner_preset_id))
+                .collect();
+            preset_ids.extend(
+                matches_by_notes
+                    .into_iter()
+                    .filter(|(db_id, p)| !existing.contains(&(*db_id, p.inner_preset_id))),
+            );
+        }
+        preset_ids
+    }
+
+    /// Finds presets whose user notes match the search expression.
+    ///
+    /// The providers don't know anything about user notes, so we let them deliver all presets
+    /// matching the filters and pick the ones with matching notes. This is only done for databases
+    /// which actually contain presets with matching notes.
+    fn gather_preset_ids_matching_notes(
+        &self,
+        input: &BuildInput,
+        provider_context: &ProviderContext,
+        favorites: &PotFavorites,
+    ) -> Vec<(DatabaseId, SortablePresetId)> {
+        let matching_preset_ids: NonCryptoHashSet<PresetId> = {
+            let user_metadata = self.read_lock_user_metadata();
+            let matching_keys: NonCryptoHashSet<&str> = user_metadata
+                .entries()
+                .filter(|(_, m)| input.search_evaluator.matches_notes(&m.notes))
+                .map(|(key, _)| key)
+                .collect();
+            if matching_keys.is_empty() {
+                return vec![];
+            }
+            self.read_lock_user_metadata_index()
+                .iter()
+                .filter(|(_, key)| matching_keys.contains(key.as_str()))
+                .map(|(preset_id, _)| *preset_id)
+                .collect()
+        };
+        if matching_preset_ids.is_empty() {
+            return vec![];
+        }
+        let matching_db_ids: NonCryptoHashSet<DatabaseId> = matching_preset_ids
+            .iter()
+            .map(|preset_id| preset_id.database_id)
+            .collect();
+        let mut notes_search_candidates = blocking_write_lock(
+            &self.notes_search_candidates,
+            "pot db gather_preset_ids_matching_notes",
+        );
+        let pot_db_revision = self.revision();
+        let candidates_are_valid = notes_search_candidates.as_ref().is_some_and(|c| {
+            c.pot_db_revision == pot_db_revision
+                && c.filters == input.filters
+                && c.filter_excludes == input.filter_excludes
+                && c.db_ids == matching_db_ids
+        });
+        if !candidates_are_valid {
+            let unrestricted_input = BuildInput {
+                search_evaluator: input.search_evaluator.without_search_expression(),
+                ..input.clone()
+            };
+            let preset_ids = self.query_preset_ids(
+                &unrestricted_input,
+                provider_context,
+                favorites,
+                Some(&matching_db_ids),
+            );
+            *notes_search_candidates = Some(NotesSearchCandidates {
+                pot_db_revision,
+                filters: input.filters,
+                filter_excludes: input.filter_excludes.clone(),
+                db_ids: matching_db_ids,
+                preset_ids,
+            });
+        }
+        let Some(candidates) = notes_search_candidates.as_ref() else {
+            return vec![];
+        };
+        candidates
+            .preset_ids
+            .iter()
+            .filter(|(db_id, sortable_preset_id)| {
+                let preset_id = PresetId::new(*db_id, sortable_preset_id.inner_preset_id);
+                matching_preset_ids.contains(&preset_id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// If `only_db_ids` is given, only these databases are queried.
+    fn query_preset_ids(
+        &self,
+        input: &BuildInput,
+        provider_context: &ProviderContext,
+        favorites: &PotFavorites,
+        only_db_ids: Option<&NonCryptoHashSet<DatabaseId>>,
     ) -> Vec<(DatabaseId, SortablePresetId)> {
         self.read_lock_databases()
             .deref()
@@ -357,10 +1516,14 @@ impl PotDatabase {
             .filter(|(db_id, _)| {
                 input.filters.database_matches(**db_id)
                     && !input.filter_excludes.contains_database(**db_id)
+                    && only_db_ids.map_or(true, |ids| ids.contains(db_id))
             })
             .filter_map(|(db_id, db)| {
                 // Acquire database access
                 let db = blocking_read_lock(db, "pot db build_collections 2");
+                if db.is_virtual() && !database_is_selected_explicitly(&input.filters, *db_id) {
+                    return None;
+                }
                 // Don't even try to get presets if one filter is set which is not
                 // supported by database.
                 if input
@@ -455,6 +1618,10 @@ pub struct BuildOutput {
     pub stats: Stats,
 }
 
+fn database_is_selected_explicitly(filters: &Filters, db_id: DatabaseId) -> bool {
+    filters.get(PotFilterKind::Database) == Some(FilterItemId(Some(Fil::Database(db_id))))
+}
+
 fn measure_duration<R>(duration: &mut Duration, f: impl FnOnce() -> R) -> R {
     let start = Instant::now();
     let r = f();
@@ -487,6 +1654,15 @@ fn add_constant_filter_items(
     if affected_kinds.contains(PotFilterKind::HasPreview) {
         filter_item_collections.set(PotFilterKind::HasPreview, create_filter_items_has_preview());
     }
+    if affected_kinds.contains(PotFilterKind::Rating) {
+        filter_item_collections.set(PotFilterKind::Rating, create_filter_items_rating());
+    }
+    if affected_kinds.contains(PotFilterKind::IsDuplicate) {
+        filter_item_collections.set(
+            PotFilterKind::IsDuplicate,
+            create_filter_items_is_duplicate(),
+        );
+    }
     if affected_kinds.contains(PotFilterKind::ProductKind) {
         filter_item_collections.set(
             PotFilterKind::ProductKind,
@@ -495,6 +1671,39 @@ fn add_constant_filter_items(
     }
 }
 
+/// Returns the IDs of the databases which changed since the last call, or `None` if the watcher
+/// is gone.
+fn take_changed_db_ids(
+    state: &Weak<Mutex<DatabaseWatcherState>>,
+) -> Option<NonCryptoHashSet<DatabaseId>> {
+    let state = state.upgrade()?;
+    let changed_db_ids =
+        mem::take(&mut blocking_lock(&state, "pot db take changed").changed_db_ids);
+    Some(changed_db_ids)
+}
+
+fn create_filter_items_tag(user_metadata: &PotUserMetadataStore) -> Vec<FilterItem> {
+    let tag_items = user_metadata
+        .all_tags()
+        .into_iter()
+        .map(|tag| FilterItem::simple(Fil::text(tag), tag, '🏷', ""));
+    iter::once(FilterItem::none()).chain(tag_items).collect()
+}
+
+fn create_filter_items_rating() -> Vec<FilterItem> {
+    (1..=MAX_PRESET_RATING)
+        .map(|rating| {
+            let stars = "★".repeat(rating as usize);
+            let name = if rating == MAX_PRESET_RATING {
+                stars
+            } else {
+                format!("{stars} and more")
+            };
+            FilterItem::simple(Fil::Rating(rating), &name, '★', "")
+        })
+        .collect()
+}
+
 fn create_filter_items_is_available() -> Vec<FilterItem> {
     vec![
         FilterItem::simple(FIL_IS_AVAILABLE_FALSE, "Not available", '❌', ""),
@@ -546,6 +1755,13 @@ fn create_filter_items_is_user() -> Vec<FilterItem> {
     ]
 }
 
+fn create_filter_items_is_duplicate() -> Vec<FilterItem> {
+    vec![
+        FilterItem::simple(FIL_IS_DUPLICATE_FALSE, "Hide duplicates", '🗋', "Display each preset only once, even if it exists multiple times with the same content or, if the content is unknown, with a similar name for the same product. This filter can take long when operating on a large preset list!"),
+        FilterItem::simple(FIL_IS_DUPLICATE_TRUE, "Only duplicates", '🗐', "Display only presets that exist multiple times with the same content or, if the content is unknown, with a similar name for the same product. This filter can take long when operating on a large preset list!"),
+    ]
+}
+
 fn create_filter_items_has_preview() -> Vec<FilterItem> {
     vec![
         FilterItem::simple(FIL_HAS_PREVIEW_FALSE, "No preview", '🔇', "Display only presets that have no preview. This filter can take very long when operating on a large preset list because it checks whether the preview files actually exist!"),
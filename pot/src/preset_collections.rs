@@ -0,0 +1,292 @@
+use crate::json_store::{load_json_store, save_json_store};
+use crate::{PersistentPresetId, PotPreset};
+use anyhow::{bail, Context};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Version of the portable preset collection file format.
+const PORTABLE_FILE_VERSION: u32 = 1;
+
+/// Refers to a preset within a preset collection.
+///
+/// Contains not just the persistent preset ID but also name and product, so that the preset can
+/// be found on another machine, where the persistent ID might not match.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PresetCollectionEntry {
+    /// String representation of the persistent preset ID.
+    pub preset_id: String,
+    pub preset_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_name: Option<String>,
+}
+
+impl PresetCollectionEntry {
+    pub fn from_preset(preset: &PotPreset) -> Self {
+        Self {
+            preset_id: preset.common.persistent_id.to_string(),
+            preset_name: preset.common.name.clone(),
+            product_name: preset.common.product_name.clone(),
+        }
+    }
+
+    pub fn persistent_preset_id(&self) -> Option<PersistentPresetId> {
+        self.preset_id.parse().ok()
+    }
+}
+
+/// A named, ordered list of presets, e.g. the presets needed for a particular gig.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PresetCollection {
+    pub name: String,
+    #[serde(default)]
+    pub presets: Vec<PresetCollectionEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PortablePresetCollectionFileContent {
+    version: u32,
+    #[serde(flatten)]
+    collection: PresetCollection,
+}
+
+impl PresetCollection {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            presets: vec![],
+        }
+    }
+
+    pub fn contains(&self, preset_id: &str) -> bool {
+        self.presets.iter().any(|e| e.preset_id == preset_id)
+    }
+
+    /// Adds the given entry at the end. Returns `false` if the preset is contained already.
+    pub fn add(&mut self, entry: PresetCollectionEntry) -> bool {
+        if self.contains(&entry.preset_id) {
+            return false;
+        }
+        self.presets.push(entry);
+        true
+    }
+
+    /// Returns `false` if the preset was not contained.
+    pub fn remove(&mut self, preset_id: &str) -> bool {
+        let count_before = self.presets.len();
+        self.presets.retain(|e| e.preset_id != preset_id);
+        self.presets.len() != count_before
+    }
+
+    /// Moves the given preset by the given number of positions (negative = towards the start).
+    pub fn move_preset(&mut self, preset_id: &str, delta: isize) {
+        let Some(index) = self.presets.iter().position(|e| e.preset_id == preset_id) else {
+            return;
+        };
+        let new_index = index
+            .saturating_add_signed(delta)
+            .min(self.presets.len() - 1);
+        let entry = self.presets.remove(index);
+        self.presets.insert(new_index, entry);
+    }
+
+    /// Reads a collection from a portable file, e.g. one exported on another machine.
+    pub fn import(file_path: &Utf8Path) -> anyhow::Result<Self> {
+        let json = fs::read_to_string(file_path).context("couldn't read preset collection file")?;
+        let content: PortablePresetCollectionFileContent =
+            serde_json::from_str(&json).context("invalid preset collection file")?;
+        if content.version > PORTABLE_FILE_VERSION {
+            bail!(
+                "preset collection file has been created with a newer version (file format {})",
+                content.version
+            );
+        }
+        Ok(content.collection)
+    }
+
+    /// Writes this collection to a portable file.
+    pub fn export(&self, file_path: &Utf8Path) -> anyhow::Result<()> {
+        if let Some(dir) = file_path.parent() {
+            fs::create_dir_all(dir).context("couldn't create export directory")?;
+        }
+        let content = PortablePresetCollectionFileContent {
+            version: PORTABLE_FILE_VERSION,
+            collection: self.clone(),
+        };
+        let json = serde_json::to_string_pretty(&content)?;
+        fs::write(file_path, json).context("couldn't write preset collection file")?;
+        Ok(())
+    }
+}
+
+/// Local store of all preset collections.
+#[derive(Debug)]
+pub struct PresetCollectionStore {
+    file_path: Utf8PathBuf,
+    collections: Vec<PresetCollection>,
+}
+
+#[derive(Default, Deserialize)]
+struct PresetCollectionStoreFileContent {
+    #[serde(default)]
+    collections: Vec<PresetCollection>,
+}
+
+#[derive(Serialize)]
+struct PresetCollectionStoreFileContentRef<'a> {
+    collections: &'a [PresetCollection],
+}
+
+impl PresetCollectionStore {
+    pub fn load(file_path: Utf8PathBuf) -> Self {
+        let content: PresetCollectionStoreFileContent =
+            load_json_store(&file_path, "Pot preset collections");
+        Self {
+            file_path,
+            collections: content.collections,
+        }
+    }
+
+    pub fn collections(&self) -> &[PresetCollection] {
+        &self.collections
+    }
+
+    pub fn find(&self, name: &str) -> Option<&PresetCollection> {
+        self.collections.iter().find(|c| c.name == name)
+    }
+
+    /// Adds the given collection and saves the store.
+    ///
+    /// If a collection with the same name exists already, the name gets a number suffix. Returns
+    /// the final name.
+    pub fn add(&mut self, mut collection: PresetCollection) -> anyhow::Result<String> {
+        let base_name = collection.name.trim().to_string();
+        if base_name.is_empty() {
+            bail!("collection name must not be empty");
+        }
+        let mut name = base_name.clone();
+        let mut counter = 2;
+        while self.find(&name).is_some() {
+            name = format!("{base_name} ({counter})");
+            counter += 1;
+        }
+        collection.name = name.clone();
+        let mut collections = self.collections.clone();
+        collections.push(collection);
+        self.save(collections)?;
+        Ok(name)
+    }
+
+    /// Removes the given collection and saves the store.
+    pub fn remove(&mut self, name: &str) -> anyhow::Result<()> {
+        let mut collections = self.collections.clone();
+        collections.retain(|c| c.name != name);
+        self.save(collections)
+    }
+
+    /// Modifies the given collection and saves the store.
+    pub fn update<R>(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut PresetCollection) -> R,
+    ) -> anyhow::Result<R> {
+        let mut collections = self.collections.clone();
+        let collection = collections
+            .iter_mut()
+            .find(|c| c.name == name)
+            .context("preset collection not found")?;
+        let result = f(collection);
+        self.save(collections)?;
+        Ok(result)
+    }
+
+    /// Saves the given collections and makes them the current ones.
+    ///
+    /// If saving fails, the current collections stay as they are, so that memory and disk don't
+    /// diverge.
+    fn save(&mut self, collections: Vec<PresetCollection>) -> anyhow::Result<()> {
+        let content = PresetCollectionStoreFileContentRef {
+            collections: &collections,
+        };
+        save_json_store(&self.file_path, "Pot preset collections", &content)?;
+        self.collections = collections;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(preset_id: &str) -> PresetCollectionEntry {
+        PresetCollectionEntry {
+            preset_id: preset_id.to_string(),
+            preset_name: preset_id.to_string(),
+            product_name: None,
+        }
+    }
+
+    #[test]
+    fn add_remove_and_move() {
+        // Given
+        let mut collection = PresetCollection::new("Gig".to_string());
+        // When
+        let added_a = collection.add(entry("a"));
+        let added_b = collection.add(entry("b"));
+        let added_a_again = collection.add(entry("a"));
+        collection.add(entry("c"));
+        collection.move_preset("c", -5);
+        collection.move_preset("b", 1);
+        let removed_a = collection.remove("a");
+        let removed_x = collection.remove("x");
+        // Then
+        assert!(added_a && added_b && !added_a_again);
+        assert!(removed_a && !removed_x);
+        let ids: Vec<_> = collection
+            .presets
+            .iter()
+            .map(|e| e.preset_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c", "b"]);
+    }
+
+    #[test]
+    fn keep_collections_unchanged_if_saving_fails() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let mut store = PresetCollectionStore::load(dir_path.join("collections.json"));
+        store.add(PresetCollection::new("Gig".to_string())).unwrap();
+        // A file where the Pot directory should be makes saving fail
+        fs::write(dir_path.join("blocker"), "").unwrap();
+        store.file_path = dir_path.join("blocker/collections.json");
+        // When
+        let add_result = store.add(PresetCollection::new("Rehearsal".to_string()));
+        let remove_result = store.remove("Gig");
+        let update_result = store.update("Gig", |c| c.add(entry("a")));
+        // Then
+        assert!(add_result.is_err() && remove_result.is_err() && update_result.is_err());
+        assert_eq!(store.collections().len(), 1);
+        assert!(store.find("Gig").unwrap().presets.is_empty());
+    }
+
+    #[test]
+    fn portable_file_format() {
+        // Given
+        let json = r#"{
+            "version": 1,
+            "name": "Gig",
+            "presets": [
+                { "preset_id": "a", "preset_name": "Warm Pad", "product_name": "Synth" }
+            ]
+        }"#;
+        // When
+        let content: PortablePresetCollectionFileContent = serde_json::from_str(json).unwrap();
+        // Then
+        assert_eq!(content.collection.name, "Gig");
+        assert_eq!(
+            content.collection.presets[0].product_name.as_deref(),
+            Some("Synth")
+        );
+    }
+}
@@ -7,6 +7,7 @@ use enumset::{enum_set, EnumSet};
 use helgobox_api::persistence::PotFilterKind;
 use std::borrow::Cow;
 use std::error::Error;
+use std::path::PathBuf;
 
 /// A database ID that's only stable during the runtime of ReaLearn.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -25,8 +26,24 @@ pub trait Database {
         enum_set!()
     }
 
+    /// Returns whether this database only contains presets of other databases (e.g. recently
+    /// loaded presets).
+    ///
+    /// The presets of virtual databases are only shown if the database is selected explicitly,
+    /// otherwise they would appear twice. They are not sorted by name.
+    fn is_virtual(&self) -> bool {
+        false
+    }
+
     fn refresh(&mut self, context: &ProviderContext) -> Result<(), Box<dyn Error>>;
 
+    /// Returns the directories containing the files this database is based on.
+    ///
+    /// Used for watching the file system. Changes within these directories trigger a refresh.
+    fn watched_dirs(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+
     fn query_filter_collections(
         &self,
         context: &ProviderContext,
@@ -130,3 +147,5 @@ pub const FIL_PRODUCT_KIND_LOOP: Fil = Fil::ProductKind(ProductKind::Loop);
 pub const FIL_PRODUCT_KIND_ONE_SHOT: Fil = Fil::ProductKind(ProductKind::OneShot);
 pub const FIL_HAS_PREVIEW_TRUE: Fil = Fil::Boolean(true);
 pub const FIL_HAS_PREVIEW_FALSE: Fil = Fil::Boolean(false);
+pub const FIL_IS_DUPLICATE_TRUE: Fil = Fil::Boolean(true);
+pub const FIL_IS_DUPLICATE_FALSE: Fil = Fil::Boolean(false);
@@ -8,21 +8,22 @@ use crate::{
 };
 use std::borrow::Cow;
 
-use crate::plugins::{PluginCore, PluginDatabase};
+use crate::index_cache::{scan_files, FileIndexCache, ScannedFile};
+use crate::plugins::{is_fx_rxml_line, PluginCore};
 use base::hash_util::{NonCryptoHashSet, NonCryptoIndexMap, PersistentHash, PersistentHasher};
 use camino::Utf8PathBuf;
 use either::Either;
 use enumset::{enum_set, EnumSet};
 use helgobox_api::persistence::PotFilterKind;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::hash::Hasher;
 use std::io::{BufRead, BufReader};
 use std::iter;
-use std::path::Path;
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
 
 pub struct DirectoryDatabase {
     persistent_id: PersistentDatabaseId,
@@ -31,6 +32,7 @@ pub struct DirectoryDatabase {
     name: &'static str,
     description: &'static str,
     entries: Vec<PresetEntry>,
+    index_cache: FileIndexCache<ProcessedFile>,
 }
 
 pub struct DirectoryDbConfig {
@@ -39,6 +41,7 @@ pub struct DirectoryDbConfig {
     pub valid_extensions: &'static [&'static str],
     pub name: &'static str,
     pub description: &'static str,
+    pub index_cache_file: Utf8PathBuf,
 }
 
 impl DirectoryDatabase {
@@ -53,10 +56,18 @@ impl DirectoryDatabase {
             root_dir: config.root_dir,
             valid_extensions: config.valid_extensions.iter().map(OsStr::new).collect(),
             description: config.description,
+            index_cache: FileIndexCache::new(config.index_cache_file),
         };
         Ok(db)
     }
 
+    fn scan_preset_files(&self) -> Vec<ScannedFile> {
+        scan_files(self.root_dir.as_std_path(), None, |path| {
+            path.extension()
+                .is_some_and(|ext| self.valid_extensions.contains(ext))
+        })
+    }
+
     fn query_presets_internal<'a>(
         &'a self,
         filter_input: &'a FilterInput,
@@ -100,27 +111,19 @@ impl Database for DirectoryDatabase {
     }
 
     fn refresh(&mut self, ctx: &ProviderContext) -> Result<(), Box<dyn Error>> {
-        self.entries = WalkDir::new(&self.root_dir)
-            .follow_links(true)
+        let scanned_files = self.scan_preset_files();
+        self.entries = self
+            .index_cache
+            .update(&scanned_files, |f| process_file(&f.path).ok())
             .into_iter()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                if !entry.file_type().is_file() {
-                    return None;
-                }
-                let extension = entry.path().extension()?;
-                if !self.valid_extensions.contains(extension) {
-                    return None;
-                }
-                let relative_path = entry.path().strip_prefix(&self.root_dir).ok()?;
-                // Immediately exclude relative paths that can't be represented as valid UTF-8.
-                // Otherwise we will potentially open a can of worms (regarding persistence etc.).
-                let processing_output = process_file(entry.path(), ctx.plugin_db).ok()?;
+            .filter_map(|(scanned_file, processed_file)| {
                 let preset_entry = PresetEntry {
-                    preset_name: entry.path().file_stem()?.to_str()?.to_string(),
-                    relative_path: relative_path.to_str()?.to_string(),
-                    plugin_cores: processing_output.used_plugins,
-                    content_hash: processing_output.content_hash,
+                    preset_name: scanned_file.path.file_stem()?.to_str()?.to_string(),
+                    relative_path: scanned_file.relative_path.clone(),
+                    plugin_cores: ctx.plugin_db.detect_plugins_from_rxml_lines(
+                        processed_file.fx_lines.iter().map(String::as_str),
+                    ),
+                    content_hash: processed_file.content_hash,
                 };
                 Some(preset_entry)
             })
@@ -128,6 +131,10 @@ impl Database for DirectoryDatabase {
         Ok(())
     }
 
+    fn watched_dirs(&self) -> Vec<PathBuf> {
+        vec![self.root_dir.clone().into_std_path_buf()]
+    }
+
     fn query_filter_collections(
         &self,
         _: &ProviderContext,
@@ -204,12 +211,15 @@ impl Database for DirectoryDatabase {
     }
 }
 
-struct FileProcessingOutput {
+/// Result of processing a preset file. Cached, so it must not depend on the plug-in database.
+#[derive(Clone, Serialize, Deserialize)]
+struct ProcessedFile {
     content_hash: PersistentHash,
-    used_plugins: NonCryptoIndexMap<PluginId, PluginCore>,
+    /// Lines which declare FX. Resolved to plug-ins on each refresh.
+    fx_lines: Vec<String>,
 }
 
-/// Finds used plug-ins in a REAPER-XML-like text file (e.g. RPP, RfxChain, RTrackTemplate).
+/// Finds FX declarations in a REAPER-XML-like text file (e.g. RPP, RfxChain, RTrackTemplate).
 ///
 /// Examples entries:
 ///
@@ -218,12 +228,9 @@ struct FileProcessingOutput {
 ///     <VST "VSTi: ReaSamplOmatic5000 (Cockos)"
 ///     <CLAP "CLAPi: Surge XT (Surge Synth Team)"
 /// ```
-fn process_file(
-    path: &Path,
-    plugin_db: &PluginDatabase,
-) -> Result<FileProcessingOutput, Box<dyn Error>> {
+fn process_file(path: &Path) -> Result<ProcessedFile, Box<dyn Error>> {
     let file = File::open(path)?;
-    let mut used_plugins = NonCryptoIndexMap::default();
+    let mut fx_lines = Vec::new();
     let mut buffer = String::new();
     let mut reader = BufReader::new(&file);
     let mut hasher = PersistentHasher::new();
@@ -234,14 +241,14 @@ fn process_file(
         }
         hasher.write(buffer.as_bytes());
         let line = buffer.trim();
-        if let Some(plugin) = plugin_db.detect_plugin_from_rxml_line(line) {
-            used_plugins.insert(plugin.common.core.id, plugin.common.core);
+        if is_fx_rxml_line(line) {
+            fx_lines.push(line.to_string());
         }
         buffer.clear();
     }
-    let output = FileProcessingOutput {
+    let output = ProcessedFile {
         content_hash: hasher.digest_128(),
-        used_plugins,
+        fx_lines,
     };
     Ok(output)
 }
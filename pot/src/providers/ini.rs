@@ -8,7 +8,8 @@ use crate::{
 };
 use std::borrow::Cow;
 
-use crate::plugins::{Plugin, PluginCore, SuperPluginKind};
+use crate::index_cache::{scan_files, FileIndexCache, ScannedFile};
+use crate::plugins::{Plugin, PluginCore, PluginDatabase, SuperPluginKind};
 use base::hash_util::{PersistentHash, PersistentHasher};
 use camino::Utf8PathBuf;
 use either::Either;
@@ -16,22 +17,25 @@ use enumset::{enum_set, EnumSet};
 use helgobox_api::persistence::PotFilterKind;
 use ini::Ini;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::hash::Hasher;
 use std::iter;
+use std::path::PathBuf;
 use std::str::FromStr;
-use walkdir::WalkDir;
 
 pub struct IniDatabase {
     persistent_id: PersistentDatabaseId,
     root_dir: Utf8PathBuf,
     entries: Vec<PresetEntry>,
+    index_cache: FileIndexCache<Vec<IniPreset>>,
 }
 
 impl IniDatabase {
     pub fn open(
         persistent_id: PersistentDatabaseId,
         root_dir: Utf8PathBuf,
+        index_cache_file: Utf8PathBuf,
     ) -> Result<Self, Box<dyn Error>> {
         if !root_dir.try_exists()? {
             return Err("path to presets root directory doesn't exist".into());
@@ -40,10 +44,19 @@ impl IniDatabase {
             persistent_id,
             entries: Default::default(),
             root_dir,
+            index_cache: FileIndexCache::new(index_cache_file),
         };
         Ok(db)
     }
 
+    fn scan_ini_files(&self) -> Vec<ScannedFile> {
+        scan_files(self.root_dir.as_std_path(), Some(1), |path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| parse_file_name(n).is_some())
+        })
+    }
+
     fn query_presets_internal<'a>(
         &'a self,
         filter_input: &'a FilterInput,
@@ -80,7 +93,7 @@ impl Database for IniDatabase {
     }
 
     fn description(&self) -> Cow<str> {
-        "All FX presets that you saved via \"Save preset...\" in REAPER's FX window.\n\".vstpreset\"-style presets are not yet supported!"
+        "All FX presets that you saved via \"Save preset...\" in REAPER's FX window.\n\".vstpreset\"-style presets are covered by the \"VST3 presets\" database."
             .into()
     }
 
@@ -89,119 +102,21 @@ impl Database for IniDatabase {
     }
 
     fn refresh(&mut self, ctx: &ProviderContext) -> Result<(), Box<dyn Error>> {
-        let file_name_regex = base::regex!(r#"(?i)(.*?)-(.*).ini"#);
-        self.entries = WalkDir::new(&self.root_dir)
-            .max_depth(1)
-            .follow_links(true)
+        let scanned_files = self.scan_ini_files();
+        self.entries = self
+            .index_cache
+            .update(&scanned_files, |f| process_file(f).ok())
             .into_iter()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                if !entry.file_type().is_file() {
-                    return None;
-                }
-                let file_name = entry.file_name().to_str()?;
-                // Example file names:
-                // - vst-Zebra2.ini
-                // - vst3-FM8-1168312232-builtin.ini
-                // - vst-TDR Nova-builtin.ini
-                // - vst-reacomp.ini
-                // - vst3-Massive.ini
-                // - clap-org_surge-synth-team_surge-xt.ini
-                // - js-analysis_hund.ini
-                let captures = file_name_regex.captures(file_name)?;
-                let plugin_kind_str = captures.get(1)?.as_str();
-                let plugin_kind = PluginKind::from_str(plugin_kind_str).ok()?;
-                let plugin_identifier = captures.get(2)?.as_str();
-                if plugin_identifier.ends_with("-builtin") {
-                    return None;
-                }
-                let (main_plugin_identifier, shell_qualifier) =
-                    match plugin_identifier.rsplit_once('-') {
-                        // Example: vst3-Zebra2-959560201.ini
-                        // (interpret the number behind the dash as shell qualifier)
-                        Some((left, right))
-                            if right.len() >= 5 && right.chars().all(|ch| ch.is_ascii_digit()) =>
-                        {
-                            (left, Some(right))
-                        }
-                        // Examples: "vst-Tritik-Irid.ini", "vst-Zebra2.ini"
-                        _ => (plugin_identifier, None),
-                    };
-                let plugin = ctx.plugin_db.plugins().find(|p| {
-                    if p.common.core.id.kind() != plugin_kind {
-                        return false;
-                    }
-                    match &p.kind {
-                        SuperPluginKind::Vst(k) => {
-                            let unsafe_char_regex = base::regex!(r#"[^a-zA-Z0-9_]"#);
-                            let safe_main_plugin_identifier =
-                                unsafe_char_regex.replace_all(main_plugin_identifier, "_");
-                            let file_name_prefix = format!("{safe_main_plugin_identifier}.");
-                            tracing::trace!(
-                                "Test VST '{}' should start with INI plug-in file name prefix '{file_name_prefix}'",
-                                k.safe_file_name
-                            );
-                            if !k.safe_file_name.starts_with(&file_name_prefix) {
-                                return false;
-                            }
-                            let plugin_shell_qualifier = k.shell_qualifier.as_deref();
-                            if shell_qualifier != plugin_shell_qualifier {
-                                return false;
-                            }
-                            true
-                        }
-                        SuperPluginKind::Clap(k) => {
-                            let safe_plugin_id = k.id.replace('.', "_");
-                            if plugin_identifier != safe_plugin_id {
-                                return false;
-                            }
-                            true
-                        }
-                        SuperPluginKind::Js(k) => {
-                            let lowercase_safe_path = k.path.replace(['/', '\\', '.'], "_").to_lowercase();
-                            let lowercase_plugin_identifier = plugin_identifier.to_lowercase();
-                            tracing::trace!(
-                                "Test JS '{lowercase_safe_path}' vs. INI plug-in identifier '{lowercase_plugin_identifier}'"
-                            );
-                            if lowercase_plugin_identifier != lowercase_safe_path {
-                                return false;
-                            }
-                            true
-                        }
-                    }
-                });
-                let ini_file = Ini::load_from_file(entry.path()).ok()?;
-                let general_section = ini_file.section(Some("General"))?;
-                let nb_presets = general_section.get("NbPresets")?;
-                let preset_count: u32 = nb_presets.parse().ok()?;
-                let plugin_identifier = plugin_identifier.to_string();
-                let iter = (0..preset_count).filter_map(move |i| {
-                    let section_name = format!("Preset{i}");
-                    let section = ini_file.section(Some(section_name))?;
-                    let name = section.get("Name")?;
-                    // Calculate hash. At first add info about the plug-in. Without that info,
-                    // the content could be ambiguous.
-                    let mut hasher = PersistentHasher::new();
-                    let plugin_kind_str = plugin_kind.as_ref();
-                    let plugin_info = format!("{plugin_kind_str}-{plugin_identifier}");
-                    hasher.write(plugin_info.as_bytes());
-                    // Calculate hash out of data properties ("Data", "Data_1", "Data_2", ...)
-                    let data = section.get("Data")?;
-                    hasher.write(data.as_bytes());
-                    let mut i = 1;
-                    while let Some(more_data) = section.get(format!("Data{i}")) {
-                        hasher.write(more_data.as_bytes());
-                        i += 1;
-                    }
-                    // Build entry
-                    let preset_entry = PresetEntry {
-                        preset_name: name.to_string(),
-                        plugin_kind,
-                        plugin_identifier: plugin_identifier.clone(),
-                        plugin: plugin.map(|p| p.common.core),
-                        content_hash: Some(hasher.digest_128()),
-                    };
-                    Some(preset_entry)
+            .filter_map(|(scanned_file, ini_presets)| {
+                let (plugin_kind, plugin_identifier) =
+                    parse_file_name(&scanned_file.relative_path)?;
+                let plugin = find_plugin(ctx.plugin_db, plugin_kind, plugin_identifier);
+                let iter = ini_presets.into_iter().map(move |ini_preset| PresetEntry {
+                    preset_name: ini_preset.name,
+                    plugin_kind,
+                    plugin_identifier: plugin_identifier.to_string(),
+                    plugin: plugin.map(|p| p.common.core),
+                    content_hash: Some(ini_preset.content_hash),
                 });
                 Some(iter)
             })
@@ -210,6 +125,10 @@ impl Database for IniDatabase {
         Ok(())
     }
 
+    fn watched_dirs(&self) -> Vec<PathBuf> {
+        vec![self.root_dir.clone().into_std_path_buf()]
+    }
+
     fn query_filter_collections(
         &self,
         _: &ProviderContext,
@@ -290,6 +209,138 @@ impl Database for IniDatabase {
     }
 }
 
+/// Finds the plug-in to which the presets in the INI file belong.
+fn find_plugin<'a>(
+    plugin_db: &'a PluginDatabase,
+    plugin_kind: PluginKind,
+    plugin_identifier: &str,
+) -> Option<&'a Plugin> {
+    let (main_plugin_identifier, shell_qualifier) = match plugin_identifier.rsplit_once('-') {
+        // Example: vst3-Zebra2-959560201.ini
+        // (interpret the number behind the dash as shell qualifier)
+        Some((left, right)) if right.len() >= 5 && right.chars().all(|ch| ch.is_ascii_digit()) => {
+            (left, Some(right))
+        }
+        // Examples: "vst-Tritik-Irid.ini", "vst-Zebra2.ini"
+        _ => (plugin_identifier, None),
+    };
+    plugin_db.plugins().find(|p| {
+        if p.common.core.id.kind() != plugin_kind {
+            return false;
+        }
+        match &p.kind {
+            SuperPluginKind::Vst(k) => {
+                let unsafe_char_regex = base::regex!(r#"[^a-zA-Z0-9_]"#);
+                let safe_main_plugin_identifier =
+                    unsafe_char_regex.replace_all(main_plugin_identifier, "_");
+                let file_name_prefix = format!("{safe_main_plugin_identifier}.");
+                tracing::trace!(
+                    "Test VST '{}' should start with INI plug-in file name prefix '{file_name_prefix}'",
+                    k.safe_file_name
+                );
+                if !k.safe_file_name.starts_with(&file_name_prefix) {
+                    return false;
+                }
+                let plugin_shell_qualifier = k.shell_qualifier.as_deref();
+                if shell_qualifier != plugin_shell_qualifier {
+                    return false;
+                }
+                true
+            }
+            SuperPluginKind::Clap(k) => {
+                let safe_plugin_id = k.id.replace('.', "_");
+                if plugin_identifier != safe_plugin_id {
+                    return false;
+                }
+                true
+            }
+            // LV2 presets are covered by the LV2 preset database
+            SuperPluginKind::Lv2(_) => false,
+            SuperPluginKind::Js(k) => {
+                let lowercase_safe_path = k.path.replace(['/', '\\', '.'], "_").to_lowercase();
+                let lowercase_plugin_identifier = plugin_identifier.to_lowercase();
+                tracing::trace!(
+                    "Test JS '{lowercase_safe_path}' vs. INI plug-in identifier '{lowercase_plugin_identifier}'"
+                );
+                if lowercase_plugin_identifier != lowercase_safe_path {
+                    return false;
+                }
+                true
+            }
+        }
+    })
+}
+
+/// Extracts plug-in kind and identifier from the INI file name.
+///
+/// Returns `None` if the file doesn't contain user presets.
+fn parse_file_name(file_name: &str) -> Option<(PluginKind, &str)> {
+    // Example file names:
+    // - vst-Zebra2.ini
+    // - vst3-FM8-1168312232-builtin.ini
+    // - vst-TDR Nova-builtin.ini
+    // - vst-reacomp.ini
+    // - vst3-Massive.ini
+    // - clap-org_surge-synth-team_surge-xt.ini
+    // - js-analysis_hund.ini
+    let file_name_regex = base::regex!(r#"(?i)(.*?)-(.*).ini"#);
+    let captures = file_name_regex.captures(file_name)?;
+    let plugin_kind_str = captures.get(1)?.as_str();
+    let plugin_kind = PluginKind::from_str(plugin_kind_str).ok()?;
+    let plugin_identifier = captures.get(2)?.as_str();
+    if plugin_identifier.ends_with("-builtin") {
+        return None;
+    }
+    Some((plugin_kind, plugin_identifier))
+}
+
+/// A preset within an INI file. Cached, so it must not depend on the plug-in database.
+#[derive(Clone, Serialize, Deserialize)]
+struct IniPreset {
+    name: String,
+    content_hash: PersistentHash,
+}
+
+fn process_file(scanned_file: &ScannedFile) -> Result<Vec<IniPreset>, Box<dyn Error>> {
+    let (plugin_kind, plugin_identifier) =
+        parse_file_name(&scanned_file.relative_path).ok_or("unexpected file name")?;
+    let ini_file = Ini::load_from_file(&scanned_file.path)?;
+    let general_section = ini_file
+        .section(Some("General"))
+        .ok_or("missing general section")?;
+    let nb_presets = general_section
+        .get("NbPresets")
+        .ok_or("missing preset count")?;
+    let preset_count: u32 = nb_presets.parse()?;
+    let presets = (0..preset_count)
+        .filter_map(|i| {
+            let section_name = format!("Preset{i}");
+            let section = ini_file.section(Some(section_name))?;
+            let name = section.get("Name")?;
+            // Calculate hash. At first add info about the plug-in. Without that info,
+            // the content could be ambiguous.
+            let mut hasher = PersistentHasher::new();
+            let plugin_kind_str = plugin_kind.as_ref();
+            let plugin_info = format!("{plugin_kind_str}-{plugin_identifier}");
+            hasher.write(plugin_info.as_bytes());
+            // Calculate hash out of data properties ("Data", "Data_1", "Data_2", ...)
+            let data = section.get("Data")?;
+            hasher.write(data.as_bytes());
+            let mut i = 1;
+            while let Some(more_data) = section.get(format!("Data{i}")) {
+                hasher.write(more_data.as_bytes());
+                i += 1;
+            }
+            let preset = IniPreset {
+                name: name.to_string(),
+                content_hash: hasher.digest_128(),
+            };
+            Some(preset)
+        })
+        .collect();
+    Ok(presets)
+}
+
 /// Example: `vst3-Surge XT.ini|My Preset`
 fn create_persistent_inner_id(preset_entry: &PresetEntry) -> PersistentInnerPresetId {
     let plugin_kind = preset_entry.plugin_kind.as_ref();
@@ -329,3 +380,60 @@ fn build_product_name<'a>(preset_entry: &'a PresetEntry, plugin: Option<&Plugin>
         Some(p) => p.common.to_string().into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_cache::corrupt_file_keeping_stamp;
+    use std::fs;
+
+    #[test]
+    fn use_index_cache() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let root_dir = Utf8PathBuf::from_path_buf(dir.path().join("presets")).unwrap();
+        fs::create_dir(&root_dir).unwrap();
+        let ini_file = root_dir.join("vst-Zebra2.ini");
+        fs::write(
+            &ini_file,
+            "[General]\nNbPresets=1\n\n[Preset0]\nName=Warm Pad\nData=ABC\n",
+        )
+        .unwrap();
+        fs::write(
+            root_dir.join("vst-Zebra2-builtin.ini"),
+            "[General]\nNbPresets=0\n",
+        )
+        .unwrap();
+        let cache_file = Utf8PathBuf::from_path_buf(dir.path().join("cache.msgpack")).unwrap();
+        let open_db = || {
+            IniDatabase::open(
+                PersistentDatabaseId::new("fx-presets".to_string()),
+                root_dir.clone(),
+                cache_file.clone(),
+            )
+            .unwrap()
+        };
+        let plugin_db = PluginDatabase::default();
+        let ctx = ProviderContext::new(&plugin_db);
+        let mut db = open_db();
+        db.refresh(&ctx).unwrap();
+        // Only the cache knows the original content now
+        corrupt_file_keeping_stamp(ini_file.as_std_path());
+        // When
+        let mut reopened_db = open_db();
+        reopened_db.refresh(&ctx).unwrap();
+        // Then
+        let preset_names = |db: &IniDatabase| {
+            db.entries
+                .iter()
+                .map(|e| e.preset_name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(preset_names(&db), vec!["Warm Pad"]);
+        assert_eq!(preset_names(&reopened_db), vec!["Warm Pad"]);
+        assert_eq!(
+            reopened_db.watched_dirs(),
+            vec![root_dir.into_std_path_buf()]
+        );
+    }
+}
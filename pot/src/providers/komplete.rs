@@ -5,7 +5,7 @@ use crate::provider_database::{
     FIL_IS_USER_PRESET_TRUE,
 };
 use crate::{
-    Fil, FiledBasedPotPresetKind, InnerBuildInput, InnerPresetId, MacroParamBank,
+    fuzzy_match, Fil, FiledBasedPotPresetKind, InnerBuildInput, InnerPresetId, MacroParamBank,
     PersistentDatabaseId, PersistentInnerPresetId, PersistentPresetId, PluginKind, PotFxParam,
     PotFxParamId, PotPreset, PotPresetCommon, PotPresetKind, PotPresetMetaData, ProductId,
     SearchEvaluator, SearchField, SearchOptions,
@@ -19,6 +19,7 @@ use base::hash_util::{NonCryptoHashMap, NonCryptoHashSet};
 use camino::{Utf8Path, Utf8PathBuf};
 use chrono::NaiveDateTime;
 use riff_io::{ChunkMeta, Entry, RiffFile};
+use rusqlite::functions::FunctionFlags;
 use rusqlite::{Connection, OpenFlags, Row, ToSql};
 use std::borrow::Cow;
 use std::collections::BTreeSet;
@@ -501,11 +502,13 @@ impl Database for KompleteDatabase {
             Some(FilterItemId(Some(FIL_IS_SUPPORTED_FALSE))),
         );
         // Look for exact preset name match
-        let search_evaluator = SearchEvaluator::new(
+        let search_evaluator = SearchEvaluator::new_plain(
             preset_name,
             SearchOptions {
                 use_wildcards: true,
                 search_fields: enum_set!(SearchField::PresetName),
+                fuzzy: false,
+                sort_by_relevance: false,
             },
         );
         let mut preset_db = blocking_lock(
@@ -695,6 +698,7 @@ impl PresetDb {
         let (main_db_path, favorites_db_path) = path_to_main_and_favorites_db()?;
         let connection =
             Connection::open_with_flags(main_db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        register_fuzzy_match_function(&connection)?;
         let db = Self {
             connection,
             favorites_db_path,
@@ -1112,6 +1116,20 @@ impl PresetDb {
         } else {
             format!("%{search_expression}%")
         };
+        // SQL LIKE can't tolerate typos, so we use our own function in this case
+        let use_fuzzy_matching = search_evaluator.use_fuzzy_matching();
+        let text_condition = |column: &str| {
+            if use_fuzzy_matching {
+                format!("{FUZZY_MATCH_FUNCTION}(?, {column})")
+            } else {
+                format!("{column} LIKE ?")
+            }
+        };
+        let text_param = if use_fuzzy_matching {
+            search_expression
+        } else {
+            &like_expression
+        };
         if !search_expression.is_empty() {
             if search_evaluator.options.search_fields.is_empty() {
                 sql.where_and_false();
@@ -1124,18 +1142,33 @@ impl PresetDb {
                     }
                     match field {
                         SearchField::PresetName => {
-                            conjunction += "i.name LIKE ?";
-                            sql.add_param(&like_expression);
+                            conjunction += &text_condition("i.name");
+                            sql.add_param(text_param);
                         }
                         SearchField::ProductName => {
                             sql.more_from(BANK_CHAIN_JOIN);
-                            conjunction += "bc.entry1 LIKE ?";
-                            sql.add_param(&like_expression);
+                            conjunction += &text_condition("bc.entry1");
+                            sql.add_param(text_param);
                         }
                         SearchField::FileExtension => {
                             conjunction += "i.file_ext LIKE ?";
                             sql.add_param(search_expression);
                         }
+                        SearchField::Metadata => {
+                            conjunction += &format!(
+                                "({} OR {} OR {})",
+                                text_condition("i.vendor"),
+                                text_condition("i.author"),
+                                text_condition("i.comment")
+                            );
+                            sql.add_param(text_param);
+                            sql.add_param(text_param);
+                            sql.add_param(text_param);
+                        }
+                        SearchField::Notes => {
+                            // User notes are searched by the pot database itself
+                            conjunction += "0";
+                        }
                     }
                 }
                 conjunction += ")";
@@ -1217,6 +1250,24 @@ fn path_to_main_and_favorites_db() -> Result<(PathBuf, PathBuf), &'static str> {
     Ok((main_db_path, favorites_db_path))
 }
 
+const FUZZY_MATCH_FUNCTION: &str = "pot_fuzzy_match";
+
+/// Makes [`fuzzy_match`] available in SQL queries.
+fn register_fuzzy_match_function(connection: &Connection) -> rusqlite::Result<()> {
+    connection.create_scalar_function(
+        FUZZY_MATCH_FUNCTION,
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let query: String = ctx.get(0)?;
+            let text: Option<String> = ctx.get(1)?;
+            let matches =
+                text.is_some_and(|t| fuzzy_match(&query, &t.to_lowercase(), true).is_some());
+            Ok(matches)
+        },
+    )
+}
+
 fn map_to_komplete_filter_id(row: &Row) -> Result<Option<u32>, rusqlite::Error> {
     row.get(0)
 }
@@ -0,0 +1,189 @@
+use crate::provider_database::{
+    Database, InnerFilterItem, InnerFilterItemCollections, ProviderContext, SortablePresetId,
+};
+use crate::{
+    FilterInput, InnerBuildInput, InnerPresetId, PersistentDatabaseId, PotPreset, SearchInput,
+};
+use base::blocking_read_lock;
+use enumset::{enum_set, EnumSet};
+use helgobox_api::persistence::PotFilterKind;
+use itertools::Itertools;
+use std::borrow::Cow;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+
+/// Presets from the preset load history, resolved to the presets of the actual databases.
+///
+/// Maintained by the Pot database because resolving requires access to all other databases.
+#[derive(Debug, Default)]
+pub struct ResolvedLoadHistory {
+    /// Most recent first.
+    pub recently_loaded: Vec<PotPreset>,
+    /// Most often loaded first.
+    pub most_used: Vec<PotPreset>,
+}
+
+pub type SharedResolvedLoadHistory = Arc<RwLock<ResolvedLoadHistory>>;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum LoadHistoryDatabaseKind {
+    RecentlyLoaded,
+    MostUsed,
+}
+
+/// Virtual database which contains presets of other databases, based on the preset load history.
+pub struct LoadHistoryDatabase {
+    persistent_id: PersistentDatabaseId,
+    kind: LoadHistoryDatabaseKind,
+    resolved_history: SharedResolvedLoadHistory,
+}
+
+impl LoadHistoryDatabase {
+    pub fn open(
+        kind: LoadHistoryDatabaseKind,
+        resolved_history: SharedResolvedLoadHistory,
+    ) -> Self {
+        let persistent_id = match kind {
+            LoadHistoryDatabaseKind::RecentlyLoaded => "recently-loaded",
+            LoadHistoryDatabaseKind::MostUsed => "most-used",
+        };
+        Self {
+            persistent_id: PersistentDatabaseId::new(persistent_id.to_string()),
+            kind,
+            resolved_history,
+        }
+    }
+
+    fn with_presets<R>(&self, f: impl FnOnce(&[PotPreset]) -> R) -> R {
+        let resolved_history = blocking_read_lock(&self.resolved_history, "load history db");
+        let presets = match self.kind {
+            LoadHistoryDatabaseKind::RecentlyLoaded => &resolved_history.recently_loaded,
+            LoadHistoryDatabaseKind::MostUsed => &resolved_history.most_used,
+        };
+        f(presets)
+    }
+}
+
+fn query_presets_internal<'a>(
+    presets: &'a [PotPreset],
+    ctx: &'a ProviderContext,
+    filter_input: &'a FilterInput,
+) -> impl Iterator<Item = (usize, &'a PotPreset)> + 'a {
+    presets.iter().enumerate().filter(move |(i, preset)| {
+        let plugin = preset
+            .common
+            .plugin_ids
+            .first()
+            .and_then(|id| ctx.plugin_db.find_plugin_by_id(id));
+        let id = InnerPresetId(*i as _);
+        filter_input.everything_matches(plugin.map(|p| &p.common.core), id)
+    })
+}
+
+impl Database for LoadHistoryDatabase {
+    fn persistent_id(&self) -> &PersistentDatabaseId {
+        &self.persistent_id
+    }
+
+    fn name(&self) -> Cow<str> {
+        match self.kind {
+            LoadHistoryDatabaseKind::RecentlyLoaded => "Recently loaded".into(),
+            LoadHistoryDatabaseKind::MostUsed => "Most used".into(),
+        }
+    }
+
+    fn description(&self) -> Cow<str> {
+        match self.kind {
+            LoadHistoryDatabaseKind::RecentlyLoaded => {
+                "The presets you loaded most recently, most recent first".into()
+            }
+            LoadHistoryDatabaseKind::MostUsed => {
+                "The presets you loaded most often, most often loaded first".into()
+            }
+        }
+    }
+
+    fn supported_advanced_filter_kinds(&self) -> EnumSet<PotFilterKind> {
+        enum_set!(PotFilterKind::Bank)
+    }
+
+    fn is_virtual(&self) -> bool {
+        true
+    }
+
+    fn refresh(&mut self, _: &ProviderContext) -> Result<(), Box<dyn Error>> {
+        // The Pot database resolves the history after all other databases have been refreshed
+        Ok(())
+    }
+
+    fn query_filter_collections(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+        _: EnumSet<PotFilterKind>,
+    ) -> Result<InnerFilterItemCollections, Box<dyn Error>> {
+        let mut new_filters = *input.filter_input.filters;
+        new_filters.clear_this_and_dependent_filters(PotFilterKind::Bank);
+        let product_items = self.with_presets(|presets| {
+            query_presets_internal(presets, ctx, &input.filter_input.with_filters(&new_filters))
+                .flat_map(|(_, preset)| preset.common.product_ids.iter().copied())
+                .unique()
+                .map(InnerFilterItem::Product)
+                .collect()
+        });
+        let mut collections = InnerFilterItemCollections::empty();
+        collections.set(PotFilterKind::Bank, product_items);
+        Ok(collections)
+    }
+
+    fn query_presets(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+    ) -> Result<Vec<SortablePresetId>, Box<dyn Error>> {
+        let preset_ids = self.with_presets(|presets| {
+            query_presets_internal(presets, ctx, &input.filter_input)
+                .filter(|(_, preset)| {
+                    let search_input = LoadHistorySearchInput { preset };
+                    input.search_evaluator.matches(search_input)
+                })
+                .map(|(i, preset)| SortablePresetId::new(i as _, preset.common.name.clone()))
+                .collect()
+        });
+        Ok(preset_ids)
+    }
+
+    fn find_preset_by_id(
+        &self,
+        _: &ProviderContext,
+        preset_id: InnerPresetId,
+    ) -> Option<PotPreset> {
+        self.with_presets(|presets| presets.get(preset_id.0 as usize).cloned())
+    }
+}
+
+struct LoadHistorySearchInput<'a> {
+    preset: &'a PotPreset,
+}
+
+impl SearchInput for LoadHistorySearchInput<'_> {
+    fn preset_name(&self) -> &str {
+        &self.preset.common.name
+    }
+
+    fn product_name(&self) -> Option<Cow<str>> {
+        self.preset
+            .common
+            .product_name
+            .as_deref()
+            .map(Cow::Borrowed)
+    }
+
+    fn file_extension(&self) -> Option<&str> {
+        self.preset.kind.file_extension()
+    }
+
+    fn metadata(&self) -> Option<Cow<str>> {
+        Some(self.preset.common.metadata.searchable_text()?.into())
+    }
+}
@@ -0,0 +1,526 @@
+use crate::provider_database::{
+    Database, InnerFilterItem, InnerFilterItemCollections, ProviderContext, SortablePresetId,
+    FIL_IS_SUPPORTED_FALSE, FIL_IS_SUPPORTED_TRUE, FIL_IS_USER_PRESET_FALSE,
+    FIL_IS_USER_PRESET_TRUE,
+};
+use crate::{
+    Fil, FilterInput, FilterItem, FilterItemId, InnerBuildInput, InnerPresetId,
+    InternalPotPresetKind, PersistentDatabaseId, PersistentInnerPresetId, PersistentPresetId,
+    PipeEscaped, PluginId, PotPreset, PotPresetCommon, PotPresetKind, SearchInput,
+};
+use std::borrow::Cow;
+
+use crate::lv2::{Lv2Bundle, Lv2PathDir};
+use crate::plugins::PluginCore;
+use crate::{lv2, ProductId};
+use base::hash_util::{NonCryptoHashMap, PersistentHash, PersistentHasher};
+use enumset::{enum_set, EnumSet};
+use helgobox_api::persistence::PotFilterKind;
+use itertools::Itertools;
+use std::error::Error;
+use std::hash::Hasher;
+
+/// Provides the presets of LV2 plug-ins, both the ones shipped with the plug-ins and the ones
+/// saved by the user (in `~/.lv2`).
+pub struct Lv2PresetDatabase {
+    persistent_id: PersistentDatabaseId,
+    lv2_path: Vec<Lv2PathDir>,
+    entries: Vec<PresetEntry>,
+}
+
+impl Lv2PresetDatabase {
+    pub fn open(
+        persistent_id: PersistentDatabaseId,
+        lv2_path: Vec<Lv2PathDir>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let lv2_path: Vec<_> = lv2_path
+            .into_iter()
+            .filter(|d| d.path.try_exists().unwrap_or(false))
+            .collect();
+        if lv2_path.is_empty() {
+            return Err("none of the LV2 directories exists".into());
+        }
+        let db = Self {
+            persistent_id,
+            lv2_path,
+            entries: Default::default(),
+        };
+        Ok(db)
+    }
+
+    fn query_presets_internal<'a>(
+        &'a self,
+        filter_input: &'a FilterInput,
+    ) -> impl Iterator<Item = (usize, &'a PresetEntry)> + 'a {
+        // Support is checked per preset. The generic check would exclude all presets when
+        // showing unsupported presets only.
+        let mut filters_without_support = *filter_input.filters;
+        filters_without_support.set(PotFilterKind::IsSupported, None);
+        self.entries.iter().enumerate().filter(move |(i, e)| {
+            let id = InnerPresetId(*i as _);
+            let filters = filter_input.filters;
+            let is_user_fil = if e.is_user {
+                FIL_IS_USER_PRESET_TRUE
+            } else {
+                FIL_IS_USER_PRESET_FALSE
+            };
+            let is_supported_fil = if e.plugin_id.is_some() {
+                FIL_IS_SUPPORTED_TRUE
+            } else {
+                FIL_IS_SUPPORTED_FALSE
+            };
+            let bank_matches = match filters.effective_sub_bank() {
+                None => true,
+                Some(FilterItemId(wanted_fil)) => e.bank_fil() == *wanted_fil,
+            };
+            filters.matches(PotFilterKind::IsUser, is_user_fil)
+                && filters.matches(PotFilterKind::IsSupported, is_supported_fil)
+                && bank_matches
+                && filter_input
+                    .with_filters(&filters_without_support)
+                    .everything_matches(e.plugin.as_ref(), id)
+        })
+    }
+}
+
+struct PresetEntry {
+    /// The value of `rdfs:label`.
+    preset_name: String,
+    /// Example: `http://calf.sourceforge.net/plugins/Reverb#preset1`
+    preset_uri: String,
+    is_user: bool,
+    /// The value of `lv2:appliesTo`.
+    plugin_uri: Option<String>,
+    /// If `None`, the preset doesn't say which plug-in it applies to or the plug-in URI is too
+    /// long to be used as plug-in ID. Such presets can't be loaded.
+    plugin_id: Option<PluginId>,
+    /// If `None`, it means the corresponding plug-in is not installed.
+    plugin: Option<PluginCore>,
+    bank: Option<Lv2Bank>,
+    content_hash: Option<PersistentHash>,
+}
+
+struct Lv2Bank {
+    uri: String,
+    label: String,
+}
+
+impl PresetEntry {
+    fn bank_fil(&self) -> Option<Fil> {
+        self.bank.as_ref().map(|b| Fil::text(&b.uri))
+    }
+}
+
+impl Database for Lv2PresetDatabase {
+    fn persistent_id(&self) -> &PersistentDatabaseId {
+        &self.persistent_id
+    }
+
+    fn name(&self) -> Cow<str> {
+        "LV2 presets".into()
+    }
+
+    fn description(&self) -> Cow<str> {
+        "All presets of LV2 plug-ins found in the LV2 path (LV2_PATH or the default locations)"
+            .into()
+    }
+
+    fn supported_advanced_filter_kinds(&self) -> EnumSet<PotFilterKind> {
+        enum_set!(PotFilterKind::Bank | PotFilterKind::SubBank)
+    }
+
+    fn refresh(&mut self, ctx: &ProviderContext) -> Result<(), Box<dyn Error>> {
+        let bundles: Vec<_> = lv2::crawl_lv2_bundles(&self.lv2_path)
+            .filter_map(|(dir_index, mut bundle)| {
+                let preset_uris: Vec<_> = bundle
+                    .subjects_of_type(lv2::PSET_PRESET)
+                    .map(|s| s.to_string())
+                    .collect();
+                let defines_banks = bundle
+                    .subjects_of_type(lv2::PSET_BANK_CLASS)
+                    .next()
+                    .is_some();
+                if preset_uris.is_empty() && !defines_banks {
+                    return None;
+                }
+                // Labels and port values are often in separate preset files
+                bundle.load_see_also_files(&preset_uris);
+                Some((self.lv2_path[dir_index].is_user, bundle, preset_uris))
+            })
+            .collect();
+        // Banks can be defined in a different bundle than the presets that refer to them
+        let bank_labels: NonCryptoHashMap<&str, &str> = bundles
+            .iter()
+            .flat_map(|(_, bundle, preset_uris)| {
+                let declared_banks = bundle.subjects_of_type(lv2::PSET_BANK_CLASS);
+                let referenced_banks = preset_uris
+                    .iter()
+                    .filter_map(move |p| bundle.node(p, lv2::PSET_BANK));
+                declared_banks
+                    .chain(referenced_banks)
+                    .filter_map(move |bank_uri| {
+                        let label = bundle.literal(bank_uri, lv2::RDFS_LABEL)?;
+                        Some((bank_uri, label))
+                    })
+            })
+            .collect();
+        let bank_labels = &bank_labels;
+        let entries = bundles
+            .iter()
+            .flat_map(|(is_user, bundle, preset_uris)| {
+                preset_uris.iter().map(move |preset_uri| {
+                    let plugin_uri = bundle.node(preset_uri, lv2::LV2_APPLIES_TO);
+                    let plugin_id = plugin_uri.and_then(|uri| PluginId::lv2(uri).ok());
+                    let plugin = plugin_id
+                        .and_then(|id| ctx.plugin_db.find_plugin_by_id(&id))
+                        .map(|p| p.common.core);
+                    let bank = bundle.node(preset_uri, lv2::PSET_BANK).map(|uri| Lv2Bank {
+                        uri: uri.to_string(),
+                        label: bank_labels
+                            .get(uri)
+                            .map(|l| l.to_string())
+                            .unwrap_or_else(|| last_uri_segment(uri).to_string()),
+                    });
+                    let preset_name = bundle
+                        .literal(preset_uri, lv2::RDFS_LABEL)
+                        .unwrap_or_else(|| last_uri_segment(preset_uri));
+                    PresetEntry {
+                        preset_name: preset_name.to_string(),
+                        preset_uri: preset_uri.clone(),
+                        is_user: *is_user,
+                        plugin_uri: plugin_uri.map(|uri| uri.to_string()),
+                        plugin_id,
+                        plugin,
+                        bank,
+                        content_hash: calculate_content_hash(bundle, preset_uri, plugin_uri),
+                    }
+                })
+            })
+            .collect();
+        self.entries = entries;
+        Ok(())
+    }
+
+    fn query_filter_collections(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+        affected_kinds: EnumSet<PotFilterKind>,
+    ) -> Result<InnerFilterItemCollections, Box<dyn Error>> {
+        let mut collections = InnerFilterItemCollections::empty();
+        if affected_kinds.contains(PotFilterKind::Bank) {
+            let mut new_filters = *input.filter_input.filters;
+            new_filters.clear_this_and_dependent_filters(PotFilterKind::Bank);
+            let product_items = self
+                .query_presets_internal(&input.filter_input.with_filters(&new_filters))
+                .filter_map(|(_, entry)| Some(entry.plugin.as_ref()?.product_id))
+                .unique()
+                .map(InnerFilterItem::Product)
+                .collect();
+            collections.set(PotFilterKind::Bank, product_items);
+        }
+        if affected_kinds.contains(PotFilterKind::SubBank) {
+            let mut new_filters = *input.filter_input.filters;
+            new_filters.clear_this_and_dependent_filters(PotFilterKind::SubBank);
+            let mut has_entries_without_bank = false;
+            let mut bank_items: Vec<_> = self
+                .query_presets_internal(&input.filter_input.with_filters(&new_filters))
+                .filter_map(|(_, entry)| {
+                    if entry.bank.is_none() {
+                        has_entries_without_bank = true;
+                    }
+                    Some((entry.bank.as_ref()?, entry.plugin.map(|p| p.product_id)))
+                })
+                .unique_by(|(bank, _)| bank.uri.clone())
+                .map(|(bank, product_id)| FilterItem {
+                    persistent_id: bank.uri.clone(),
+                    id: FilterItemId(Some(Fil::text(&bank.uri))),
+                    parent_name: product_id.and_then(|id| build_product_name_by_id(ctx, id)),
+                    name: Some(bank.label.clone()),
+                    icon: None,
+                    more_info: None,
+                })
+                .filter(|item| {
+                    !input
+                        .filter_input
+                        .excludes
+                        .contains(PotFilterKind::SubBank, item.id)
+                })
+                .sorted_by(|a, b| (&a.parent_name, &a.name).cmp(&(&b.parent_name, &b.name)))
+                .map(InnerFilterItem::Unique)
+                .collect();
+            if has_entries_without_bank {
+                bank_items.insert(0, InnerFilterItem::Unique(FilterItem::none()));
+            }
+            collections.set(PotFilterKind::SubBank, bank_items);
+        }
+        Ok(collections)
+    }
+
+    fn query_presets(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+    ) -> Result<Vec<SortablePresetId>, Box<dyn Error>> {
+        let preset_ids = self
+            .query_presets_internal(&input.filter_input)
+            .filter(|(_, entry)| {
+                let search_input = Lv2SearchInput {
+                    ctx,
+                    preset_entry: entry,
+                };
+                input.search_evaluator.matches(search_input)
+            })
+            .map(|(i, entry)| SortablePresetId::new(i as _, entry.preset_name.clone()))
+            .collect();
+        Ok(preset_ids)
+    }
+
+    fn find_preset_by_id(
+        &self,
+        ctx: &ProviderContext,
+        preset_id: InnerPresetId,
+    ) -> Option<PotPreset> {
+        let preset_entry = self.entries.get(preset_id.0 as usize)?;
+        let preset = PotPreset {
+            common: PotPresetCommon {
+                persistent_id: PersistentPresetId::new(
+                    self.persistent_id().clone(),
+                    create_persistent_inner_id(preset_entry),
+                ),
+                name: preset_entry.preset_name.clone(),
+                context_name: preset_entry.bank.as_ref().map(|b| b.label.clone()),
+                plugin_ids: preset_entry.plugin_id.into_iter().collect(),
+                product_ids: preset_entry
+                    .plugin
+                    .as_ref()
+                    .map(|p| p.product_id)
+                    .into_iter()
+                    .collect(),
+                product_name: build_product_name(ctx, preset_entry).map(|n| n.to_string()),
+                content_hash: preset_entry.content_hash,
+                db_specific_preview_file: None,
+                is_supported: preset_entry.plugin_id.is_some(),
+                is_available: preset_entry.plugin.is_some(),
+                metadata: Default::default(),
+            },
+            kind: PotPresetKind::Internal(InternalPotPresetKind {
+                plugin_id: preset_entry.plugin_id,
+            }),
+        };
+        Some(preset)
+    }
+}
+
+/// Calculates a hash out of the plug-in URI and the port values of the preset.
+///
+/// Returns `None` if the preset doesn't have any port values (e.g. if it only contains state).
+fn calculate_content_hash(
+    bundle: &Lv2Bundle,
+    preset_uri: &str,
+    plugin_uri: Option<&str>,
+) -> Option<PersistentHash> {
+    let port_values: Vec<_> = bundle
+        .nodes(preset_uri, lv2::LV2_PORT)
+        .filter_map(|port| {
+            let symbol = bundle.literal(port, lv2::LV2_SYMBOL)?;
+            let value = bundle.literal(port, lv2::PSET_VALUE)?;
+            Some((symbol, value))
+        })
+        .sorted()
+        .collect();
+    if port_values.is_empty() {
+        return None;
+    }
+    let mut hasher = PersistentHasher::new();
+    hasher.write(plugin_uri.unwrap_or_default().as_bytes());
+    for (symbol, value) in port_values {
+        hasher.write(symbol.as_bytes());
+        hasher.write(value.as_bytes());
+    }
+    Some(hasher.digest_128())
+}
+
+/// Example: `http://example.org/presets#Warm%20Pad` => `Warm%20Pad`
+fn last_uri_segment(uri: &str) -> &str {
+    let segment = uri
+        .rsplit(['#', '/'])
+        .find(|s| !s.is_empty())
+        .unwrap_or(uri);
+    segment.strip_suffix(".ttl").unwrap_or(segment)
+}
+
+/// Example: `http://calf.sourceforge.net/plugins/Reverb#preset1`
+fn create_persistent_inner_id(preset_entry: &PresetEntry) -> PersistentInnerPresetId {
+    let escaped_uri = PipeEscaped(preset_entry.preset_uri.as_str());
+    PersistentInnerPresetId::new(escaped_uri.to_string())
+}
+
+struct Lv2SearchInput<'a> {
+    ctx: &'a ProviderContext<'a>,
+    preset_entry: &'a PresetEntry,
+}
+
+impl SearchInput for Lv2SearchInput<'_> {
+    fn preset_name(&self) -> &str {
+        &self.preset_entry.preset_name
+    }
+
+    fn product_name(&self) -> Option<Cow<str>> {
+        build_product_name(self.ctx, self.preset_entry)
+    }
+
+    fn file_extension(&self) -> Option<&str> {
+        None
+    }
+}
+
+fn build_product_name<'a>(
+    ctx: &ProviderContext,
+    preset_entry: &'a PresetEntry,
+) -> Option<Cow<'a, str>> {
+    let plugin = preset_entry
+        .plugin
+        .as_ref()
+        .and_then(|core| ctx.plugin_db.find_plugin_by_id(&core.id));
+    match plugin {
+        None => Some(preset_entry.plugin_uri.as_deref()?.into()),
+        Some(p) => Some(p.common.to_string().into()),
+    }
+}
+
+fn build_product_name_by_id(ctx: &ProviderContext, product_id: ProductId) -> Option<String> {
+    let product = ctx.plugin_db.find_product_by_id(&product_id)?;
+    Some(product.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::PluginDatabase;
+    use crate::{Filters, PotFilterExcludes, SearchEvaluator};
+    use camino::Utf8PathBuf;
+    use std::fs;
+
+    fn create_lv2_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle_path = dir.path().join("synth-presets.lv2");
+        fs::create_dir(&bundle_path).unwrap();
+        fs::write(
+            bundle_path.join("manifest.ttl"),
+            r#"
+                @prefix lv2: <http://lv2plug.in/ns/lv2core#> .
+                @prefix pset: <http://lv2plug.in/ns/ext/presets#> .
+                @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+                <http://example.org/synth#pads> a pset:Bank ;
+                    rdfs:label "Pads" .
+                <http://example.org/synth#warm-pad> a pset:Preset ;
+                    lv2:appliesTo <http://example.org/synth> ;
+                    pset:bank <http://example.org/synth#pads> ;
+                    rdfs:label "Warm Pad" ;
+                    lv2:port [
+                        lv2:symbol "cutoff" ;
+                        pset:value 0.25
+                    ] .
+                <Bright%20Lead.ttl> a pset:Preset ;
+                    lv2:appliesTo <http://example.org/synth> ;
+                    rdfs:seeAlso <Bright%20Lead.ttl> .
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            bundle_path.join("Bright Lead.ttl"),
+            r#"
+                @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+                <> rdfs:label "Bright Lead" .
+            "#,
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn refresh_and_query() {
+        // Given
+        let dir = create_lv2_dir();
+        let lv2_path = vec![Lv2PathDir {
+            path: Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap(),
+            is_user: true,
+        }];
+        let mut db =
+            Lv2PresetDatabase::open(PersistentDatabaseId::new("lv2".to_string()), lv2_path)
+                .unwrap();
+        let plugin_db = PluginDatabase::default();
+        let ctx = ProviderContext::new(&plugin_db);
+        let filters = Filters::default();
+        let excludes = PotFilterExcludes::default();
+        let db_favorites = Default::default();
+        let search_evaluator = SearchEvaluator::default();
+        let input = InnerBuildInput {
+            filter_input: FilterInput {
+                filters: &filters,
+                excludes: &excludes,
+                db_favorites: &db_favorites,
+            },
+            search_evaluator: &search_evaluator,
+        };
+        // When
+        db.refresh(&ctx).unwrap();
+        let preset_ids = db.query_presets(&ctx, input).unwrap();
+        let presets: Vec<_> = preset_ids
+            .iter()
+            .map(|id| db.find_preset_by_id(&ctx, id.inner_preset_id).unwrap())
+            .collect();
+        // Then
+        assert_eq!(presets.len(), 2);
+        let warm_pad = presets
+            .iter()
+            .find(|p| p.common.name == "Warm Pad")
+            .unwrap();
+        assert_eq!(warm_pad.common.context_name.as_deref(), Some("Pads"));
+        assert_eq!(
+            warm_pad.common.persistent_id.to_string(),
+            "lv2|http://example.org/synth#warm-pad"
+        );
+        assert_eq!(
+            warm_pad.common.plugin_ids,
+            vec![PluginId::lv2("http://example.org/synth").unwrap()]
+        );
+        assert!(warm_pad.common.content_hash.is_some());
+        assert!(warm_pad.common.is_supported);
+        assert!(!warm_pad.common.is_available);
+        let bright_lead = presets
+            .iter()
+            .find(|p| p.common.name == "Bright Lead")
+            .unwrap();
+        assert_eq!(bright_lead.common.context_name, None);
+        assert_eq!(bright_lead.common.content_hash, None);
+    }
+
+    #[test]
+    fn fail_if_no_lv2_dir_exists() {
+        // Given
+        let lv2_path = vec![Lv2PathDir {
+            path: "/non/existing/lv2".into(),
+            is_user: false,
+        }];
+        // When
+        let result =
+            Lv2PresetDatabase::open(PersistentDatabaseId::new("lv2".to_string()), lv2_path);
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn extract_last_uri_segment() {
+        assert_eq!(
+            last_uri_segment("http://example.org/presets#Warm%20Pad"),
+            "Warm%20Pad"
+        );
+        assert_eq!(
+            last_uri_segment("file:///home/me/.lv2/My.lv2/Bright.ttl"),
+            "Bright"
+        );
+        assert_eq!(last_uri_segment("http://example.org/synth/"), "synth");
+    }
+}
@@ -2,4 +2,9 @@ pub mod defaults;
 pub mod directory;
 pub mod ini;
 pub mod komplete;
+pub mod load_history;
+pub mod lv2;
+pub mod preset_collections;
 pub mod projects;
+pub mod samples;
+pub mod vst3;
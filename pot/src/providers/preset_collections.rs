@@ -0,0 +1,239 @@
+use crate::provider_database::{
+    Database, InnerFilterItem, InnerFilterItemCollections, ProviderContext, SortablePresetId,
+};
+use crate::{
+    Fil, FilterInput, FilterItem, FilterItemId, InnerBuildInput, InnerPresetId,
+    PersistentDatabaseId, PotPreset, SearchInput,
+};
+use base::blocking_read_lock;
+use enumset::{enum_set, EnumSet};
+use helgobox_api::persistence::PotFilterKind;
+use itertools::Itertools;
+use std::borrow::Cow;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+
+/// Preset collections, resolved to the presets of the actual databases.
+///
+/// Maintained by the Pot database because resolving requires access to all other databases.
+#[derive(Debug, Default)]
+pub struct ResolvedPresetCollections {
+    pub collections: Vec<ResolvedPresetCollection>,
+}
+
+#[derive(Debug)]
+pub struct ResolvedPresetCollection {
+    pub name: String,
+    /// Presets in collection order. Entries which couldn't be resolved are left out.
+    pub presets: Vec<ResolvedPresetCollectionEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ResolvedPresetCollectionEntry {
+    /// Preset ID of the collection entry.
+    ///
+    /// Not necessarily the persistent ID of the resolved preset. If the collection has been
+    /// imported from another machine, the preset might have been found by name and product.
+    pub entry_preset_id: String,
+    pub preset: PotPreset,
+}
+
+pub type SharedResolvedPresetCollections = Arc<RwLock<ResolvedPresetCollections>>;
+
+impl ResolvedPresetCollections {
+    /// Returns the presets of all collections, each one with its inner preset ID.
+    ///
+    /// The inner preset ID is the index within the concatenation of all collections.
+    pub fn presets(&self) -> impl Iterator<Item = (&ResolvedPresetCollection, usize, &PotPreset)> {
+        self.collections
+            .iter()
+            .flat_map(|c| c.presets.iter().map(move |e| (c, &e.preset)))
+            .enumerate()
+            .map(|(i, (c, p))| (c, i, p))
+    }
+
+    pub fn find_preset(&self, inner_preset_id: InnerPresetId) -> Option<&PotPreset> {
+        self.presets()
+            .nth(inner_preset_id.0 as usize)
+            .map(|(_, _, preset)| preset)
+    }
+
+    /// Returns the inner preset IDs of the presets in the given collection, in collection order.
+    pub fn preset_ids_of_collection(&self, name: &str) -> Vec<InnerPresetId> {
+        self.presets()
+            .filter(|(c, _, _)| c.name == name)
+            .map(|(_, i, _)| InnerPresetId(i as _))
+            .collect()
+    }
+}
+
+/// Virtual database which contains presets of other databases, as put together by the user in
+/// preset collections.
+pub struct PresetCollectionDatabase {
+    persistent_id: PersistentDatabaseId,
+    resolved_collections: SharedResolvedPresetCollections,
+}
+
+impl PresetCollectionDatabase {
+    pub fn open(resolved_collections: SharedResolvedPresetCollections) -> Self {
+        Self {
+            persistent_id: PersistentDatabaseId::new("collections".to_string()),
+            resolved_collections,
+        }
+    }
+
+    fn with_collections<R>(&self, f: impl FnOnce(&ResolvedPresetCollections) -> R) -> R {
+        let resolved_collections =
+            blocking_read_lock(&self.resolved_collections, "preset collection db");
+        f(&resolved_collections)
+    }
+}
+
+fn query_presets_internal<'a>(
+    collections: &'a ResolvedPresetCollections,
+    ctx: &'a ProviderContext,
+    filter_input: &'a FilterInput,
+) -> impl Iterator<Item = (&'a ResolvedPresetCollection, usize, &'a PotPreset)> + 'a {
+    collections
+        .presets()
+        .filter(move |(collection, i, preset)| {
+            if !filter_input
+                .filters
+                .matches(PotFilterKind::Collection, Fil::text(&collection.name))
+            {
+                return false;
+            }
+            let plugin = preset
+                .common
+                .plugin_ids
+                .first()
+                .and_then(|id| ctx.plugin_db.find_plugin_by_id(id));
+            let id = InnerPresetId(*i as _);
+            filter_input.everything_matches(plugin.map(|p| &p.common.core), id)
+        })
+}
+
+impl Database for PresetCollectionDatabase {
+    fn persistent_id(&self) -> &PersistentDatabaseId {
+        &self.persistent_id
+    }
+
+    fn name(&self) -> Cow<str> {
+        "Collections".into()
+    }
+
+    fn description(&self) -> Cow<str> {
+        "Your own preset collections, e.g. the presets needed for a particular gig".into()
+    }
+
+    fn supported_advanced_filter_kinds(&self) -> EnumSet<PotFilterKind> {
+        enum_set!(PotFilterKind::Collection | PotFilterKind::Bank)
+    }
+
+    fn is_virtual(&self) -> bool {
+        true
+    }
+
+    fn refresh(&mut self, _: &ProviderContext) -> Result<(), Box<dyn Error>> {
+        // The Pot database resolves the collections after all other databases have been refreshed
+        Ok(())
+    }
+
+    fn query_filter_collections(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+        affected_kinds: EnumSet<PotFilterKind>,
+    ) -> Result<InnerFilterItemCollections, Box<dyn Error>> {
+        let mut collections = InnerFilterItemCollections::empty();
+        self.with_collections(|resolved_collections| {
+            if affected_kinds.contains(PotFilterKind::Collection) {
+                // Empty collections should be selectable as well
+                let collection_items = resolved_collections
+                    .collections
+                    .iter()
+                    .map(|c| {
+                        let item = FilterItem {
+                            persistent_id: c.name.clone(),
+                            id: FilterItemId(Some(Fil::text(&c.name))),
+                            parent_name: None,
+                            name: Some(c.name.clone()),
+                            icon: None,
+                            more_info: Some(format!("{} presets", c.presets.len())),
+                        };
+                        InnerFilterItem::Unique(item)
+                    })
+                    .collect();
+                collections.set(PotFilterKind::Collection, collection_items);
+            }
+            if affected_kinds.contains(PotFilterKind::Bank) {
+                let mut new_filters = *input.filter_input.filters;
+                new_filters.clear_this_and_dependent_filters(PotFilterKind::Bank);
+                let product_items = query_presets_internal(
+                    resolved_collections,
+                    ctx,
+                    &input.filter_input.with_filters(&new_filters),
+                )
+                .flat_map(|(_, _, preset)| preset.common.product_ids.iter().copied())
+                .unique()
+                .map(InnerFilterItem::Product)
+                .collect();
+                collections.set(PotFilterKind::Bank, product_items);
+            }
+        });
+        Ok(collections)
+    }
+
+    fn query_presets(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+    ) -> Result<Vec<SortablePresetId>, Box<dyn Error>> {
+        let preset_ids = self.with_collections(|resolved_collections| {
+            query_presets_internal(resolved_collections, ctx, &input.filter_input)
+                .filter(|(_, _, preset)| {
+                    let search_input = PresetCollectionSearchInput { preset };
+                    input.search_evaluator.matches(search_input)
+                })
+                .map(|(_, i, preset)| SortablePresetId::new(i as _, preset.common.name.clone()))
+                .collect()
+        });
+        Ok(preset_ids)
+    }
+
+    fn find_preset_by_id(
+        &self,
+        _: &ProviderContext,
+        preset_id: InnerPresetId,
+    ) -> Option<PotPreset> {
+        self.with_collections(|resolved_collections| {
+            resolved_collections.find_preset(preset_id).cloned()
+        })
+    }
+}
+
+struct PresetCollectionSearchInput<'a> {
+    preset: &'a PotPreset,
+}
+
+impl SearchInput for PresetCollectionSearchInput<'_> {
+    fn preset_name(&self) -> &str {
+        &self.preset.common.name
+    }
+
+    fn product_name(&self) -> Option<Cow<str>> {
+        self.preset
+            .common
+            .product_name
+            .as_deref()
+            .map(Cow::Borrowed)
+    }
+
+    fn file_extension(&self) -> Option<&str> {
+        self.preset.kind.file_extension()
+    }
+
+    fn metadata(&self) -> Option<Cow<str>> {
+        Some(self.preset.common.metadata.searchable_text()?.into())
+    }
+}
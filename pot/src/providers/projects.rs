@@ -8,14 +8,17 @@ use crate::{
 };
 use std::borrow::Cow;
 
-use crate::plugins::{PluginCore, PluginDatabase};
+use crate::index_cache::{index_cache_file, scan_files, FileIndexCache, ScannedFile};
+use crate::plugins::{is_fx_rxml_line, PluginCore};
 use base::hash_util::{
     calculate_persistent_non_crypto_hash_one_shot, NonCryptoIndexMap, PersistentHash,
 };
+use camino::Utf8PathBuf;
 use either::Either;
 use enumset::{enum_set, EnumSet};
 use helgobox_api::persistence::PotFilterKind;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use std::error::Error;
 use std::ffi::OsStr;
@@ -23,7 +26,6 @@ use std::ffi::OsStr;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::{fs, iter};
-use walkdir::WalkDir;
 
 pub struct ProjectDatabase {
     persistent_id: PersistentDatabaseId,
@@ -32,6 +34,7 @@ pub struct ProjectDatabase {
     description: String,
     projects: Vec<Proj>,
     preset_entries: Vec<PresetEntry>,
+    index_cache: FileIndexCache<Vec<ScannedTrack>>,
 }
 
 pub struct ProjectDbConfig {
@@ -42,6 +45,14 @@ pub struct ProjectDbConfig {
 
 impl ProjectDatabase {
     pub fn open(config: ProjectDbConfig) -> Result<Self, Box<dyn Error>> {
+        let index_cache_file = index_cache_file(&index_cache_name(&config.root_dir));
+        Self::open_with_index_cache_file(config, index_cache_file)
+    }
+
+    fn open_with_index_cache_file(
+        config: ProjectDbConfig,
+        index_cache_file: Utf8PathBuf,
+    ) -> Result<Self, Box<dyn Error>> {
         if !config.root_dir.try_exists()? {
             return Err("path to projects root directory doesn't exist".into());
         }
@@ -52,10 +63,17 @@ impl ProjectDatabase {
             description: format!("Projects in {}", config.root_dir.to_string_lossy()),
             root_dir: config.root_dir,
             projects: vec![],
+            index_cache: FileIndexCache::new(index_cache_file),
         };
         Ok(db)
     }
 
+    fn scan_project_files(&self) -> Vec<ScannedFile> {
+        scan_files(&self.root_dir, None, |path| {
+            path.extension() == Some(OsStr::new("RPP"))
+        })
+    }
+
     fn query_presets_internal<'a>(
         &'a self,
         filter_input: &'a FilterInput,
@@ -110,35 +128,51 @@ impl Database for ProjectDatabase {
     }
 
     fn refresh(&mut self, ctx: &ProviderContext) -> Result<(), Box<dyn Error>> {
-        self.preset_entries = WalkDir::new(&self.root_dir)
-            .follow_links(true)
+        let scanned_files = self.scan_project_files();
+        let processed_files = self
+            .index_cache
+            .update(&scanned_files, |f| process_file(&f.path).ok());
+        self.projects.clear();
+        self.preset_entries = processed_files
             .into_iter()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                if !entry.file_type().is_file() {
-                    return None;
-                }
-                let extension = entry.path().extension()?;
-                if extension != OsStr::new("RPP") {
-                    return None;
-                }
-                let relative_path = entry.path().strip_prefix(&self.root_dir).ok()?;
-                let stem = entry.path().file_stem()?;
-                // Immediately exclude relative paths that can't be represented as valid UTF-8.
-                // Otherwise we will potentially open a can of worms (regarding persistence etc.).
+            .filter_map(|(scanned_file, scanned_tracks)| {
                 let project = Proj {
-                    name: stem.to_str()?.to_string(),
-                    relative_path_to_rpp: relative_path.to_str()?.to_string(),
+                    name: scanned_file.path.file_stem()?.to_str()?.to_string(),
+                    relative_path_to_rpp: scanned_file.relative_path.clone(),
                 };
                 self.projects.push(project);
                 let project_id = ProjectId(self.projects.len() as u32 - 1);
-                process_file(entry.path(), ctx.plugin_db, project_id).ok()
+                let iter = scanned_tracks.into_iter().filter_map(move |t| {
+                    let used_plugins = ctx
+                        .plugin_db
+                        .detect_plugins_from_rxml_lines(t.fx_lines.iter().map(String::as_str));
+                    if used_plugins.is_empty() {
+                        return None;
+                    }
+                    let track_preset = TrackPreset {
+                        preset_name: t.preset_name,
+                        track_id: t.track_id,
+                        fx_chain_range: t.fx_chain_range,
+                        used_plugins,
+                        content_hash: t.content_hash,
+                    };
+                    let preset_entry = PresetEntry {
+                        project_id,
+                        track_preset,
+                    };
+                    Some(preset_entry)
+                });
+                Some(iter)
             })
             .flatten()
             .collect();
         Ok(())
     }
 
+    fn watched_dirs(&self) -> Vec<PathBuf> {
+        vec![self.root_dir.clone()]
+    }
+
     fn query_filter_collections(
         &self,
         _: &ProviderContext,
@@ -256,13 +290,27 @@ struct TrackPreset {
     content_hash: PersistentHash,
 }
 
-fn process_file(
-    path: &Path,
-    plugin_db: &PluginDatabase,
-    project_id: ProjectId,
-) -> Result<Vec<PresetEntry>, Box<dyn Error>> {
+/// A track with FX found in a project file. Cached, so it must not depend on the plug-in database.
+#[derive(Clone, Serialize, Deserialize)]
+struct ScannedTrack {
+    preset_name: String,
+    track_id: String,
+    fx_chain_range: Range<usize>,
+    /// Lines which declare FX. Resolved to plug-ins on each refresh.
+    fx_lines: Vec<String>,
+    content_hash: PersistentHash,
+}
+
+/// The persistent ID is random, so we identify the cache by root directory.
+fn index_cache_name(root_dir: &Path) -> String {
+    let root_dir_hash =
+        calculate_persistent_non_crypto_hash_one_shot(root_dir.to_string_lossy().as_bytes());
+    format!("projects-{:x}", root_dir_hash.get())
+}
+
+fn process_file(path: &Path) -> Result<Vec<ScannedTrack>, Box<dyn Error>> {
     let rppxml = fs::read_to_string(path)?;
-    Ok(extract_presets(&rppxml, plugin_db, project_id))
+    Ok(extract_tracks(&rppxml))
 }
 
 /// Example: `maojiao/2023-02-03-ben/2023-02-03-ben.RPP|0FF9F738-7CF6-8A49-9AEA-A9AF26DF9C46`
@@ -275,11 +323,7 @@ fn create_persistent_inner_id(
     PersistentInnerPresetId::new(id)
 }
 
-fn extract_presets(
-    rppxml: &str,
-    plugin_db: &PluginDatabase,
-    project_id: ProjectId,
-) -> Vec<PresetEntry> {
+fn extract_tracks(rppxml: &str) -> Vec<ScannedTrack> {
     use rppxml_parser::*;
     let parser = OneShotParser::new(rppxml);
     #[derive(Debug, Default)]
@@ -288,7 +332,7 @@ fn extract_presets(
         name: Option<&'a str>,
         rfx_chain_start: Option<usize>,
         rfx_chain_end: Option<usize>,
-        used_plugins: NonCryptoIndexMap<PluginId, PluginCore>,
+        fx_lines: Vec<String>,
     }
     impl<'a> P<'a> {
         pub fn new(track_id: &'a str) -> Self {
@@ -297,7 +341,7 @@ fn extract_presets(
                 name: None,
                 rfx_chain_start: None,
                 rfx_chain_end: None,
-                used_plugins: Default::default(),
+                fx_lines: vec![],
             }
         }
     }
@@ -316,11 +360,9 @@ fn extract_presets(
                     }
                     ["REAPER_PROJECT", "TRACK", "FXCHAIN", _] => {
                         if let Some(p) = &mut preset {
-                            if let Some(plugin) =
-                                plugin_db.detect_plugin_from_rxml_line(line.trim())
-                            {
-                                p.used_plugins
-                                    .insert(plugin.common.core.id, plugin.common.core);
+                            let line = line.trim();
+                            if is_fx_rxml_line(line) {
+                                p.fx_lines.push(line.to_string());
                             }
                         }
                     }
@@ -370,7 +412,7 @@ fn extract_presets(
     presets
         .into_iter()
         .filter_map(|p| {
-            if p.used_plugins.is_empty() {
+            if p.fx_lines.is_empty() {
                 return None;
             }
             let fx_chain_range = p.rfx_chain_start?..p.rfx_chain_end?;
@@ -379,18 +421,14 @@ fn extract_presets(
             let content_hash = calculate_persistent_non_crypto_hash_one_shot(
                 rppxml[fx_chain_range.clone()].as_bytes(),
             );
-            let track_preset = TrackPreset {
+            let track = ScannedTrack {
                 preset_name,
                 track_id,
                 fx_chain_range,
-                used_plugins: p.used_plugins,
+                fx_lines: p.fx_lines,
                 content_hash,
             };
-            let preset_entry = PresetEntry {
-                project_id,
-                track_preset,
-            };
-            Some(preset_entry)
+            Some(track)
         })
         .collect()
 }
@@ -428,3 +466,63 @@ fn build_product_name(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_cache::corrupt_file_keeping_stamp;
+    use crate::plugins::PluginDatabase;
+
+    #[test]
+    fn use_index_cache() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let root_dir = dir.path().join("projects");
+        fs::create_dir(&root_dir).unwrap();
+        let project_file = root_dir.join("Song.RPP");
+        fs::write(&project_file, "<REAPER_PROJECT 0.1 \"7.0\" 1700000000\n>\n").unwrap();
+        let cache_file = Utf8PathBuf::from_path_buf(dir.path().join("cache.msgpack")).unwrap();
+        let open_db = || {
+            let config = ProjectDbConfig {
+                persistent_id: PersistentDatabaseId::new("projects".to_string()),
+                root_dir: root_dir.clone(),
+                name: "Projects".to_string(),
+            };
+            ProjectDatabase::open_with_index_cache_file(config, cache_file.clone()).unwrap()
+        };
+        let plugin_db = PluginDatabase::default();
+        let ctx = ProviderContext::new(&plugin_db);
+        let mut db = open_db();
+        db.refresh(&ctx).unwrap();
+        // Only the cache knows the original content now
+        corrupt_file_keeping_stamp(&project_file);
+        // When
+        let mut reopened_db = open_db();
+        reopened_db.refresh(&ctx).unwrap();
+        // Then
+        let project_names = |db: &ProjectDatabase| {
+            db.projects
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(project_names(&db), vec!["Song"]);
+        assert_eq!(project_names(&reopened_db), vec!["Song"]);
+        assert_eq!(reopened_db.watched_dirs(), vec![root_dir]);
+    }
+
+    #[test]
+    fn derive_index_cache_name_from_root_dir() {
+        // Given
+        let root_dir = Path::new("/home/user/projects");
+        // When
+        let name = index_cache_name(root_dir);
+        // Then
+        assert!(name.starts_with("projects-"));
+        assert_eq!(name, index_cache_name(root_dir));
+        assert_ne!(
+            name,
+            index_cache_name(Path::new("/home/user/other-projects"))
+        );
+    }
+}
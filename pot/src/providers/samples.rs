@@ -0,0 +1,393 @@
+use crate::audio_metadata::AudioMetadata;
+use crate::provider_database::{
+    Database, InnerFilterItem, InnerFilterItemCollections, ProviderContext, SortablePresetId,
+    FIL_IS_AVAILABLE_TRUE, FIL_IS_SUPPORTED_TRUE, FIL_IS_USER_PRESET_TRUE, FIL_PRODUCT_KIND_LOOP,
+    FIL_PRODUCT_KIND_ONE_SHOT,
+};
+use crate::{
+    Fil, FiledBasedPotPresetKind, FilterInput, FilterItem, FilterItemId, InnerBuildInput,
+    InnerPresetId, PersistentDatabaseId, PersistentInnerPresetId, PersistentPresetId, PipeEscaped,
+    PotPreset, PotPresetCommon, PotPresetKind, PotPresetMetaData, SearchInput,
+    AUDIO_FILE_EXTENSIONS,
+};
+use std::borrow::Cow;
+
+use camino::Utf8PathBuf;
+use chrono::{DateTime, Utc};
+use enumset::{enum_set, EnumSet};
+use helgobox_api::persistence::PotFilterKind;
+use itertools::Itertools;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use walkdir::WalkDir;
+
+/// Database of audio samples, e.g. drum one-shots and loops.
+///
+/// Each top-level folder in the root directory is treated as sample library (shown as product),
+/// deeper folders as banks.
+pub struct SampleDatabase {
+    persistent_id: PersistentDatabaseId,
+    root_dir: Utf8PathBuf,
+    entries: Vec<SampleEntry>,
+}
+
+impl SampleDatabase {
+    pub fn open(
+        persistent_id: PersistentDatabaseId,
+        root_dir: Utf8PathBuf,
+    ) -> Result<Self, Box<dyn Error>> {
+        if !root_dir.try_exists()? {
+            return Err("path to sample directory doesn't exist".into());
+        }
+        let db = Self {
+            persistent_id,
+            root_dir,
+            entries: Default::default(),
+        };
+        Ok(db)
+    }
+
+    fn query_presets_internal<'a>(
+        &'a self,
+        filter_input: &'a FilterInput,
+    ) -> impl Iterator<Item = (usize, &'a SampleEntry)> + 'a {
+        self.entries.iter().enumerate().filter(move |(i, e)| {
+            let id = InnerPresetId(*i as _);
+            let filters = filter_input.filters;
+            let excludes = filter_input.excludes;
+            let product_kind_fil = if e.audio_metadata.is_loop() {
+                FIL_PRODUCT_KIND_LOOP
+            } else {
+                FIL_PRODUCT_KIND_ONE_SHOT
+            };
+            let sub_bank_matches = match filters.effective_sub_bank() {
+                None => true,
+                Some(FilterItemId(wanted_fil)) => e.sub_folder_fil() == *wanted_fil,
+            };
+            filters.matches(PotFilterKind::IsAvailable, FIL_IS_AVAILABLE_TRUE)
+                && filters.matches(PotFilterKind::IsSupported, FIL_IS_SUPPORTED_TRUE)
+                && filters.matches(PotFilterKind::IsUser, FIL_IS_USER_PRESET_TRUE)
+                && filters.matches(PotFilterKind::ProductKind, product_kind_fil)
+                && filters.favorite_matches(filter_input.db_favorites, id)
+                && filters.matches_optional(PotFilterKind::Bank, e.library_fil())
+                && sub_bank_matches
+                && filters.matches_optional(PotFilterKind::Tempo, e.tempo_fil())
+                && filters.matches_optional(PotFilterKind::Key, e.key_fil())
+                && !excludes.contains(PotFilterKind::Bank, FilterItemId(e.library_fil()))
+                && !excludes.contains(PotFilterKind::SubBank, FilterItemId(e.sub_folder_fil()))
+        })
+    }
+
+    fn build_filter_items(
+        &self,
+        input: &InnerBuildInput,
+        kind: PotFilterKind,
+    ) -> Vec<InnerFilterItem> {
+        let mut new_filters = *input.filter_input.filters;
+        new_filters.clear_this_and_dependent_filters(kind);
+        if kind == PotFilterKind::SubBank {
+            // Banks are narrowed down by the library filter
+            new_filters.set(
+                PotFilterKind::Bank,
+                input.filter_input.filters.get(PotFilterKind::Bank),
+            );
+        }
+        let filter_input = input.filter_input.with_filters(&new_filters);
+        let entries: Vec<_> = self
+            .query_presets_internal(&filter_input)
+            .map(|(_, e)| e)
+            .collect();
+        let has_entries_without_value = entries.iter().any(|e| match kind {
+            PotFilterKind::Bank => e.library.is_none(),
+            PotFilterKind::SubBank => e.sub_folder.is_none(),
+            PotFilterKind::Tempo => e.audio_metadata.tempo.is_none(),
+            PotFilterKind::Key => e.audio_metadata.key.is_none(),
+            _ => false,
+        });
+        let mut items: Vec<_> = match kind {
+            PotFilterKind::Bank | PotFilterKind::SubBank => entries
+                .iter()
+                .filter_map(|e| {
+                    let library = e.library.as_deref()?;
+                    let item = if kind == PotFilterKind::Bank {
+                        build_filter_item(e.library_fil()?, None, library)
+                    } else {
+                        let sub_folder = e.sub_folder.as_deref()?;
+                        build_filter_item(e.sub_folder_fil()?, Some(library), sub_folder)
+                    };
+                    Some(item)
+                })
+                .unique_by(|item| item.id)
+                .sorted_by(|a, b| (&a.parent_name, &a.name).cmp(&(&b.parent_name, &b.name)))
+                .collect(),
+            PotFilterKind::Tempo => entries
+                .iter()
+                .filter_map(|e| Some((e.audio_metadata.rounded_tempo()?, e.tempo_fil()?)))
+                .unique_by(|(tempo, _)| *tempo)
+                .sorted_by_key(|(tempo, _)| *tempo)
+                .map(|(tempo, fil)| build_filter_item(fil, None, &format!("{tempo} BPM")))
+                .collect(),
+            PotFilterKind::Key => entries
+                .iter()
+                .filter_map(|e| {
+                    let key = e.audio_metadata.key?;
+                    // Keys with unknown mode are merged with the major ones
+                    Some(((key.root, key.is_minor == Some(true)), key.to_string()))
+                })
+                .unique()
+                .sorted()
+                .map(|(_, name)| build_filter_item(Fil::text(&name), None, &name))
+                .collect(),
+            _ => vec![],
+        };
+        items.retain(|item| !input.filter_input.excludes.contains(kind, item.id));
+        if has_entries_without_value {
+            items.insert(0, FilterItem::none());
+        }
+        items.into_iter().map(InnerFilterItem::Unique).collect()
+    }
+}
+
+fn build_filter_item(fil: Fil, parent_name: Option<&str>, name: &str) -> FilterItem {
+    FilterItem {
+        persistent_id: name.to_string(),
+        id: FilterItemId(Some(fil)),
+        parent_name: parent_name.map(|p| p.to_string()),
+        name: Some(name.to_string()),
+        icon: None,
+        more_info: None,
+    }
+}
+
+struct SampleEntry {
+    sample_name: String,
+    relative_path: String,
+    file_ext: String,
+    /// Top-level folder. `None` if the sample is located directly in the root directory.
+    library: Option<String>,
+    /// Folder path within the library. `None` if the sample is located directly in the library
+    /// folder.
+    sub_folder: Option<String>,
+    audio_metadata: AudioMetadata,
+    /// Contains a human-readable summary of the audio metadata as comment.
+    metadata: PotPresetMetaData,
+}
+
+impl SampleEntry {
+    fn library_fil(&self) -> Option<Fil> {
+        self.library.as_deref().map(Fil::text)
+    }
+
+    fn sub_folder_fil(&self) -> Option<Fil> {
+        let library = self.library.as_ref()?;
+        let sub_folder = self.sub_folder.as_ref()?;
+        Some(Fil::text(&format!("{library}|{sub_folder}")))
+    }
+
+    fn tempo_fil(&self) -> Option<Fil> {
+        let tempo = self.audio_metadata.rounded_tempo()?;
+        Some(Fil::text(&tempo.to_string()))
+    }
+
+    fn key_fil(&self) -> Option<Fil> {
+        let key = self.audio_metadata.key?;
+        Some(Fil::text(&key.to_string()))
+    }
+}
+
+impl Database for SampleDatabase {
+    fn persistent_id(&self) -> &PersistentDatabaseId {
+        &self.persistent_id
+    }
+
+    fn name(&self) -> Cow<str> {
+        "Samples".into()
+    }
+
+    fn description(&self) -> Cow<str> {
+        "All audio files in the \"Helgoboss/Pot/Samples\" directory of your REAPER resource \
+        directory.\n\
+        Each top-level folder is treated as one sample library. Symbolic links are followed, so \
+        you can link existing sample libraries into that directory."
+            .into()
+    }
+
+    fn supported_advanced_filter_kinds(&self) -> EnumSet<PotFilterKind> {
+        enum_set!(
+            PotFilterKind::Bank
+                | PotFilterKind::SubBank
+                | PotFilterKind::Tempo
+                | PotFilterKind::Key
+        )
+    }
+
+    fn refresh(&mut self, _: &ProviderContext) -> Result<(), Box<dyn Error>> {
+        self.entries = WalkDir::new(&self.root_dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                if !entry.file_type().is_file() {
+                    return None;
+                }
+                let file_ext = entry.path().extension()?.to_str()?.to_lowercase();
+                if !AUDIO_FILE_EXTENSIONS.contains(&file_ext.as_str()) {
+                    return None;
+                }
+                // Immediately exclude relative paths that can't be represented as valid UTF-8.
+                // Otherwise we will potentially open a can of worms (regarding persistence etc.).
+                let relative_path = entry.path().strip_prefix(&self.root_dir).ok()?;
+                let relative_path = relative_path.to_str()?.replace('\\', "/");
+                // Files with unreadable or unknown headers are still useful samples
+                let file = File::open(entry.path()).ok()?;
+                let mut audio_metadata =
+                    AudioMetadata::read(&mut BufReader::new(file), &file_ext).unwrap_or_default();
+                audio_metadata.complement_from_path(&relative_path);
+                let mut folders: Vec<&str> = relative_path.split('/').collect();
+                folders.pop();
+                let (library, sub_folder) = match folders.split_first() {
+                    None => (None, None),
+                    Some((library, [])) => (Some(library.to_string()), None),
+                    Some((library, rest)) => (Some(library.to_string()), Some(rest.join("/"))),
+                };
+                let summary = build_summary(&audio_metadata);
+                let file_metadata = entry.metadata().ok();
+                let sample_entry = SampleEntry {
+                    sample_name: entry.path().file_stem()?.to_str()?.to_string(),
+                    file_ext,
+                    library,
+                    sub_folder,
+                    metadata: PotPresetMetaData {
+                        author: None,
+                        vendor: None,
+                        comment: Some(summary),
+                        file_size_in_bytes: file_metadata.as_ref().map(|m| m.len()),
+                        modification_date: file_metadata
+                            .and_then(|m| m.modified().ok())
+                            .map(|t| DateTime::<Utc>::from(t).naive_utc()),
+                    },
+                    relative_path,
+                    audio_metadata,
+                };
+                Some(sample_entry)
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn query_filter_collections(
+        &self,
+        _: &ProviderContext,
+        input: InnerBuildInput,
+        affected_kinds: EnumSet<PotFilterKind>,
+    ) -> Result<InnerFilterItemCollections, Box<dyn Error>> {
+        let mut collections = InnerFilterItemCollections::empty();
+        for kind in affected_kinds.intersection(self.supported_advanced_filter_kinds()) {
+            collections.set(kind, self.build_filter_items(&input, kind));
+        }
+        Ok(collections)
+    }
+
+    fn query_presets(
+        &self,
+        _: &ProviderContext,
+        input: InnerBuildInput,
+    ) -> Result<Vec<SortablePresetId>, Box<dyn Error>> {
+        let preset_ids = self
+            .query_presets_internal(&input.filter_input)
+            .filter(|(_, entry)| {
+                let search_input = SampleSearchInput { entry };
+                input.search_evaluator.matches(search_input)
+            })
+            .map(|(i, entry)| SortablePresetId::new(i as _, entry.sample_name.clone()))
+            .collect();
+        Ok(preset_ids)
+    }
+
+    fn find_preset_by_id(
+        &self,
+        _: &ProviderContext,
+        preset_id: InnerPresetId,
+    ) -> Option<PotPreset> {
+        let entry = self.entries.get(preset_id.0 as usize)?;
+        let preset = PotPreset {
+            common: PotPresetCommon {
+                persistent_id: PersistentPresetId::new(
+                    self.persistent_id().clone(),
+                    create_persistent_inner_id(entry),
+                ),
+                name: entry.sample_name.clone(),
+                context_name: entry.sub_folder.clone(),
+                plugin_ids: vec![],
+                product_ids: vec![],
+                product_name: entry.library.clone(),
+                content_hash: None,
+                db_specific_preview_file: None,
+                is_supported: true,
+                is_available: true,
+                metadata: entry.metadata.clone(),
+            },
+            kind: PotPresetKind::FileBased(FiledBasedPotPresetKind {
+                file_ext: entry.file_ext.clone(),
+                path: self.root_dir.join(&entry.relative_path),
+            }),
+        };
+        Some(preset)
+    }
+}
+
+/// Example: "Loop, 120 BPM, Am, 8.00 s, stereo, 44100 Hz"
+fn build_summary(m: &AudioMetadata) -> String {
+    let kind = if m.is_loop() { "Loop" } else { "One shot" };
+    let tempo = m
+        .tempo
+        .map(|t| format!("{} BPM", (t * 100.0).round() / 100.0));
+    let key = m.key.map(|k| k.to_string());
+    let duration = m.duration.map(|d| format!("{:.2} s", d.as_secs_f64()));
+    let channels = m.channel_count.map(|c| match c {
+        1 => "mono".to_string(),
+        2 => "stereo".to_string(),
+        _ => format!("{c} channels"),
+    });
+    let sample_rate = m.sample_rate.map(|r| format!("{r} Hz"));
+    [
+        Some(kind.to_string()),
+        tempo,
+        key,
+        duration,
+        channels,
+        sample_rate,
+    ]
+    .into_iter()
+    .flatten()
+    .join(", ")
+}
+
+/// Example: `Funk Kit/Loops/Groove 98bpm.wav`
+fn create_persistent_inner_id(entry: &SampleEntry) -> PersistentInnerPresetId {
+    let escaped_path = PipeEscaped(entry.relative_path.as_str());
+    PersistentInnerPresetId::new(escaped_path.to_string())
+}
+
+struct SampleSearchInput<'a> {
+    entry: &'a SampleEntry,
+}
+
+impl SearchInput for SampleSearchInput<'_> {
+    fn preset_name(&self) -> &str {
+        &self.entry.sample_name
+    }
+
+    fn product_name(&self) -> Option<Cow<str>> {
+        Some(self.entry.library.as_deref()?.into())
+    }
+
+    fn file_extension(&self) -> Option<&str> {
+        Some(&self.entry.file_ext)
+    }
+
+    fn metadata(&self) -> Option<Cow<str>> {
+        Some(self.entry.metadata.searchable_text()?.into())
+    }
+}
@@ -0,0 +1,678 @@
+use crate::provider_database::{
+    Database, InnerFilterItem, InnerFilterItemCollections, ProviderContext, SortablePresetId,
+    FIL_IS_USER_PRESET_FALSE, FIL_IS_USER_PRESET_TRUE,
+};
+use crate::{
+    parse_vst3_uid, Fil, FiledBasedPotPresetKind, FilterInput, FilterItem, FilterItemId,
+    InnerBuildInput, InnerPresetId, PersistentDatabaseId, PersistentInnerPresetId,
+    PersistentPresetId, PipeEscaped, PluginId, PotPreset, PotPresetCommon, PotPresetKind,
+    PotPresetMetaData, SearchInput,
+};
+use std::borrow::Cow;
+
+use crate::plugins::PluginCore;
+use base::hash_util::{calculate_persistent_non_crypto_hash_one_shot, PersistentHash};
+use camino::Utf8PathBuf;
+use chrono::{DateTime, Utc};
+use enumset::{enum_set, EnumSet};
+use helgobox_api::persistence::PotFilterKind;
+use itertools::Itertools;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+pub struct Vst3PresetDatabase {
+    persistent_id: PersistentDatabaseId,
+    root_dirs: Vec<Vst3PresetRootDir>,
+    entries: Vec<PresetEntry>,
+}
+
+pub struct Vst3PresetRootDir {
+    pub path: Utf8PathBuf,
+    /// `true` for folders that contain presets saved by the user, `false` for factory presets.
+    pub is_user: bool,
+}
+
+impl Vst3PresetDatabase {
+    pub fn open(
+        persistent_id: PersistentDatabaseId,
+        root_dirs: Vec<Vst3PresetRootDir>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let root_dirs: Vec<_> = root_dirs
+            .into_iter()
+            .filter(|d| d.path.try_exists().unwrap_or(false))
+            .collect();
+        if root_dirs.is_empty() {
+            return Err("none of the VST3 preset directories exists".into());
+        }
+        let db = Self {
+            persistent_id,
+            root_dirs,
+            entries: Default::default(),
+        };
+        Ok(db)
+    }
+
+    fn query_presets_internal<'a>(
+        &'a self,
+        filter_input: &'a FilterInput,
+    ) -> impl Iterator<Item = (usize, &'a PresetEntry)> + 'a {
+        self.entries.iter().enumerate().filter(|(i, e)| {
+            let id = InnerPresetId(*i as _);
+            let filters = filter_input.filters;
+            let is_user_fil = if self.root_dirs[e.root_dir_index].is_user {
+                FIL_IS_USER_PRESET_TRUE
+            } else {
+                FIL_IS_USER_PRESET_FALSE
+            };
+            let sub_category_matches = match filters.effective_sub_category() {
+                None => true,
+                Some(FilterItemId(wanted_fil)) => e.sub_category_fil() == *wanted_fil,
+            };
+            let mode_matches = match filters.get(PotFilterKind::Mode) {
+                None => true,
+                Some(FilterItemId(None)) => e.modes.is_empty(),
+                Some(FilterItemId(Some(wanted_fil))) => {
+                    e.modes.iter().any(|m| Fil::text(m) == wanted_fil)
+                }
+            };
+            filters.matches(PotFilterKind::IsUser, is_user_fil)
+                && filters.matches_optional(PotFilterKind::Category, e.category_fil())
+                && sub_category_matches
+                && mode_matches
+                && filter_input.everything_matches(e.plugin.as_ref(), id)
+        })
+    }
+
+    fn build_filter_items(
+        &self,
+        input: &InnerBuildInput,
+        kind: PotFilterKind,
+    ) -> Vec<InnerFilterItem> {
+        let mut new_filters = *input.filter_input.filters;
+        new_filters.clear_this_and_dependent_filters(kind);
+        if kind == PotFilterKind::SubCategory {
+            // Sub categories are narrowed down by the category filter
+            new_filters.set(
+                PotFilterKind::Category,
+                input.filter_input.filters.get(PotFilterKind::Category),
+            );
+        }
+        let filter_input = input.filter_input.with_filters(&new_filters);
+        let entries = self.query_presets_internal(&filter_input).map(|(_, e)| e);
+        if kind == PotFilterKind::Bank {
+            return entries
+                .filter_map(|e| Some(e.plugin.as_ref()?.product_id))
+                .unique()
+                .map(InnerFilterItem::Product)
+                .collect();
+        }
+        let mut has_entries_without_value = false;
+        let mut items: Vec<_> = entries
+            .flat_map(|e| {
+                let values: Vec<(Option<&str>, &str)> = match kind {
+                    PotFilterKind::Category => e
+                        .category
+                        .as_deref()
+                        .map(|c| (None, c))
+                        .into_iter()
+                        .collect(),
+                    PotFilterKind::SubCategory => e
+                        .category
+                        .as_deref()
+                        .zip(e.sub_category.as_deref())
+                        .map(|(c, s)| (Some(c), s))
+                        .into_iter()
+                        .collect(),
+                    PotFilterKind::Mode => e.modes.iter().map(|m| (None, m.as_str())).collect(),
+                    _ => vec![],
+                };
+                if values.is_empty() {
+                    has_entries_without_value = true;
+                }
+                values
+            })
+            .unique()
+            .map(|(parent_name, name)| {
+                let fil = match parent_name {
+                    None => Fil::text(name),
+                    Some(p) => Fil::text(&format!("{p}|{name}")),
+                };
+                FilterItem {
+                    persistent_id: name.to_string(),
+                    id: FilterItemId(Some(fil)),
+                    parent_name: parent_name.map(|p| p.to_string()),
+                    name: Some(name.to_string()),
+                    icon: None,
+                    more_info: None,
+                }
+            })
+            .filter(|item| !input.filter_input.excludes.contains(kind, item.id))
+            .sorted_by(|a, b| (&a.parent_name, &a.name).cmp(&(&b.parent_name, &b.name)))
+            .map(InnerFilterItem::Unique)
+            .collect();
+        if has_entries_without_value {
+            items.insert(0, InnerFilterItem::Unique(FilterItem::none()));
+        }
+        items
+    }
+}
+
+struct PresetEntry {
+    preset_name: String,
+    root_dir_index: usize,
+    relative_path: String,
+    plugin_id: PluginId,
+    /// If `None`, it means the corresponding plug-in is not installed/scanned.
+    plugin: Option<PluginCore>,
+    /// Plug-in name as found in the preset's meta info. Used as fallback if the plug-in is not
+    /// installed.
+    plugin_name: Option<String>,
+    category: Option<String>,
+    sub_category: Option<String>,
+    modes: Vec<String>,
+    metadata: PotPresetMetaData,
+    content_hash: PersistentHash,
+}
+
+impl PresetEntry {
+    fn category_fil(&self) -> Option<Fil> {
+        self.category.as_deref().map(Fil::text)
+    }
+
+    fn sub_category_fil(&self) -> Option<Fil> {
+        let category = self.category.as_ref()?;
+        let sub_category = self.sub_category.as_ref()?;
+        Some(Fil::text(&format!("{category}|{sub_category}")))
+    }
+}
+
+impl Database for Vst3PresetDatabase {
+    fn persistent_id(&self) -> &PersistentDatabaseId {
+        &self.persistent_id
+    }
+
+    fn name(&self) -> Cow<str> {
+        "VST3 presets".into()
+    }
+
+    fn description(&self) -> Cow<str> {
+        "All \".vstpreset\" files in the standard VST3 preset directories".into()
+    }
+
+    fn supported_advanced_filter_kinds(&self) -> EnumSet<PotFilterKind> {
+        enum_set!(
+            PotFilterKind::Bank
+                | PotFilterKind::Category
+                | PotFilterKind::SubCategory
+                | PotFilterKind::Mode
+        )
+    }
+
+    fn refresh(&mut self, ctx: &ProviderContext) -> Result<(), Box<dyn Error>> {
+        self.entries = self
+            .root_dirs
+            .iter()
+            .enumerate()
+            .flat_map(|(root_dir_index, root_dir)| {
+                WalkDir::new(&root_dir.path)
+                    .follow_links(true)
+                    .into_iter()
+                    .filter_map(move |entry| {
+                        let entry = entry.ok()?;
+                        if !entry.file_type().is_file() {
+                            return None;
+                        }
+                        let extension = entry.path().extension()?;
+                        if !extension.eq_ignore_ascii_case("vstpreset") {
+                            return None;
+                        }
+                        // Immediately exclude relative paths that can't be represented as valid
+                        // UTF-8. Otherwise we will potentially open a can of worms (regarding
+                        // persistence etc.).
+                        let relative_path = entry.path().strip_prefix(&root_dir.path).ok()?;
+                        let relative_path = relative_path.to_str()?.to_string();
+                        let bytes = fs::read(entry.path()).ok()?;
+                        let preset_file = Vst3PresetFile::parse(&bytes).ok()?;
+                        let meta_info = preset_file.meta_info();
+                        let plugin = ctx
+                            .plugin_db
+                            .find_plugin_by_id(&preset_file.plugin_id)
+                            .map(|p| p.common.core);
+                        let (category, sub_category) = match meta_info.category() {
+                            None => (None, None),
+                            Some((c, s)) => (Some(c.to_string()), s.map(|s| s.to_string())),
+                        };
+                        let file_metadata = entry.metadata().ok();
+                        let preset_entry = PresetEntry {
+                            preset_name: entry.path().file_stem()?.to_str()?.to_string(),
+                            root_dir_index,
+                            relative_path,
+                            plugin_id: preset_file.plugin_id,
+                            plugin,
+                            plugin_name: meta_info.get("PlugInName").map(|n| n.to_string()),
+                            category,
+                            sub_category,
+                            modes: meta_info.characters().map(|c| c.to_string()).collect(),
+                            metadata: PotPresetMetaData {
+                                author: meta_info.author().map(|a| a.to_string()),
+                                vendor: None,
+                                comment: meta_info.comment().map(|c| c.to_string()),
+                                file_size_in_bytes: file_metadata.as_ref().map(|m| m.len()),
+                                modification_date: file_metadata
+                                    .and_then(|m| m.modified().ok())
+                                    .map(|t| DateTime::<Utc>::from(t).naive_utc()),
+                            },
+                            content_hash: calculate_persistent_non_crypto_hash_one_shot(&bytes),
+                        };
+                        Some(preset_entry)
+                    })
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn query_filter_collections(
+        &self,
+        _: &ProviderContext,
+        input: InnerBuildInput,
+        affected_kinds: EnumSet<PotFilterKind>,
+    ) -> Result<InnerFilterItemCollections, Box<dyn Error>> {
+        let mut collections = InnerFilterItemCollections::empty();
+        for kind in affected_kinds.intersection(self.supported_advanced_filter_kinds()) {
+            collections.set(kind, self.build_filter_items(&input, kind));
+        }
+        Ok(collections)
+    }
+
+    fn query_presets(
+        &self,
+        ctx: &ProviderContext,
+        input: InnerBuildInput,
+    ) -> Result<Vec<SortablePresetId>, Box<dyn Error>> {
+        let preset_ids = self
+            .query_presets_internal(&input.filter_input)
+            .filter(|(_, entry)| {
+                let search_input = Vst3SearchInput {
+                    ctx,
+                    preset_entry: entry,
+                };
+                input.search_evaluator.matches(search_input)
+            })
+            .map(|(i, entry)| SortablePresetId::new(i as _, entry.preset_name.clone()))
+            .collect();
+        Ok(preset_ids)
+    }
+
+    fn find_preset_by_id(
+        &self,
+        ctx: &ProviderContext,
+        preset_id: InnerPresetId,
+    ) -> Option<PotPreset> {
+        let preset_entry = self.entries.get(preset_id.0 as usize)?;
+        let root_dir = &self.root_dirs[preset_entry.root_dir_index];
+        let preset = PotPreset {
+            common: PotPresetCommon {
+                persistent_id: PersistentPresetId::new(
+                    self.persistent_id().clone(),
+                    create_persistent_inner_id(preset_entry),
+                ),
+                name: preset_entry.preset_name.clone(),
+                context_name: None,
+                plugin_ids: vec![preset_entry.plugin_id],
+                product_ids: preset_entry
+                    .plugin
+                    .as_ref()
+                    .map(|p| p.product_id)
+                    .into_iter()
+                    .collect(),
+                product_name: build_product_name(ctx, preset_entry).map(|n| n.to_string()),
+                content_hash: Some(preset_entry.content_hash),
+                db_specific_preview_file: None,
+                is_supported: true,
+                is_available: preset_entry.plugin.is_some(),
+                metadata: preset_entry.metadata.clone(),
+            },
+            kind: PotPresetKind::FileBased(FiledBasedPotPresetKind {
+                file_ext: "vstpreset".to_string(),
+                path: root_dir.path.join(&preset_entry.relative_path),
+            }),
+        };
+        Some(preset)
+    }
+}
+
+/// Returns the standard VST3 preset directories of the current operating system.
+///
+/// See https://steinbergmedia.github.io/vst3_dev_portal/pages/Technical+Documentation/Locations+Format/Preset+Locations.html
+pub fn standard_vst3_preset_root_dirs() -> Vec<Vst3PresetRootDir> {
+    fn user(path: Option<Utf8PathBuf>) -> Option<Vst3PresetRootDir> {
+        Some(Vst3PresetRootDir {
+            path: path?,
+            is_user: true,
+        })
+    }
+    fn factory(path: Option<Utf8PathBuf>) -> Option<Vst3PresetRootDir> {
+        Some(Vst3PresetRootDir {
+            path: path?,
+            is_user: false,
+        })
+    }
+    fn dir(base: Option<std::path::PathBuf>, sub_path: &str) -> Option<Utf8PathBuf> {
+        let path = Utf8PathBuf::from_path_buf(base?).ok()?;
+        Some(path.join(sub_path))
+    }
+    let dirs = if cfg!(target_os = "windows") {
+        [
+            user(dir(dirs::document_dir(), "VST3 Presets")),
+            user(dir(dirs::data_dir(), "VST3 Presets")),
+            factory(dir(
+                std::env::var_os("PROGRAMDATA").map(Into::into),
+                "VST3 Presets",
+            )),
+        ]
+    } else if cfg!(target_os = "macos") {
+        [
+            user(dir(dirs::home_dir(), "Library/Audio/Presets")),
+            factory(Some("/Library/Audio/Presets".into())),
+            None,
+        ]
+    } else {
+        [
+            user(dir(dirs::home_dir(), ".vst3/presets")),
+            factory(Some("/usr/share/vst3/presets".into())),
+            factory(Some("/usr/local/share/vst3/presets".into())),
+        ]
+    };
+    dirs.into_iter().flatten().collect()
+}
+
+/// Example: `Steinberg Media Technologies/HALion Sonic/Pads/Warm Pad.vstpreset`
+fn create_persistent_inner_id(preset_entry: &PresetEntry) -> PersistentInnerPresetId {
+    let escaped_path = PipeEscaped(preset_entry.relative_path.as_str());
+    PersistentInnerPresetId::new(escaped_path.to_string())
+}
+
+struct Vst3SearchInput<'a> {
+    ctx: &'a ProviderContext<'a>,
+    preset_entry: &'a PresetEntry,
+}
+
+impl SearchInput for Vst3SearchInput<'_> {
+    fn preset_name(&self) -> &str {
+        &self.preset_entry.preset_name
+    }
+
+    fn product_name(&self) -> Option<Cow<str>> {
+        build_product_name(self.ctx, self.preset_entry)
+    }
+
+    fn file_extension(&self) -> Option<&str> {
+        Some("vstpreset")
+    }
+
+    fn metadata(&self) -> Option<Cow<str>> {
+        Some(self.preset_entry.metadata.searchable_text()?.into())
+    }
+}
+
+fn build_product_name<'a>(
+    ctx: &ProviderContext,
+    preset_entry: &'a PresetEntry,
+) -> Option<Cow<'a, str>> {
+    let plugin = preset_entry
+        .plugin
+        .as_ref()
+        .and_then(|core| ctx.plugin_db.find_plugin_by_id(&core.id));
+    match plugin {
+        None => Some(preset_entry.plugin_name.as_deref()?.into()),
+        Some(p) => Some(p.common.to_string().into()),
+    }
+}
+
+/// A parsed VST3 preset file.
+///
+/// Format (all integers little-endian):
+///
+/// ```text
+/// Header:     "VST3" | version (i32) | class ID (32 ASCII hex chars) | chunk list offset (i64)
+/// Chunk list: "List" | entry count (i32) | entries: chunk ID (4 chars) | offset (i64) | size (i64)
+/// ```
+///
+/// The chunk with ID "Info" contains the meta info as XML.
+pub struct Vst3PresetFile<'a> {
+    pub plugin_id: PluginId,
+    pub meta_info_xml: Option<&'a str>,
+}
+
+impl<'a> Vst3PresetFile<'a> {
+    pub fn load_plugin_id(path: &Path) -> Result<PluginId, Box<dyn Error>> {
+        let bytes = fs::read(path)?;
+        let file = Vst3PresetFile::parse(&bytes)?;
+        Ok(file.plugin_id)
+    }
+
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, &'static str> {
+        const HEADER_SIZE: usize = 48;
+        if bytes.len() < HEADER_SIZE || &bytes[0..4] != b"VST3" {
+            return Err("not a VST3 preset file");
+        }
+        let class_id = std::str::from_utf8(&bytes[8..40]).map_err(|_| "invalid class ID")?;
+        let plugin_id = PluginId::vst3(parse_vst3_uid(class_id)?);
+        let list_offset = read_i64(bytes, 40)?;
+        let meta_info_xml = find_chunk(bytes, list_offset, b"Info")?
+            .and_then(|chunk| std::str::from_utf8(chunk).ok());
+        let file = Self {
+            plugin_id,
+            meta_info_xml,
+        };
+        Ok(file)
+    }
+
+    pub fn meta_info(&self) -> Vst3PresetMetaInfo {
+        Vst3PresetMetaInfo::parse(self.meta_info_xml.unwrap_or_default())
+    }
+}
+
+fn find_chunk<'a>(
+    bytes: &'a [u8],
+    list_offset: i64,
+    chunk_id: &[u8; 4],
+) -> Result<Option<&'a [u8]>, &'static str> {
+    let list_offset = usize::try_from(list_offset).map_err(|_| "invalid chunk list offset")?;
+    if bytes.get(list_offset..list_offset + 4) != Some(&b"List"[..]) {
+        return Err("chunk list not found");
+    }
+    let entry_count = read_i32(bytes, list_offset + 4)?;
+    for i in 0..entry_count.max(0) as usize {
+        let entry_offset = list_offset + 8 + i * 20;
+        // A corrupt entry count must not make us loop on
+        let entry_id = bytes
+            .get(entry_offset..entry_offset + 4)
+            .ok_or("chunk list out of bounds")?;
+        if entry_id != chunk_id {
+            continue;
+        }
+        let offset = read_i64(bytes, entry_offset + 4)?;
+        let size = read_i64(bytes, entry_offset + 12)?;
+        let from = usize::try_from(offset).map_err(|_| "invalid chunk offset")?;
+        let size = usize::try_from(size).map_err(|_| "invalid chunk size")?;
+        let until = from.checked_add(size).ok_or("invalid chunk size")?;
+        let chunk = bytes.get(from..until).ok_or("chunk out of bounds")?;
+        return Ok(Some(chunk));
+    }
+    Ok(None)
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> Result<i32, &'static str> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .ok_or("unexpected end of file")?;
+    Ok(i32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn read_i64(bytes: &[u8], offset: usize) -> Result<i64, &'static str> {
+    let slice = bytes
+        .get(offset..offset + 8)
+        .ok_or("unexpected end of file")?;
+    Ok(i64::from_le_bytes(slice.try_into().unwrap()))
+}
+
+/// Attributes of the "MetaInfo" XML chunk of a VST3 preset.
+///
+/// Example:
+///
+/// ```xml
+/// <MetaInfo>
+///     <Attr id="PlugInName" value="HALion Sonic" type="string" flags="writeProtected"/>
+///     <Attr id="MusicalCategory" value="Pad|Warm Pad" type="string" flags=""/>
+///     <Attr id="MusicalCharacter" value="Soft|Dark" type="string" flags=""/>
+/// </MetaInfo>
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Vst3PresetMetaInfo {
+    attributes: Vec<(String, String)>,
+}
+
+impl Vst3PresetMetaInfo {
+    pub fn parse(xml: &str) -> Self {
+        let attr_regex = base::regex!(r#"<Attr\s([^>]*)>"#);
+        let key_value_regex = base::regex!(r#"(\w+)\s*=\s*"([^"]*)""#);
+        let attributes = attr_regex
+            .captures_iter(xml)
+            .filter_map(|attr_captures| {
+                let mut id = None;
+                let mut value = None;
+                for kv_captures in key_value_regex.captures_iter(attr_captures.get(1)?.as_str()) {
+                    let v = kv_captures.get(2)?.as_str();
+                    match kv_captures.get(1)?.as_str() {
+                        "id" => id = Some(v),
+                        "value" => value = Some(v),
+                        _ => {}
+                    }
+                }
+                Some((unescape_xml(id?), unescape_xml(value?)))
+            })
+            .collect();
+        Self { attributes }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attr_id, value)| attr_id == id && !value.trim().is_empty())
+            .map(|(_, value)| value.trim())
+    }
+
+    /// Returns the category and optional sub category.
+    pub fn category(&self) -> Option<(&str, Option<&str>)> {
+        let value = self
+            .get("MusicalCategory")
+            .or_else(|| self.get("MusicalInstrument"))?;
+        let res = match value.split_once('|') {
+            None => (value, None),
+            Some((category, sub_category)) => {
+                let sub_category = Some(sub_category.trim()).filter(|s| !s.is_empty());
+                (category.trim(), sub_category)
+            }
+        };
+        Some(res)
+    }
+
+    pub fn characters(&self) -> impl Iterator<Item = &str> {
+        self.get("MusicalCharacter")
+            .into_iter()
+            .flat_map(|v| v.split('|'))
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.get("Author")
+            .or_else(|| self.get("MediaAuthor"))
+            .or_else(|| self.get("Creator"))
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.get("Comment").or_else(|| self.get("MediaComment"))
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_preset_file(class_id: &str, meta_info_xml: &str) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(b"VST3");
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+        bytes.extend_from_slice(class_id.as_bytes());
+        let info_offset = 48i64;
+        let list_offset = info_offset + meta_info_xml.len() as i64;
+        bytes.extend_from_slice(&list_offset.to_le_bytes());
+        bytes.extend_from_slice(meta_info_xml.as_bytes());
+        bytes.extend_from_slice(b"List");
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+        bytes.extend_from_slice(b"Info");
+        bytes.extend_from_slice(&info_offset.to_le_bytes());
+        bytes.extend_from_slice(&(meta_info_xml.len() as i64).to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn parse_preset_file() {
+        // Given
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<MetaInfo>
+	<Attr id="MediaType" value="VstPreset" type="string" flags="writeProtected"></Attr>
+	<Attr id="PlugInName" value="HALion Sonic" type="string" flags="writeProtected"></Attr>
+	<Attr id="MusicalCategory" value="Pad|Warm Pad" type="string" flags=""></Attr>
+	<Attr id="MusicalCharacter" value="Soft|Dark" type="string" flags=""/>
+	<Attr id="Author" value="Tom &amp; Jerry" type="string" flags=""/>
+</MetaInfo>"#;
+        let bytes = build_preset_file("565354534D44327A6562726132000000", xml);
+        // When
+        let file = Vst3PresetFile::parse(&bytes).unwrap();
+        let meta_info = file.meta_info();
+        // Then
+        assert_eq!(
+            file.plugin_id,
+            PluginId::vst3([0x56535453, 0x4D44327A, 0x65627261, 0x32000000])
+        );
+        assert_eq!(meta_info.get("PlugInName"), Some("HALion Sonic"));
+        assert_eq!(meta_info.category(), Some(("Pad", Some("Warm Pad"))));
+        assert_eq!(
+            meta_info.characters().collect::<Vec<_>>(),
+            vec!["Soft", "Dark"]
+        );
+        assert_eq!(meta_info.author(), Some("Tom & Jerry"));
+        assert_eq!(meta_info.comment(), None);
+    }
+
+    #[test]
+    fn reject_invalid_file() {
+        assert!(Vst3PresetFile::parse(b"RIFF").is_err());
+    }
+
+    #[test]
+    fn reject_corrupt_chunk_list() {
+        // Given
+        let mut bytes = b"List".to_vec();
+        bytes.extend_from_slice(&i32::MAX.to_le_bytes());
+        bytes.extend_from_slice(b"Comp");
+        bytes.extend_from_slice(&[0; 16]);
+        // When
+        let result = find_chunk(&bytes, 0, b"Info");
+        // Then
+        assert_eq!(result, Err("chunk list out of bounds"));
+    }
+}
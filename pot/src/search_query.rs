@@ -0,0 +1,804 @@
+//! A small query language for searching presets.
+//!
+//! Examples:
+//!
+//! - `bass NOT sub product:Serum`
+//! - `"warm pad" OR (name:keys AND -author:"john doe")`
+//! - `tag:favorite size>1MB date>=2023-06`
+//!
+//! Words without field prefix are matched against the search fields chosen in the options.
+//! Adjacent terms are combined with AND. AND binds stronger than OR. `-term` is short for
+//! `NOT term`. Text comparisons are case-insensitive substring matches.
+
+use crate::{PotPreset, PresetUserMetadata, SearchField};
+use chrono::{NaiveDate, NaiveDateTime};
+use enumset::EnumSet;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::CharIndices;
+use wildmatch::WildMatch;
+
+#[derive(Clone)]
+pub enum SearchQuery {
+    Term(SearchTerm),
+    Not(Box<SearchQuery>),
+    And(Vec<SearchQuery>),
+    Or(Vec<SearchQuery>),
+}
+
+#[derive(Clone)]
+pub enum SearchTerm {
+    /// If no field is given, the default search fields are used.
+    Text {
+        field: Option<TextField>,
+        matcher: TextMatcher,
+    },
+    /// Compares the modification date with the given range (e.g. a whole year or month).
+    Date {
+        op: CompareOp,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    },
+    /// Compares the file size in bytes.
+    Size { op: CompareOp, bytes: u64 },
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TextField {
+    Name,
+    Product,
+    Extension,
+    Author,
+    Vendor,
+    Comment,
+    Tag,
+    Notes,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CompareOp {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone)]
+pub enum TextMatcher {
+    /// Lowercase text to be contained.
+    Contains(String),
+    Wildcard(WildMatch),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SearchQueryError {
+    pub msg: Cow<'static, str>,
+    /// Character position within the search expression (0-based).
+    pub position: usize,
+}
+
+impl Display for SearchQueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at position {})", self.msg, self.position + 1)
+    }
+}
+
+impl std::error::Error for SearchQueryError {}
+
+/// The data against which a query is evaluated.
+pub struct SearchQueryCandidate<'a> {
+    pub preset: &'a PotPreset,
+    pub user_metadata: Option<&'a PresetUserMetadata>,
+    /// Fields to be searched by terms without field prefix.
+    pub default_fields: EnumSet<SearchField>,
+}
+
+impl SearchQuery {
+    /// Returns `true` if the given expression uses any query syntax (quotes, parentheses,
+    /// operators, field prefixes or comparisons).
+    ///
+    /// Expressions without query syntax should be treated as plain search text, which all
+    /// databases can evaluate very efficiently.
+    pub fn is_structured(expression: &str) -> bool {
+        if expression.contains(['"', '(', ')']) {
+            return true;
+        }
+        expression.split_whitespace().any(|word| {
+            matches!(word, "AND" | "OR" | "NOT")
+                || (word.len() > 1 && word.starts_with('-'))
+                || split_field_prefix(word).is_some()
+        })
+    }
+
+    pub fn parse(expression: &str, use_wildcards: bool) -> Result<Self, SearchQueryError> {
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser {
+            tokens,
+            index: 0,
+            use_wildcards,
+            end_position: expression.chars().count(),
+        };
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(SearchQueryError {
+                msg: "unexpected closing parenthesis".into(),
+                position: token.position,
+            });
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, candidate: &SearchQueryCandidate) -> bool {
+        match self {
+            SearchQuery::Term(t) => t.matches(candidate),
+            SearchQuery::Not(q) => !q.matches(candidate),
+            SearchQuery::And(queries) => queries.iter().all(|q| q.matches(candidate)),
+            SearchQuery::Or(queries) => queries.iter().any(|q| q.matches(candidate)),
+        }
+    }
+}
+
+impl SearchTerm {
+    fn matches(&self, candidate: &SearchQueryCandidate) -> bool {
+        let preset = candidate.preset;
+        let metadata = &preset.common.metadata;
+        match self {
+            SearchTerm::Text { field, matcher } => match field {
+                None => candidate.default_fields.iter().any(|f| match f {
+                    SearchField::PresetName => matcher.matches_field(TextField::Name, candidate),
+                    SearchField::ProductName => {
+                        matcher.matches_field(TextField::Product, candidate)
+                    }
+                    SearchField::FileExtension => {
+                        matcher.matches_field(TextField::Extension, candidate)
+                    }
+                    // Same text as in plain search
+                    SearchField::Metadata => {
+                        matcher.matches_optional(metadata.searchable_text().as_deref())
+                    }
+                    SearchField::Notes => matcher.matches_field(TextField::Notes, candidate),
+                }),
+                Some(f) => matcher.matches_field(*f, candidate),
+            },
+            SearchTerm::Date { op, start, end } => {
+                let Some(date) = metadata.modification_date else {
+                    return false;
+                };
+                match op {
+                    CompareOp::Equal => date >= *start && date < *end,
+                    CompareOp::Less => date < *start,
+                    CompareOp::LessOrEqual => date < *end,
+                    CompareOp::Greater => date >= *end,
+                    CompareOp::GreaterOrEqual => date >= *start,
+                }
+            }
+            SearchTerm::Size { op, bytes } => {
+                let Some(size) = metadata.file_size_in_bytes else {
+                    return false;
+                };
+                match op {
+                    CompareOp::Equal => size == *bytes,
+                    CompareOp::Less => size < *bytes,
+                    CompareOp::LessOrEqual => size <= *bytes,
+                    CompareOp::Greater => size > *bytes,
+                    CompareOp::GreaterOrEqual => size >= *bytes,
+                }
+            }
+        }
+    }
+}
+
+impl TextMatcher {
+    fn matches_field(&self, field: TextField, candidate: &SearchQueryCandidate) -> bool {
+        let preset = candidate.preset;
+        let metadata = &preset.common.metadata;
+        let user_metadata = candidate.user_metadata;
+        match field {
+            TextField::Name => self.matches(&preset.common.name),
+            TextField::Product => self.matches_optional(preset.common.product_name.as_deref()),
+            TextField::Extension => self.matches_optional(preset.kind.file_extension()),
+            TextField::Author => self.matches_optional(metadata.author.as_deref()),
+            TextField::Vendor => self.matches_optional(metadata.vendor.as_deref()),
+            TextField::Comment => self.matches_optional(metadata.comment.as_deref()),
+            TextField::Tag => user_metadata.is_some_and(|m| m.tags.iter().any(|t| self.matches(t))),
+            TextField::Notes => {
+                user_metadata.is_some_and(|m| !m.notes.is_empty() && self.matches(&m.notes))
+            }
+        }
+    }
+
+    fn matches_optional(&self, text: Option<&str>) -> bool {
+        text.is_some_and(|t| self.matches(t))
+    }
+
+    fn matches(&self, text: &str) -> bool {
+        let lowercase_text = text.to_lowercase();
+        match self {
+            TextMatcher::Contains(s) => lowercase_text.contains(s.as_str()),
+            TextMatcher::Wildcard(w) => w.matches(&lowercase_text),
+        }
+    }
+}
+
+enum FieldKind {
+    Text(TextField),
+    Date,
+    Size,
+}
+
+fn parse_field_name(name: &str) -> Option<FieldKind> {
+    let kind = match name.to_lowercase().as_str() {
+        "name" => FieldKind::Text(TextField::Name),
+        "product" | "plugin" => FieldKind::Text(TextField::Product),
+        "ext" | "extension" => FieldKind::Text(TextField::Extension),
+        "author" => FieldKind::Text(TextField::Author),
+        "vendor" => FieldKind::Text(TextField::Vendor),
+        "comment" => FieldKind::Text(TextField::Comment),
+        "tag" => FieldKind::Text(TextField::Tag),
+        "notes" => FieldKind::Text(TextField::Notes),
+        "date" | "modified" => FieldKind::Date,
+        "size" => FieldKind::Size,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Splits a word such as `name:bass` or `size>=1MB` into field name and the remainder
+/// (starting with the operator).
+///
+/// Returns `None` if the word doesn't start with a known field name, so that preset names such as
+/// `Vox:Lead` can still be searched as plain text.
+fn split_field_prefix(word: &str) -> Option<(&str, &str)> {
+    let op_index = word.find([':', '<', '>', '='])?;
+    let (name, rest) = word.split_at(op_index);
+    parse_field_name(name)?;
+    Some((name, rest))
+}
+
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+enum TokenKind {
+    OpenParen,
+    CloseParen,
+    And,
+    Or,
+    Not,
+    Term {
+        field: Option<String>,
+        op: Option<String>,
+        value: String,
+    },
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, SearchQueryError> {
+    let mut tokens = vec![];
+    let mut chars = expression.char_indices().peekable();
+    // We report character positions, not byte positions
+    let char_pos = |byte_index: usize| expression[..byte_index].chars().count();
+    while let Some(&(i, c)) = chars.peek() {
+        let position = char_pos(i);
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token {
+                    kind: TokenKind::OpenParen,
+                    position,
+                });
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token {
+                    kind: TokenKind::CloseParen,
+                    position,
+                });
+            }
+            '-' => {
+                chars.next();
+                let followed_by_term = chars
+                    .peek()
+                    .is_some_and(|(_, next)| !next.is_whitespace() && *next != ')');
+                if followed_by_term {
+                    tokens.push(Token {
+                        kind: TokenKind::Not,
+                        position,
+                    });
+                } else {
+                    // A lonely dash is just text
+                    tokens.push(Token {
+                        kind: TokenKind::Term {
+                            field: None,
+                            op: None,
+                            value: "-".to_string(),
+                        },
+                        position,
+                    });
+                }
+            }
+            '"' => {
+                chars.next();
+                let value = read_quoted(&mut chars, position)?;
+                tokens.push(Token {
+                    kind: TokenKind::Term {
+                        field: None,
+                        op: None,
+                        value,
+                    },
+                    position,
+                });
+            }
+            _ => {
+                // Read bare word up to the first character that can't be part of it
+                let mut word = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                let kind = match word.as_str() {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    _ => {
+                        if let Some((field, rest)) = split_field_prefix(&word) {
+                            let (op, value) = split_operator(rest);
+                            let mut value = value.to_string();
+                            // Field values can be quoted as well, e.g. product:"Serum 2"
+                            if value.is_empty() && chars.peek().is_some_and(|(_, c)| *c == '"') {
+                                let (quote_index, _) = chars.next().unwrap();
+                                value = read_quoted(&mut chars, char_pos(quote_index))?;
+                            }
+                            TokenKind::Term {
+                                field: Some(field.to_string()),
+                                op: Some(op.to_string()),
+                                value,
+                            }
+                        } else {
+                            TokenKind::Term {
+                                field: None,
+                                op: None,
+                                value: word,
+                            }
+                        }
+                    }
+                };
+                tokens.push(Token { kind, position });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn read_quoted(
+    chars: &mut Peekable<CharIndices>,
+    start_position: usize,
+) -> Result<String, SearchQueryError> {
+    let mut value = String::new();
+    for (_, c) in chars.by_ref() {
+        if c == '"' {
+            return Ok(value);
+        }
+        value.push(c);
+    }
+    Err(SearchQueryError {
+        msg: "missing closing quote".into(),
+        position: start_position,
+    })
+}
+
+/// Splits `:>=1MB` into `>=` and `1MB`.
+fn split_operator(text: &str) -> (&str, &str) {
+    let text = text.strip_prefix(':').unwrap_or(text);
+    for op in [">=", "<=", ">", "<", "="] {
+        if let Some(value) = text.strip_prefix(op) {
+            return (op, value);
+        }
+    }
+    ("", text)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+    use_wildcards: bool,
+    end_position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.index);
+        self.index += 1;
+        token
+    }
+
+    fn current_position(&self) -> usize {
+        self.peek().map(|t| t.position).unwrap_or(self.end_position)
+    }
+
+    fn parse_or(&mut self) -> Result<SearchQuery, SearchQueryError> {
+        let mut operands = vec![self.parse_and()?];
+        while matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Or,
+                ..
+            })
+        ) {
+            self.next();
+            operands.push(self.parse_and()?);
+        }
+        Ok(simplify(operands, SearchQuery::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<SearchQuery, SearchQueryError> {
+        let mut operands = vec![self.parse_not()?];
+        loop {
+            match self.peek().map(|t| &t.kind) {
+                None | Some(TokenKind::Or | TokenKind::CloseParen) => break,
+                Some(TokenKind::And) => {
+                    self.next();
+                }
+                // Implicit AND
+                _ => {}
+            }
+            operands.push(self.parse_not()?);
+        }
+        Ok(simplify(operands, SearchQuery::And))
+    }
+
+    fn parse_not(&mut self) -> Result<SearchQuery, SearchQueryError> {
+        if matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Not,
+                ..
+            })
+        ) {
+            self.next();
+            let operand = self.parse_not()?;
+            return Ok(SearchQuery::Not(Box::new(operand)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<SearchQuery, SearchQueryError> {
+        let position = self.current_position();
+        let use_wildcards = self.use_wildcards;
+        let Some(token) = self.next() else {
+            return Err(SearchQueryError {
+                msg: "search term expected".into(),
+                position,
+            });
+        };
+        match &token.kind {
+            TokenKind::Term { field, op, value } => {
+                let term = create_term(
+                    field.as_deref(),
+                    op.as_deref(),
+                    value,
+                    use_wildcards,
+                    position,
+                )?;
+                return Ok(SearchQuery::Term(term));
+            }
+            TokenKind::OpenParen => {}
+            TokenKind::CloseParen | TokenKind::And | TokenKind::Or | TokenKind::Not => {
+                return Err(SearchQueryError {
+                    msg: "search term expected".into(),
+                    position,
+                });
+            }
+        }
+        let query = self.parse_or()?;
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::CloseParen,
+                ..
+            }) => Ok(query),
+            _ => Err(SearchQueryError {
+                msg: "missing closing parenthesis".into(),
+                position,
+            }),
+        }
+    }
+}
+
+fn simplify(
+    mut operands: Vec<SearchQuery>,
+    combine: fn(Vec<SearchQuery>) -> SearchQuery,
+) -> SearchQuery {
+    if operands.len() == 1 {
+        operands.remove(0)
+    } else {
+        combine(operands)
+    }
+}
+
+fn create_term(
+    field: Option<&str>,
+    op: Option<&str>,
+    value: &str,
+    use_wildcards: bool,
+    position: usize,
+) -> Result<SearchTerm, SearchQueryError> {
+    let error = |msg: String| SearchQueryError {
+        msg: msg.into(),
+        position,
+    };
+    let Some(field) = field else {
+        return Ok(SearchTerm::Text {
+            field: None,
+            matcher: create_text_matcher(value, use_wildcards),
+        });
+    };
+    let field_kind =
+        parse_field_name(field).ok_or_else(|| error(format!("unknown field \"{field}\"")))?;
+    if value.is_empty() {
+        return Err(error(format!("value for field \"{field}\" expected")));
+    }
+    let op = match op.unwrap_or_default() {
+        "" | "=" => CompareOp::Equal,
+        "<" => CompareOp::Less,
+        "<=" => CompareOp::LessOrEqual,
+        ">" => CompareOp::Greater,
+        ">=" => CompareOp::GreaterOrEqual,
+        op => return Err(error(format!("unknown operator \"{op}\""))),
+    };
+    let term = match field_kind {
+        FieldKind::Text(text_field) => {
+            if op != CompareOp::Equal {
+                return Err(error(format!(
+                    "field \"{field}\" doesn't support comparisons"
+                )));
+            }
+            SearchTerm::Text {
+                field: Some(text_field),
+                matcher: create_text_matcher(value, use_wildcards),
+            }
+        }
+        FieldKind::Date => {
+            let (start, end) = parse_date_range(value).ok_or_else(|| {
+                error(format!(
+                    "invalid date \"{value}\" (expected YYYY, YYYY-MM or YYYY-MM-DD)"
+                ))
+            })?;
+            SearchTerm::Date { op, start, end }
+        }
+        FieldKind::Size => {
+            let bytes = parse_size(value).ok_or_else(|| {
+                error(format!(
+                    "invalid size \"{value}\" (expected e.g. 500KB or 2MB)"
+                ))
+            })?;
+            SearchTerm::Size { op, bytes }
+        }
+    };
+    Ok(term)
+}
+
+fn create_text_matcher(value: &str, use_wildcards: bool) -> TextMatcher {
+    let value = value.to_lowercase();
+    if use_wildcards && value.contains(['*', '?']) {
+        TextMatcher::Wildcard(WildMatch::new(&value))
+    } else {
+        TextMatcher::Contains(value)
+    }
+}
+
+/// Parses `2023`, `2023-06` or `2023-06-15` into the corresponding half-open range.
+fn parse_date_range(text: &str) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let parts: Vec<_> = text.split('-').collect();
+    let numbers: Vec<u32> = parts
+        .iter()
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let (start, end) = match numbers.as_slice() {
+        [year] => {
+            let year = *year as i32;
+            (
+                NaiveDate::from_ymd_opt(year, 1, 1)?,
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+            )
+        }
+        [year, month] => {
+            let year = *year as i32;
+            let start = NaiveDate::from_ymd_opt(year, *month, 1)?;
+            let end = if *month == 12 {
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+            } else {
+                NaiveDate::from_ymd_opt(year, month + 1, 1)?
+            };
+            (start, end)
+        }
+        [year, month, day] => {
+            let start = NaiveDate::from_ymd_opt(*year as i32, *month, *day)?;
+            (start, start.succ_opt()?)
+        }
+        _ => return None,
+    };
+    Some((start.and_hms_opt(0, 0, 0)?, end.and_hms_opt(0, 0, 0)?))
+}
+
+/// Parses sizes such as `1024`, `500KB`, `1.5MB` or `2g` (binary units).
+fn parse_size(text: &str) -> Option<u64> {
+    let lowercase = text.to_lowercase();
+    let unit_start = lowercase
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(lowercase.len());
+    let (number, unit) = lowercase.split_at(unit_start);
+    let number: f64 = number.parse().ok()?;
+    let factor: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" => 1024,
+        "m" | "mb" => 1024 * 1024,
+        "g" | "gb" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some((number * factor as f64).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        InternalPotPresetKind, PersistentDatabaseId, PersistentInnerPresetId, PersistentPresetId,
+        PotPresetCommon, PotPresetKind, PotPresetMetaData,
+    };
+
+    #[test]
+    fn detect_structured_queries() {
+        assert!(!SearchQuery::is_structured("warm pad"));
+        assert!(!SearchQuery::is_structured("bass-lead - synth"));
+        assert!(SearchQuery::is_structured("bass NOT sub"));
+        assert!(SearchQuery::is_structured("bass -sub"));
+        assert!(SearchQuery::is_structured("product:Serum"));
+        assert!(SearchQuery::is_structured("size>1MB"));
+        assert!(SearchQuery::is_structured("\"warm pad\""));
+        assert!(!SearchQuery::is_structured("Vox:Lead"));
+        assert!(!SearchQuery::is_structured("a=b"));
+    }
+
+    #[test]
+    fn parse_operators_and_fields() {
+        let query =
+            SearchQuery::parse("bass NOT sub product:\"Serum 2\" OR size>=1.5MB", false).unwrap();
+        let SearchQuery::Or(operands) = query else {
+            panic!("expected OR");
+        };
+        assert_eq!(operands.len(), 2);
+        let SearchQuery::And(and_operands) = &operands[0] else {
+            panic!("expected AND");
+        };
+        assert_eq!(and_operands.len(), 3);
+        assert!(matches!(and_operands[1], SearchQuery::Not(_)));
+        let SearchQuery::Term(SearchTerm::Text {
+            field: Some(TextField::Product),
+            matcher: TextMatcher::Contains(product),
+        }) = &and_operands[2]
+        else {
+            panic!("expected product term");
+        };
+        assert_eq!(product, "serum 2");
+        let SearchQuery::Term(SearchTerm::Size { op, bytes }) = &operands[1] else {
+            panic!("expected size term");
+        };
+        assert_eq!(*op, CompareOp::GreaterOrEqual);
+        assert_eq!(*bytes, 1572864);
+    }
+
+    #[test]
+    fn parse_date_ranges() {
+        let (start, end) = parse_date_range("2023-12").unwrap();
+        assert_eq!(start.to_string(), "2023-12-01 00:00:00");
+        assert_eq!(end.to_string(), "2024-01-01 00:00:00");
+        assert!(parse_date_range("2023-13").is_none());
+    }
+
+    #[test]
+    fn match_presets() {
+        // Given
+        let preset = preset(PotPresetMetaData {
+            author: Some("John Doe".to_string()),
+            vendor: None,
+            comment: None,
+            file_size_in_bytes: Some(2 * 1024 * 1024),
+            modification_date: NaiveDate::from_ymd_opt(2023, 6, 15)
+                .and_then(|d| d.and_hms_opt(12, 0, 0)),
+        });
+        let mut user_metadata = PresetUserMetadata::default();
+        user_metadata.add_tag("Favorite");
+        user_metadata.notes = "Great for ambient".to_string();
+        let candidate = SearchQueryCandidate {
+            preset: &preset,
+            user_metadata: Some(&user_metadata),
+            default_fields: SearchField::PresetName | SearchField::ProductName,
+        };
+        // When
+        let matches = |expr: &str| SearchQuery::parse(expr, true).unwrap().matches(&candidate);
+        // Then
+        assert!(matches("warm NOT bass"));
+        assert!(matches("serum -author:jane"));
+        assert!(!matches("john"));
+        assert!(matches("bass OR (pad AND product:\"serum 2\")"));
+        assert!(matches("name:w*pad"));
+        assert!(matches("tag:fav notes:ambient"));
+        assert!(!matches("tag:bass"));
+        assert!(matches("size>1MB size<=2MB"));
+        assert!(!matches("size>2MB"));
+        assert!(matches("date:2023-06 date>=2023 date<2024"));
+        assert!(!matches("date>2023"));
+    }
+
+    #[test]
+    fn match_same_metadata_text_as_plain_search() {
+        // Given
+        let preset = preset(PotPresetMetaData {
+            vendor: Some("Loopmasters".to_string()),
+            comment: Some("Loop, 120 BPM, Am".to_string()),
+            ..Default::default()
+        });
+        let candidate = SearchQueryCandidate {
+            preset: &preset,
+            user_metadata: None,
+            default_fields: EnumSet::only(SearchField::Metadata),
+        };
+        // When
+        let matches = |expr: &str| SearchQuery::parse(expr, true).unwrap().matches(&candidate);
+        // Then
+        assert_eq!(
+            preset.common.metadata.searchable_text().as_deref(),
+            Some("Loopmasters\nLoop, 120 BPM, Am")
+        );
+        assert!(matches("\"120 bpm\" NOT bass"));
+        assert!(matches("loopmasters -pad"));
+        assert!(!matches("warm -bass"));
+    }
+
+    fn preset(metadata: PotPresetMetaData) -> PotPreset {
+        PotPreset::new(
+            PotPresetCommon {
+                persistent_id: PersistentPresetId::new(
+                    PersistentDatabaseId::new("test".to_string()),
+                    PersistentInnerPresetId::new("1".to_string()),
+                ),
+                name: "Warm Pad".to_string(),
+                context_name: None,
+                plugin_ids: vec![],
+                product_ids: vec![],
+                product_name: Some("Serum 2".to_string()),
+                content_hash: None,
+                db_specific_preview_file: None,
+                is_supported: true,
+                is_available: true,
+                metadata,
+            },
+            PotPresetKind::Internal(InternalPotPresetKind { plugin_id: None }),
+        )
+    }
+
+    #[test]
+    fn report_errors() {
+        let error = |expr| SearchQuery::parse(expr, false).err().unwrap();
+        assert_eq!(error("(bass OR lead").position, 0);
+        assert_eq!(error("bass \"warm").position, 5);
+        assert_eq!(error("bass OR").position, 7);
+        assert_eq!(
+            error("name>bass").msg,
+            "field \"name\" doesn't support comparisons"
+        );
+        assert_eq!(error("size>big").position, 0);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::json_store::{load_json_store, save_json_store};
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Global Pot settings, shared by all Pot units.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct PotSettings {
+    /// Whether the database directories should be watched for changes.
+    #[serde(default)]
+    pub watch_folders: bool,
+}
+
+/// Local store of the global Pot settings.
+#[derive(Debug)]
+pub struct PotSettingsStore {
+    file_path: Utf8PathBuf,
+    settings: PotSettings,
+}
+
+impl PotSettingsStore {
+    pub fn load(file_path: Utf8PathBuf) -> Self {
+        let settings = load_json_store(&file_path, "Pot settings");
+        Self {
+            file_path,
+            settings,
+        }
+    }
+
+    pub fn settings(&self) -> &PotSettings {
+        &self.settings
+    }
+
+    /// Modifies the settings and saves the store if they have changed.
+    ///
+    /// If saving fails, the settings stay as they are.
+    pub fn update(&mut self, f: impl FnOnce(&mut PotSettings)) -> anyhow::Result<()> {
+        let mut settings = self.settings.clone();
+        f(&mut settings);
+        if settings == self.settings {
+            return Ok(());
+        }
+        save_json_store(&self.file_path, "Pot settings", &settings)?;
+        self.settings = settings;
+        Ok(())
+    }
+}
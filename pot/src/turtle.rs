@@ -0,0 +1,524 @@
+//! A minimal parser for the [Turtle](https://www.w3.org/TR/turtle/) RDF format, as used by LV2.
+//!
+//! It supports everything that typically occurs in LV2 manifests and preset files (prefixes,
+//! relative IRIs, predicate and object lists, nested blank nodes, literals) but doesn't try to be
+//! fully standard-compliant. E.g. literal datatypes and language tags are parsed but dropped and
+//! collections are parsed but not turned into triples.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// A node (IRI or blank node) or a literal.
+///
+/// Blank nodes are represented as nodes whose ID starts with `_:`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Term {
+    Node(String),
+    Literal(String),
+}
+
+impl Term {
+    pub fn as_node(&self) -> Option<&str> {
+        match self {
+            Term::Node(n) => Some(n),
+            Term::Literal(_) => None,
+        }
+    }
+
+    pub fn as_literal(&self) -> Option<&str> {
+        match self {
+            Term::Node(_) => None,
+            Term::Literal(l) => Some(l),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Triple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: Term,
+}
+
+/// Parses the given Turtle document.
+///
+/// `base_iri` is used to resolve relative IRIs, usually the `file://` URI of the document.
+pub fn parse_turtle(text: &str, base_iri: &str) -> Result<Vec<Triple>, String> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        document_iri: base_iri.to_string(),
+        base_iri: base_iri.to_string(),
+        prefixes: Default::default(),
+        blank_node_count: 0,
+        triples: vec![],
+    };
+    parser.parse_document()?;
+    Ok(parser.triples)
+}
+
+/// Resolves a possibly relative IRI against the given base IRI.
+pub fn resolve_iri(base_iri: &str, iri: &str) -> String {
+    let has_scheme = iri.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    if has_scheme {
+        return iri.to_string();
+    }
+    if iri.is_empty() {
+        return base_iri.to_string();
+    }
+    if iri.starts_with('#') {
+        let without_fragment = base_iri.split('#').next().unwrap_or(base_iri);
+        return format!("{without_fragment}{iri}");
+    }
+    if iri.starts_with('/') {
+        let authority_end = base_iri
+            .find("://")
+            .and_then(|i| base_iri[i + 3..].find('/').map(|j| i + 3 + j))
+            .unwrap_or(base_iri.len());
+        return format!("{}{iri}", &base_iri[..authority_end]);
+    }
+    let dir = match base_iri.rfind('/') {
+        None => "",
+        Some(i) => &base_iri[..=i],
+    };
+    format!("{dir}{iri}")
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+enum Token {
+    Iri(String),
+    PrefixedName(String),
+    Literal(String),
+    Directive(String),
+    Punct(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '<' => {
+                chars.next();
+                let mut iri = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err("unterminated IRI".to_string()),
+                        Some('>') => break,
+                        Some(c) => iri.push(c),
+                    }
+                }
+                tokens.push(Token::Iri(iri));
+            }
+            '"' | '\'' => {
+                let literal = read_string(&mut chars)?;
+                tokens.push(Token::Literal(literal));
+                // Language tag or datatype is dropped
+                if chars.peek() == Some(&'@') {
+                    read_word(&mut chars);
+                } else if chars.peek() == Some(&'^') {
+                    chars.next();
+                    chars.next();
+                    if chars.peek() == Some(&'<') {
+                        for c in chars.by_ref() {
+                            if c == '>' {
+                                break;
+                            }
+                        }
+                    } else {
+                        read_word(&mut chars);
+                    }
+                }
+            }
+            '[' | ']' | '(' | ')' | ',' | ';' => {
+                chars.next();
+                tokens.push(Token::Punct(c));
+            }
+            '.' => {
+                chars.next();
+                tokens.push(Token::Punct('.'));
+            }
+            '@' => {
+                chars.next();
+                tokens.push(Token::Directive(read_word(&mut chars)));
+            }
+            _ => {
+                let word = read_word(&mut chars);
+                if word.is_empty() {
+                    return Err(format!("unexpected character '{c}'"));
+                }
+                let (word, has_trailing_dot) = match word.strip_suffix('.') {
+                    // A trailing dot belongs to the statement, not to the name or number
+                    Some(w) if !w.is_empty() => (w.to_string(), true),
+                    _ => (word, false),
+                };
+                let token =
+                    if word.eq_ignore_ascii_case("prefix") || word.eq_ignore_ascii_case("base") {
+                        Token::Directive(word.to_lowercase())
+                    } else if word.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+')
+                        || word == "true"
+                        || word == "false"
+                    {
+                        Token::Literal(word)
+                    } else {
+                        Token::PrefixedName(word)
+                    };
+                tokens.push(token);
+                if has_trailing_dot {
+                    tokens.push(Token::Punct('.'));
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn read_word(chars: &mut Peekable<Chars>) -> String {
+    let mut word = String::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || "<>\"'[](),;#".contains(c) {
+            break;
+        }
+        word.push(c);
+        chars.next();
+    }
+    word
+}
+
+fn read_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let quote = chars.next().ok_or("expected quote")?;
+    // Detect long string (triple quotes)
+    let mut opening_quote_count = 1;
+    while opening_quote_count < 3 && chars.peek() == Some(&quote) {
+        chars.next();
+        opening_quote_count += 1;
+    }
+    if opening_quote_count == 2 {
+        // Empty string
+        return Ok(String::new());
+    }
+    let is_long = opening_quote_count == 3;
+    let mut result = String::new();
+    let mut closing_quote_count = 0;
+    loop {
+        let c = chars.next().ok_or("unterminated string")?;
+        if c == quote {
+            if !is_long {
+                return Ok(result);
+            }
+            closing_quote_count += 1;
+            if closing_quote_count == 3 {
+                return Ok(result);
+            }
+            continue;
+        }
+        for _ in 0..closing_quote_count {
+            result.push(quote);
+        }
+        closing_quote_count = 0;
+        if c == '\\' {
+            let escaped = chars.next().ok_or("unterminated escape sequence")?;
+            match escaped {
+                'n' => result.push('\n'),
+                't' => result.push('\t'),
+                'r' => result.push('\r'),
+                'b' => result.push('\u{8}'),
+                'f' => result.push('\u{c}'),
+                'u' | 'U' => {
+                    let len = if escaped == 'u' { 4 } else { 8 };
+                    let hex: String = chars.by_ref().take(len).collect();
+                    let code = u32::from_str_radix(&hex, 16)
+                        .map_err(|_| "invalid unicode escape sequence")?;
+                    result.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                c => result.push(c),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    document_iri: String,
+    base_iri: String,
+    prefixes: Vec<(String, String)>,
+    blank_node_count: u32,
+    triples: Vec<Triple>,
+}
+
+impl Parser {
+    fn parse_document(&mut self) -> Result<(), String> {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Directive(d) => {
+                    self.pos += 1;
+                    self.parse_directive(&d)?;
+                }
+                _ => {
+                    self.parse_triples()?;
+                    self.expect_punct('.')?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_directive(&mut self, directive: &str) -> Result<(), String> {
+        match directive {
+            "prefix" => {
+                let Some(Token::PrefixedName(name)) = self.next() else {
+                    return Err("expected prefix name".to_string());
+                };
+                let Some(Token::Iri(iri)) = self.next() else {
+                    return Err("expected prefix IRI".to_string());
+                };
+                let prefix = name.strip_suffix(':').unwrap_or(&name).to_string();
+                let iri = resolve_iri(&self.base_iri, &iri);
+                self.prefixes.push((prefix, iri));
+            }
+            "base" => {
+                let Some(Token::Iri(iri)) = self.next() else {
+                    return Err("expected base IRI".to_string());
+                };
+                self.base_iri = resolve_iri(&self.base_iri, &iri);
+            }
+            d => return Err(format!("unknown directive {d}")),
+        }
+        // SPARQL-style directives don't end with a dot
+        if self.peek() == Some(&Token::Punct('.')) {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn parse_triples(&mut self) -> Result<(), String> {
+        if self.peek() == Some(&Token::Punct('[')) {
+            let subject = self.parse_blank_node_property_list()?;
+            if self.peek() != Some(&Token::Punct('.')) {
+                self.parse_predicate_object_list(&subject)?;
+            }
+        } else {
+            let subject = match self.parse_object()? {
+                Term::Node(n) => n,
+                Term::Literal(_) => return Err("literal can't be a subject".to_string()),
+            };
+            self.parse_predicate_object_list(&subject)?;
+        }
+        Ok(())
+    }
+
+    fn parse_predicate_object_list(&mut self, subject: &str) -> Result<(), String> {
+        loop {
+            let predicate = match self.next() {
+                Some(Token::PrefixedName(n)) if n == "a" => RDF_TYPE.to_string(),
+                Some(Token::PrefixedName(n)) => self.expand_prefixed_name(&n)?,
+                Some(Token::Iri(iri)) => resolve_iri(&self.base_iri, &iri),
+                t => return Err(format!("expected predicate but got {t:?}")),
+            };
+            loop {
+                let object = self.parse_object()?;
+                self.triples.push(Triple {
+                    subject: subject.to_string(),
+                    predicate: predicate.clone(),
+                    object,
+                });
+                if self.peek() == Some(&Token::Punct(',')) {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+            // Skip semicolons (there can be multiple ones or a trailing one)
+            let mut had_semicolon = false;
+            while self.peek() == Some(&Token::Punct(';')) {
+                self.pos += 1;
+                had_semicolon = true;
+            }
+            let list_continues = had_semicolon
+                && !matches!(
+                    self.peek(),
+                    None | Some(Token::Punct('.')) | Some(Token::Punct(']'))
+                );
+            if !list_continues {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Term, String> {
+        let term = match self.next() {
+            Some(Token::Iri(iri)) => Term::Node(resolve_iri(&self.base_iri, &iri)),
+            Some(Token::PrefixedName(n)) => {
+                if let Some(label) = n.strip_prefix("_:") {
+                    Term::Node(self.blank_node(label))
+                } else {
+                    Term::Node(self.expand_prefixed_name(&n)?)
+                }
+            }
+            Some(Token::Literal(l)) => Term::Literal(l),
+            Some(Token::Punct('[')) => {
+                self.pos -= 1;
+                Term::Node(self.parse_blank_node_property_list()?)
+            }
+            Some(Token::Punct('(')) => {
+                while self.peek() != Some(&Token::Punct(')')) {
+                    if self.peek().is_none() {
+                        return Err("unterminated collection".to_string());
+                    }
+                    self.parse_object()?;
+                }
+                self.pos += 1;
+                Term::Node(self.new_blank_node())
+            }
+            t => return Err(format!("expected object but got {t:?}")),
+        };
+        Ok(term)
+    }
+
+    fn parse_blank_node_property_list(&mut self) -> Result<String, String> {
+        self.expect_punct('[')?;
+        let node = self.new_blank_node();
+        if self.peek() != Some(&Token::Punct(']')) {
+            self.parse_predicate_object_list(&node)?;
+        }
+        self.expect_punct(']')?;
+        Ok(node)
+    }
+
+    fn expand_prefixed_name(&self, name: &str) -> Result<String, String> {
+        let (prefix, local) = name
+            .split_once(':')
+            .ok_or_else(|| format!("invalid prefixed name {name}"))?;
+        let (_, iri) = self
+            .prefixes
+            .iter()
+            .rev()
+            .find(|(p, _)| p == prefix)
+            .ok_or_else(|| format!("unknown prefix {prefix}"))?;
+        Ok(format!("{iri}{}", local.replace('\\', "")))
+    }
+
+    fn new_blank_node(&mut self) -> String {
+        let node = self.blank_node(&format!("genid{}", self.blank_node_count));
+        self.blank_node_count += 1;
+        node
+    }
+
+    /// Blank node labels are only unique within one document, so we qualify them with the
+    /// document IRI in order to allow merging triples of multiple documents.
+    fn blank_node(&self, label: &str) -> String {
+        format!("_:{}#{label}", self.document_iri)
+    }
+
+    fn expect_punct(&mut self, punct: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Punct(p)) if p == punct => Ok(()),
+            t => Err(format!("expected '{punct}' but got {t:?}")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lv2_preset_manifest() {
+        // Given
+        let text = r#"
+@prefix lv2:  <http://lv2plug.in/ns/lv2core#> .
+@prefix pset: <http://lv2plug.in/ns/ext/presets#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+# A preset
+<Warm%20Pad.ttl>
+    a pset:Preset ;
+    lv2:appliesTo <http://example.org/synth> ;
+    rdfs:label """Warm "Pad\"""" ;
+    lv2:port [
+        lv2:symbol "gain" ;
+        pset:value 0.5
+    ] , [ lv2:symbol "cutoff"; pset:value -1.0e3 ] ;
+    rdfs:seeAlso <Warm%20Pad.ttl> .
+"#;
+        // When
+        let triples = parse_turtle(text, "file:///home/me/.lv2/synth.lv2/manifest.ttl").unwrap();
+        // Then
+        let subject = "file:///home/me/.lv2/synth.lv2/Warm%20Pad.ttl";
+        let find = |predicate: &str| -> Vec<&Term> {
+            triples
+                .iter()
+                .filter(|t| t.subject == subject && t.predicate == predicate)
+                .map(|t| &t.object)
+                .collect()
+        };
+        assert_eq!(
+            find(RDF_TYPE),
+            vec![&Term::Node(
+                "http://lv2plug.in/ns/ext/presets#Preset".to_string()
+            )]
+        );
+        assert_eq!(
+            find("http://www.w3.org/2000/01/rdf-schema#label"),
+            vec![&Term::Literal("Warm \"Pad\"".to_string())]
+        );
+        assert_eq!(find("http://lv2plug.in/ns/lv2core#port").len(), 2);
+        assert!(triples.iter().any(
+            |t| t.subject.starts_with("_:") && t.object == Term::Literal("-1.0e3".to_string())
+        ));
+        assert_eq!(
+            find("http://www.w3.org/2000/01/rdf-schema#seeAlso"),
+            vec![&Term::Node(subject.to_string())]
+        );
+    }
+
+    #[test]
+    fn iri_resolution() {
+        let base = "file:///usr/lib/lv2/a.lv2/manifest.ttl";
+        assert_eq!(
+            resolve_iri(base, "b.ttl"),
+            "file:///usr/lib/lv2/a.lv2/b.ttl"
+        );
+        assert_eq!(
+            resolve_iri(base, "#x"),
+            "file:///usr/lib/lv2/a.lv2/manifest.ttl#x"
+        );
+        assert_eq!(
+            resolve_iri(base, "http://example.org/x"),
+            "http://example.org/x"
+        );
+        assert_eq!(resolve_iri(base, "/tmp/c.ttl"), "file:///tmp/c.ttl");
+    }
+}
@@ -0,0 +1,274 @@
+use crate::json_store::{load_json_store, save_json_store};
+use crate::{Fil, FilterItemId, Filters, PersistentPresetId};
+use camino::Utf8PathBuf;
+use helgobox_api::persistence::PotFilterKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The highest possible preset rating.
+pub const MAX_PRESET_RATING: u8 = 5;
+
+/// Metadata that the user attaches to a preset, independent of the database provider.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct PresetUserMetadata {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    /// Rating from 1 to [`MAX_PRESET_RATING`]. `None` means "not rated".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+impl PresetUserMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.rating.is_none() && self.notes.is_empty()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.trim();
+        if !tag.is_empty() {
+            self.tags.insert(tag.to_string());
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.remove(tag.trim());
+    }
+
+    /// Sets the rating. 0 removes the rating, values above the maximum are clamped.
+    pub fn set_rating(&mut self, rating: u8) {
+        self.rating = if rating == 0 {
+            None
+        } else {
+            Some(rating.min(MAX_PRESET_RATING))
+        };
+    }
+}
+
+/// Returns whether the given user metadata (`None` if the preset doesn't have any) matches the tag
+/// and rating filters.
+pub fn user_metadata_matches_filters(
+    metadata: Option<&PresetUserMetadata>,
+    filters: &Filters,
+) -> bool {
+    let tag_matches = match filters.get(PotFilterKind::Tag) {
+        // <Any>
+        None => true,
+        // <None>
+        Some(FilterItemId(None)) => metadata.map_or(true, |m| m.tags.is_empty()),
+        // Specific tag
+        Some(FilterItemId(Some(fil))) => {
+            metadata.is_some_and(|m| m.tags.iter().any(|tag| Fil::text(tag) == fil))
+        }
+    };
+    let rating_matches = match filters.wants_min_rating() {
+        None => true,
+        Some(min_rating) => metadata
+            .and_then(|m| m.rating)
+            .is_some_and(|rating| rating >= min_rating),
+    };
+    tag_matches && rating_matches
+}
+
+/// Returns whether any of the filters which are evaluated by [`user_metadata_matches_filters`]
+/// is set.
+pub fn user_metadata_filters_are_set(filters: &Filters) -> bool {
+    filters.get(PotFilterKind::Tag).is_some() || filters.wants_min_rating().is_some()
+}
+
+/// Local store of user metadata, keyed by persistent preset ID.
+///
+/// Because the key is the persistent preset ID, this works across all databases and survives
+/// database refreshes.
+#[derive(Debug)]
+pub struct PotUserMetadataStore {
+    file_path: Utf8PathBuf,
+    entries: BTreeMap<String, PotUserMetadataEntry>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct PotUserMetadataEntry {
+    /// Name of the preset at the time of the last modification.
+    ///
+    /// Makes it possible to look up the preset without crawling its whole database.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    preset_name: String,
+    #[serde(flatten)]
+    metadata: PresetUserMetadata,
+}
+
+#[derive(Default, Deserialize)]
+struct PotUserMetadataFileContent {
+    #[serde(default)]
+    presets: BTreeMap<String, PotUserMetadataEntry>,
+}
+
+#[derive(Serialize)]
+struct PotUserMetadataFileContentRef<'a> {
+    presets: &'a BTreeMap<String, PotUserMetadataEntry>,
+}
+
+impl PotUserMetadataStore {
+    pub fn load(file_path: Utf8PathBuf) -> Self {
+        let content: PotUserMetadataFileContent = load_json_store(&file_path, "Pot user metadata");
+        Self {
+            file_path,
+            entries: content.presets,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, preset_id: &PersistentPresetId) -> Option<&PresetUserMetadata> {
+        self.get_by_key(&preset_id.to_string())
+    }
+
+    pub fn get_by_key(&self, key: &str) -> Option<&PresetUserMetadata> {
+        self.entries.get(key).map(|e| &e.metadata)
+    }
+
+    /// Returns the preset name which has been memorized together with the metadata of the preset
+    /// with the given key.
+    pub fn preset_name_by_key(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|e| e.preset_name.as_str())
+    }
+
+    /// Returns all metadata entries, keyed by the string representation of the persistent preset
+    /// ID.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &PresetUserMetadata)> {
+        self.entries.iter().map(|(k, e)| (k.as_str(), &e.metadata))
+    }
+
+    /// Returns all tags which are assigned to at least one preset.
+    pub fn all_tags(&self) -> BTreeSet<&str> {
+        self.entries
+            .values()
+            .flat_map(|e| e.metadata.tags.iter().map(|t| t.as_str()))
+            .collect()
+    }
+
+    /// Modifies the metadata of the given preset in memory. Call [`Self::save`] to persist it.
+    pub fn update<R>(
+        &mut self,
+        preset_id: &PersistentPresetId,
+        preset_name: &str,
+        f: impl FnOnce(&mut PresetUserMetadata) -> R,
+    ) -> R {
+        let key = preset_id.to_string();
+        let entry = self.entries.entry(key.clone()).or_default();
+        let result = f(&mut entry.metadata);
+        if entry.metadata.is_empty() {
+            self.entries.remove(&key);
+        } else {
+            entry.preset_name = preset_name.to_string();
+        }
+        result
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = PotUserMetadataFileContentRef {
+            presets: &self.entries,
+        };
+        save_json_store(&self.file_path, "Pot user metadata", &content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_rating() {
+        // Given
+        let mut metadata = PresetUserMetadata::default();
+        // When
+        let ratings: Vec<_> = [3, MAX_PRESET_RATING + 1, 0]
+            .into_iter()
+            .map(|rating| {
+                metadata.set_rating(rating);
+                metadata.rating
+            })
+            .collect();
+        // Then
+        assert_eq!(ratings, vec![Some(3), Some(MAX_PRESET_RATING), None]);
+        assert!(metadata.is_empty());
+    }
+
+    #[test]
+    fn add_and_remove_tags() {
+        // Given
+        let mut metadata = PresetUserMetadata::default();
+        // When
+        metadata.add_tag(" Bass ");
+        metadata.add_tag("Pad");
+        metadata.add_tag("  ");
+        metadata.remove_tag("Pad ");
+        // Then
+        assert!(metadata.has_tag("Bass"));
+        assert!(!metadata.has_tag("Pad"));
+        assert_eq!(metadata.tags.len(), 1);
+    }
+
+    #[test]
+    fn update_removes_empty_entries() {
+        // Given
+        let mut store = PotUserMetadataStore {
+            file_path: Default::default(),
+            entries: Default::default(),
+        };
+        let preset_id: PersistentPresetId = "fx-presets|1".parse().unwrap();
+        // When
+        store.update(&preset_id, "Warm Pad", |m| m.add_tag("Pad"));
+        // Then
+        assert_eq!(store.preset_name_by_key("fx-presets|1"), Some("Warm Pad"));
+        assert!(store.get(&preset_id).unwrap().has_tag("Pad"));
+        store.update(&preset_id, "Warm Pad", |m| m.remove_tag("Pad"));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn filters() {
+        // Given
+        let mut tagged_and_rated = PresetUserMetadata::default();
+        tagged_and_rated.add_tag("Bass");
+        tagged_and_rated.set_rating(4);
+        let with_tag_filter = |tag: Option<&str>| {
+            let mut filters = Filters::empty();
+            filters.set(PotFilterKind::Tag, Some(FilterItemId(tag.map(Fil::text))));
+            filters
+        };
+        let mut with_rating_filter = Filters::empty();
+        with_rating_filter.set(
+            PotFilterKind::Rating,
+            Some(FilterItemId(Some(Fil::Rating(3)))),
+        );
+        // When
+        let matches = |metadata: Option<&PresetUserMetadata>, filters: &Filters| {
+            user_metadata_matches_filters(metadata, filters)
+        };
+        // Then
+        assert!(!user_metadata_filters_are_set(&Filters::empty()));
+        assert!(user_metadata_filters_are_set(&with_rating_filter));
+        assert!(matches(None, &Filters::empty()));
+        assert!(matches(
+            Some(&tagged_and_rated),
+            &with_tag_filter(Some("Bass"))
+        ));
+        assert!(!matches(
+            Some(&tagged_and_rated),
+            &with_tag_filter(Some("Pad"))
+        ));
+        assert!(!matches(None, &with_tag_filter(Some("Bass"))));
+        assert!(matches(None, &with_tag_filter(None)));
+        assert!(!matches(Some(&tagged_and_rated), &with_tag_filter(None)));
+        assert!(matches(Some(&tagged_and_rated), &with_rating_filter));
+        assert!(!matches(None, &with_rating_filter));
+    }
+}